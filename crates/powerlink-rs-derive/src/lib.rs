@@ -0,0 +1,416 @@
+// crates/powerlink-rs-derive/src/lib.rs
+//! Derive macros for `powerlink-rs`.
+//!
+//! `#[derive(PowerlinkObjects)]` binds the fields of an application struct to
+//! Object Dictionary entries. Each field carries an `#[od(...)]` attribute:
+//!
+//! ```ignore
+//! #[derive(PowerlinkObjects)]
+//! pub struct IoModule {
+//!     #[od(index = 0x6000, name = "Digital_Inputs_8bit", access = "rw", pdo_mapping = "optional")]
+//!     digital_inputs: u8,
+//!     #[od(index = 0x6401, sub_index = 1, name = "Analog_Inputs", pdo_mapping = "default")]
+//!     analog_input_1: i16,
+//!     #[od(index = 0x6401, sub_index = 2, min = -1000, max = 1000)]
+//!     analog_input_2: i16,
+//! }
+//! ```
+//!
+//! Supported keys:
+//! - `index` (required) and `sub_index` (default 0). A field at sub-index 0
+//!   becomes a VAR; fields sharing an index at sub-indices 1..=N become an
+//!   ARRAY if they all have the same type, otherwise a RECORD.
+//! - `name`, `category` (`"mandatory"`, `"optional"`, `"conditional"`),
+//!   `access` (`"ro"`, `"wo"`, `"wos"`, `"rw"`, `"rws"`, `"const"`, `"cond"`),
+//!   `pdo_mapping` (`"no"`, `"optional"`, `"default"`), `min` and `max`.
+//!   These describe the whole entry, so for ARRAY/RECORD entries they may be
+//!   given on any one of its fields.
+//!
+//! The macro implements `powerlink_rs::od::PowerlinkObjects` and adds
+//! `read_<field>`/`write_<field>` associated functions for typed access.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, format_ident, quote};
+use std::collections::BTreeMap;
+use syn::{Data, DeriveInput, Expr, Fields, Ident, LitInt, LitStr, Type, parse_macro_input};
+
+#[proc_macro_derive(PowerlinkObjects, attributes(od))]
+pub fn derive_powerlink_objects(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The parsed `#[od(...)]` attribute of a single field.
+struct FieldSpec {
+    ident: Ident,
+    ty: Type,
+    index: u16,
+    sub_index: u8,
+    name: Option<LitStr>,
+    category: Option<TokenStream2>,
+    access: Option<TokenStream2>,
+    /// Set by `access = "ro"` or `"const"`: the stack must not write the object.
+    read_only: bool,
+    pdo_mapping: Option<TokenStream2>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    span: Span,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "PowerlinkObjects can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "PowerlinkObjects requires a struct with named fields",
+        ));
+    };
+
+    let specs = fields
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut entries: BTreeMap<u16, Vec<&FieldSpec>> = BTreeMap::new();
+    for spec in &specs {
+        entries.entry(spec.index).or_default().push(spec);
+    }
+
+    let registrations = entries
+        .iter()
+        .map(|(index, fields)| entry_registration(*index, fields))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let loads = specs.iter().map(|spec| {
+        let (ident, ty, index, sub_index) = (&spec.ident, &spec.ty, spec.index, spec.sub_index);
+        quote! {
+            if let ::core::option::Option::Some(value) = od.read_as::<#ty>(#index, #sub_index) {
+                self.#ident = value;
+            }
+        }
+    });
+
+    // Access is set per entry, so a read-only entry skips all of its fields.
+    let read_only_entries: Vec<u16> = entries
+        .iter()
+        .filter(|(_, fields)| fields.iter().any(|f| f.read_only))
+        .map(|(index, _)| *index)
+        .collect();
+    let stores: Vec<_> = specs
+        .iter()
+        .filter(|spec| !read_only_entries.contains(&spec.index))
+        .map(|spec| {
+            let (ident, index, sub_index) = (&spec.ident, spec.index, spec.sub_index);
+            quote! {
+                (
+                    #index,
+                    #sub_index,
+                    ::powerlink_rs::od::ObjectType::into_object_value(
+                        ::core::clone::Clone::clone(&self.#ident),
+                    ),
+                )
+            }
+        })
+        .collect();
+    let store_count = stores.len();
+
+    let vis = &input.vis;
+    let accessors = specs.iter().map(|spec| {
+        let (ident, ty, index, sub_index) = (&spec.ident, &spec.ty, spec.index, spec.sub_index);
+        let read_fn = format_ident!("read_{}", ident);
+        let write_fn = format_ident!("write_{}", ident);
+        let read_doc = format!("Reads `{}` from 0x{:04X}/{}.", ident, index, sub_index);
+        let write_doc = format!("Writes `{}` to 0x{:04X}/{}.", ident, index, sub_index);
+        quote! {
+            #[doc = #read_doc]
+            #vis fn #read_fn(
+                od: &::powerlink_rs::od::ObjectDictionary<'_>,
+            ) -> ::core::option::Option<#ty> {
+                od.read_as(#index, #sub_index)
+            }
+
+            #[doc = #write_doc]
+            #vis fn #write_fn(
+                od: &mut ::powerlink_rs::od::ObjectDictionary<'_>,
+                value: #ty,
+            ) -> ::core::result::Result<(), ::powerlink_rs::PowerlinkError> {
+                od.write_as(#index, #sub_index, value)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::powerlink_rs::od::PowerlinkObjects for #name #ty_generics #where_clause {
+            fn register(&self, od: &mut ::powerlink_rs::od::ObjectDictionary<'_>) {
+                #(#registrations)*
+            }
+
+            fn load(&mut self, od: &::powerlink_rs::od::ObjectDictionary<'_>) {
+                #(#loads)*
+            }
+
+            fn store(
+                &self,
+                od: &mut ::powerlink_rs::od::ObjectDictionary<'_>,
+            ) -> ::core::result::Result<(), ::powerlink_rs::PowerlinkError> {
+                let values: [(u16, u8, ::powerlink_rs::od::ObjectValue); #store_count] =
+                    [#(#stores),*];
+                // Check every field first, so a rejected value leaves the OD unchanged.
+                for (index, sub_index, value) in &values {
+                    od.check_write(*index, *sub_index, value)?;
+                }
+                for (index, sub_index, value) in values {
+                    od.write(index, sub_index, value)?;
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<FieldSpec> {
+    let ident = field.ident.clone().expect("named field");
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("od"))
+        .ok_or_else(|| syn::Error::new_spanned(field, "missing #[od(index = ...)] attribute"))?;
+
+    let mut index = None;
+    let mut spec = FieldSpec {
+        ident,
+        ty: field.ty.clone(),
+        index: 0,
+        sub_index: 0,
+        name: None,
+        category: None,
+        access: None,
+        read_only: false,
+        pdo_mapping: None,
+        min: None,
+        max: None,
+        span: attr.pound_token.span,
+    };
+
+    attr.parse_nested_meta(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .map(Ident::to_string)
+            .unwrap_or_default();
+        match key.as_str() {
+            "index" => index = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u16>()?),
+            "sub_index" => {
+                spec.sub_index = meta.value()?.parse::<LitInt>()?.base10_parse::<u8>()?
+            }
+            "name" => spec.name = Some(meta.value()?.parse()?),
+            "category" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                let variant = match lit.value().as_str() {
+                    "mandatory" => quote!(Mandatory),
+                    "optional" => quote!(Optional),
+                    "conditional" => quote!(Conditional),
+                    _ => return Err(syn::Error::new_spanned(lit, "unknown category")),
+                };
+                spec.category = Some(quote!(::powerlink_rs::od::Category::#variant));
+            }
+            "access" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                let variant = match lit.value().as_str() {
+                    "ro" => quote!(ReadOnly),
+                    "wo" => quote!(WriteOnly),
+                    "wos" => quote!(WriteOnlyStore),
+                    "rw" => quote!(ReadWrite),
+                    "rws" => quote!(ReadWriteStore),
+                    "const" => quote!(Constant),
+                    "cond" => quote!(Conditional),
+                    _ => return Err(syn::Error::new_spanned(lit, "unknown access type")),
+                };
+                spec.read_only = matches!(lit.value().as_str(), "ro" | "const");
+                spec.access = Some(quote!(::powerlink_rs::od::AccessType::#variant));
+            }
+            "pdo_mapping" => {
+                let lit: LitStr = meta.value()?.parse()?;
+                let variant = match lit.value().as_str() {
+                    "no" => quote!(No),
+                    "optional" => quote!(Optional),
+                    "default" => quote!(Default),
+                    _ => return Err(syn::Error::new_spanned(lit, "unknown PDO mapping")),
+                };
+                spec.pdo_mapping = Some(quote!(::powerlink_rs::od::PdoMapping::#variant));
+            }
+            "min" => spec.min = Some(meta.value()?.parse()?),
+            "max" => spec.max = Some(meta.value()?.parse()?),
+            _ => return Err(meta.error("unknown od attribute key")),
+        }
+        Ok(())
+    })?;
+
+    spec.index = index.ok_or_else(|| syn::Error::new_spanned(attr, "missing `index`"))?;
+    if spec.min.is_some() != spec.max.is_some() {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "`min` and `max` must be given together",
+        ));
+    }
+    Ok(spec)
+}
+
+/// Returns the single entry-level setting given on any field of an entry,
+/// or an error if the fields disagree.
+fn entry_setting<'a, T: ToTokens + 'a>(
+    index: u16,
+    key: &str,
+    values: impl Iterator<Item = (&'a Option<T>, Span)>,
+) -> syn::Result<Option<&'a T>> {
+    let mut found: Option<&T> = None;
+    for (value, span) in values {
+        let Some(value) = value else { continue };
+        match found {
+            Some(prev)
+                if prev.to_token_stream().to_string() != value.to_token_stream().to_string() =>
+            {
+                return Err(syn::Error::new(
+                    span,
+                    format!("conflicting `{}` for object 0x{:04X}", key, index),
+                ));
+            }
+            _ => found = Some(value),
+        }
+    }
+    Ok(found)
+}
+
+fn entry_registration(index: u16, fields: &[&FieldSpec]) -> syn::Result<TokenStream2> {
+    let mut fields = fields.to_vec();
+    fields.sort_by_key(|f| f.sub_index);
+    let first = fields[0];
+
+    let is_variable = first.sub_index == 0;
+    if is_variable && fields.len() > 1 {
+        return Err(syn::Error::new(
+            fields[1].span,
+            format!("object 0x{:04X} already has a field at sub-index 0", index),
+        ));
+    }
+    if !is_variable {
+        for (expected, field) in (1u8..).zip(&fields) {
+            if field.sub_index != expected {
+                return Err(syn::Error::new(
+                    field.span,
+                    format!(
+                        "sub-indices of object 0x{:04X} must be contiguous from 1 (expected {})",
+                        index, expected
+                    ),
+                ));
+            }
+        }
+    }
+
+    let setting = |key, get: fn(&FieldSpec) -> &Option<TokenStream2>| {
+        entry_setting(index, key, fields.iter().map(|f| (get(f), f.span)))
+    };
+    let category = setting("category", |f| &f.category)?
+        .cloned()
+        .unwrap_or_else(|| quote!(::powerlink_rs::od::Category::Optional));
+    let access = option_tokens(setting("access", |f| &f.access)?);
+    let pdo_mapping = option_tokens(setting("pdo_mapping", |f| &f.pdo_mapping)?);
+    let name = entry_setting(index, "name", fields.iter().map(|f| (&f.name, f.span)))?
+        .map(LitStr::value)
+        .unwrap_or_else(|| first.ident.to_string());
+
+    let ranged: Vec<_> = fields.iter().filter(|f| f.min.is_some()).collect();
+    if ranged.len() > 1 {
+        return Err(syn::Error::new(
+            ranged[1].span,
+            format!(
+                "value range for object 0x{:04X} given more than once",
+                index
+            ),
+        ));
+    }
+    let value_range = match ranged.first() {
+        Some(field) => {
+            let (ty, min, max) = (&field.ty, &field.min, &field.max);
+            quote! {
+                ::core::option::Option::Some(::powerlink_rs::od::ValueRange {
+                    min: <#ty as ::powerlink_rs::od::ObjectType>::into_object_value(#min),
+                    max: <#ty as ::powerlink_rs::od::ObjectType>::into_object_value(#max),
+                    values: ::core::default::Default::default(),
+                })
+            }
+        }
+        None => quote!(::core::option::Option::None),
+    };
+
+    let value_of = |field: &FieldSpec| {
+        let ident = &field.ident;
+        quote! {
+            ::powerlink_rs::od::ObjectType::into_object_value(
+                ::core::clone::Clone::clone(&self.#ident),
+            )
+        }
+    };
+
+    let (object, default_value) = if is_variable {
+        let value = value_of(first);
+        (
+            quote!(::powerlink_rs::od::Object::Variable(#value)),
+            quote!(::core::option::Option::Some(#value)),
+        )
+    } else {
+        let values = fields.iter().map(|f| value_of(f));
+        let type_of = |f: &FieldSpec| f.ty.to_token_stream().to_string();
+        let same_type = fields.iter().all(|f| type_of(f) == type_of(first));
+        let kind = if same_type {
+            quote!(Array)
+        } else {
+            quote!(Record)
+        };
+        (
+            quote! {
+                ::powerlink_rs::od::Object::#kind(
+                    ::core::iter::IntoIterator::into_iter([#(#values),*]).collect(),
+                )
+            },
+            quote!(::core::option::Option::None),
+        )
+    };
+
+    Ok(quote! {
+        od.insert(
+            #index,
+            ::powerlink_rs::od::ObjectEntry {
+                object: #object,
+                name: #name,
+                category: #category,
+                access: #access,
+                default_value: #default_value,
+                value_range: #value_range,
+                pdo_mapping: #pdo_mapping,
+            },
+        );
+    })
+}
+
+fn option_tokens(value: Option<&TokenStream2>) -> TokenStream2 {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
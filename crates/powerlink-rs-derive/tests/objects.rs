@@ -0,0 +1,123 @@
+//! Integration tests for `#[derive(PowerlinkObjects)]`.
+//!
+//! These tests check that the generated registrations produce the expected
+//! `ObjectEntry` layout and that the typed accessors and `load`/`store`
+//! round-trip through the Object Dictionary.
+
+use powerlink_rs::PowerlinkError;
+use powerlink_rs::od::{Object, ObjectDictionary, ObjectValue, PowerlinkObjects};
+use powerlink_rs_derive::PowerlinkObjects;
+
+#[derive(Debug, Default, PowerlinkObjects)]
+pub struct IoModule {
+    #[od(
+        index = 0x6000,
+        name = "Digital_Inputs_8bit",
+        access = "rw",
+        pdo_mapping = "optional"
+    )]
+    digital_inputs: u8,
+    #[od(index = 0x6401, sub_index = 1, name = "Analog_Inputs", access = "rw")]
+    analog_input_1: i16,
+    #[od(index = 0x6401, sub_index = 2, min = -1000, max = 1000)]
+    analog_input_2: i16,
+    #[od(index = 0x2000, sub_index = 1, name = "Settings", access = "rw")]
+    enabled: bool,
+    #[od(index = 0x2000, sub_index = 2)]
+    label: String,
+    #[od(index = 0x2001, access = "ro")]
+    firmware: u32,
+}
+
+fn create_od() -> (IoModule, ObjectDictionary<'static>) {
+    let module = IoModule {
+        digital_inputs: 0x5A,
+        analog_input_2: 7,
+        label: "io".into(),
+        firmware: 0x0102_0304,
+        ..Default::default()
+    };
+    let mut od = ObjectDictionary::new(None);
+    module.register(&mut od);
+    (module, od)
+}
+
+#[test]
+fn test_register_builds_entries() {
+    let (_, od) = create_od();
+
+    assert_eq!(
+        od.read_object(0x6000),
+        Some(&Object::Variable(ObjectValue::Unsigned8(0x5A)))
+    );
+    assert_eq!(
+        od.read_object(0x6401),
+        Some(&Object::Array(vec![
+            ObjectValue::Integer16(0),
+            ObjectValue::Integer16(7)
+        ]))
+    );
+    assert_eq!(
+        od.read_object(0x2000),
+        Some(&Object::Record(vec![
+            ObjectValue::Boolean(0),
+            ObjectValue::VisibleString("io".into())
+        ]))
+    );
+    assert_eq!(od.find_by_name("Digital_Inputs_8bit"), Some((0x6000, 0)));
+    assert_eq!(od.find_by_name("firmware"), Some((0x2001, 0)));
+}
+
+#[test]
+fn test_typed_accessors() {
+    let (_, mut od) = create_od();
+
+    IoModule::write_analog_input_1(&mut od, -12).unwrap();
+    assert_eq!(IoModule::read_analog_input_1(&od), Some(-12));
+    assert_eq!(
+        od.read(0x6401, 1).as_deref(),
+        Some(&ObjectValue::Integer16(-12))
+    );
+
+    IoModule::write_enabled(&mut od, true).unwrap();
+    assert_eq!(IoModule::read_enabled(&od), Some(true));
+
+    // Range and access metadata are enforced on every write.
+    assert_eq!(
+        IoModule::write_analog_input_2(&mut od, 1001),
+        Err(PowerlinkError::ValueTooHigh)
+    );
+    assert!(IoModule::write_firmware(&mut od, 1).is_err());
+}
+
+#[test]
+fn test_load_and_store() {
+    let (mut module, mut od) = create_od();
+
+    IoModule::write_digital_inputs(&mut od, 0x0F).unwrap();
+    IoModule::write_label(&mut od, "renamed".into()).unwrap();
+    module.load(&od);
+    assert_eq!(module.digital_inputs, 0x0F);
+    assert_eq!(module.label, "renamed");
+
+    // The read-only firmware object is skipped; all other fields are written.
+    module.analog_input_2 = -500;
+    module.label = "stored".into();
+    module.firmware = 7;
+    assert_eq!(module.store(&mut od), Ok(()));
+    assert_eq!(IoModule::read_analog_input_2(&od), Some(-500));
+    assert_eq!(IoModule::read_label(&od).as_deref(), Some("stored"));
+    assert_eq!(IoModule::read_firmware(&od), Some(0x0102_0304));
+}
+
+#[test]
+fn test_store_rejects_all_fields_on_invalid_value() {
+    let (mut module, mut od) = create_od();
+
+    // analog_input_2 is out of range, so neither field is written.
+    module.digital_inputs = 0x11;
+    module.analog_input_2 = 2000;
+    assert_eq!(module.store(&mut od), Err(PowerlinkError::ValueTooHigh));
+    assert_eq!(IoModule::read_digital_inputs(&od), Some(0x5A));
+    assert_eq!(IoModule::read_analog_input_2(&od), Some(7));
+}
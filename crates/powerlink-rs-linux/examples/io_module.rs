@@ -16,10 +16,14 @@ use powerlink_rs::{
     ControlledNode,
     NetworkInterface,
     PowerlinkError,
+    PowerlinkObjects,
     frame::basic::MacAddress,
     nmt::{flags::FeatureFlags, states::NmtState},
     node::{ManagingNode, Node, NodeAction}, // Corrected import
-    od::{AccessType, Category, Object, ObjectDictionary, ObjectEntry, ObjectValue, PdoMapping},
+    od::{
+        AccessType, Category, Object, ObjectDictionary, ObjectEntry, ObjectValue, PdoMapping,
+        PowerlinkObjects as _,
+    },
     pdo::PdoMappingEntry,
     types::{C_ADR_MN_DEF_NODE_ID, IpAddress}, // Import IpAddress for UDP
 };
@@ -33,6 +37,7 @@ use std::{
 
 const CN_NODE_ID: u8 = 42;
 const CYCLE_TIME_US: u32 = 20_000; // 20ms
+const LINK_CHECK_INTERVAL_US: u64 = 100_000; // 100ms
 
 // OD indices for our I/O module's application data
 const IDX_DIGITAL_INPUTS: u16 = 0x6000;
@@ -40,6 +45,56 @@ const IDX_ANALOG_INPUTS: u16 = 0x6001;
 const IDX_DIGITAL_OUTPUTS: u16 = 0x6200;
 const IDX_ANALOG_OUTPUTS: u16 = 0x6201;
 
+/// The application objects of the I/O module CN, at the indices above.
+#[derive(Default, PowerlinkObjects)]
+struct IoModule {
+    // Inputs (data source on CN): the application writes, the network reads.
+    #[od(
+        index = 0x6000,
+        name = "Digital_Inputs_8bit",
+        category = "mandatory",
+        access = "rw",
+        pdo_mapping = "optional"
+    )]
+    digital_inputs: u8,
+    #[od(
+        index = 0x6001,
+        sub_index = 1,
+        name = "Analog_Inputs_4x16bit",
+        category = "mandatory"
+    )]
+    analog_input_1: u16,
+    #[od(index = 0x6001, sub_index = 2)]
+    analog_input_2: u16,
+    #[od(index = 0x6001, sub_index = 3)]
+    analog_input_3: u16,
+    #[od(index = 0x6001, sub_index = 4)]
+    analog_input_4: u16,
+
+    // Outputs (data sink on CN): the network writes, the application reads.
+    #[od(
+        index = 0x6200,
+        name = "Digital_Outputs_8bit",
+        category = "mandatory",
+        access = "rw",
+        pdo_mapping = "optional"
+    )]
+    digital_outputs: u8,
+    #[od(
+        index = 0x6201,
+        sub_index = 1,
+        name = "Analog_Outputs_4x16bit",
+        category = "mandatory"
+    )]
+    analog_output_1: u16,
+    #[od(index = 0x6201, sub_index = 2)]
+    analog_output_2: u16,
+    #[od(index = 0x6201, sub_index = 3)]
+    analog_output_3: u16,
+    #[od(index = 0x6201, sub_index = 4)]
+    analog_output_4: u16,
+}
+
 /// Creates the Object Dictionary for the I/O module CN.
 fn get_cn_od(node_id: u8) -> ObjectDictionary<'static> {
     let mut od = ObjectDictionary::new(None);
@@ -48,67 +103,7 @@ fn get_cn_od(node_id: u8) -> ObjectDictionary<'static> {
     add_mandatory_cn_objects(&mut od, node_id);
 
     // --- Application Data Objects ---
-    // Inputs (data source on CN)
-    od.insert(
-        IDX_DIGITAL_INPUTS,
-        ObjectEntry {
-            object: Object::Variable(ObjectValue::Unsigned8(0)),
-            name: "Digital_Inputs_8bit",
-            category: Category::Mandatory,
-            access: Some(AccessType::ReadWrite), // Application writes, network reads
-            default_value: Some(ObjectValue::Unsigned8(0)),
-            value_range: None,
-            pdo_mapping: Some(PdoMapping::Optional),
-        },
-    );
-    od.insert(
-        IDX_ANALOG_INPUTS,
-        ObjectEntry {
-            object: Object::Array(vec![
-                ObjectValue::Unsigned16(0), // Sub-index 1
-                ObjectValue::Unsigned16(0), // Sub-index 2
-                ObjectValue::Unsigned16(0), // Sub-index 3
-                ObjectValue::Unsigned16(0), // Sub-index 4
-            ]),
-            name: "Analog_Inputs_4x16bit",
-            category: Category::Mandatory,
-            access: None,
-            default_value: None,
-            value_range: None,
-            pdo_mapping: None,
-        },
-    );
-
-    // Outputs (data sink on CN)
-    od.insert(
-        IDX_DIGITAL_OUTPUTS,
-        ObjectEntry {
-            object: Object::Variable(ObjectValue::Unsigned8(0)),
-            name: "Digital_Outputs_8bit",
-            category: Category::Mandatory,
-            access: Some(AccessType::ReadWrite), // Network writes, application reads
-            default_value: Some(ObjectValue::Unsigned8(0)),
-            value_range: None,
-            pdo_mapping: Some(PdoMapping::Optional),
-        },
-    );
-    od.insert(
-        IDX_ANALOG_OUTPUTS,
-        ObjectEntry {
-            object: Object::Array(vec![
-                ObjectValue::Unsigned16(0),
-                ObjectValue::Unsigned16(0),
-                ObjectValue::Unsigned16(0),
-                ObjectValue::Unsigned16(0),
-            ]),
-            name: "Analog_Outputs_4x16bit",
-            category: Category::Mandatory,
-            access: None,
-            default_value: None,
-            value_range: None,
-            pdo_mapping: None,
-        },
-    );
+    IoModule::default().register(&mut od);
 
     // --- PDO Configuration ---
 
@@ -382,6 +377,7 @@ fn run_cn_logic(interface_name: &str) {
     let mut udp_buffer = [0u8; 1500];
     let start_time = Instant::now();
     let mut digital_input_counter: u8 = 0;
+    let mut next_link_check_us = 0;
 
     loop {
         let current_time_us = start_time.elapsed().as_micros() as u64;
@@ -389,18 +385,10 @@ fn run_cn_logic(interface_name: &str) {
         // --- Application Logic: Simulate Hardware I/O ---
         // 1. Read simulated hardware inputs and write to OD
         digital_input_counter = digital_input_counter.wrapping_add(1);
-        node.context
-            .core
-            .od
-            .write(
-                IDX_DIGITAL_INPUTS,
-                0,
-                ObjectValue::Unsigned8(digital_input_counter),
-            )
-            .unwrap();
+        IoModule::write_digital_inputs(&mut node.context.core.od, digital_input_counter).unwrap();
 
         // 2. Read outputs from OD (written by MN) and "write" to simulated hardware
-        if let Some(do_val) = node.context.core.od.read_u8(IDX_DIGITAL_OUTPUTS, 0) {
+        if let Some(do_val) = IoModule::read_digital_outputs(&node.context.core.od) {
             if do_val != 0 {
                 info!("[CN] Digital outputs received from MN: {:#04x}", do_val);
             }
@@ -444,6 +432,14 @@ fn run_cn_logic(interface_name: &str) {
             }
         }
 
+        // 5. Poll the link state so loss of link and dropped frames are reported
+        if current_time_us >= next_link_check_us {
+            next_link_check_us = current_time_us + LINK_CHECK_INTERVAL_US;
+            if let Some(status) = interface.link_status() {
+                node.report_link_status(status, current_time_us);
+            }
+        }
+
         // Small sleep to prevent busy-looping if receive_frame returns immediately
         thread::sleep(Duration::from_micros(100));
     }
@@ -458,6 +454,7 @@ fn run_mn_logic(interface_name: &str, cn_mac: MacAddress) {
 
     let start_time = Instant::now();
     let mut last_log_time = Instant::now();
+    let mut next_link_check_us = 0;
     let mut eth_buffer = [0u8; 1518];
     // The `sdo-udp` feature is enabled for this example
     let mut udp_buffer = [0u8; 1500];
@@ -511,6 +508,14 @@ fn run_mn_logic(interface_name: &str, cn_mac: MacAddress) {
             }
         }
 
+        // 5. Poll the link state so loss of link and dropped frames are reported
+        if current_time_us >= next_link_check_us {
+            next_link_check_us = current_time_us + LINK_CHECK_INTERVAL_US;
+            if let Some(status) = interface.link_status() {
+                node.report_link_status(status, current_time_us);
+            }
+        }
+
         // --- Application Logic: Mirror Inputs to Outputs ---
         if node.nmt_state() == NmtState::NmtOperational {
             if let Some(di_val) = node.context.core.od.read_u8(IDX_DIGITAL_INPUTS, 0) {
@@ -927,7 +932,10 @@ fn setup_cn_node<'a>(
     };
     interface.set_read_timeout(Duration::from_millis(10))?; // Set a default timeout
     let mac = interface.local_mac_address();
-    let node = ControlledNode::new(od, mac.into())?;
+    let mut node = ControlledNode::new(od, mac.into())?;
+    // The I/O module needs no further configuration by the application
+    // before it may enter ReadyToOperate (NMT_CT6).
+    node.confirm_configuration_complete();
     Ok((interface, node))
 }
 
@@ -1,14 +1,21 @@
 // crates/powerlink-rs-linux/src/lib.rs
 #![cfg(target_os = "linux")]
 
+mod link;
+mod storage;
+
+pub use storage::FileStorage;
+
+use link::LinkMonitor;
 use pnet::datalink::{self, Channel, NetworkInterface as PnetInterface};
 use powerlink_rs::{
-    NetworkInterface,
+    LinkStatus, NetworkInterface,
     hal::PowerlinkError,
     types::{C_SDO_EPL_PORT, IpAddress},
 };
 use std::io;
 use std::net::{SocketAddr, UdpSocket};
+use std::os::fd::RawFd;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -31,6 +38,8 @@ pub struct LinuxPnetInterface {
     // UDP Handling (added)
     udp_socket: Arc<UdpSocket>, // Arc allows sharing the socket if needed later
     local_ip_address: IpAddress,
+    // Link state and driver drops from sysfs
+    link_monitor: LinkMonitor,
     // PCAP file writer (conditionally compiled)
     #[cfg(feature = "pcap")]
     pcap_writer: Option<Mutex<Savefile>>,
@@ -47,16 +56,10 @@ impl LinuxPnetInterface {
         let mac_address = interface.mac.ok_or("Interface has no MAC address")?.into();
 
         // --- Raw Ethernet Setup ---
-        let config_raw = datalink::Config {
-            read_timeout: Some(Duration::from_millis(1)), // Use a small timeout for potentially non-blocking receive
-            promiscuous: true,
-            ..Default::default()
-        };
-        let (tx_raw, rx_raw) = match datalink::channel(&interface, config_raw) {
-            Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
-            Ok(_) => return Err("Unsupported raw channel type".to_string()),
-            Err(e) => return Err(format!("Raw channel error: {}", e)),
-        };
+        // Use a small timeout for potentially non-blocking receive
+        let (tx_raw, rx_raw, packet_socket) =
+            open_raw_channel(&interface, Duration::from_millis(1))
+                .map_err(|e| format!("Raw channel error: {}", e))?;
 
         // --- UDP Socket Setup ---
         let local_ip = interface
@@ -79,6 +82,9 @@ impl LinuxPnetInterface {
             .set_nonblocking(true)
             .map_err(|e| format!("Failed to set UDP socket non-blocking: {}", e))?;
 
+        let mut link_monitor = LinkMonitor::new(interface_name);
+        link_monitor.set_packet_socket(packet_socket);
+
         Ok(Self {
             tx_raw: Mutex::new(tx_raw),
             rx_raw: Mutex::new(rx_raw),
@@ -87,6 +93,7 @@ impl LinuxPnetInterface {
             mac_address,
             udp_socket: Arc::new(udp_socket),
             local_ip_address,
+            link_monitor,
             // Conditionally compile the pcap_writer field
             #[cfg(feature = "pcap")]
             pcap_writer: None,
@@ -128,19 +135,13 @@ impl LinuxPnetInterface {
     /// Sets the read timeout for the underlying *raw Ethernet* channel.
     /// Re-creates the raw channel.
     pub fn set_read_timeout(&mut self, duration: Duration) -> Result<(), PowerlinkError> {
-        let config = datalink::Config {
-            read_timeout: Some(duration),
-            promiscuous: true,
-            ..Default::default()
-        };
-
-        match datalink::channel(&self.pnet_iface, config) {
-            Ok(Channel::Ethernet(tx, rx)) => {
+        match open_raw_channel(&self.pnet_iface, duration) {
+            Ok((tx, rx, packet_socket)) => {
                 *self.tx_raw.lock().unwrap() = tx;
                 *self.rx_raw.lock().unwrap() = rx;
+                self.link_monitor.set_packet_socket(packet_socket);
                 Ok(())
             }
-            Ok(_) => Err(PowerlinkError::IoError),
             Err(e) => {
                 eprintln!("Failed to set read timeout for raw socket: {}", e);
                 Err(PowerlinkError::IoError)
@@ -149,6 +150,32 @@ impl LinuxPnetInterface {
     }
 }
 
+/// Sender and receiver of the raw Ethernet channel, and its packet socket.
+type RawChannel = (
+    Box<dyn datalink::DataLinkSender>,
+    Box<dyn datalink::DataLinkReceiver>,
+    RawFd,
+);
+
+/// Opens the raw Ethernet channel on a packet socket opened by the HAL, so
+/// the socket can be queried for dropped frames while pnet owns it.
+fn open_raw_channel(interface: &PnetInterface, read_timeout: Duration) -> io::Result<RawChannel> {
+    let packet_socket = link::open_packet_socket()?;
+    let config = datalink::Config {
+        read_timeout: Some(read_timeout),
+        promiscuous: true,
+        socket_fd: Some(packet_socket),
+        ..Default::default()
+    };
+    match datalink::channel(interface, config)? {
+        Channel::Ethernet(tx, rx) => Ok((tx, rx, packet_socket)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Unsupported raw channel type",
+        )),
+    }
+}
+
 impl NetworkInterface for LinuxPnetInterface {
     // --- Raw Ethernet Methods ---
     fn send_frame(&mut self, frame: &[u8]) -> Result<(), PowerlinkError> {
@@ -214,6 +241,10 @@ impl NetworkInterface for LinuxPnetInterface {
         self.mac_address
     }
 
+    fn link_status(&mut self) -> Option<LinkStatus> {
+        self.link_monitor.query()
+    }
+
     // --- UDP Methods ---
     fn send_udp(
         &mut self,
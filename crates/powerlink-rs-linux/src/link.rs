@@ -0,0 +1,228 @@
+// crates/powerlink-rs-linux/src/link.rs
+//! Link state and driver drop statistics read from sysfs.
+//!
+//! The kernel exposes the carrier state kept by the driver (the same
+//! `IFLA_CARRIER` reported over rtnetlink) and the ethtool link settings under
+//! `/sys/class/net/<interface>/`. Frames lost by the MAC or driver show up in
+//! the `statistics/` counters. Frames the kernel could not queue on the raw
+//! channel's packet socket are read with `PACKET_STATISTICS`; the HAL opens
+//! that socket itself and hands it to pnet so the descriptor is known.
+
+use log::warn;
+use powerlink_rs::LinkStatus;
+use std::fs;
+use std::io;
+use std::mem;
+use std::os::fd::RawFd;
+use std::path::{Path, PathBuf};
+
+const SYSFS_NET: &str = "/sys/class/net";
+
+/// Statistics counting frames lost to MAC or driver buffer overruns and underruns.
+/// `rx_dropped` is left out: it also counts frames of protocols nobody
+/// listens to, which are not losses of the POWERLINK stack.
+const DROP_STATISTICS: [&str; 4] = [
+    "rx_fifo_errors",
+    "rx_over_errors",
+    "rx_missed_errors",
+    "tx_fifo_errors",
+];
+
+/// Reads the link state of one network interface.
+pub(crate) struct LinkMonitor {
+    dir: PathBuf,
+    /// Sum of the drop statistics at the previous query.
+    dropped_total: Option<u64>,
+    /// Packet socket of the raw channel, if the HAL opened it.
+    packet_socket: Option<RawFd>,
+}
+
+impl LinkMonitor {
+    pub(crate) fn new(interface_name: &str) -> Self {
+        Self::with_root(Path::new(SYSFS_NET), interface_name)
+    }
+
+    fn with_root(root: &Path, interface_name: &str) -> Self {
+        Self {
+            dir: root.join(interface_name),
+            dropped_total: None,
+            packet_socket: None,
+        }
+    }
+
+    /// Sets the packet socket whose drops are reported. Called again whenever
+    /// the raw channel is re-created.
+    pub(crate) fn set_packet_socket(&mut self, socket: RawFd) {
+        self.packet_socket = Some(socket);
+    }
+
+    /// Returns the current link state, or `None` if the interface has no
+    /// sysfs entry. Dropped frames are counted from the previous query; the
+    /// first query only records the starting point of the sysfs counters,
+    /// while the socket reports its drops since it was opened.
+    pub(crate) fn query(&mut self) -> Option<LinkStatus> {
+        if !self.dir.is_dir() {
+            warn!("No sysfs entry for interface at {}", self.dir.display());
+            return None;
+        }
+        // Reading the carrier fails while the interface is administratively down.
+        let link_up = self.read("carrier").is_some_and(|carrier| carrier == "1");
+        // Speed and duplex are unknown for virtual interfaces; only flag known bad modes.
+        let speed = self.read("speed").and_then(|s| s.parse::<i64>().ok());
+        let duplex = self.read("duplex");
+        let bad_physical_mode = link_up
+            && (speed.is_some_and(|s| s > 0 && s != 100) || duplex.as_deref() == Some("full"));
+
+        let total = DROP_STATISTICS
+            .iter()
+            .filter_map(|name| self.read(&format!("statistics/{}", name)))
+            .filter_map(|value| value.parse::<u64>().ok())
+            .sum::<u64>();
+        let socket_dropped = self.packet_socket.and_then(packet_socket_drops);
+        let dropped = self
+            .dropped_total
+            .map_or(0, |previous| total.saturating_sub(previous))
+            + u64::from(socket_dropped.unwrap_or(0));
+        self.dropped_total = Some(total);
+
+        Some(LinkStatus {
+            link_up,
+            bad_physical_mode,
+            dropped_frames: dropped.min(u32::MAX as u64) as u32,
+        })
+    }
+
+    fn read(&self, attribute: &str) -> Option<String> {
+        fs::read_to_string(self.dir.join(attribute))
+            .ok()
+            .map(|value| value.trim().to_string())
+    }
+}
+
+/// Opens a raw `AF_PACKET` socket receiving all protocols, for the pnet channel
+/// to bind. pnet takes ownership and closes it together with the channel.
+pub(crate) fn open_packet_socket() -> io::Result<RawFd> {
+    let protocol = (libc::ETH_P_ALL as u16).to_be() as libc::c_int;
+    // SAFETY: socket(2) takes no pointers; the result is checked below.
+    let socket = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, protocol) };
+    if socket == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+/// Returns the frames the packet socket dropped since the previous call.
+/// The kernel resets the statistics each time they are read.
+fn packet_socket_drops(socket: RawFd) -> Option<u32> {
+    let mut stats = libc::tpacket_stats {
+        tp_packets: 0,
+        tp_drops: 0,
+    };
+    let mut len = mem::size_of::<libc::tpacket_stats>() as libc::socklen_t;
+    // SAFETY: `stats` and `len` describe a writable tpacket_stats buffer.
+    let result = unsafe {
+        libc::getsockopt(
+            socket,
+            libc::SOL_PACKET,
+            libc::PACKET_STATISTICS,
+            (&mut stats as *mut libc::tpacket_stats).cast(),
+            &mut len,
+        )
+    };
+    if result == -1 {
+        warn!(
+            "Failed to read PACKET_STATISTICS: {}",
+            io::Error::last_os_error()
+        );
+        return None;
+    }
+    Some(stats.tp_drops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a fake sysfs tree for one test and returns its root.
+    fn fake_sysfs(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("powerlink-rs-link-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("eth0/statistics")).unwrap();
+        root
+    }
+
+    fn set(root: &Path, attribute: &str, value: &str) {
+        fs::write(root.join("eth0").join(attribute), format!("{}\n", value)).unwrap();
+    }
+
+    #[test]
+    fn test_missing_interface() {
+        let root = fake_sysfs("missing");
+        assert_eq!(LinkMonitor::with_root(&root, "eth1").query(), None);
+    }
+
+    #[test]
+    fn test_carrier_and_physical_mode() {
+        let root = fake_sysfs("carrier");
+        let mut monitor = LinkMonitor::with_root(&root, "eth0");
+        // No carrier attribute: the interface is down.
+        assert!(!monitor.query().unwrap().link_up);
+
+        set(&root, "carrier", "1");
+        set(&root, "speed", "100");
+        set(&root, "duplex", "half");
+        let status = monitor.query().unwrap();
+        assert!(status.link_up);
+        assert!(!status.bad_physical_mode);
+
+        set(&root, "speed", "1000");
+        assert!(monitor.query().unwrap().bad_physical_mode);
+        set(&root, "speed", "-1");
+        set(&root, "duplex", "full");
+        assert!(monitor.query().unwrap().bad_physical_mode);
+
+        set(&root, "carrier", "0");
+        let status = monitor.query().unwrap();
+        assert!(!status.link_up);
+        assert!(!status.bad_physical_mode);
+    }
+
+    #[test]
+    fn test_dropped_frames_since_last_query() {
+        let root = fake_sysfs("dropped");
+        let mut monitor = LinkMonitor::with_root(&root, "eth0");
+        set(&root, "statistics/rx_dropped", "10");
+        set(&root, "statistics/rx_fifo_errors", "2");
+        assert_eq!(monitor.query().unwrap().dropped_frames, 0);
+
+        // Frames dropped for unhandled protocols are not counted.
+        set(&root, "statistics/rx_dropped", "13");
+        set(&root, "statistics/rx_missed_errors", "3");
+        set(&root, "statistics/tx_fifo_errors", "1");
+        assert_eq!(monitor.query().unwrap().dropped_frames, 4);
+        assert_eq!(monitor.query().unwrap().dropped_frames, 0);
+    }
+
+    #[test]
+    fn test_packet_socket_drops() {
+        // Opening a packet socket needs CAP_NET_RAW.
+        let Ok(socket) = open_packet_socket() else {
+            return;
+        };
+        let root = fake_sysfs("socket");
+        let mut monitor = LinkMonitor::with_root(&root, "eth0");
+        monitor.set_packet_socket(socket);
+        assert!(packet_socket_drops(socket).is_some());
+        assert!(monitor.query().is_some());
+        // SAFETY: the socket is not used after this point.
+        unsafe { libc::close(socket) };
+    }
+
+    #[test]
+    fn test_packet_statistics_need_a_packet_socket() {
+        use std::os::fd::AsRawFd;
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert_eq!(packet_socket_drops(socket.as_raw_fd()), None);
+    }
+}
@@ -0,0 +1,395 @@
+// crates/powerlink-rs-linux/src/storage.rs
+//! File-backed implementation of `ObjectDictionaryStorage`.
+//!
+//! All stored parameters and the "Restore Defaults" flag live in a single file:
+//!
+//! ```text
+//! magic "PLOD" | format u8 | flags u8 | count u32
+//! count * (index u16 | sub-index u8 | data type u16 | length u16 | data)
+//! CRC-32 u32 over everything before it
+//! ```
+//!
+//! All integers are little-endian. Data types use the POWERLINK data type
+//! indices (e.g. 0x0007 for UNSIGNED32). The file is replaced atomically by
+//! writing a temporary file, syncing it and renaming it over the old one.
+
+use log::{info, warn};
+use powerlink_rs::{
+    NetTime, ObjectDictionaryStorage, PowerlinkError,
+    common::{TimeDifference, TimeOfDay},
+    frame::MacAddress,
+    od::ObjectValue,
+    types::BitString,
+};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"PLOD";
+const FORMAT_VERSION: u8 = 1;
+const FLAG_RESTORE_DEFAULTS: u8 = 1 << 0;
+const HEADER_LEN: usize = 10;
+const CRC_LEN: usize = 4;
+
+type Parameters = BTreeMap<(u16, u8), ObjectValue>;
+
+/// Stores Object Dictionary parameters in a single file on the local filesystem.
+///
+/// A missing file is treated as empty storage. A corrupt file (bad magic,
+/// unknown format or CRC mismatch) is ignored with a warning, so the node
+/// boots with its default values; the next save replaces it.
+#[derive(Debug)]
+pub struct FileStorage {
+    path: PathBuf,
+    restore_requested: bool,
+}
+
+impl FileStorage {
+    /// Opens the storage file at `path`, reading the persistent
+    /// "Restore Defaults" flag if the file exists and is valid.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let restore_requested = read_file(&path)
+            .map(|(flags, _)| flags & FLAG_RESTORE_DEFAULTS != 0)
+            .unwrap_or(false);
+        Self {
+            path,
+            restore_requested,
+        }
+    }
+
+    /// Returns the path of the storage file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn flags(&self) -> u8 {
+        if self.restore_requested {
+            FLAG_RESTORE_DEFAULTS
+        } else {
+            0
+        }
+    }
+
+    fn stored_parameters(&self) -> Parameters {
+        read_file(&self.path)
+            .map(|(_, params)| params)
+            .unwrap_or_default()
+    }
+
+    /// Writes the file to a temporary path, syncs it and renames it into place.
+    fn write_atomic(&self, flags: u8, params: &Parameters) -> Result<(), PowerlinkError> {
+        let data = encode(flags, params)?;
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let write = || -> std::io::Result<()> {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&tmp_path)?;
+            file.write_all(&data)?;
+            file.sync_all()?;
+            fs::rename(&tmp_path, &self.path)?;
+            // Make the rename itself durable.
+            if let Some(dir) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                File::open(dir)?.sync_all()?;
+            }
+            Ok(())
+        };
+        write().map_err(|e| {
+            warn!("Failed to write storage file {:?}: {}", self.path, e);
+            let _ = fs::remove_file(&tmp_path);
+            PowerlinkError::StorageError("Failed to write storage file")
+        })
+    }
+}
+
+impl ObjectDictionaryStorage for FileStorage {
+    fn load(&mut self) -> Result<Parameters, PowerlinkError> {
+        Ok(self.stored_parameters())
+    }
+
+    fn save(&mut self, parameters: &Parameters) -> Result<(), PowerlinkError> {
+        self.write_atomic(self.flags(), parameters)
+    }
+
+    fn clear(&mut self) -> Result<(), PowerlinkError> {
+        self.write_atomic(self.flags(), &Parameters::new())
+    }
+
+    fn restore_defaults_requested(&self) -> bool {
+        self.restore_requested
+    }
+
+    fn request_restore_defaults(&mut self) -> Result<(), PowerlinkError> {
+        let params = self.stored_parameters();
+        self.write_atomic(self.flags() | FLAG_RESTORE_DEFAULTS, &params)?;
+        self.restore_requested = true;
+        Ok(())
+    }
+
+    fn clear_restore_defaults_flag(&mut self) -> Result<(), PowerlinkError> {
+        let params = self.stored_parameters();
+        self.write_atomic(self.flags() & !FLAG_RESTORE_DEFAULTS, &params)?;
+        self.restore_requested = false;
+        Ok(())
+    }
+}
+
+/// Reads and validates the storage file. Returns `None` if the file does not
+/// exist or is corrupt.
+fn read_file(path: &Path) -> Option<(u8, Parameters)> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Failed to read storage file {:?}: {}", path, e);
+            return None;
+        }
+    };
+    let decoded = decode(&data);
+    if decoded.is_none() {
+        warn!(
+            "Storage file {:?} is corrupt. Falling back to default values.",
+            path
+        );
+    } else {
+        info!("Read storage file {:?}.", path);
+    }
+    decoded
+}
+
+fn encode(flags: u8, params: &Parameters) -> Result<Vec<u8>, PowerlinkError> {
+    let mut data = Vec::with_capacity(HEADER_LEN + CRC_LEN + params.len() * 12);
+    data.extend_from_slice(MAGIC);
+    data.push(FORMAT_VERSION);
+    data.push(flags);
+    data.extend_from_slice(&(params.len() as u32).to_le_bytes());
+    for (&(index, sub_index), value) in params {
+        let bytes = value.serialize();
+        let len = u16::try_from(bytes.len())
+            .map_err(|_| PowerlinkError::StorageError("Stored parameter is too large"))?;
+        data.extend_from_slice(&index.to_le_bytes());
+        data.push(sub_index);
+        data.extend_from_slice(&data_type(value).to_le_bytes());
+        data.extend_from_slice(&len.to_le_bytes());
+        data.extend_from_slice(&bytes);
+    }
+    let crc = crc32(&data);
+    data.extend_from_slice(&crc.to_le_bytes());
+    Ok(data)
+}
+
+fn decode(data: &[u8]) -> Option<(u8, Parameters)> {
+    if data.len() < HEADER_LEN + CRC_LEN {
+        return None;
+    }
+    let (body, crc) = data.split_at(data.len() - CRC_LEN);
+    if crc32(body) != u32::from_le_bytes(crc.try_into().ok()?) {
+        return None;
+    }
+    if &body[0..4] != MAGIC || body[4] != FORMAT_VERSION {
+        return None;
+    }
+    let flags = body[5];
+    let count = u32::from_le_bytes(body[6..10].try_into().ok()?);
+
+    let mut params = Parameters::new();
+    let mut rest = &body[HEADER_LEN..];
+    for _ in 0..count {
+        if rest.len() < 7 {
+            return None;
+        }
+        let index = u16::from_le_bytes([rest[0], rest[1]]);
+        let sub_index = rest[2];
+        let template = type_template(u16::from_le_bytes([rest[3], rest[4]]))?;
+        let len = u16::from_le_bytes([rest[5], rest[6]]) as usize;
+        let value_bytes = rest.get(7..7 + len)?;
+        let value = ObjectValue::deserialize(value_bytes, &template).ok()?;
+        params.insert((index, sub_index), value);
+        rest = &rest[7 + len..];
+    }
+    rest.is_empty().then_some((flags, params))
+}
+
+/// BIT strings have no POWERLINK data type index. They are stored with this
+/// code in the high byte and the number of bits in the low byte.
+const BIT_STRING_TYPE: u16 = 0xFF00;
+
+/// Returns the POWERLINK data type index of a value.
+fn data_type(value: &ObjectValue) -> u16 {
+    match value {
+        ObjectValue::Boolean(_) => 0x0001,
+        ObjectValue::Integer8(_) => 0x0002,
+        ObjectValue::Integer16(_) => 0x0003,
+        ObjectValue::Integer32(_) => 0x0004,
+        ObjectValue::Unsigned8(_) => 0x0005,
+        ObjectValue::Unsigned16(_) => 0x0006,
+        ObjectValue::Unsigned32(_) => 0x0007,
+        ObjectValue::Real32(_) => 0x0008,
+        ObjectValue::VisibleString(_) => 0x0009,
+        ObjectValue::OctetString(_) => 0x000A,
+        ObjectValue::UnicodeString(_) => 0x000B,
+        ObjectValue::TimeOfDay(_) => 0x000C,
+        ObjectValue::TimeDifference(_) => 0x000D,
+        ObjectValue::Domain(_) => 0x000F,
+        ObjectValue::Integer24(_) => 0x0010,
+        ObjectValue::Real64(_) => 0x0011,
+        ObjectValue::Integer40(_) => 0x0012,
+        ObjectValue::Integer48(_) => 0x0013,
+        ObjectValue::Integer56(_) => 0x0014,
+        ObjectValue::Integer64(_) => 0x0015,
+        ObjectValue::Unsigned24(_) => 0x0016,
+        ObjectValue::Unsigned40(_) => 0x0018,
+        ObjectValue::Unsigned48(_) => 0x0019,
+        ObjectValue::Unsigned56(_) => 0x001A,
+        ObjectValue::Unsigned64(_) => 0x001B,
+        ObjectValue::MacAddress(_) => 0x0401,
+        ObjectValue::IpAddress(_) => 0x0402,
+        ObjectValue::NetTime(_) => 0x0403,
+        ObjectValue::BitString(v) => BIT_STRING_TYPE | v.len as u16,
+    }
+}
+
+/// Returns a value of the given data type, used as a template for deserializing.
+fn type_template(data_type: u16) -> Option<ObjectValue> {
+    Some(match data_type {
+        0x0001 => ObjectValue::Boolean(0),
+        0x0002 => ObjectValue::Integer8(0),
+        0x0003 => ObjectValue::Integer16(0),
+        0x0004 => ObjectValue::Integer32(0),
+        0x0005 => ObjectValue::Unsigned8(0),
+        0x0006 => ObjectValue::Unsigned16(0),
+        0x0007 => ObjectValue::Unsigned32(0),
+        0x0008 => ObjectValue::Real32(0.0),
+        0x0009 => ObjectValue::VisibleString(String::new()),
+        0x000A => ObjectValue::OctetString(Vec::new()),
+        0x000B => ObjectValue::UnicodeString(Vec::new()),
+        0x000C => ObjectValue::TimeOfDay(TimeOfDay { ms: 0, days: 0 }),
+        0x000D => ObjectValue::TimeDifference(TimeDifference { ms: 0, days: 0 }),
+        0x000F => ObjectValue::Domain(Vec::new()),
+        0x0010 => ObjectValue::Integer24(0),
+        0x0011 => ObjectValue::Real64(0.0),
+        0x0012 => ObjectValue::Integer40(0),
+        0x0013 => ObjectValue::Integer48(0),
+        0x0014 => ObjectValue::Integer56(0),
+        0x0015 => ObjectValue::Integer64(0),
+        0x0016 => ObjectValue::Unsigned24(0),
+        0x0018 => ObjectValue::Unsigned40(0),
+        0x0019 => ObjectValue::Unsigned48(0),
+        0x001A => ObjectValue::Unsigned56(0),
+        0x001B => ObjectValue::Unsigned64(0),
+        0x0401 => ObjectValue::MacAddress(MacAddress::default()),
+        0x0402 => ObjectValue::IpAddress([0; 4]),
+        0x0403 => ObjectValue::NetTime(NetTime {
+            seconds: 0,
+            nanoseconds: 0,
+        }),
+        t if t & 0xFF00 == BIT_STRING_TYPE && (1..=64).contains(&(t & 0xFF)) => {
+            ObjectValue::BitString(BitString::new(t as u8, 0))
+        }
+        _ => return None,
+    })
+}
+
+/// CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320).
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fresh path in the system temp directory for one test.
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "powerlink-rs-storage-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("od.bin")
+    }
+
+    fn sample_parameters() -> Parameters {
+        let mut params = Parameters::new();
+        params.insert((0x1006, 0), ObjectValue::Unsigned32(400));
+        params.insert((0x1F8A, 1), ObjectValue::Integer24(-5));
+        params.insert((0x2000, 0), ObjectValue::VisibleString("io".into()));
+        params.insert((0x2001, 2), ObjectValue::Real64(1.5));
+        params.insert(
+            (0x2002, 0),
+            ObjectValue::BitString(BitString::new(12, 0xABC)),
+        );
+        params
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path("round-trip");
+        let mut storage = FileStorage::new(&path);
+        assert!(storage.load().unwrap().is_empty());
+
+        storage.save(&sample_parameters()).unwrap();
+        assert!(!path.with_extension("bin.tmp").exists());
+
+        let mut reopened = FileStorage::new(&path);
+        assert_eq!(reopened.load().unwrap(), sample_parameters());
+        reopened.clear().unwrap();
+        assert!(reopened.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_flag_persists() {
+        let path = temp_path("restore-flag");
+        let mut storage = FileStorage::new(&path);
+        storage.save(&sample_parameters()).unwrap();
+        storage.request_restore_defaults().unwrap();
+
+        let mut reopened = FileStorage::new(&path);
+        assert!(reopened.restore_defaults_requested());
+        assert_eq!(reopened.load().unwrap(), sample_parameters());
+
+        reopened.clear().unwrap();
+        reopened.clear_restore_defaults_flag().unwrap();
+        assert!(!FileStorage::new(&path).restore_defaults_requested());
+    }
+
+    #[test]
+    fn test_corrupt_file_falls_back_to_defaults() {
+        let path = temp_path("corrupt");
+        let mut storage = FileStorage::new(&path);
+        storage.save(&sample_parameters()).unwrap();
+
+        let mut data = fs::read(&path).unwrap();
+        data[HEADER_LEN + 3] ^= 0xFF;
+        fs::write(&path, &data).unwrap();
+
+        let mut reopened = FileStorage::new(&path);
+        assert!(!reopened.restore_defaults_requested());
+        assert!(reopened.load().unwrap().is_empty());
+
+        // The next save replaces the corrupt file.
+        reopened.save(&sample_parameters()).unwrap();
+        assert_eq!(reopened.load().unwrap(), sample_parameters());
+    }
+}
@@ -293,13 +293,15 @@ fn setup_node<'a>(
         }
     };
     let mac = interface.local_mac_address();
-    let node = match ControlledNode::new(od, mac.into()) {
+    let mut node = match ControlledNode::new(od, mac.into()) {
         Ok(n) => n,
         Err(e) => {
             error!("Failed to create ControlledNode: {:?}", e);
             panic!("Node creation failed.");
         }
     };
+    // The test application has nothing to configure (NMT_CT6).
+    node.confirm_configuration_complete();
     (interface, node)
 }
 
@@ -13,8 +13,12 @@ use serde::Serialize;
 // --- Imports from the powerlink-rs core crate ---
 use powerlink_rs::{
     frame::error::MnErrorCounters as CoreMnErrorCounters,
-    node::{CnState, mn::MnContext},
+    node::{
+        CnState,
+        mn::{CnErrorRecord, MnContext},
+    },
     od::ObjectDictionary,
+    types::NodeId,
 };
 // -------------------------------------------------
 
@@ -25,6 +29,10 @@ pub struct CnInfo {
     pub node_id: u8,
     pub nmt_state: String,
     pub communication_ok: bool,
+    /// Serial number from the CN's last accepted IdentResponse.
+    pub serial_number: Option<u32>,
+    /// True if the serial number differs from NMT_MNSerialNoList_AU32 (0x1F88).
+    pub serial_number_mismatch: bool,
 }
 
 impl CnInfo {
@@ -42,6 +50,49 @@ impl CnInfo {
     }
 }
 
+/// A serializable snapshot of an error entry reported by a Controlled Node
+/// and recorded in the MN's error log.
+#[derive(Serialize, Clone, Debug)]
+pub struct CnErrorInfo {
+    pub node_id: u8,
+    pub error_code: u16,
+    pub mode: String,
+    pub profile: u16,
+    pub additional_information: u64,
+    /// Timestamp reported by the CN, as `seconds.nanoseconds`.
+    pub timestamp: String,
+    /// MN time (microseconds) at which the entry was received.
+    pub received_time_us: u64,
+    pub acknowledged: bool,
+}
+
+impl CnErrorInfo {
+    fn from_record(node_id: NodeId, record: &CnErrorRecord) -> Self {
+        let entry = &record.entry;
+        Self {
+            node_id: node_id.0,
+            error_code: entry.error_code,
+            mode: format!("{:?}", entry.entry_type.mode),
+            profile: entry.entry_type.profile,
+            additional_information: entry.additional_information,
+            timestamp: format!(
+                "{}.{:09}",
+                entry.timestamp.seconds, entry.timestamp.nanoseconds
+            ),
+            received_time_us: record.received_time_us,
+            acknowledged: record.acknowledged,
+        }
+    }
+}
+
+/// A serializable snapshot of an entry of the node's event queue.
+#[derive(Serialize, Clone, Debug)]
+pub struct NodeEventInfo {
+    /// Time (microseconds) at which the event was recorded.
+    pub time_us: u64,
+    pub event: String,
+}
+
 /// A serializable DTO for the node's diagnostic counters,
 /// primarily from OD 0x1101 and 0x1102.
 #[derive(Serialize, Clone, Debug, Default)]
@@ -106,6 +157,10 @@ pub struct DiagnosticSnapshot {
     pub dll_error_counters: MnDllErrorCounters,
     /// A structured representation of the node's diagnostic counters (OD 0x1101/0x1102).
     pub diagnostic_counters: DiagnosticCounters,
+    /// Error entries reported by the Controlled Nodes, oldest first per node.
+    pub cn_errors: Vec<CnErrorInfo>,
+    /// The most recent node events, oldest first.
+    pub events: Vec<NodeEventInfo>,
 }
 
 impl DiagnosticSnapshot {
@@ -122,15 +177,40 @@ impl DiagnosticSnapshot {
                 node_id: id.0,
                 nmt_state: CnInfo::state_to_string(info.state),
                 communication_ok: info.communication_ok,
+                serial_number: info.identity.map(|identity| identity.serial_no),
+                serial_number_mismatch: info.serial_no_mismatch,
+            })
+            .collect();
+
+        // 2. Flatten the per-CN error logs
+        let cn_errors = context
+            .cn_error_logs
+            .iter()
+            .flat_map(|(id, log)| {
+                log.records()
+                    .map(move |record| CnErrorInfo::from_record(*id, record))
+            })
+            .collect();
+
+        // 3. Copy the recent events without consuming them
+        let events = context
+            .core
+            .events
+            .recent()
+            .map(|e| NodeEventInfo {
+                time_us: e.time_us,
+                event: format!("{:?}", e.event),
             })
             .collect();
 
-        // 2. Build the snapshot
+        // 4. Build the snapshot
         DiagnosticSnapshot {
             mn_nmt_state: format!("{:?}", context.nmt_state_machine.current_state()),
             cn_states,
             dll_error_counters: MnDllErrorCounters::from_core(&context.dll_error_manager.counters),
             diagnostic_counters: DiagnosticCounters::from_od(&context.core.od),
+            cn_errors,
+            events,
         }
     }
 }
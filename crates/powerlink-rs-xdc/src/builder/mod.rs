@@ -14,6 +14,7 @@ use crate::error::XdcError;
 use crate::model;
 use crate::types;
 use crate::types::XdcFile;
+use crate::resolver::utils;
 use alloc::format;
 use alloc::string::String;
 use alloc::string::ToString;
@@ -229,7 +230,7 @@ fn build_comm_profile(
                     name: sub_obj.name.clone(),
                     object_type: sub_obj.object_type.clone(),
                     actual_value,
-                    data_type: sub_obj.data_type.clone(),
+                    data_type: xdc_data_type(sub_obj.data_type.as_ref()),
                     low_limit: sub_obj.low_limit.clone(),
                     high_limit: sub_obj.high_limit.clone(),
                     access_type: sub_obj.access_type.map(map_access_type_to_model),
@@ -254,7 +255,7 @@ fn build_comm_profile(
             object_type: obj.object_type.clone(),
             actual_value: object_actual_value,
             sub_object: model_sub_objects,
-            data_type: obj.data_type.clone(),
+            data_type: xdc_data_type(obj.data_type.as_ref()),
             low_limit: obj.low_limit.clone(),
             high_limit: obj.high_limit.clone(),
             access_type: obj.access_type.map(map_access_type_to_model),
@@ -305,6 +306,14 @@ fn format_hex_u8(val: u8) -> String {
     format!("{:02X}", val)
 }
 
+/// Returns the `dataType` attribute for an object. Types without a POWERLINK
+/// data type index (BITSTRING) are left to the referenced parameter.
+fn xdc_data_type(data_type: Option<&String>) -> Option<String> {
+    data_type
+        .filter(|id| id.as_str() != utils::BIT_STRING_TYPE_ID)
+        .cloned()
+}
+
 /// Helper to format a value into a string for serialization.
 /// Currently assumes input bytes are already valid UTF-8 strings.
 fn format_value_to_string(data: &[u8], _data_type_id: Option<&str>) -> Result<String, XdcError> {
@@ -450,6 +459,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_xdc_data_type_omits_bit_string() {
+        assert_eq!(
+            xdc_data_type(Some(&"0007".to_string())),
+            Some("0007".to_string())
+        );
+        assert_eq!(
+            xdc_data_type(Some(&utils::BIT_STRING_TYPE_ID.to_string())),
+            None
+        );
+        assert_eq!(xdc_data_type(None), None);
+    }
+
     #[test]
     fn test_map_access_type_to_model() {
         use crate::model::app_layers::ObjectAccessType as ModelAccess;
@@ -13,11 +13,14 @@ use crate::types::{self, XdcFile};
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
+use powerlink_rs::frame::error::DeviceErrors;
 use powerlink_rs::nmt::flags::FeatureFlags;
 use powerlink_rs::od::{
     AccessType, Category, Object, ObjectDictionary, ObjectEntry, ObjectValue, PdoMapping,
     ValueRange,
 };
+use powerlink_rs::types::BitString;
 
 /// Configuration settings for the NMT (Network Management) state machine,
 /// extracted from the XDC profile's `<NetworkManagement>` block.
@@ -119,6 +122,40 @@ pub fn extract_nmt_settings(xdc_file: &XdcFile) -> Result<NmtSettings, XdcError>
     })
 }
 
+/// Names the device-specific static error bits from the XDC
+/// `<Diagnostic><StaticErrorBitField>` definitions.
+///
+/// # Arguments
+/// * `xdc_file` - The parsed XDC file structure.
+/// * `device_errors` - The node's device errors, e.g. from `ControlledNode::device_errors`.
+///
+/// # Returns
+/// * `Result<usize, XdcError>` - The number of bits defined, or an error if a
+///   bit offset lies outside the device-specific range (16..=63).
+pub fn define_static_error_bits(
+    xdc_file: &XdcFile,
+    device_errors: &mut DeviceErrors,
+) -> Result<usize, XdcError> {
+    let bits = xdc_file
+        .network_management
+        .as_ref()
+        .and_then(|nm| nm.diagnostic.as_ref())
+        .and_then(|diag| diag.static_error_bit_field.as_ref());
+    let Some(bits) = bits else {
+        return Ok(0);
+    };
+    for bit in bits {
+        device_errors
+            .define_bit(&bit.name, bit.offset)
+            .map_err(|_| {
+                XdcError::ValidationError(
+                    "Static error bit offset outside the device-specific range 16..=63",
+                )
+            })?;
+    }
+    Ok(bits.len())
+}
+
 /// Converts a parsed `XdcFile` into the runtime `ObjectDictionary`.
 ///
 /// This function iterates through the `ObjectList` in the XDC, parsing values and attributes
@@ -320,6 +357,10 @@ fn map_data_to_value(
         None => return Ok(None),
     };
 
+    if id_str == utils::BIT_STRING_TYPE_ID {
+        return map_bit_string(value_str).map(Some);
+    }
+
     let type_name = match utils::get_standard_type_from_hex(id_str) {
         Some(t) => t,
         None => return Ok(None), // Unknown or custom type
@@ -342,7 +383,7 @@ fn map_data_to_value(
         }};
     }
 
-    match type_name {
+    let value = match type_name {
         DataTypeName::Boolean => {
             let s = value_str.trim();
             let val = match s {
@@ -388,6 +429,14 @@ fn map_data_to_value(
             }),
         DataTypeName::Integer64 => parse_num!(i64, ObjectValue::Integer64),
         DataTypeName::Unsigned64 => parse_num!(u64, ObjectValue::Unsigned64),
+        DataTypeName::Integer24 => parse_num!(i32, ObjectValue::Integer24),
+        DataTypeName::Integer40 => parse_num!(i64, ObjectValue::Integer40),
+        DataTypeName::Integer48 => parse_num!(i64, ObjectValue::Integer48),
+        DataTypeName::Integer56 => parse_num!(i64, ObjectValue::Integer56),
+        DataTypeName::Unsigned24 => parse_num!(u32, ObjectValue::Unsigned24),
+        DataTypeName::Unsigned40 => parse_num!(u64, ObjectValue::Unsigned40),
+        DataTypeName::Unsigned48 => parse_num!(u64, ObjectValue::Unsigned48),
+        DataTypeName::Unsigned56 => parse_num!(u64, ObjectValue::Unsigned56),
 
         DataTypeName::MacAddress => {
             let bytes = crate::parser::parse_hex_string(value_str)?;
@@ -410,7 +459,43 @@ fn map_data_to_value(
             Ok(Some(ObjectValue::IpAddress(arr)))
         }
         _ => Ok(None),
+    }?;
+
+    // Narrow integer types are parsed into wider Rust types; reject values
+    // that do not fit the declared bit width.
+    if let Some(v) = &value {
+        v.check_bit_width()
+            .map_err(|_| XdcError::InvalidAttributeFormat {
+                attribute: "defaultValue or actualValue (numeric)",
+            })?;
     }
+    Ok(value)
+}
+
+/// Parses the value of a BITSTRING parameter. Without a `size` attribute a
+/// BITSTRING holds a single bit.
+fn map_bit_string(value_str: &str) -> Result<ObjectValue, XdcError> {
+    let s = value_str.trim();
+    let bits = match s {
+        "true" => Ok(1),
+        "false" => Ok(0),
+        _ => match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse::<u64>(),
+        },
+    };
+    let value = bits
+        // Not `BitString::new`, which would drop the excess bits checked below.
+        .map(|bits| ObjectValue::BitString(BitString { len: 1, bits }))
+        .map_err(|_| XdcError::InvalidAttributeFormat {
+            attribute: "defaultValue or actualValue (bit string)",
+        })?;
+    value
+        .check_bit_width()
+        .map_err(|_| XdcError::InvalidAttributeFormat {
+            attribute: "defaultValue or actualValue (bit string)",
+        })?;
+    Ok(value)
 }
 
 fn map_access_type(access: types::ParameterAccess) -> AccessType {
@@ -459,6 +544,16 @@ fn resolve_value_range(
         None => return None,
     };
 
+    // Enumerated allowed values further restrict the range.
+    let values: Vec<ObjectValue> = allowed_values
+        .map(|av| {
+            av.values
+                .iter()
+                .filter_map(|v| parse_string_to_value(&v.value, dt_id))
+                .collect()
+        })
+        .unwrap_or_default();
+
     // Prioritize explicit limits if present
     if let (Some(low_str), Some(high_str)) = (low_limit_str, high_limit_str) {
         match (
@@ -469,6 +564,7 @@ fn resolve_value_range(
                 return Some(ValueRange {
                     min: min_val,
                     max: max_val,
+                    values,
                 });
             }
             _ => {}
@@ -486,6 +582,7 @@ fn resolve_value_range(
                     return Some(ValueRange {
                         min: min_val,
                         max: max_val,
+                        values,
                     });
                 }
                 _ => {}
@@ -493,7 +590,16 @@ fn resolve_value_range(
         }
     }
 
-    None
+    // Only enumerated values: the range spans the smallest to the largest.
+    let min = values
+        .iter()
+        .min_by(|a, b| a.numeric_cmp(b).unwrap_or(Ordering::Equal))?
+        .clone();
+    let max = values
+        .iter()
+        .max_by(|a, b| a.numeric_cmp(b).unwrap_or(Ordering::Equal))?
+        .clone();
+    Some(ValueRange { min, max, values })
 }
 
 #[cfg(test)]
@@ -634,6 +740,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_map_data_to_value_narrow_integers() {
+        assert_eq!(
+            map_data_to_value("0xABCDEF", Some("0016")).unwrap(),
+            Some(ObjectValue::Unsigned24(0xAB_CDEF))
+        );
+        assert_eq!(
+            map_data_to_value("-5", Some("0010")).unwrap(),
+            Some(ObjectValue::Integer24(-5))
+        );
+        assert_eq!(
+            map_data_to_value("0x123456789A", Some("0018")).unwrap(),
+            Some(ObjectValue::Unsigned40(0x12_3456_789A))
+        );
+        assert_eq!(
+            map_data_to_value("-1", Some("0014")).unwrap(),
+            Some(ObjectValue::Integer56(-1))
+        );
+        // Values that do not fit the declared width are rejected.
+        assert!(map_data_to_value("0x1000000", Some("0016")).is_err());
+        assert!(map_data_to_value("8388608", Some("0010")).is_err());
+    }
+
+    #[test]
+    fn test_map_data_to_value_bit_string() {
+        let id = Some(utils::BIT_STRING_TYPE_ID);
+        assert_eq!(
+            map_data_to_value("1", id).unwrap(),
+            Some(ObjectValue::BitString(BitString::new(1, 1)))
+        );
+        assert_eq!(
+            map_data_to_value("false", id).unwrap(),
+            Some(ObjectValue::BitString(BitString::new(1, 0)))
+        );
+        // A BITSTRING parameter holds a single bit.
+        assert!(map_data_to_value("0x2", id).is_err());
+        assert!(map_data_to_value("on", id).is_err());
+    }
+
     #[test]
     fn test_map_access_type() {
         use types::ParameterAccess as Public;
@@ -782,10 +927,113 @@ mod tests {
         assert_eq!(settings.cycle_time_max, 50000);
     }
 
+    #[test]
+    fn test_define_static_error_bits() {
+        let mut xdc_file = XdcFile {
+            network_management: Some(NetworkManagement {
+                diagnostic: Some(types::Diagnostic {
+                    static_error_bit_field: Some(vec![types::StaticErrorBit {
+                        name: "OverTemperature".to_string(),
+                        offset: 20,
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut device_errors = DeviceErrors::new();
+        assert_eq!(
+            define_static_error_bits(&xdc_file, &mut device_errors).unwrap(),
+            1
+        );
+        assert_eq!(device_errors.offset_of("OverTemperature"), Some(20));
+
+        // Offsets inside the Error Register octet are rejected.
+        if let Some(bits) = xdc_file
+            .network_management
+            .as_mut()
+            .and_then(|nm| nm.diagnostic.as_mut())
+            .and_then(|d| d.static_error_bit_field.as_mut())
+        {
+            bits[0].offset = 2;
+        }
+        assert!(define_static_error_bits(&xdc_file, &mut device_errors).is_err());
+        assert_eq!(
+            define_static_error_bits(&XdcFile::default(), &mut device_errors).unwrap(),
+            0
+        );
+    }
+
     #[test]
     fn test_extract_nmt_settings_missing_block() {
         let xdc_file = XdcFile::default();
         let result = extract_nmt_settings(&xdc_file);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_resolve_value_range_with_allowed_values() {
+        let allowed = types::AllowedValues {
+            values: vec![
+                types::Value {
+                    value: "4".to_string(),
+                    ..Default::default()
+                },
+                types::Value {
+                    value: "1".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Enumerated values only: the range spans the listed values.
+        let range = resolve_value_range(None, None, Some(&allowed), Some("0005")).unwrap();
+        assert_eq!(range.min, ObjectValue::Unsigned8(1));
+        assert_eq!(range.max, ObjectValue::Unsigned8(4));
+        assert_eq!(
+            range.values,
+            vec![ObjectValue::Unsigned8(4), ObjectValue::Unsigned8(1)]
+        );
+
+        // Explicit limits keep the enumerated values.
+        let range =
+            resolve_value_range(Some("0"), Some("3"), Some(&allowed), Some("0005")).unwrap();
+        assert_eq!(range.max, ObjectValue::Unsigned8(3));
+        assert_eq!(range.values.len(), 2);
+    }
+
+    #[test]
+    fn test_to_core_od_enforces_limits_on_write() {
+        let xdc_file = types::XdcFile {
+            object_dictionary: types::ObjectDictionary {
+                objects: vec![crate::types::Object {
+                    index: 0x2000,
+                    name: "Limited".to_string(),
+                    object_type: "7".to_string(),
+                    data_type: Some("0006".to_string()), // Unsigned16
+                    access_type: Some(types::ParameterAccess::ReadWrite),
+                    low_limit: Some("10".to_string()),
+                    high_limit: Some("20".to_string()),
+                    data: Some(String::from("15")),
+                    ..Default::default()
+                }],
+            },
+            ..Default::default()
+        };
+
+        let mut core_od = to_core_od(&xdc_file).unwrap();
+        assert!(
+            core_od
+                .write(0x2000, 0, ObjectValue::Unsigned16(20))
+                .is_ok()
+        );
+        assert_eq!(
+            core_od.write(0x2000, 0, ObjectValue::Unsigned16(21)),
+            Err(powerlink_rs::PowerlinkError::ValueTooHigh)
+        );
+    }
 }
@@ -24,7 +24,9 @@ mod types;
 
 // Functions
 pub use builder::save_xdc_to_string;
-pub use converter::{NmtSettings, extract_nmt_settings, to_core_od, xdc_to_storage_map};
+pub use converter::{
+    NmtSettings, define_static_error_bits, extract_nmt_settings, to_core_od, xdc_to_storage_map,
+};
 pub use error::XdcError;
 pub use parser::{load_xdc_from_str, load_xdd_defaults_from_str};
 
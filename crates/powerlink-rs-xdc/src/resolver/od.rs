@@ -228,6 +228,7 @@ fn map_param_type_to_hex(dt: &model::app_process::ParameterDataType) -> Option<S
         PDT::ULINT | PDT::LWORD => Some("001B".to_string()),
         PDT::LREAL => Some("0011".to_string()),
         PDT::WSTRING => Some("000B".to_string()),
+        PDT::BITSTRING => Some(utils::BIT_STRING_TYPE_ID.to_string()),
         // Reference types (DataTypeIDRef, VariableRef) cannot be simply mapped to a hex code
         // without further context, so we return None.
         _ => None,
@@ -546,6 +547,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_map_param_type_to_hex() {
+        use model::app_process::ParameterDataType as PDT;
+        assert_eq!(map_param_type_to_hex(&PDT::UINT).as_deref(), Some("0006"));
+        assert_eq!(
+            map_param_type_to_hex(&PDT::BITSTRING).as_deref(),
+            Some(utils::BIT_STRING_TYPE_ID)
+        );
+        assert_eq!(
+            map_param_type_to_hex(&PDT::VariableRef(Default::default())),
+            None
+        );
+    }
+
     // --- INTEGRATION TEST for resolve_object_dictionary ---
 
     #[test]
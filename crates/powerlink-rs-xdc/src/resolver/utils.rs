@@ -33,6 +33,13 @@ pub(super) fn extract_description(items: &[model::common::LabelChoice]) -> Optio
     })
 }
 
+/// Data type ID of objects bound to a BITSTRING parameter.
+///
+/// BITSTRING is an Application Process type (EPSG 311) with no index in the
+/// POWERLINK data type table, so objects inheriting it get this non-numeric ID.
+/// It is not written back as a `dataType` attribute.
+pub(crate) const BIT_STRING_TYPE_ID: &str = "BITSTRING";
+
 /// Maps a POWERLINK hex string ID (from EPSG 311, Table 56) to the `DataTypeName` enum.
 ///
 /// Example: "0006" -> `DataTypeName::Unsigned16`.
@@ -1,6 +1,6 @@
 //! Defines the structure and codec for the StatusResponse service payload.
 
-use crate::frame::error::{ErrorEntry, ErrorEntryMode};
+use crate::frame::error::{DeviceErrors, ErrorEntry, ErrorEntryMode};
 use crate::frame::poll::{PRFlag, RSFlag};
 use crate::hal::PowerlinkError;
 use crate::nmt::states::NmtState;
@@ -25,14 +25,14 @@ pub struct StaticErrorBitField {
 }
 
 impl StaticErrorBitField {
-    /// Creates a new `StaticErrorBitField` by reading from the Object Dictionary.
-    pub fn new(od: &ObjectDictionary) -> Self {
+    /// Creates a new `StaticErrorBitField` from the Error Register in the
+    /// Object Dictionary and the device-specific error bits.
+    pub fn new(od: &ObjectDictionary, device_errors: &DeviceErrors) -> Self {
         Self {
             error_register: od
                 .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
                 .unwrap_or(0),
-            // TODO: Populate specific_errors from OD if defined
-            specific_errors: [0; 7],
+            specific_errors: device_errors.specific_errors(),
         }
     }
 
@@ -165,31 +165,37 @@ impl StatusResponsePayload {
     }
 
     /// Deserializes a `StatusResponsePayload` from a byte slice.
+    ///
+    /// The ASnd decoder strips the zero padding of minimum-size frames, which
+    /// can include the end of the header. Missing header octets read as zero.
     pub fn deserialize(buffer: &[u8]) -> Result<Self, PowerlinkError> {
-        if buffer.len() < STATUS_PAYLOAD_HEADER_SIZE {
+        // Octets 0-2 hold the flags and the NMT state, which is never zero.
+        if buffer.len() < 3 {
             warn!(
-                "StatusResponse payload too short. Expected min {}, got {}",
-                STATUS_PAYLOAD_HEADER_SIZE,
+                "StatusResponse payload too short. Expected min 3, got {}",
                 buffer.len()
             );
             return Err(PowerlinkError::BufferTooShort);
         }
+        let mut header = [0u8; STATUS_PAYLOAD_HEADER_SIZE];
+        let header_len = buffer.len().min(STATUS_PAYLOAD_HEADER_SIZE);
+        header[..header_len].copy_from_slice(&buffer[..header_len]);
 
         // Octet 0: Flags
-        let octet0 = buffer[0];
+        let octet0 = header[0];
         let en_flag = (octet0 & (1 << 5)) != 0;
         let ec_flag = (octet0 & (1 << 4)) != 0;
 
         // Octet 1: Flags
-        let octet1 = buffer[1];
+        let octet1 = header[1];
         let pr = PRFlag::try_from(octet1 >> 3)?;
         let rs = RSFlag::new(octet1 & 0b111);
 
         // Octet 2: NMTState
-        let nmt_state = NmtState::try_from(buffer[2])?;
+        let nmt_state = NmtState::try_from(header[2])?;
 
         // Octets 6-13: StaticErrorBitField
-        let static_error_bit_field = StaticErrorBitField::deserialize(&buffer[6..14])?;
+        let static_error_bit_field = StaticErrorBitField::deserialize(&header[6..14])?;
 
         // Octets 14..: Error Entries
         let mut error_entries = Vec::new();
@@ -297,4 +303,33 @@ mod tests {
             StatusResponsePayload::deserialize(&buffer[..size]).expect("Deserialization failed");
         assert_eq!(deserialized.error_entries.len(), 0);
     }
+
+    #[test]
+    fn test_status_response_without_padding() {
+        let payload = StatusResponsePayload {
+            en_flag: false,
+            ec_flag: true,
+            pr: crate::frame::poll::PRFlag::PrioNmtRequest,
+            rs: crate::frame::poll::RSFlag::new(0),
+            nmt_state: NmtState::NmtOperational,
+            static_error_bit_field: StaticErrorBitField {
+                error_register: 0x01,
+                specific_errors: [0x01, 0, 0, 0, 0, 0, 0],
+            },
+            error_entries: vec![],
+        };
+
+        let mut buffer = [0u8; 50];
+        payload
+            .serialize(&mut buffer)
+            .expect("Serialization failed");
+        // The ASnd decoder keeps the payload up to its last non-zero octet.
+        let deserialized =
+            StatusResponsePayload::deserialize(&buffer[..8]).expect("Deserialization failed");
+        assert_eq!(deserialized, payload);
+        assert_eq!(
+            StatusResponsePayload::deserialize(&buffer[..2]),
+            Err(PowerlinkError::BufferTooShort)
+        );
+    }
 }
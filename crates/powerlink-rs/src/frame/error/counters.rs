@@ -1,7 +1,13 @@
 use super::traits::{ErrorCounters, ErrorHandler};
 use super::types::{DllError, NmtAction};
-use crate::types::NodeId;
+use crate::od::{ObjectDictionary, ObjectValue, constants};
+use crate::types::{C_ADR_MAX_CN_NODE_ID, NodeId};
 use alloc::collections::BTreeMap;
+use log::warn;
+
+/// Default threshold of the DLL error counters, used until the Object Dictionary
+/// provides one (EPSG DS 301, Section 4.7.4.1).
+pub const DEFAULT_DLL_ERROR_THRESHOLD: u32 = 15;
 
 /// Implements the 8:1 threshold counter logic from the specification.
 /// (EPSG DS 301, Section 4.7.4.1)
@@ -13,7 +19,8 @@ pub struct ThresholdCounter {
 }
 
 impl ThresholdCounter {
-    /// Creates a new counter with a specific threshold.
+    /// Creates a new counter with a specific threshold. A threshold of 0
+    /// disables the threshold check.
     pub fn new(threshold: u32) -> Self {
         Self {
             cumulative_cnt: 0,
@@ -22,9 +29,13 @@ impl ThresholdCounter {
         }
     }
 
-    /// Increments the counter by 8 when an error occurs.
+    /// Records an error: the cumulative counter is incremented by 1 and, unless
+    /// the threshold check is disabled, the threshold counter by 8.
     pub fn increment(&mut self) {
-        self.threshold_cnt = self.threshold_cnt.saturating_add(8);
+        self.cumulative_cnt = self.cumulative_cnt.saturating_add(1);
+        if self.threshold > 0 {
+            self.threshold_cnt = self.threshold_cnt.saturating_add(8);
+        }
     }
 
     /// Decrements the counter by 1 for each error-free cycle.
@@ -37,7 +48,6 @@ impl ThresholdCounter {
     pub fn check_and_reset(&mut self) -> bool {
         if self.threshold > 0 && self.threshold_cnt >= self.threshold {
             self.threshold_cnt = 0;
-            self.cumulative_cnt = self.cumulative_cnt.saturating_add(1);
             true
         } else {
             false
@@ -52,6 +62,64 @@ impl ThresholdCounter {
     pub fn cumulative_count(&self) -> u32 {
         self.cumulative_cnt
     }
+
+    /// Returns the threshold counter, raised by 8 per error and lowered by 1 per cycle.
+    pub fn threshold_count(&self) -> u32 {
+        self.threshold_cnt
+    }
+
+    /// Returns the threshold that triggers the error action (0 = disabled).
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Restarts the counter with the given cumulative count and threshold.
+    fn restart(&mut self, cumulative_cnt: u32, threshold: u32) {
+        self.cumulative_cnt = cumulative_cnt;
+        self.threshold_cnt = 0;
+        self.threshold = threshold;
+    }
+
+    /// Restarts the counter from a DLL_ErrorCntRec_TYPE record.
+    /// Sub-indices missing from the OD keep their current values.
+    fn load_record(&mut self, od: &ObjectDictionary, index: u16) {
+        let cumulative_cnt = od
+            .read_u32(index, constants::SUBIDX_DLL_ERROR_CUM_CNT_U32)
+            .unwrap_or(self.cumulative_cnt);
+        let threshold = od
+            .read_u32(index, constants::SUBIDX_DLL_ERROR_THRESHOLD_U32)
+            .unwrap_or(self.threshold);
+        self.restart(cumulative_cnt, threshold);
+    }
+
+    /// Writes the counts to a DLL_ErrorCntRec_TYPE record.
+    fn write_record(&self, od: &mut ObjectDictionary, index: u16) {
+        write_counter(
+            od,
+            index,
+            constants::SUBIDX_DLL_ERROR_CUM_CNT_U32,
+            self.cumulative_cnt,
+        );
+        write_counter(
+            od,
+            index,
+            constants::SUBIDX_DLL_ERROR_THR_CNT_U32,
+            self.threshold_cnt,
+        );
+    }
+}
+
+/// Writes a counter value to the OD, if the device implements the object.
+fn write_counter(od: &mut ObjectDictionary, index: u16, sub_index: u8, value: u32) {
+    if od.read_object(index).is_none() {
+        return;
+    }
+    if let Err(e) = od.write_internal(index, sub_index, ObjectValue::Unsigned32(value), false) {
+        warn!(
+            "Failed to update DLL error counter {:#06X}/{}: {:?}",
+            index, sub_index, e
+        );
+    }
 }
 
 // --- Controlled Node (CN) Counters ---
@@ -75,16 +143,25 @@ impl CnErrorCounters {
     /// Creates a new set of counters, with default thresholds from the specification.
     pub fn new() -> Self {
         CnErrorCounters {
-            loss_of_soc: ThresholdCounter::new(15),
-            loss_of_soa: ThresholdCounter::new(15),
-            loss_of_preq: ThresholdCounter::new(15),
-            crc_errors: ThresholdCounter::new(15),
-            collision: ThresholdCounter::new(15),
-            soc_jitter: ThresholdCounter::new(15),
-            heartbeat_timeout: ThresholdCounter::new(15), // Added heartbeat counter
+            loss_of_soc: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            loss_of_soa: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            loss_of_preq: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            crc_errors: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            collision: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            soc_jitter: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            heartbeat_timeout: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD), // Added heartbeat counter
             loss_of_link_cumulative: 0,
         }
     }
+}
+
+impl Default for CnErrorCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorCounters for CnErrorCounters {
     /// Checks if any of the threshold counters are currently active ( > 0).
     fn is_any_active(&self) -> bool {
         self.loss_of_soc.is_active()
@@ -95,15 +172,7 @@ impl CnErrorCounters {
             || self.soc_jitter.is_active()
             || self.heartbeat_timeout.is_active() // Added heartbeat check
     }
-}
-
-impl Default for CnErrorCounters {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl ErrorCounters for CnErrorCounters {
     fn on_cycle_complete(&mut self) -> bool {
         let was_active = self.is_any_active();
 
@@ -171,6 +240,12 @@ impl ErrorCounters for CnErrorCounters {
                 status_changed = true; // PDO errors are signallable.
                 false
             }
+            // Physical layer and MAC errors are logged without an NMT action.
+            DllError::BadPhysicalMode | DllError::MacBuffer => {
+                handler.log_error(&error);
+                status_changed = true;
+                false
+            }
             // Errors handled by MN are ignored here.
             _ => false,
         };
@@ -183,12 +258,53 @@ impl ErrorCounters for CnErrorCounters {
         }
         (nmt_action, status_changed)
     }
+
+    fn load_from_od(&mut self, od: &ObjectDictionary) {
+        self.collision
+            .load_record(od, constants::IDX_DLL_CN_COLLISION_REC);
+        self.loss_of_soc
+            .load_record(od, constants::IDX_DLL_CN_LOSS_SOC_REC);
+        self.loss_of_soa
+            .load_record(od, constants::IDX_DLL_CN_LOSS_SOA_REC);
+        self.loss_of_preq
+            .load_record(od, constants::IDX_DLL_CN_LOSS_PREQ_REC);
+        self.soc_jitter
+            .load_record(od, constants::IDX_DLL_CN_SOC_JITTER_REC);
+        self.crc_errors
+            .load_record(od, constants::IDX_DLL_CN_CRC_ERROR_REC);
+        // The heartbeat consumer has no counter object; it keeps its threshold.
+        self.heartbeat_timeout.threshold_cnt = 0;
+        self.loss_of_link_cumulative = od
+            .read_u32(constants::IDX_DLL_CN_LOSS_OF_LINK_CUM_U32, 0)
+            .unwrap_or(self.loss_of_link_cumulative);
+    }
+
+    fn write_to_od(&self, od: &mut ObjectDictionary) {
+        self.collision
+            .write_record(od, constants::IDX_DLL_CN_COLLISION_REC);
+        self.loss_of_soc
+            .write_record(od, constants::IDX_DLL_CN_LOSS_SOC_REC);
+        self.loss_of_soa
+            .write_record(od, constants::IDX_DLL_CN_LOSS_SOA_REC);
+        self.loss_of_preq
+            .write_record(od, constants::IDX_DLL_CN_LOSS_PREQ_REC);
+        self.soc_jitter
+            .write_record(od, constants::IDX_DLL_CN_SOC_JITTER_REC);
+        self.crc_errors
+            .write_record(od, constants::IDX_DLL_CN_CRC_ERROR_REC);
+        write_counter(
+            od,
+            constants::IDX_DLL_CN_LOSS_OF_LINK_CUM_U32,
+            0,
+            self.loss_of_link_cumulative,
+        );
+    }
 }
 
 // --- Managing Node (MN) Counters ---
 
 /// Holds all DLL error counters for a Managing Node.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MnErrorCounters {
     pub crc_errors: ThresholdCounter,
     pub collision: ThresholdCounter,
@@ -203,29 +319,75 @@ pub struct MnErrorCounters {
 }
 
 impl MnErrorCounters {
+    /// Creates a new set of counters, with default thresholds from the specification.
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            crc_errors: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            collision: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            cycle_time_exceeded: ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD),
+            loss_of_link_cumulative: 0,
+            cn_late_pres: BTreeMap::new(),
+            cn_loss_of_pres: BTreeMap::new(),
+            cn_loss_of_status_response: BTreeMap::new(),
+        }
     }
 
     // Helper methods to get or insert a counter for a given node.
     fn loss_pres_counter_for(&mut self, node_id: NodeId) -> &mut ThresholdCounter {
         self.cn_loss_of_pres
             .entry(node_id)
-            .or_insert_with(|| ThresholdCounter::new(15))
+            .or_insert_with(|| ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD))
     }
     fn late_pres_counter_for(&mut self, node_id: NodeId) -> &mut ThresholdCounter {
         self.cn_late_pres
             .entry(node_id)
-            .or_insert_with(|| ThresholdCounter::new(15))
+            .or_insert_with(|| ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD))
     }
     fn loss_status_res_counter_for(&mut self, node_id: NodeId) -> &mut ThresholdCounter {
         self.cn_loss_of_status_response
             .entry(node_id)
-            .or_insert_with(|| ThresholdCounter::new(15))
+            .or_insert_with(|| ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD))
+    }
+}
+
+impl Default for MnErrorCounters {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// Creates a per-CN counter from the DLL_MNCN*_AU32 arrays, where the sub-index is the Node ID.
+fn load_cn_counter(
+    od: &ObjectDictionary,
+    node_id: NodeId,
+    cumulative_index: u16,
+    threshold_index: u16,
+) -> ThresholdCounter {
+    let mut counter = ThresholdCounter::new(DEFAULT_DLL_ERROR_THRESHOLD);
+    let cumulative_cnt = od.read_u32(cumulative_index, node_id.0).unwrap_or(0);
+    let threshold = od
+        .read_u32(threshold_index, node_id.0)
+        .unwrap_or(DEFAULT_DLL_ERROR_THRESHOLD);
+    counter.restart(cumulative_cnt, threshold);
+    counter
+}
+
 impl ErrorCounters for MnErrorCounters {
+    fn is_any_active(&self) -> bool {
+        self.crc_errors.is_active()
+            || self.collision.is_active()
+            || self.cycle_time_exceeded.is_active()
+            || self.cn_late_pres.values().any(ThresholdCounter::is_active)
+            || self
+                .cn_loss_of_pres
+                .values()
+                .any(ThresholdCounter::is_active)
+            || self
+                .cn_loss_of_status_response
+                .values()
+                .any(ThresholdCounter::is_active)
+    }
+
     fn on_cycle_complete(&mut self) -> bool {
         self.crc_errors.decrement();
         self.collision.decrement();
@@ -284,6 +446,18 @@ impl ErrorCounters for MnErrorCounters {
                 status_changed = true;
                 (false, None)
             }
+            DllError::LossOfLink => {
+                self.loss_of_link_cumulative = self.loss_of_link_cumulative.saturating_add(1);
+                handler.log_error(&error);
+                status_changed = true;
+                (false, None)
+            }
+            // Physical layer and MAC errors are logged without an NMT action.
+            DllError::BadPhysicalMode | DllError::MacBuffer => {
+                handler.log_error(&error);
+                status_changed = true;
+                (false, None)
+            }
             // Errors handled by CN are ignored here.
             _ => (false, None),
         };
@@ -302,11 +476,100 @@ impl ErrorCounters for MnErrorCounters {
         }
         (nmt_action, status_changed)
     }
+
+    fn load_from_od(&mut self, od: &ObjectDictionary) {
+        self.crc_errors
+            .load_record(od, constants::IDX_DLL_MN_CRC_ERROR_REC);
+        self.collision
+            .load_record(od, constants::IDX_DLL_MN_COLLISION_REC);
+        self.cycle_time_exceeded
+            .load_record(od, constants::IDX_DLL_MN_CYC_TIME_EXCEED_REC);
+        self.loss_of_link_cumulative = od
+            .read_u32(constants::IDX_DLL_MN_LOSS_OF_LINK_CUM_U32, 0)
+            .unwrap_or(self.loss_of_link_cumulative);
+
+        // Per-CN counters are rebuilt for the assigned CNs. Counters for other
+        // nodes are created on demand with the default threshold.
+        self.cn_late_pres.clear();
+        self.cn_loss_of_pres.clear();
+        self.cn_loss_of_status_response.clear();
+        for id in 1..=C_ADR_MAX_CN_NODE_ID {
+            let assigned = od
+                .read_u32(constants::IDX_NMT_NODE_ASSIGNMENT_AU32, id)
+                .is_some_and(|assignment| assignment & 1 != 0);
+            if !assigned {
+                continue;
+            }
+            let node_id = NodeId(id);
+            self.cn_late_pres.insert(
+                node_id,
+                load_cn_counter(
+                    od,
+                    node_id,
+                    constants::IDX_DLL_MN_CN_LATE_PRES_CUM_CNT_AU32,
+                    constants::IDX_DLL_MN_CN_LATE_PRES_THRESHOLD_AU32,
+                ),
+            );
+            self.cn_loss_of_pres.insert(
+                node_id,
+                load_cn_counter(
+                    od,
+                    node_id,
+                    constants::IDX_DLL_MN_CN_LOSS_PRES_CUM_CNT_AU32,
+                    constants::IDX_DLL_MN_CN_LOSS_PRES_THRESHOLD_AU32,
+                ),
+            );
+        }
+    }
+
+    fn write_to_od(&self, od: &mut ObjectDictionary) {
+        self.crc_errors
+            .write_record(od, constants::IDX_DLL_MN_CRC_ERROR_REC);
+        self.collision
+            .write_record(od, constants::IDX_DLL_MN_COLLISION_REC);
+        self.cycle_time_exceeded
+            .write_record(od, constants::IDX_DLL_MN_CYC_TIME_EXCEED_REC);
+        write_counter(
+            od,
+            constants::IDX_DLL_MN_LOSS_OF_LINK_CUM_U32,
+            0,
+            self.loss_of_link_cumulative,
+        );
+        for (node_id, counter) in &self.cn_late_pres {
+            write_counter(
+                od,
+                constants::IDX_DLL_MN_CN_LATE_PRES_CUM_CNT_AU32,
+                node_id.0,
+                counter.cumulative_cnt,
+            );
+            write_counter(
+                od,
+                constants::IDX_DLL_MN_CN_LATE_PRES_THR_CNT_AU32,
+                node_id.0,
+                counter.threshold_cnt,
+            );
+        }
+        for (node_id, counter) in &self.cn_loss_of_pres {
+            write_counter(
+                od,
+                constants::IDX_DLL_MN_CN_LOSS_PRES_CUM_CNT_AU32,
+                node_id.0,
+                counter.cumulative_cnt,
+            );
+            write_counter(
+                od,
+                constants::IDX_DLL_MN_CN_LOSS_PRES_THR_CNT_AU32,
+                node_id.0,
+                counter.threshold_cnt,
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::od::utils::{new_cn_default, new_mn_default};
     use alloc::vec::Vec;
 
     // A mock error handler for testing purposes.
@@ -383,6 +646,32 @@ mod tests {
         assert_eq!(handler.logged_errors[0], error);
     }
 
+    #[test]
+    fn test_link_errors_are_logged_without_action() {
+        let mut handler = TestErrorHandler {
+            logged_errors: Vec::new(),
+        };
+        let mut cn = CnErrorCounters::new();
+        let mut mn = MnErrorCounters::new();
+        for error in [
+            DllError::LossOfLink,
+            DllError::BadPhysicalMode,
+            DllError::MacBuffer,
+        ] {
+            assert_eq!(
+                cn.handle_error(error, &mut handler),
+                (NmtAction::None, true)
+            );
+            assert_eq!(
+                mn.handle_error(error, &mut handler),
+                (NmtAction::None, true)
+            );
+        }
+        assert_eq!(cn.loss_of_link_cumulative, 1);
+        assert_eq!(mn.loss_of_link_cumulative, 1);
+        assert_eq!(handler.logged_errors.len(), 6);
+    }
+
     #[test]
     fn test_counters_on_cycle_complete() {
         let mut cn_counters = CnErrorCounters::new();
@@ -431,4 +720,118 @@ mod tests {
             7
         );
     }
+
+    #[test]
+    fn test_threshold_zero_disables_check() {
+        let mut counter = ThresholdCounter::new(0);
+        for _ in 0..10 {
+            counter.increment();
+            assert!(!counter.check_and_reset());
+        }
+        // Errors are still counted, but never accumulate towards a threshold.
+        assert_eq!(counter.cumulative_count(), 10);
+        assert!(!counter.is_active());
+    }
+
+    #[test]
+    fn test_cn_counters_use_od_thresholds() {
+        let mut od = new_cn_default(NodeId(1)).unwrap();
+        od.write(
+            constants::IDX_DLL_CN_LOSS_SOC_REC,
+            constants::SUBIDX_DLL_ERROR_THRESHOLD_U32,
+            ObjectValue::Unsigned32(24),
+        )
+        .unwrap();
+        od.write(
+            constants::IDX_DLL_CN_CRC_ERROR_REC,
+            constants::SUBIDX_DLL_ERROR_THRESHOLD_U32,
+            ObjectValue::Unsigned32(0),
+        )
+        .unwrap();
+
+        let mut counters = CnErrorCounters::new();
+        counters.load_from_od(&od);
+        let mut handler = TestErrorHandler {
+            logged_errors: Vec::new(),
+        };
+
+        // A threshold of 24 takes three errors instead of the default two.
+        for _ in 0..2 {
+            let (action, _) = counters.handle_error(DllError::LossOfSoc, &mut handler);
+            assert_eq!(action, NmtAction::None);
+        }
+        let (action, _) = counters.handle_error(DllError::LossOfSoc, &mut handler);
+        assert_eq!(action, NmtAction::ResetCommunication);
+
+        // A threshold of 0 never triggers.
+        for _ in 0..5 {
+            let (action, _) = counters.handle_error(DllError::Crc, &mut handler);
+            assert_eq!(action, NmtAction::None);
+        }
+
+        counters.write_to_od(&mut od);
+        let read = |index, sub_index| od.read_u32(index, sub_index);
+        assert_eq!(
+            read(
+                constants::IDX_DLL_CN_LOSS_SOC_REC,
+                constants::SUBIDX_DLL_ERROR_CUM_CNT_U32
+            ),
+            Some(3)
+        );
+        assert_eq!(
+            read(
+                constants::IDX_DLL_CN_LOSS_SOC_REC,
+                constants::SUBIDX_DLL_ERROR_THR_CNT_U32
+            ),
+            Some(0)
+        );
+        assert_eq!(
+            read(
+                constants::IDX_DLL_CN_CRC_ERROR_REC,
+                constants::SUBIDX_DLL_ERROR_CUM_CNT_U32
+            ),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_mn_counters_use_od_thresholds() {
+        let mut od = new_mn_default(NodeId(240)).unwrap();
+        let node_id = NodeId(3);
+        od.write(
+            constants::IDX_NMT_NODE_ASSIGNMENT_AU32,
+            node_id.0,
+            ObjectValue::Unsigned32(1),
+        )
+        .unwrap();
+        od.write(
+            constants::IDX_DLL_MN_CN_LOSS_PRES_THRESHOLD_AU32,
+            node_id.0,
+            ObjectValue::Unsigned32(0),
+        )
+        .unwrap();
+
+        let mut counters = MnErrorCounters::new();
+        counters.load_from_od(&od);
+        let mut handler = TestErrorHandler {
+            logged_errors: Vec::new(),
+        };
+        let error = DllError::LossOfPres { node_id };
+        for _ in 0..4 {
+            assert_eq!(
+                counters.handle_error(error, &mut handler).0,
+                NmtAction::None
+            );
+        }
+
+        counters.write_to_od(&mut od);
+        assert_eq!(
+            od.read_u32(constants::IDX_DLL_MN_CN_LOSS_PRES_CUM_CNT_AU32, node_id.0),
+            Some(4)
+        );
+        assert_eq!(
+            od.read_u32(constants::IDX_DLL_MN_CN_LOSS_PRES_THR_CNT_AU32, node_id.0),
+            Some(0)
+        );
+    }
 }
@@ -0,0 +1,174 @@
+// crates/powerlink-rs/src/frame/error/device.rs
+//! Device profile and vendor-specific errors raised by the application.
+//!
+//! These complement the DLL errors detected by the stack. Active errors are
+//! reported in the device-specific part of the Static Error Bit Field
+//! (EPSG DS 301, Section 6.5.8.1) and as entries in the emergency queue and
+//! the Error History (0x1003).
+
+use crate::PowerlinkError;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use core::ops::RangeInclusive;
+
+/// Bit offsets of the Static Error Bit Field that a device may use.
+/// Octet 0 mirrors ERR_ErrorRegister_U8 (0x1001) and octet 1 is reserved.
+pub const DEVICE_ERROR_BITS: RangeInclusive<u8> = 16..=63;
+
+/// Describes a device profile or vendor-specific error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeviceError {
+    /// The error code reported in the error entry.
+    pub error_code: u16,
+    /// The profile defining the error code: 0x001 for vendor-specific errors,
+    /// or the device profile number (0x003..=0xFFF).
+    pub profile: u16,
+    /// Offset in the Static Error Bit Field (16..=63) set while the error is active.
+    pub static_bit: Option<u8>,
+    /// Bits of ERR_ErrorRegister_U8 (0x1001) set while the error is active,
+    /// in addition to the generic error bit.
+    pub error_register: u8,
+    /// Additional information reported in the error entry.
+    pub additional_information: u64,
+}
+
+/// The device-specific part of the Static Error Bit Field, together with the
+/// names of its bits (usually taken from the XDC `<StaticErrorBitField>`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceErrors {
+    /// Octets 1..=7 of the Static Error Bit Field.
+    specific_errors: [u8; 7],
+    /// Bit names mapped to their offsets.
+    names: BTreeMap<String, u8>,
+    /// Active errors, keyed by error code.
+    active: BTreeMap<u16, DeviceError>,
+}
+
+impl DeviceErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns a name to a static error bit.
+    pub fn define_bit(&mut self, name: &str, offset: u8) -> Result<(), PowerlinkError> {
+        check_offset(offset)?;
+        self.names.insert(name.into(), offset);
+        Ok(())
+    }
+
+    /// Returns the offset of a named static error bit.
+    pub fn offset_of(&self, name: &str) -> Option<u8> {
+        self.names.get(name).copied()
+    }
+
+    /// Returns true if the static error bit at `offset` is set.
+    pub fn is_set(&self, offset: u8) -> bool {
+        DEVICE_ERROR_BITS.contains(&offset)
+            && self.specific_errors[offset as usize / 8 - 1] & (1 << (offset % 8)) != 0
+    }
+
+    /// Returns true if any device error is active.
+    pub fn any_active(&self) -> bool {
+        !self.active.is_empty()
+    }
+
+    /// Octets 1..=7 of the Static Error Bit Field.
+    pub fn specific_errors(&self) -> [u8; 7] {
+        self.specific_errors
+    }
+
+    /// Error register bits requested by all active errors.
+    pub fn error_register(&self) -> u8 {
+        self.active
+            .values()
+            .fold(0, |acc, error| acc | error.error_register)
+    }
+
+    /// Marks an error as active and sets its static bit.
+    /// Returns true if the error was not already active.
+    pub(crate) fn activate(&mut self, error: &DeviceError) -> Result<bool, PowerlinkError> {
+        if let Some(offset) = error.static_bit {
+            check_offset(offset)?;
+            self.specific_errors[offset as usize / 8 - 1] |= 1 << (offset % 8);
+        }
+        Ok(self.active.insert(error.error_code, *error).is_none())
+    }
+
+    /// Marks an error as cleared. Its static bit stays set while another
+    /// active error shares it. Returns true if the error was active.
+    pub(crate) fn deactivate(&mut self, error: &DeviceError) -> Result<bool, PowerlinkError> {
+        if let Some(offset) = error.static_bit {
+            check_offset(offset)?;
+        }
+        let was_active = self.active.remove(&error.error_code).is_some();
+        self.specific_errors = [0; 7];
+        for offset in self.active.values().filter_map(|e| e.static_bit) {
+            self.specific_errors[offset as usize / 8 - 1] |= 1 << (offset % 8);
+        }
+        Ok(was_active)
+    }
+}
+
+fn check_offset(offset: u8) -> Result<(), PowerlinkError> {
+    if DEVICE_ERROR_BITS.contains(&offset) {
+        Ok(())
+    } else {
+        Err(PowerlinkError::ValidationError(
+            "Static error bit offset outside the device-specific range 16..=63",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_bits_follow_active_errors() {
+        let mut errors = DeviceErrors::new();
+        errors.define_bit("OverTemperature", 17).unwrap();
+        assert!(errors.define_bit("ErrorRegister", 3).is_err());
+
+        let error = DeviceError {
+            error_code: 0x4210,
+            profile: 0x001,
+            static_bit: errors.offset_of("OverTemperature"),
+            error_register: 1 << 3,
+            ..Default::default()
+        };
+        assert!(errors.activate(&error).unwrap());
+        assert!(!errors.activate(&error).unwrap());
+        assert!(errors.is_set(17));
+        assert_eq!(errors.specific_errors(), [0, 0b10, 0, 0, 0, 0, 0]);
+        assert_eq!(errors.error_register(), 1 << 3);
+
+        assert!(errors.deactivate(&error).unwrap());
+        assert!(!errors.any_active());
+        assert_eq!(errors.specific_errors(), [0; 7]);
+    }
+
+    #[test]
+    fn test_shared_static_bit_stays_set_until_last_error_clears() {
+        let mut errors = DeviceErrors::new();
+        let first = DeviceError {
+            error_code: 0x4210,
+            profile: 0x001,
+            static_bit: Some(20),
+            ..Default::default()
+        };
+        let second = DeviceError {
+            error_code: 0x4220,
+            ..first
+        };
+        errors.activate(&first).unwrap();
+        errors.activate(&second).unwrap();
+
+        assert!(errors.deactivate(&first).unwrap());
+        assert!(errors.is_set(20));
+        assert!(errors.any_active());
+
+        assert!(errors.deactivate(&second).unwrap());
+        assert!(!errors.is_set(20));
+        assert_eq!(errors.specific_errors(), [0; 7]);
+    }
+}
@@ -1,5 +1,8 @@
 use super::traits::{ErrorCounters, ErrorHandler};
 use super::types::{DllError, NmtAction};
+use crate::hal::LinkStatus;
+use crate::od::ObjectDictionary;
+use alloc::vec::Vec;
 
 /// The central manager, generic over the counter set and the handler.
 pub struct DllErrorManager<C, H>
@@ -9,6 +12,12 @@ where
 {
     pub counters: C,
     pub handler: H,
+    /// Errors handled since the node last collected them, with the resulting action.
+    reported: Vec<(DllError, NmtAction)>,
+    /// The counters changed since they were last written to the OD.
+    od_outdated: bool,
+    /// The link state from the last `LinkStatus`, used to detect transitions.
+    link: LinkStatus,
 }
 
 impl<C, H> DllErrorManager<C, H>
@@ -17,14 +26,118 @@ where
     H: ErrorHandler,
 {
     pub fn new(counters: C, handler: H) -> Self {
-        Self { counters, handler }
+        Self {
+            counters,
+            handler,
+            reported: Vec::new(),
+            od_outdated: false,
+            link: LinkStatus::default(),
+        }
     }
 
     pub fn handle_error(&mut self, error: DllError) -> (NmtAction, bool) {
-        self.counters.handle_error(error, &mut self.handler)
+        let (action, signaled) = self.counters.handle_error(error, &mut self.handler);
+        self.reported.push((error, action));
+        self.od_outdated = true;
+        (action, signaled)
+    }
+
+    /// Returns the errors handled since the last call, for the node's event queue.
+    pub(crate) fn take_reported(&mut self) -> Vec<(DllError, NmtAction)> {
+        core::mem::take(&mut self.reported)
+    }
+
+    /// Converts a `LinkStatus` from the HAL into the DLL errors it implies.
+    ///
+    /// Loss of link and a bad physical mode are raised once when they occur,
+    /// not on every query. Any dropped frames raise `E_DLL_MAC_BUFFER`.
+    pub fn link_errors(&mut self, status: LinkStatus) -> Vec<DllError> {
+        let mut errors = Vec::new();
+        if self.link.link_up && !status.link_up {
+            errors.push(DllError::LossOfLink);
+        }
+        if status.link_up && status.bad_physical_mode && !self.link.bad_physical_mode {
+            errors.push(DllError::BadPhysicalMode);
+        }
+        if status.dropped_frames > 0 {
+            errors.push(DllError::MacBuffer);
+        }
+        self.link = LinkStatus {
+            // Keep reporting a bad mode once the link comes back in that mode.
+            bad_physical_mode: status.link_up && status.bad_physical_mode,
+            ..status
+        };
+        errors
     }
 
     pub fn on_cycle_complete(&mut self) -> bool {
+        self.od_outdated |= self.counters.is_any_active();
         self.counters.on_cycle_complete()
     }
+
+    /// Reloads the thresholds and cumulative counts from the OD, restarting
+    /// the threshold counts. Called when the node resets its communication.
+    pub fn load_from_od(&mut self, od: &ObjectDictionary) {
+        self.counters.load_from_od(od);
+        self.od_outdated = true;
+    }
+
+    /// Writes the counters to the OD if they changed, so SDO readers see live values.
+    pub fn sync_od(&mut self, od: &mut ObjectDictionary) {
+        if core::mem::take(&mut self.od_outdated) {
+            self.counters.write_to_od(od);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::error::{CnErrorCounters, LoggingErrorHandler};
+
+    fn manager() -> DllErrorManager<CnErrorCounters, LoggingErrorHandler> {
+        DllErrorManager::new(CnErrorCounters::new(), LoggingErrorHandler)
+    }
+
+    fn status(link_up: bool, bad_physical_mode: bool, dropped_frames: u32) -> LinkStatus {
+        LinkStatus {
+            link_up,
+            bad_physical_mode,
+            dropped_frames,
+        }
+    }
+
+    #[test]
+    fn test_loss_of_link_raised_once_per_transition() {
+        let mut manager = manager();
+        assert!(manager.link_errors(status(true, false, 0)).is_empty());
+        assert_eq!(
+            manager.link_errors(status(false, false, 0)),
+            [DllError::LossOfLink]
+        );
+        // Still down: not raised again.
+        assert!(manager.link_errors(status(false, false, 0)).is_empty());
+        // Back up, then lost again.
+        assert!(manager.link_errors(status(true, false, 0)).is_empty());
+        assert_eq!(
+            manager.link_errors(status(false, false, 0)),
+            [DllError::LossOfLink]
+        );
+    }
+
+    #[test]
+    fn test_bad_physical_mode_and_mac_buffer() {
+        let mut manager = manager();
+        assert_eq!(
+            manager.link_errors(status(true, true, 3)),
+            [DllError::BadPhysicalMode, DllError::MacBuffer]
+        );
+        assert!(manager.link_errors(status(true, true, 0)).is_empty());
+        // The mode is checked again after the link is re-established.
+        manager.link_errors(status(false, false, 0));
+        assert_eq!(
+            manager.link_errors(status(true, true, 0)),
+            [DllError::BadPhysicalMode]
+        );
+    }
 }
@@ -2,12 +2,16 @@
 //! types, counters, and the main error manager.
 
 pub mod counters;
+pub mod device;
 pub mod manager;
 pub mod status_response;
 pub mod traits;
 pub mod types;
 
-pub use counters::{CnErrorCounters, MnErrorCounters, ThresholdCounter};
+pub use counters::{
+    CnErrorCounters, DEFAULT_DLL_ERROR_THRESHOLD, MnErrorCounters, ThresholdCounter,
+};
+pub use device::{DeviceError, DeviceErrors};
 pub use manager::DllErrorManager;
 pub use status_response::{EntryType, ErrorEntry, ErrorEntryMode};
 pub use traits::{ErrorCounters, ErrorHandler, LoggingErrorHandler};
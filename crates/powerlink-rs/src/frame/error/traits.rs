@@ -1,4 +1,6 @@
 use super::types::{DllError, NmtAction};
+use crate::od::ObjectDictionary;
+use alloc::boxed::Box;
 use log::error;
 
 /// A trait that defines how DLL errors are reported or logged.
@@ -6,6 +8,13 @@ pub trait ErrorHandler {
     fn log_error(&mut self, error: &DllError);
 }
 
+/// Lets nodes hold an application-provided handler as `Box<dyn ErrorHandler>`.
+impl<H: ErrorHandler + ?Sized> ErrorHandler for Box<H> {
+    fn log_error(&mut self, error: &DllError) {
+        (**self).log_error(error);
+    }
+}
+
 /// A `no_std` compatible error handler that does nothing.
 pub struct NoOpErrorHandler;
 impl ErrorHandler for NoOpErrorHandler {
@@ -37,6 +46,13 @@ pub trait ErrorCounters: Sized {
     /// Returns `true` if the last active error was just cleared in this cycle.
     fn on_cycle_complete(&mut self) -> bool;
 
+    /// Returns true if any threshold counter is currently above zero.
+    /// While it does, the counters are written to the OD every cycle; the
+    /// default reports none, so they are only written after an error.
+    fn is_any_active(&self) -> bool {
+        false
+    }
+
     /// Processes a given error, updates the appropriate counter, and returns an NMT action
     /// and a boolean indicating if the error status has changed and should be signaled.
     fn handle_error<H: ErrorHandler>(
@@ -44,4 +60,11 @@ pub trait ErrorCounters: Sized {
         error: DllError,
         handler: &mut H,
     ) -> (NmtAction, bool);
+
+    /// Reinitialises the counters from their Object Dictionary objects.
+    /// Called at NMT_GS_RESET_COMMUNICATION, so configured thresholds take effect.
+    fn load_from_od(&mut self, _od: &ObjectDictionary) {}
+
+    /// Writes the current counter values to their Object Dictionary objects.
+    fn write_to_od(&self, _od: &mut ObjectDictionary) {}
 }
@@ -44,6 +44,14 @@ pub enum PowerlinkError {
     SubObjectNotFound,
     /// An attempt was made to write/deserialize a value with an incorrect data type.
     TypeMismatch,
+    /// A written value is above the maximum of the object's value range.
+    ValueTooHigh,
+    /// A written value is below the minimum of the object's value range.
+    ValueTooLow,
+    /// A written value is not one of the object's allowed values.
+    InvalidValue,
+    /// An application observer vetoed an Object Dictionary write with the given SDO abort code.
+    WriteRejected(u32),
     /// An error occurred in the storage backend.
     StorageError(&'static str),
     /// A mandatory object was missing or invalid during validation.
@@ -84,6 +92,10 @@ impl fmt::Display for PowerlinkError {
             Self::ObjectNotFound => write!(f, "OD index not found"),
             Self::SubObjectNotFound => write!(f, "OD sub-index not found"),
             Self::TypeMismatch => write!(f, "Data type mismatch"),
+            Self::ValueTooHigh => write!(f, "Value written too high"),
+            Self::ValueTooLow => write!(f, "Value written too low"),
+            Self::InvalidValue => write!(f, "Value not in the allowed values"),
+            Self::WriteRejected(code) => write!(f, "Write rejected with abort code {:#010X}", code),
             Self::StorageError(s) => write!(f, "Storage error: {}", s),
             Self::ValidationError(s) => write!(f, "Validation error: {}", s),
             Self::SdoSequenceError(s) => write!(f, "SDO sequence error: {}", s),
@@ -147,6 +159,30 @@ impl From<&'static str> for PowerlinkError {
     }
 }
 
+/// A snapshot of the physical link and driver state reported by a `NetworkInterface`.
+///
+/// Used by the node to raise `E_DLL_LOSS_OF_LINK`, `E_DLL_BAD_PHYS_MODE` and
+/// `E_DLL_MAC_BUFFER` (EPSG DS 301, Section 4.7.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkStatus {
+    /// `true` if the interface currently has a carrier.
+    pub link_up: bool,
+    /// `true` if the link runs in a mode other than 100 Mbit/s half duplex.
+    pub bad_physical_mode: bool,
+    /// Number of frames dropped by the driver or MAC since the previous query.
+    pub dropped_frames: u32,
+}
+
+impl Default for LinkStatus {
+    fn default() -> Self {
+        Self {
+            link_up: true,
+            bad_physical_mode: false,
+            dropped_frames: 0,
+        }
+    }
+}
+
 /// Hardware Abstraction Layer (HAL) for network communication.
 ///
 /// This trait abstracts the physical sending and receiving of raw Ethernet frames
@@ -171,6 +207,14 @@ pub trait NetworkInterface {
     /// Returns the local MAC address of the interface.
     fn local_mac_address(&self) -> [u8; 6];
 
+    /// Queries the current link state and any driver events since the last call.
+    ///
+    /// Returns `None` if the platform cannot report link state. The default
+    /// implementation does exactly that.
+    fn link_status(&mut self) -> Option<LinkStatus> {
+        None
+    }
+
     /// Sends a UDP datagram. Only available when the `sdo-udp` feature is enabled.
     ///
     /// `dest_ip`: The destination IPv4 address.
@@ -27,8 +27,12 @@ pub mod sdo;
 pub use common::{NetTime, RelativeTime};
 pub use frame::codec::{Codec, deserialize_frame};
 pub use frame::error::{DllErrorManager, ErrorHandler, LoggingErrorHandler, NoOpErrorHandler};
-pub use hal::{NetworkInterface, ObjectDictionaryStorage, PowerlinkError};
+pub use hal::{LinkStatus, NetworkInterface, ObjectDictionaryStorage, PowerlinkError};
 pub use node::cn::ControlledNode;
 pub use node::{Node, NodeAction};
 pub use pdo::{PdoError, PdoMappingEntry}; // Export PdoError
 pub use types::NodeId;
+
+/// Derive macro generating OD registrations and typed accessors for application objects.
+#[cfg(feature = "derive")]
+pub use powerlink_rs_derive::PowerlinkObjects;
@@ -8,6 +8,7 @@ use crate::frame::DllError;
 use crate::nmt::events::NmtEvent;
 use crate::od::{ObjectDictionary, ObjectValue};
 use crate::types::NodeId;
+use alloc::vec;
 use alloc::vec::Vec;
 use log::{debug, info, trace};
 
@@ -17,6 +18,14 @@ pub struct CnNmtStateMachine {
     pub node_id: NodeId,
     pub feature_flags: FeatureFlags,
     pub basic_ethernet_timeout: u32,
+    /// NMTEnableReadyToOperate was received in PreOperational2.
+    ready_to_operate_enabled: bool,
+    /// The application reported that it is configured.
+    configuration_complete: bool,
+    /// A reset passed through NMT_GS_RESET_COMMUNICATION since the node last checked.
+    communication_reset: bool,
+    /// State transitions not yet taken by the node, as (from, to).
+    transitions: Vec<(NmtState, NmtState)>,
 }
 
 impl CnNmtStateMachine {
@@ -27,6 +36,11 @@ impl CnNmtStateMachine {
             node_id,
             feature_flags,
             basic_ethernet_timeout,
+            ready_to_operate_enabled: false,
+            configuration_complete: false,
+            communication_reset: false,
+            // Powering on enters NMT_GS_INITIALISING.
+            transitions: vec![(NmtState::NmtGsOff, NmtState::NmtGsInitialising)],
         }
     }
 
@@ -78,9 +92,24 @@ impl NmtStateMachine for CnNmtStateMachine {
     }
 
     fn set_state(&mut self, new_state: NmtState) {
+        if new_state != self.current_state {
+            self.transitions.push((self.current_state, new_state));
+        }
         self.current_state = new_state;
     }
 
+    fn take_transitions(&mut self) -> Vec<(NmtState, NmtState)> {
+        core::mem::take(&mut self.transitions)
+    }
+
+    fn set_communication_reset(&mut self) {
+        self.communication_reset = true;
+    }
+
+    fn take_communication_reset(&mut self) -> bool {
+        core::mem::take(&mut self.communication_reset)
+    }
+
     /// Processes an external event and transitions the NMT state accordingly.
     fn process_event(
         &mut self,
@@ -99,6 +128,14 @@ impl NmtStateMachine for CnNmtStateMachine {
                 | NmtEvent::ResetCommunication
                 | NmtEvent::ResetConfiguration
         ) {
+            self.ready_to_operate_enabled = false;
+            // Only a reset of the application invalidates its configuration.
+            if matches!(
+                event,
+                NmtEvent::Reset | NmtEvent::SwReset | NmtEvent::ResetNode
+            ) {
+                self.configuration_complete = false;
+            }
             self.reset(event, od); // Pass OD to reset
             if old_state != self.current_state {
                 self.update_od_state(od);
@@ -129,12 +166,28 @@ impl NmtStateMachine for CnNmtStateMachine {
 
             // (NMT_CT5) The MN enables the next state, but we wait for application readiness.
             (NmtState::NmtPreOperational2, NmtEvent::EnableReadyToOperate) => {
-                debug!("Received EnableReadyToOperate, waiting for application confirmation.");
-                NmtState::NmtPreOperational2
+                self.ready_to_operate_enabled = true;
+                if self.configuration_complete {
+                    NmtState::NmtReadyToOperate
+                } else {
+                    debug!("Received EnableReadyToOperate, waiting for application confirmation.");
+                    NmtState::NmtPreOperational2
+                }
             }
-            // (NMT_CT6) The application signals it's ready, moving to ReadyToOperate.
+            // (NMT_CT6) The application signals it's ready, moving to ReadyToOperate
+            // once the MN has enabled it.
             (NmtState::NmtPreOperational2, NmtEvent::CnConfigurationComplete) => {
-                NmtState::NmtReadyToOperate
+                self.configuration_complete = true;
+                if self.ready_to_operate_enabled {
+                    NmtState::NmtReadyToOperate
+                } else {
+                    NmtState::NmtPreOperational2
+                }
+            }
+            // The application may be configured before the CN reaches PreOperational2.
+            (current, NmtEvent::CnConfigurationComplete) => {
+                self.configuration_complete = true;
+                current
             }
             // (NMT_CT7) The MN commands the CN to start full operation.
             (NmtState::NmtReadyToOperate, NmtEvent::StartNode) => NmtState::NmtOperational,
@@ -186,8 +239,11 @@ impl NmtStateMachine for CnNmtStateMachine {
                 "[NMT] State changed from {:?} to {:?}",
                 old_state, next_state
             );
-            self.current_state = next_state;
+            self.set_state(next_state);
             self.update_od_state(od);
+            if next_state != NmtState::NmtPreOperational2 {
+                self.ready_to_operate_enabled = false;
+            }
         }
 
         if errors.is_empty() {
@@ -292,6 +348,32 @@ mod tests {
         assert_eq!(od.read_u8(0x1F8C, 0), Some(NmtState::NmtNotActive as u8));
     }
 
+    #[test]
+    fn test_transitions_include_power_on_and_reset_steps() {
+        let mut od = get_test_od();
+        let mut nmt = get_test_nmt();
+        nmt.run_internal_initialisation(&mut od);
+        nmt.process_event(NmtEvent::SocSoAReceived, &mut od);
+        nmt.process_event(NmtEvent::ResetCommunication, &mut od);
+
+        use NmtState::*;
+        assert_eq!(
+            nmt.take_transitions(),
+            vec![
+                (NmtGsOff, NmtGsInitialising),
+                (NmtGsInitialising, NmtGsResetApplication),
+                (NmtGsResetApplication, NmtGsResetCommunication),
+                (NmtGsResetCommunication, NmtGsResetConfiguration),
+                (NmtGsResetConfiguration, NmtNotActive),
+                (NmtNotActive, NmtPreOperational1),
+                (NmtPreOperational1, NmtGsResetCommunication),
+                (NmtGsResetCommunication, NmtGsResetConfiguration),
+                (NmtGsResetConfiguration, NmtNotActive),
+            ]
+        );
+        assert!(nmt.take_transitions().is_empty());
+    }
+
     #[test]
     fn test_full_boot_up_happy_path() {
         let mut od = get_test_od();
@@ -315,6 +397,27 @@ mod tests {
         assert_eq!(od.read_u8(0x1F8C, 0), Some(NmtState::NmtOperational as u8));
     }
 
+    #[test]
+    fn test_configuration_complete_before_enable_ready_to_operate() {
+        let mut od = get_test_od();
+        let mut nmt = get_test_nmt();
+        nmt.current_state = NmtState::NmtPreOperational1;
+
+        // The application is ready before the MN enables ReadyToOperate.
+        nmt.process_event(NmtEvent::CnConfigurationComplete, &mut od);
+        nmt.process_event(NmtEvent::SocReceived, &mut od);
+        assert_eq!(nmt.current_state(), NmtState::NmtPreOperational2);
+
+        nmt.process_event(NmtEvent::EnableReadyToOperate, &mut od);
+        assert_eq!(nmt.current_state(), NmtState::NmtReadyToOperate);
+
+        // After a communication reset the MN has to enable it again.
+        nmt.process_event(NmtEvent::ResetCommunication, &mut od);
+        nmt.current_state = NmtState::NmtPreOperational2;
+        nmt.process_event(NmtEvent::CnConfigurationComplete, &mut od);
+        assert_eq!(nmt.current_state(), NmtState::NmtPreOperational2);
+    }
+
     #[test]
     fn test_error_handling_transition() {
         let mut od = get_test_od();
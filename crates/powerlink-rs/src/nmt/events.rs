@@ -34,6 +34,26 @@ impl TryFrom<u8> for NmtStateCommand {
     }
 }
 
+/// Offset between a state command ID and the ID of its extended variant.
+const EXTENDED_COMMAND_OFFSET: u8 = 0x20;
+
+impl NmtStateCommand {
+    /// Returns the ID of the extended variant (e.g. NMTStartNodeEx, 0x41),
+    /// which addresses the nodes set in a node list.
+    /// (Reference: EPSG DS 301, Section 7.3.1.2.3)
+    pub fn extended_id(self) -> u8 {
+        self as u8 + EXTENDED_COMMAND_OFFSET
+    }
+
+    /// Parses the ID of an extended state command.
+    pub fn try_from_extended(value: u8) -> Result<Self, PowerlinkError> {
+        match value {
+            0x41..=0x4B => Self::try_from(value - EXTENDED_COMMAND_OFFSET),
+            _ => Err(PowerlinkError::InvalidEnumValue),
+        }
+    }
+}
+
 /// Defines NMT Managing Command IDs used in ASnd(NMT_COMMAND) frames.
 /// (Reference: EPSG DS 301, Appendix 3.7, 0x60-0x7F)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,6 +120,8 @@ impl CnNmtRequest {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MnNmtCommandRequest {
     State(NmtStateCommand),
+    /// The extended variant of a state command, sent with a node list.
+    StateEx(NmtStateCommand),
     Managing(NmtManagingCommand),
 }
 
@@ -109,6 +131,7 @@ impl MnNmtCommandRequest {
     pub fn as_u8(&self) -> u8 {
         match self {
             MnNmtCommandRequest::State(cmd) => *cmd as u8,
+            MnNmtCommandRequest::StateEx(cmd) => cmd.extended_id(),
             MnNmtCommandRequest::Managing(cmd) => *cmd as u8,
         }
     }
@@ -7,6 +7,7 @@ use crate::frame::DllError;
 use crate::nmt::events::NmtEvent;
 use crate::od::{ObjectDictionary, ObjectValue};
 use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
+use alloc::vec;
 use alloc::vec::Vec;
 use log::{debug, info, warn};
 
@@ -17,6 +18,10 @@ pub struct MnNmtStateMachine {
     pub feature_flags: FeatureFlags,
     pub wait_not_active_timeout: u32,
     pub startup_flags: u32,
+    /// A reset passed through NMT_GS_RESET_COMMUNICATION since the node last checked.
+    communication_reset: bool,
+    /// State transitions not yet taken by the node, as (from, to).
+    transitions: Vec<(NmtState, NmtState)>,
 }
 
 impl MnNmtStateMachine {
@@ -33,6 +38,9 @@ impl MnNmtStateMachine {
             feature_flags,
             wait_not_active_timeout,
             startup_flags,
+            communication_reset: false,
+            // Powering on enters NMT_GS_INITIALISING.
+            transitions: vec![(NmtState::NmtGsOff, NmtState::NmtGsInitialising)],
         }
     }
 
@@ -89,9 +97,24 @@ impl NmtStateMachine for MnNmtStateMachine {
     }
 
     fn set_state(&mut self, new_state: NmtState) {
+        if new_state != self.current_state {
+            self.transitions.push((self.current_state, new_state));
+        }
         self.current_state = new_state;
     }
 
+    fn take_transitions(&mut self) -> Vec<(NmtState, NmtState)> {
+        core::mem::take(&mut self.transitions)
+    }
+
+    fn set_communication_reset(&mut self) {
+        self.communication_reset = true;
+    }
+
+    fn take_communication_reset(&mut self) -> bool {
+        core::mem::take(&mut self.communication_reset)
+    }
+
     /// Processes an external event and transitions the NMT state accordingly.
     /// The logic follows the MN state diagram (Figure 73) from the specification.
     fn process_event(
@@ -172,7 +195,7 @@ impl NmtStateMachine for MnNmtStateMachine {
                 "MN NMT state transition: {:?} -> {:?} (on event: {:?})",
                 old_state, next_state, event
             );
-            self.current_state = next_state;
+            self.set_state(next_state);
             self.update_od_state(od);
         }
 
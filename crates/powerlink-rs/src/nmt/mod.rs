@@ -3,8 +3,12 @@ pub mod cn_state_machine;
 pub mod events;
 pub mod flags;
 pub mod mn_state_machine;
+pub mod node_list;
 pub mod state_machine;
 pub mod states;
+#[cfg(feature = "sdo-udp")]
+pub mod udp;
 
 pub use events::NmtEvent;
+pub use node_list::NodeList;
 pub use state_machine::NmtStateMachine;
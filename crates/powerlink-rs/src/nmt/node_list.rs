@@ -0,0 +1,111 @@
+// crates/powerlink-rs/src/nmt/node_list.rs
+//! The 32-byte node bitmap used by extended NMT commands and NMT info services.
+//! (Reference: EPSG DS 301, Section 7.3.1.2.3)
+
+use crate::PowerlinkError;
+use crate::types::NodeId;
+
+/// Size of a node list in bytes.
+pub const NODE_LIST_SIZE: usize = 32;
+
+/// A set of Node IDs encoded as a POWERLINK node list, where Node ID `n`
+/// is bit `(n - 1) % 8` of byte `(n - 1) / 8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NodeList([u8; NODE_LIST_SIZE]);
+
+impl NodeList {
+    /// Creates an empty node list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a node list from the first 32 bytes of `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PowerlinkError> {
+        let list = bytes
+            .get(..NODE_LIST_SIZE)
+            .ok_or(PowerlinkError::BufferTooShort)?;
+        let mut out = [0u8; NODE_LIST_SIZE];
+        out.copy_from_slice(list);
+        Ok(Self(out))
+    }
+
+    /// Returns the encoded node list.
+    pub fn as_bytes(&self) -> &[u8; NODE_LIST_SIZE] {
+        &self.0
+    }
+
+    /// Adds a node. Node ID 0 and the broadcast address cannot be listed.
+    pub fn insert(&mut self, node_id: NodeId) {
+        if let Some((byte, mask)) = Self::position(node_id) {
+            self.0[byte] |= mask;
+        }
+    }
+
+    /// Removes a node.
+    pub fn remove(&mut self, node_id: NodeId) {
+        if let Some((byte, mask)) = Self::position(node_id) {
+            self.0[byte] &= !mask;
+        }
+    }
+
+    /// Returns true if the node is listed.
+    pub fn contains(&self, node_id: NodeId) -> bool {
+        Self::position(node_id).is_some_and(|(byte, mask)| self.0[byte] & mask != 0)
+    }
+
+    /// Returns true if no node is listed.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    /// Returns the number of listed nodes.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Iterates over the listed nodes in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (1..=254).map(NodeId).filter(|&id| self.contains(id))
+    }
+
+    fn position(node_id: NodeId) -> Option<(usize, u8)> {
+        match node_id.0 {
+            0 | 255 => None,
+            id => Some(((id - 1) as usize / 8, 1 << ((id - 1) % 8))),
+        }
+    }
+}
+
+impl FromIterator<NodeId> for NodeList {
+    fn from_iter<I: IntoIterator<Item = NodeId>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for node_id in iter {
+            list.insert(node_id);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_list_round_trip() {
+        let list: NodeList = [NodeId(1), NodeId(9), NodeId(240), NodeId(255)]
+            .into_iter()
+            .collect();
+        assert_eq!(list.as_bytes()[0], 0b1);
+        assert_eq!(list.as_bytes()[1], 0b1);
+        assert_eq!(list.as_bytes()[29], 0b1000_0000);
+        assert_eq!(list.len(), 3);
+
+        let parsed = NodeList::from_bytes(list.as_bytes()).unwrap();
+        assert_eq!(
+            parsed.iter().collect::<alloc::vec::Vec<_>>(),
+            [NodeId(1), NodeId(9), NodeId(240)]
+        );
+        assert!(!parsed.contains(NodeId(2)));
+        assert!(NodeList::from_bytes(&[0; 31]).is_err());
+    }
+}
@@ -15,9 +15,21 @@ pub trait NmtStateMachine {
     /// Returns the current NMT state.
     fn current_state(&self) -> NmtState;
 
-    /// Sets the internal NMT state. Required for default trait methods.
+    /// Sets the internal NMT state and records the transition, if any.
+    /// Required for default trait methods.
     fn set_state(&mut self, new_state: NmtState);
 
+    /// Returns the state transitions since the last call as (from, to), oldest first.
+    fn take_transitions(&mut self) -> Vec<(NmtState, NmtState)>;
+
+    /// Records that a reset passed through NMT_GS_RESET_COMMUNICATION.
+    /// Required for default trait methods.
+    fn set_communication_reset(&mut self);
+
+    /// Returns true once after a reset passed through NMT_GS_RESET_COMMUNICATION,
+    /// so the node can reload its communication parameters.
+    fn take_communication_reset(&mut self) -> bool;
+
     /// Processes an external event and transitions the NMT state accordingly.
     fn process_event(
         &mut self,
@@ -96,6 +108,7 @@ pub trait NmtStateMachine {
             info!("[NMT] NMT_GS_RESET_COMMUNICATION: Resetting Comm Parameters (0x1000-0x1FFF)");
             // Reset Communication Profile Area (0x1000 - 0x1FFF), excluding Error History
             od.restore_power_on_values(0x1000, 0x1FFF);
+            self.set_communication_reset();
         }
 
         // 4. NMT_GS_RESET_CONFIGURATION (ResetConfiguration)
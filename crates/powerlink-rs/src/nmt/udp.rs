@@ -0,0 +1,140 @@
+// crates/powerlink-rs/src/nmt/udp.rs
+//! UDP/IP framing of the NMTCommand and NMTRequest services.
+//!
+//! Nodes advertising `FeatureFlags::NMT_SERVICE_UDP` (0x1F82 bit 7) exchange
+//! these services over UDP with the same prefix as SDO/UDP, followed by the
+//! ASnd payload of the service.
+//!
+//! The datagrams reach a node through the UDP input of `run_cycle`, which only
+//! exists with the `sdo-udp` feature. NMT over UDP is therefore part of that
+//! feature rather than one of its own.
+
+use crate::PowerlinkError;
+use crate::frame::ServiceId;
+use crate::types::{MessageType, NodeId};
+use log::trace;
+
+/// MessageType(1) + Reserved(2) + ServiceID(1) = 4 bytes.
+const UDP_NMT_PREFIX_SIZE: usize = 4;
+
+/// An NMT service carried in a UDP datagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmtUdpService<'a> {
+    /// NMTCommand: [NMTCommandID(1), Reserved(1), NMTCommandData(0..n)].
+    Command { command_id: u8, data: &'a [u8] },
+    /// NMTRequest: [NMTRequestedCommandID(1), NMTRequestedCommandTarget(1),
+    /// NMTRequestedCommandData(0..n)].
+    Request {
+        command_id: u8,
+        target: NodeId,
+        data: &'a [u8],
+    },
+}
+
+/// Returns true if `buffer` starts with the UDP prefix of an NMT service,
+/// so a node can tell it apart from SDO/UDP traffic.
+pub fn is_nmt_udp_payload(buffer: &[u8]) -> bool {
+    buffer.len() >= UDP_NMT_PREFIX_SIZE
+        && buffer[0] == MessageType::ASnd as u8
+        && (buffer[3] == ServiceId::NmtCommand as u8 || buffer[3] == ServiceId::NmtRequest as u8)
+}
+
+/// Serializes an NMT service into a UDP payload buffer.
+///
+/// Returns the total number of bytes written to the buffer.
+pub fn serialize_nmt_udp_payload(
+    service: &NmtUdpService,
+    buffer: &mut [u8],
+) -> Result<usize, PowerlinkError> {
+    trace!("Serializing NMT UDP payload: {:?}", service);
+    let (service_id, command_id, second, data) = match *service {
+        NmtUdpService::Command { command_id, data } => {
+            (ServiceId::NmtCommand, command_id, 0u8, data)
+        }
+        NmtUdpService::Request {
+            command_id,
+            target,
+            data,
+        } => (ServiceId::NmtRequest, command_id, target.0, data),
+    };
+    let total_len = UDP_NMT_PREFIX_SIZE + 2 + data.len();
+    if buffer.len() < total_len {
+        return Err(PowerlinkError::BufferTooShort);
+    }
+
+    buffer[0] = MessageType::ASnd as u8;
+    buffer[1..3].copy_from_slice(&[0u8, 0u8]); // Reserved bytes
+    buffer[3] = service_id as u8;
+    buffer[4] = command_id;
+    buffer[5] = second;
+    buffer[6..total_len].copy_from_slice(data);
+    Ok(total_len)
+}
+
+/// Deserializes an NMT service from a received UDP payload buffer,
+/// starting with the POWERLINK UDP prefix.
+pub fn deserialize_nmt_udp_payload(buffer: &[u8]) -> Result<NmtUdpService<'_>, PowerlinkError> {
+    trace!(
+        "Deserializing NMT UDP payload ({} bytes): {:02X?}",
+        buffer.len(),
+        buffer
+    );
+    if buffer.len() < UDP_NMT_PREFIX_SIZE + 2 {
+        return Err(PowerlinkError::BufferTooShort);
+    }
+    if buffer[0] != MessageType::ASnd as u8 {
+        return Err(PowerlinkError::InvalidPlFrame);
+    }
+    let command_id = buffer[4];
+    let data = &buffer[6..];
+    match ServiceId::try_from(buffer[3]) {
+        Ok(ServiceId::NmtCommand) => Ok(NmtUdpService::Command { command_id, data }),
+        Ok(ServiceId::NmtRequest) => Ok(NmtUdpService::Request {
+            command_id,
+            target: NodeId(buffer[5]),
+            data,
+        }),
+        _ => Err(PowerlinkError::InvalidServiceId(buffer[3])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nmt_udp_payload_roundtrip() {
+        let mut buffer = [0u8; 64];
+        let request = NmtUdpService::Request {
+            command_id: 0x21,
+            target: NodeId(7),
+            data: &[],
+        };
+        let len = serialize_nmt_udp_payload(&request, &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &[0x06, 0, 0, 0x03, 0x21, 7]);
+        assert!(is_nmt_udp_payload(&buffer[..len]));
+        assert_eq!(deserialize_nmt_udp_payload(&buffer[..len]), Ok(request));
+
+        let command = NmtUdpService::Command {
+            command_id: 0x63,
+            data: &[3],
+        };
+        let len = serialize_nmt_udp_payload(&command, &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &[0x06, 0, 0, 0x04, 0x63, 0, 3]);
+        assert_eq!(deserialize_nmt_udp_payload(&buffer[..len]), Ok(command));
+    }
+
+    #[test]
+    fn test_nmt_udp_payload_rejects_other_services() {
+        let sdo = [0x06, 0, 0, ServiceId::Sdo as u8, 0, 0, 0, 0];
+        assert!(!is_nmt_udp_payload(&sdo));
+        assert_eq!(
+            deserialize_nmt_udp_payload(&sdo),
+            Err(PowerlinkError::InvalidServiceId(ServiceId::Sdo as u8))
+        );
+        assert_eq!(
+            deserialize_nmt_udp_payload(&[0x06, 0, 0, 0x04, 0x21]),
+            Err(PowerlinkError::BufferTooShort)
+        );
+    }
+}
@@ -1,25 +1,137 @@
 // crates/powerlink-rs/src/node/cn/events.rs
 use super::payload;
 use super::state::CnContext;
-use crate::common::NetTime;
-use crate::frame::error::{EntryType, ErrorEntry, ErrorEntryMode};
-use crate::frame::{ASndFrame, DllError, NmtAction, PowerlinkFrame, RequestedServiceId, ServiceId};
+use crate::frame::{NmtAction, PowerlinkFrame, RequestedServiceId, ServiceId};
 use crate::nmt::events::NmtEvent; // Removed NmtCommand
 use crate::nmt::state_machine::NmtStateMachine;
 use crate::nmt::states::NmtState;
 use crate::node::{NodeAction, PdoHandler, serialize_frame_action};
 use crate::od::constants; // Import the new constants module
+use crate::sdo::asnd::restore_trimmed_padding;
+use crate::sdo::command::SdoCommand;
+use crate::sdo::sequence::SequenceLayerHeader;
 use crate::sdo::server::SdoClientInfo;
 use crate::sdo::transport::SdoTransport;
-use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
+use crate::types::{C_ADR_BROADCAST_NODE_ID, NodeId};
 // --- NEW/MODIFIED IMPORTS ---
 use crate::nmt::events::{NmtManagingCommand, NmtServiceRequest, NmtStateCommand};
+use crate::nmt::node_list::NodeList;
 use crate::od::ObjectValue;
-use crate::od::error_history; // Import the new module
 use alloc::string::String;
 // --- END IMPORTS ---
 use crate::log::{my_debug, my_error, my_info, my_trace, my_warn};
 
+/// Maps an NMT State Command to the event it triggers in the NMT state machine.
+fn state_command_event(command: NmtStateCommand) -> NmtEvent {
+    match command {
+        NmtStateCommand::StartNode => NmtEvent::StartNode,
+        NmtStateCommand::StopNode => NmtEvent::StopNode,
+        NmtStateCommand::EnterPreOperational2 => NmtEvent::EnterPreOperational2,
+        NmtStateCommand::EnableReadyToOperate => NmtEvent::EnableReadyToOperate,
+        NmtStateCommand::ResetNode => NmtEvent::ResetNode,
+        NmtStateCommand::ResetCommunication => NmtEvent::ResetCommunication,
+        NmtStateCommand::ResetConfiguration => NmtEvent::ResetConfiguration,
+        NmtStateCommand::SwReset => NmtEvent::SwReset,
+    }
+}
+
+/// Handles the payload of an NMTCommand addressed to this node, whether it
+/// arrived in an ASnd frame or over UDP. Payload is [CmdID(1), Reserved(1), Data].
+/// Returns the event to pass to the NMT state machine for state commands.
+pub(super) fn handle_nmt_command(context: &mut CnContext, payload: &[u8]) -> Option<NmtEvent> {
+    let mut nmt_event = None;
+    if let Some(cmd_id_byte) = payload.first() {
+        // First, try to parse as an NMT State Command
+        if let Ok(cmd) = NmtStateCommand::try_from(*cmd_id_byte) {
+            // This is a state transition event
+            nmt_event = Some(state_command_event(cmd));
+        // Extended State Commands apply to the nodes set in their node list
+        // (Spec 7.3.1.2.3). Payload is [CmdID(1), Reserved(1), NodeList(32)]
+        } else if let Ok(cmd) = NmtStateCommand::try_from_extended(*cmd_id_byte) {
+            match payload.get(2..).map(NodeList::from_bytes) {
+                Some(Ok(nodes)) => {
+                    if nodes.contains(context.nmt_state_machine.node_id) {
+                        my_debug!("[CN] Received extended {:?} addressing this node.", cmd);
+                        nmt_event = Some(state_command_event(cmd));
+                    }
+                }
+                _ => {
+                    my_warn!(
+                        "[CN] Received extended NMT command with invalid payload length ({} bytes)",
+                        payload.len()
+                    );
+                }
+            }
+        // If not a state command, try to parse as an NMT Managing Command
+        } else if let Ok(cmd) = NmtManagingCommand::try_from(*cmd_id_byte) {
+            match cmd {
+                NmtManagingCommand::NmtNetHostNameSet => {
+                    // Spec 7.3.2.1.1 & Table 130
+                    // Payload is [CmdID(1), Reserved(1), HostName(32)]
+                    if payload.len() >= 34 {
+                        let hostname_bytes = &payload[2..34];
+                        // Find end of string (null terminator or end of slice)
+                        let len = hostname_bytes.iter().position(|&b| b == 0).unwrap_or(32);
+                        match String::from_utf8(hostname_bytes[..len].to_vec()) {
+                            Ok(hostname) => {
+                                my_info!("[CN] Received NmtNetHostNameSet: '{}'", hostname);
+                                // Write to OD 0x1F9A
+                                if let Err(e) = context.core.od.write_internal(
+                                    constants::IDX_NMT_HOST_NAME_VSTR, // 0x1F9A
+                                    0,
+                                    ObjectValue::VisibleString(hostname),
+                                    false, // Bypass access checks for internal write
+                                ) {
+                                    my_error!("[CN] Failed to write new hostname to OD: {:?}", e);
+                                }
+
+                                // Spec: "CN requests an IdentRequest to itself"
+                                my_info!("[CN] NmtNetHostNameSet: Queueing IdentRequest service.");
+                                // Use the new helper method on CnContext
+                                context.queue_nmt_service_request(
+                                    NmtServiceRequest::IdentRequest,
+                                    context.nmt_state_machine.node_id,
+                                );
+                            }
+                            Err(e) => {
+                                my_error!(
+                                    "[CN] Failed to parse hostname from NmtNetHostNameSet: {:?}",
+                                    e
+                                );
+                            }
+                        }
+                    } else {
+                        my_warn!(
+                            "[CN] Received NmtNetHostNameSet with invalid payload length ({} bytes)",
+                            payload.len()
+                        );
+                    }
+                }
+                NmtManagingCommand::NmtFlushArpEntry => {
+                    // Spec 7.3.2.1.2 & Table 132
+                    // Payload is [CmdID(1), Reserved(1), NodeID(1)]
+                    if payload.len() >= 3 {
+                        let node_to_flush = payload[2];
+                        my_info!(
+                            "[CN] Received NmtFlushArpEntry for Node ID {}. (ARP cache not yet implemented).",
+                            node_to_flush
+                        );
+                        // TODO: Add call to cn.arp_cache.flush(node_to_flush)
+                    } else {
+                        my_warn!(
+                            "[CN] Received NmtFlushArpEntry with invalid payload length ({} bytes)",
+                            payload.len()
+                        );
+                    }
+                }
+            }
+        } else {
+            my_warn!("[CN] Received unknown NMT Command ID: {:#04x}", cmd_id_byte);
+        }
+    }
+    nmt_event
+}
+
 /// Processes a deserialized `PowerlinkFrame`.
 pub(super) fn process_frame(
     context: &mut CnContext,
@@ -42,7 +154,14 @@ pub(super) fn process_frame(
             // SDO Rx logic is in main.rs, which has already incremented SdoRx.
             // We just need to handle the SDO Server logic here.
             my_debug!("[CN] Received SDO/ASnd frame for processing.");
-            let sdo_payload = &asnd_frame.payload;
+            let sdo_payload = &*restore_trimmed_padding(&asnd_frame.payload);
+
+            // Responses to our own client requests carry the command layer
+            // response flag; everything else is a request for our server.
+            if is_sdo_response(sdo_payload) {
+                handle_sdo_client_response(context, asnd_frame.source, sdo_payload);
+                return NodeAction::NoAction;
+            }
             let client_info = SdoClientInfo::Asnd {
                 source_node_id: asnd_frame.source,
                 source_mac: asnd_frame.eth_header.source_mac,
@@ -81,7 +200,10 @@ pub(super) fn process_frame(
                     return NodeAction::NoAction;
                 }
             };
-        } else if asnd_frame.destination == context.nmt_state_machine.node_id {
+        } else if asnd_frame.destination == context.nmt_state_machine.node_id
+            || (asnd_frame.destination.0 == C_ADR_BROADCAST_NODE_ID
+                && asnd_frame.service_id == ServiceId::NmtCommand)
+        {
             my_trace!("[CN] Received non-SDO ASnd frame: {:?}", asnd_frame);
             // Increment general AsyncRx counter for non-SDO ASnd frames
             context.core.od.increment_counter(
@@ -115,7 +237,12 @@ pub(super) fn process_frame(
                 .od
                 .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
                 .unwrap_or(0);
-            let new_err_reg = current_err_reg & !0b1;
+            // Active device errors keep the Generic Error bit set.
+            let new_err_reg = if context.device_errors.any_active() {
+                current_err_reg
+            } else {
+                current_err_reg & !0b1
+            };
             if let Err(e) = context.core.od.write_internal(
                 constants::IDX_NMT_ERROR_REGISTER_U8,
                 0,
@@ -147,17 +274,10 @@ pub(super) fn process_frame(
             if cycle_time_us > 0 {
                 let tolerance_us = tolerance_ns / 1000;
                 let deadline = current_time_us + cycle_time_us + tolerance_us;
-                match context.next_tick_us {
-                    Some(current_deadline) if deadline < current_deadline => {
-                        context.next_tick_us = Some(deadline);
-                        my_trace!("[CN] Scheduled SoC timeout check at {}us (earlier)", deadline);
-                    }
-                    None => {
-                        context.next_tick_us = Some(deadline);
-                        my_trace!("[CN] Scheduled SoC timeout check at {}us (first)", deadline);
-                    }
-                    _ => {}
-                }
+                // Each SoC re-arms the check: the deadline of the previous
+                // cycle would otherwise expire although this SoC arrived.
+                context.next_tick_us = Some(deadline);
+                my_trace!("[CN] Scheduled SoC timeout check at {}us", deadline);
             } else {
                 my_warn!("[CN] Cycle Time (0x1006) is 0, cannot schedule SoC timeout.");
                 context.soc_timeout_check_active = false;
@@ -276,97 +396,12 @@ pub(super) fn process_frame(
         PowerlinkFrame::Soc(_) => nmt_event = Some(NmtEvent::SocReceived),
         PowerlinkFrame::SoA(_) => nmt_event = Some(NmtEvent::SocSoAReceived),
         PowerlinkFrame::ASnd(asnd_frame)
-            if asnd_frame.destination == context.nmt_state_machine.node_id
+            if (asnd_frame.destination == context.nmt_state_machine.node_id
+                || asnd_frame.destination.0 == C_ADR_BROADCAST_NODE_ID)
                 && asnd_frame.service_id == ServiceId::NmtCommand =>
         {
-            // ... [Existing NMT Command parsing logic remains unchanged] ...
-            // This is an NMT command for us.
-            if let Some(cmd_id_byte) = asnd_frame.payload.first() {
-                // First, try to parse as an NMT State Command
-                if let Ok(cmd) = NmtStateCommand::try_from(*cmd_id_byte) {
-                    // This is a state transition event
-                    nmt_event = Some(match cmd {
-                        NmtStateCommand::StartNode => NmtEvent::StartNode,
-                        NmtStateCommand::StopNode => NmtEvent::StopNode,
-                        NmtStateCommand::EnterPreOperational2 => NmtEvent::EnterPreOperational2,
-                        NmtStateCommand::EnableReadyToOperate => NmtEvent::EnableReadyToOperate,
-                        NmtStateCommand::ResetNode => NmtEvent::ResetNode,
-                        NmtStateCommand::ResetCommunication => NmtEvent::ResetCommunication,
-                        NmtStateCommand::ResetConfiguration => NmtEvent::ResetConfiguration,
-                        NmtStateCommand::SwReset => NmtEvent::SwReset,
-                    });
-                // If not a state command, try to parse as an NMT Managing Command
-                } else if let Ok(cmd) = NmtManagingCommand::try_from(*cmd_id_byte) {
-                    match cmd {
-                        NmtManagingCommand::NmtNetHostNameSet => {
-                            // Spec 7.3.2.1.1 & Table 130
-                            // Payload is [CmdID(1), Reserved(1), HostName(32)]
-                            if asnd_frame.payload.len() >= 34 {
-                                let hostname_bytes = &asnd_frame.payload[2..34];
-                                // Find end of string (null terminator or end of slice)
-                                let len = hostname_bytes.iter().position(|&b| b == 0).unwrap_or(32);
-                                match String::from_utf8(hostname_bytes[..len].to_vec()) {
-                                    Ok(hostname) => {
-                                        my_info!("[CN] Received NmtNetHostNameSet: '{}'", hostname);
-                                        // Write to OD 0x1F9A
-                                        if let Err(e) = context.core.od.write_internal(
-                                            constants::IDX_NMT_HOST_NAME_VSTR, // 0x1F9A
-                                            0,
-                                            ObjectValue::VisibleString(hostname),
-                                            false, // Bypass access checks for internal write
-                                        ) {
-                                            my_error!(
-                                                "[CN] Failed to write new hostname to OD: {:?}",
-                                                e
-                                            );
-                                        }
-
-                                        // Spec: "CN requests an IdentRequest to itself"
-                                        my_info!(
-                                            "[CN] NmtNetHostNameSet: Queueing IdentRequest service."
-                                        );
-                                        // Use the new helper method on CnContext
-                                        context.queue_nmt_service_request(
-                                            NmtServiceRequest::IdentRequest,
-                                            context.nmt_state_machine.node_id,
-                                        );
-                                    }
-                                    Err(e) => {
-                                        my_error!(
-                                            "[CN] Failed to parse hostname from NmtNetHostNameSet: {:?}",
-                                            e
-                                        );
-                                    }
-                                }
-                            } else {
-                                my_warn!(
-                                    "[CN] Received NmtNetHostNameSet with invalid payload length ({} bytes)",
-                                    asnd_frame.payload.len()
-                                );
-                            }
-                        }
-                        NmtManagingCommand::NmtFlushArpEntry => {
-                            // Spec 7.3.2.1.2 & Table 132
-                            // Payload is [CmdID(1), Reserved(1), NodeID(1)]
-                            if asnd_frame.payload.len() >= 3 {
-                                let node_to_flush = asnd_frame.payload[2];
-                                my_info!(
-                                    "[CN] Received NmtFlushArpEntry for Node ID {}. (ARP cache not yet implemented).",
-                                    node_to_flush
-                                );
-                                // TODO: Add call to cn.arp_cache.flush(node_to_flush)
-                            } else {
-                                my_warn!(
-                                    "[CN] Received NmtFlushArpEntry with invalid payload length ({} bytes)",
-                                    asnd_frame.payload.len()
-                                );
-                            }
-                        }
-                    }
-                } else {
-                    my_warn!("[CN] Received unknown NMT Command ID: {:#04x}", cmd_id_byte);
-                }
-            }
+            // This is an NMT command for us (or for all nodes).
+            nmt_event = handle_nmt_command(context, &asnd_frame.payload);
         }
         _ => {}
     };
@@ -384,79 +419,7 @@ pub(super) fn process_frame(
     {
         for error in errors {
             my_warn!("[CN] DLL state machine reported error: {:?}", error);
-            // Increment history write counter for every error handled
-            context.core.od.increment_counter(
-                constants::IDX_DIAG_ERR_STATISTICS_REC,
-                constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE,
-            );
-
-            let (nmt_action, signaled) = context.dll_error_manager.handle_error(error);
-            if signaled {
-                context.error_status_changed = true;
-                let current_err_reg = context
-                    .core
-                    .od
-                    .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
-                    .unwrap_or(0);
-                let new_err_reg = current_err_reg | 0b1;
-                if current_err_reg != new_err_reg {
-                    // Only increment if the value actually changed
-                    context.core.od.increment_counter(
-                        constants::IDX_DIAG_ERR_STATISTICS_REC,
-                        constants::SUBIDX_DIAG_ERR_STATS_STATIC_ERR_CHG,
-                    );
-                }
-                if let Err(e) = context.core.od.write_internal(
-                    constants::IDX_NMT_ERROR_REGISTER_U8,
-                    0,
-                    crate::od::ObjectValue::Unsigned8(new_err_reg),
-                    false,
-                ) {
-                    my_error!("[CN] Failed to update Error Register: {:?}", e)
-                }
-
-                let error_entry = ErrorEntry {
-                    entry_type: EntryType {
-                        is_status_entry: false,
-                        send_to_queue: true,
-                        mode: ErrorEntryMode::EventOccurred,
-                        profile: 0x002,
-                    },
-                    error_code: error.to_error_code(),
-                    timestamp: NetTime {
-                        seconds: (current_time_us / 1_000_000) as u32,
-                        nanoseconds: ((current_time_us % 1_000_000) * 1000) as u32,
-                    },
-                    additional_information: match error {
-                        DllError::LossOfPres { node_id }
-                        | DllError::LatePres { node_id }
-                        | DllError::LossOfStatusRes { node_id } => node_id.0 as u64,
-                        _ => 0,
-                    },
-                };
-                if context.emergency_queue.len() < context.emergency_queue.capacity() {
-                    context.emergency_queue.push_back(error_entry.clone());
-                    // *** NEW: Write to Error History OD ***
-                    error_history::write_error_to_history(&mut context.core.od, &error_entry);
-                    
-                    my_info!("[CN] New error queued: {:?}", error_entry);
-                    // Increment emergency write counter
-                    context.core.od.increment_counter(
-                        constants::IDX_DIAG_ERR_STATISTICS_REC,
-                        constants::SUBIDX_DIAG_ERR_STATS_EMCY_WRITE,
-                    );
-                } else {
-                    my_warn!(
-                        "[CN] Emergency queue full, dropping error: {:?}",
-                        error_entry
-                    );
-                    // Increment emergency overflow counter
-                    context.core.od.increment_counter(
-                        constants::IDX_DIAG_ERR_STATISTICS_REC,
-                        constants::SUBIDX_DIAG_ERR_STATS_EMCY_OVERFLOW,
-                    );
-                }
-            }
+            let nmt_action = context.signal_dll_error(error, current_time_us);
             if nmt_action != NmtAction::None {
                 my_info!("[CN] DLL error triggered NMT action: {:?}", nmt_action);
                 context
@@ -529,12 +492,13 @@ pub(super) fn process_frame(
                                     constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
                                     constants::SUBIDX_DIAG_NMT_COUNT_ASYNC_TX,
                                 );
+                                let sdo_requests = context.pending_sdo_request_count_and_priority();
                                 Some(payload::build_ident_response(
                                     context.core.mac_address,
                                     context.nmt_state_machine.node_id,
                                     &context.core.od,
                                     soa_frame,
-                                    &context.core.sdo_client,
+                                    sdo_requests,
                                     &context.pending_nmt_requests,
                                 ))
                             }
@@ -544,6 +508,7 @@ pub(super) fn process_frame(
                                     constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
                                     constants::SUBIDX_DIAG_NMT_COUNT_ASYNC_TX,
                                 );
+                                let sdo_requests = context.pending_sdo_request_count_and_priority();
                                 Some(payload::build_status_response(
                                     context.core.mac_address,
                                     context.nmt_state_machine.node_id,
@@ -551,8 +516,9 @@ pub(super) fn process_frame(
                                     context.en_flag,
                                     context.ec_flag,
                                     &mut context.emergency_queue,
+                                    &context.device_errors,
                                     soa_frame,
-                                    &context.core.sdo_client,
+                                    sdo_requests,
                                     &context.pending_nmt_requests,
                                 ))
                             }
@@ -572,25 +538,20 @@ pub(super) fn process_frame(
                                     )
                                 })
                             }
-                            RequestedServiceId::UnspecifiedInvite => context
-                                .core
-                                .sdo_client
-                                .pop_pending_request()
-                                .map(|sdo_payload| {
+                            RequestedServiceId::UnspecifiedInvite => {
+                                payload::build_sdo_client_request(
+                                    context,
+                                    soa_frame,
+                                    current_time_us,
+                                )
+                                .inspect(|_| {
                                     // *** INCREMENT SDO TX COUNTER (ASnd Request) ***
                                     context.core.od.increment_counter(
                                         constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
                                         constants::SUBIDX_DIAG_NMT_COUNT_SDO_TX,
                                     );
-                                    PowerlinkFrame::ASnd(ASndFrame::new(
-                                        context.core.mac_address,
-                                        soa_frame.eth_header.source_mac,
-                                        NodeId(C_ADR_MN_DEF_NODE_ID),
-                                        context.nmt_state_machine.node_id,
-                                        ServiceId::Sdo,
-                                        sdo_payload.1,
-                                    ))
-                                }),
+                                })
+                            }
                             RequestedServiceId::NoService => None,
                         },
                         _ => None,
@@ -636,4 +597,40 @@ pub(super) fn process_frame(
     }
 
     NodeAction::NoAction
-}
\ No newline at end of file
+}
+
+/// Returns true if an SDO/ASnd payload is a response from a server, i.e.
+/// the response flag in the command layer header is set.
+fn is_sdo_response(sdo_payload: &[u8]) -> bool {
+    // 4 bytes sequence layer header, then the command layer flags at offset 2.
+    sdo_payload.get(6).is_some_and(|flags| flags & 0x10 != 0)
+}
+
+/// Passes a response from a remote SDO server to the CN's SDO client.
+fn handle_sdo_client_response(context: &mut CnContext, source: NodeId, sdo_payload: &[u8]) {
+    if sdo_payload.len() < 12 {
+        my_warn!("[CN] Received SDO response with invalid payload length. Ignoring.");
+        return;
+    }
+    let seq_header = match SequenceLayerHeader::deserialize(&sdo_payload[0..4]) {
+        Ok(seq_header) => seq_header,
+        Err(e) => {
+            my_error!(
+                "[CN] Failed to deserialize SDO sequence header from Node {}: {:?}",
+                source.0,
+                e
+            );
+            return;
+        }
+    };
+    match SdoCommand::deserialize(&sdo_payload[4..]) {
+        Ok(cmd) => context
+            .sdo_client_manager
+            .handle_response(source, seq_header, cmd),
+        Err(e) => my_error!(
+            "[CN] Failed to deserialize SDO command from Node {}: {:?}",
+            source.0,
+            e
+        ),
+    }
+}
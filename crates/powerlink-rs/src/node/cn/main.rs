@@ -5,31 +5,47 @@ use alloc::format;
 use super::state::CnContext;
 use crate::PowerlinkError;
 use crate::frame::basic::MacAddress;
-use crate::frame::error::{CnErrorCounters, DllErrorManager, LoggingErrorHandler};
+use crate::frame::error::{
+    CnErrorCounters, DeviceError, DeviceErrors, DllErrorManager, ErrorEntryMode, ErrorHandler,
+    LoggingErrorHandler,
+};
 use crate::frame::{DllError, NmtAction, ServiceId, deserialize_frame};
+use crate::hal::LinkStatus;
 use crate::nmt::cn_state_machine::CnNmtStateMachine;
 use crate::nmt::events::NmtEvent;
 use crate::nmt::events::{CnNmtRequest, NmtStateCommand};
 use crate::nmt::state_machine::NmtStateMachine;
 use crate::nmt::states::NmtState;
-use crate::node::{CoreNodeContext, Node, NodeAction};
+use crate::node::{CoreNodeContext, Node, NodeAction, NodeEventQueue, TimedEvent};
 use crate::od::{Object, ObjectDictionary, ObjectValue, constants};
+use crate::pdo::ProcessImage;
+use crate::sdo::command::{MultipleParamEntry, WriteMultipleParamEntry};
 use crate::sdo::transport::AsndTransport;
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::transport::UdpTransport;
-use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
+use crate::sdo::{
+    EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoClientManager, SdoServer, SdoTransferResult,
+};
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::{
     server::SdoClientInfo, transport::SdoTransport, udp::deserialize_sdo_udp_payload,
 };
 #[cfg(feature = "sdo-udp")]
+use crate::nmt::{
+    flags::FeatureFlags,
+    udp::{NmtUdpService, deserialize_nmt_udp_payload, is_nmt_udp_payload},
+};
+#[cfg(feature = "sdo-udp")]
+use crate::node::ip_from_node_id;
+#[cfg(feature = "sdo-udp")]
 use crate::types::IpAddress;
 use crate::types::{C_ADR_MN_DEF_NODE_ID, MessageType, NodeId};
+use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, VecDeque};
 use alloc::vec::Vec;
 #[cfg(feature = "sdo-udp")]
-use log::debug;
-use log::{error, info, warn};
+use log::{debug, error};
+use log::{info, warn};
 
 /// Represents a complete POWERLINK Controlled Node (CN).
 /// This struct is a thin wrapper around a context object that holds all state.
@@ -44,9 +60,18 @@ impl<'s> ControlledNode<'s> {
     /// with device-specific parameters (e.g., Identity Object 0x1018) before passing
     /// it to this constructor. This function will then read the necessary configuration
     /// from the OD to initialize the NMT state machine.
-    pub fn new(
+    ///
+    /// DLL errors are logged with `LoggingErrorHandler`.
+    pub fn new(od: ObjectDictionary<'s>, mac_address: MacAddress) -> Result<Self, PowerlinkError> {
+        Self::with_error_handler(od, mac_address, Box::new(LoggingErrorHandler))
+    }
+
+    /// Creates a new Controlled Node that reports DLL errors to `error_handler`,
+    /// e.g. to route them into an alarm system.
+    pub fn with_error_handler(
         mut od: ObjectDictionary<'s>,
         mac_address: MacAddress,
+        error_handler: Box<dyn ErrorHandler>,
     ) -> Result<Self, PowerlinkError> {
         info!("Creating new Controlled Node.");
         // Initialise the OD, which involves loading from storage or applying defaults.
@@ -100,6 +125,8 @@ impl<'s> ControlledNode<'s> {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         let mut node = Self {
@@ -107,15 +134,14 @@ impl<'s> ControlledNode<'s> {
                 core: core_context, // Use the new core context
                 nmt_state_machine,
                 dll_state_machine: Default::default(),
-                dll_error_manager: DllErrorManager::new(
-                    CnErrorCounters::new(),
-                    LoggingErrorHandler,
-                ),
+                dll_error_manager: DllErrorManager::new(CnErrorCounters::new(), error_handler),
+                sdo_client_manager: SdoClientManager::new(),
                 asnd_transport: AsndTransport,
                 #[cfg(feature = "sdo-udp")]
                 udp_transport: UdpTransport,
                 pending_nmt_requests: Vec::new(),
                 emergency_queue: VecDeque::with_capacity(10), // Default capacity for 10 errors
+                device_errors: DeviceErrors::new(),
                 heartbeat_consumers,                          // Add the new map
                 last_soc_reception_time_us: 0,
                 soc_timeout_check_active: false,
@@ -131,6 +157,7 @@ impl<'s> ControlledNode<'s> {
         node.context
             .nmt_state_machine
             .run_internal_initialisation(&mut node.context.core.od); // Access OD through core
+        node.apply_communication_reset();
 
         Ok(node)
     }
@@ -142,6 +169,255 @@ impl<'s> ControlledNode<'s> {
             .queue_sdo_request(NodeId(C_ADR_MN_DEF_NODE_ID), payload);
     }
 
+    /// Initiates an SDO Read (Upload) transfer from the MN or another CN.
+    ///
+    /// The request is sent once the MN grants an asynchronous slot; the
+    /// outcome is reported through `poll_sdo_result`.
+    pub fn read_object(
+        &mut self,
+        target: NodeId,
+        index: u16,
+        sub_index: u8,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO Read from Node {} for 0x{:04X}/{}",
+            target.0, index, sub_index
+        );
+        self.context.sdo_client_manager.read_object_by_index(
+            target,
+            index,
+            sub_index,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO Write (Download) transfer to the MN or another CN.
+    pub fn write_object(
+        &mut self,
+        target: NodeId,
+        index: u16,
+        sub_index: u8,
+        data: Vec<u8>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO Write to Node {} for 0x{:04X}/{} ({} bytes)",
+            target.0,
+            index,
+            sub_index,
+            data.len()
+        );
+        self.context.sdo_client_manager.write_object_by_index(
+            target,
+            index,
+            sub_index,
+            data,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO ReadByName transfer from the MN or another CN.
+    pub fn read_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: &str,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadByName from Node {} for '{}'",
+            target.0, name
+        );
+        self.context.sdo_client_manager.read_object_by_name(
+            target,
+            name.into(),
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO WriteByName transfer to the MN or another CN.
+    pub fn write_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: &str,
+        data: Vec<u8>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO WriteByName to Node {} for '{}' ({} bytes)",
+            target.0,
+            name,
+            data.len()
+        );
+        self.context.sdo_client_manager.write_object_by_name(
+            target,
+            name.into(),
+            data,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO ReadAllByIndex transfer, reading all sub-indices of an object.
+    pub fn read_all_by_index(
+        &mut self,
+        target: NodeId,
+        index: u16,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadAllByIndex from Node {} for 0x{:04X}",
+            target.0, index
+        );
+        self.context.sdo_client_manager.read_all_by_index(
+            target,
+            index,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO ReadMultipleParamByIndex transfer from the MN or another CN.
+    pub fn read_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<MultipleParamEntry>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadMultipleParamByIndex from Node {} ({} entries)",
+            target.0,
+            entries.len()
+        );
+        self.context.sdo_client_manager.read_multiple_params(
+            target,
+            entries,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO WriteMultipleParamByIndex transfer to the MN or another CN.
+    pub fn write_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<WriteMultipleParamEntry>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO WriteMultipleParamByIndex to Node {} ({} entries)",
+            target.0,
+            entries.len()
+        );
+        self.context.sdo_client_manager.write_multiple_params(
+            target,
+            entries,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Sets the SDO sequence layer window used for transfers to `target`:
+    /// the number of download segments sent before waiting for an ACK.
+    pub fn set_sdo_window_size(&mut self, target: NodeId, window_size: u8) {
+        self.context
+            .sdo_client_manager
+            .set_window_size(target, window_size);
+    }
+
+    /// Returns the outcome of the oldest finished SDO client transfer, if any.
+    pub fn poll_sdo_result(&mut self) -> Option<SdoTransferResult> {
+        self.context.sdo_client_manager.pop_completed()
+    }
+
+    /// Enables the process image: a contiguous input/output buffer laid out
+    /// from the active RPDO/TPDO mappings. Mapped objects are then exchanged
+    /// through the image instead of the Object Dictionary, and the image is
+    /// rebuilt automatically when the mappings change.
+    pub fn enable_process_image(&mut self) -> &mut ProcessImage {
+        self.context.core.enable_process_image()
+    }
+
+    /// Returns the process image, if enabled.
+    pub fn process_image(&mut self) -> Option<&mut ProcessImage> {
+        self.context.core.refresh_process_image();
+        self.context.core.process_image.as_mut()
+    }
+
+    /// Returns the device-specific static error bits, e.g. to name them from
+    /// the XDC `<StaticErrorBitField>` or to look up a bit offset by name.
+    pub fn device_errors(&mut self) -> &mut DeviceErrors {
+        &mut self.context.device_errors
+    }
+
+    /// Raises a device profile or vendor-specific error.
+    ///
+    /// Sets the error's static bit and Error Register bits, and reports an
+    /// "error active" entry via the emergency queue and Error History (0x1003).
+    /// Raising an error that is already active has no effect.
+    pub fn raise_device_error(
+        &mut self,
+        error: &DeviceError,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        self.context
+            .signal_device_error(error, ErrorEntryMode::ErrorActive, current_time_us)
+    }
+
+    /// Clears a previously raised device error and reports an "error cleared" entry.
+    pub fn clear_device_error(
+        &mut self,
+        error: &DeviceError,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        self.context
+            .signal_device_error(error, ErrorEntryMode::ErrorCleared, current_time_us)
+    }
+
+    /// Reports a one-off device event, without changing any static error bits.
+    pub fn report_device_event(
+        &mut self,
+        error: &DeviceError,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        self.context
+            .signal_device_error(error, ErrorEntryMode::EventOccurred, current_time_us)
+    }
+
+    /// Processes the link state queried from the `NetworkInterface`.
+    ///
+    /// Raises `E_DLL_LOSS_OF_LINK` when the link goes down, `E_DLL_BAD_PHYS_MODE`
+    /// when it comes up in the wrong mode and `E_DLL_MAC_BUFFER` when frames were
+    /// dropped. Each is counted, logged and reported in the Error History.
+    /// (Reference: EPSG DS 301, Section 4.7.4)
+    pub fn report_link_status(&mut self, status: LinkStatus, current_time_us: u64) {
+        let context = &mut self.context;
+        for error in context.dll_error_manager.link_errors(status) {
+            warn!("[CN] Link status reported error: {:?}", error);
+            if context.signal_dll_error(error, current_time_us) != NmtAction::None {
+                context
+                    .nmt_state_machine
+                    .process_event(NmtEvent::Error, &mut context.core.od);
+                context.soc_timeout_check_active = false;
+            }
+        }
+        context.dll_error_manager.sync_od(&mut context.core.od);
+    }
+
+    /// Signals that the application has finished its configuration (NMT_CT6).
+    ///
+    /// The CN enters ReadyToOperate as soon as it is in PreOperational2 and
+    /// the MN has sent NMTEnableReadyToOperate. The signal may be given at any
+    /// time and is kept until the application is reset.
+    pub fn confirm_configuration_complete(&mut self) {
+        self.context
+            .nmt_state_machine
+            .process_event(NmtEvent::CnConfigurationComplete, &mut self.context.core.od);
+    }
+
     /// Allows the application to queue an NMT state command request to be sent to the MN.
     /// (Reference: EPSG DS 301, Section 7.3.6)
     pub fn queue_nmt_request(&mut self, command: NmtStateCommand, target: NodeId) {
@@ -196,29 +472,9 @@ impl<'s> ControlledNode<'s> {
                     buffer.len()
                 );
                 // Report as InvalidFormat DLL error
-                let (nmt_action, signaled) = self
+                let nmt_action = self
                     .context
-                    .dll_error_manager
-                    .handle_error(DllError::InvalidFormat);
-                if signaled {
-                    self.context.error_status_changed = true;
-                    // Update Error Register (0x1001), Set Bit 0: Generic Error
-                    let current_err_reg = self
-                        .context
-                        .core
-                        .od
-                        .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
-                        .unwrap_or(0);
-                    let new_err_reg = current_err_reg | 0b1;
-                    if let Err(e) = self.context.core.od.write_internal(
-                        constants::IDX_NMT_ERROR_REGISTER_U8,
-                        0,
-                        crate::od::ObjectValue::Unsigned8(new_err_reg),
-                        false,
-                    ) {
-                        error!("[CN] Failed to update Error Register: {:?}", e);
-                    }
-                }
+                    .signal_dll_error(DllError::InvalidFormat, current_time_us);
                 // Trigger NMT error handling if required
                 if nmt_action != NmtAction::None {
                     self.context
@@ -231,6 +487,50 @@ impl<'s> ControlledNode<'s> {
         }
     }
 
+    /// Handles an NMTCommand received over UDP/IP. Only accepted with
+    /// NMT_SERVICE_UDP set in the feature flags (0x1F82 bit 7) and from the
+    /// MN's address, derived from the MN's Node ID.
+    #[cfg(feature = "sdo-udp")]
+    fn process_nmt_udp_service(&mut self, buffer: &[u8], source_ip: IpAddress) {
+        if !self
+            .context
+            .nmt_state_machine
+            .feature_flags
+            .contains(FeatureFlags::NMT_SERVICE_UDP)
+        {
+            warn!("[CN] Ignoring NMT service over UDP: NMT_SERVICE_UDP is not enabled.");
+            return;
+        }
+        if source_ip != ip_from_node_id(NodeId(C_ADR_MN_DEF_NODE_ID)) {
+            warn!(
+                "[CN] Ignoring NMT service over UDP from {}, which is not the MN.",
+                core::net::Ipv4Addr::from(source_ip)
+            );
+            return;
+        }
+        match deserialize_nmt_udp_payload(buffer) {
+            Ok(NmtUdpService::Command { .. }) => {
+                self.context.core.od.increment_counter(
+                    constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
+                    constants::SUBIDX_DIAG_NMT_COUNT_ASYNC_RX,
+                );
+                // The command bytes follow the UDP prefix, as in an ASnd payload.
+                if let Some(event) = events::handle_nmt_command(&mut self.context, &buffer[4..]) {
+                    self.context
+                        .nmt_state_machine
+                        .process_event(event, &mut self.context.core.od);
+                }
+            }
+            Ok(NmtUdpService::Request { command_id, .. }) => {
+                warn!(
+                    "[CN] Ignoring NMTRequest {:#04x} over UDP; only the MN serves requests.",
+                    command_id
+                );
+            }
+            Err(e) => warn!("[CN] Failed to deserialize NMT/UDP payload: {:?}", e),
+        }
+    }
+
     /// Processes a UDP datagram payload for SDO over UDP.
     #[cfg(feature = "sdo-udp")]
     fn process_udp_datagram(
@@ -247,6 +547,11 @@ impl<'s> ControlledNode<'s> {
             source_port
         );
 
+        if is_nmt_udp_payload(buffer) {
+            self.process_nmt_udp_service(buffer, source_ip);
+            return NodeAction::NoAction;
+        }
+
         // 1. Deserialize the SDO payload from the UDP datagram
         let (seq_header, cmd) = match deserialize_sdo_udp_payload(buffer) {
             Ok((seq, cmd)) => (seq, cmd),
@@ -304,6 +609,39 @@ impl<'s> ControlledNode<'s> {
         }
     }
 
+    /// Returns the oldest node event not yet polled.
+    pub fn poll_event(&mut self) -> Option<TimedEvent> {
+        self.context.core.events.poll()
+    }
+
+    /// Returns the node's event queue, e.g. to inspect recent events without
+    /// consuming them or to change its capacity.
+    pub fn events(&mut self) -> &mut NodeEventQueue {
+        &mut self.context.core.events
+    }
+
+    /// Reloads the DLL error thresholds and counts from the OD after a reset
+    /// passed through NMT_GS_RESET_COMMUNICATION.
+    fn apply_communication_reset(&mut self) {
+        let context = &mut self.context;
+        if context.nmt_state_machine.take_communication_reset() {
+            context.dll_error_manager.load_from_od(&context.core.od);
+        }
+    }
+
+    /// Moves the events gathered during a cycle into the node's event queue.
+    fn finish_cycle(&mut self, action: NodeAction) -> NodeAction {
+        self.apply_communication_reset();
+        let context = &mut self.context;
+        context.dll_error_manager.sync_od(&mut context.core.od);
+        context.core.events.collect(
+            context.nmt_state_machine.take_transitions(),
+            context.dll_error_manager.take_reported(),
+            context.sdo_client_manager.take_finished(),
+        );
+        action
+    }
+
     /// Internal tick handler, moved from the trait implementation.
     fn tick(&mut self, current_time_us: u64) -> NodeAction {
         // Call the new tick module
@@ -319,6 +657,7 @@ impl<'s> Node for ControlledNode<'s> {
         udp_datagram: Option<(&[u8], IpAddress, u16)>,
         current_time_us: u64,
     ) -> NodeAction {
+        self.context.core.events.set_time(current_time_us);
         // --- Priority 1: Ethernet Frames ---
         if let Some(buffer) = ethernet_frame {
             // Check for POWERLINK EtherType
@@ -328,7 +667,7 @@ impl<'s> Node for ControlledNode<'s> {
                 let action = self.process_ethernet_frame(buffer, current_time_us);
                 if action != NodeAction::NoAction {
                     // SDO Tx counter (for ASnd) is handled inside events::process_frame
-                    return action;
+                    return self.finish_cycle(action);
                 }
             }
             // Ignore non-POWERLINK Ethernet frames
@@ -345,16 +684,18 @@ impl<'s> Node for ControlledNode<'s> {
                 );
             }
             if action != NodeAction::NoAction {
-                return action;
+                return self.finish_cycle(action);
             }
         }
 
         // --- Priority 3: Internal Ticks ---
-        self.tick(current_time_us)
+        let action = self.tick(current_time_us);
+        self.finish_cycle(action)
     }
 
     #[cfg(not(feature = "sdo-udp"))]
     fn run_cycle(&mut self, ethernet_frame: Option<&[u8]>, current_time_us: u64) -> NodeAction {
+        self.context.core.events.set_time(current_time_us);
         // --- Priority 1: Ethernet Frames ---
         if let Some(buffer) = ethernet_frame {
             // Check for POWERLINK EtherType
@@ -364,14 +705,15 @@ impl<'s> Node for ControlledNode<'s> {
                 let action = self.process_ethernet_frame(buffer, current_time_us);
                 if action != NodeAction::NoAction {
                     // SDO Tx counter (for ASnd) is handled inside events::process_frame
-                    return action;
+                    return self.finish_cycle(action);
                 }
             }
             // Ignore non-POWERLINK Ethernet frames
         }
 
         // --- Priority 2: Internal Ticks ---
-        self.tick(current_time_us)
+        let action = self.tick(current_time_us);
+        self.finish_cycle(action)
     }
 
     fn nmt_state(&self) -> NmtState {
@@ -379,7 +721,14 @@ impl<'s> Node for ControlledNode<'s> {
     }
 
     fn next_action_time(&self) -> Option<u64> {
-        self.context.next_tick_us
+        let sdo_deadline = self
+            .context
+            .sdo_client_manager
+            .next_action_time(&self.context.core.od);
+        match (self.context.next_tick_us, sdo_deadline) {
+            (Some(tick), Some(sdo)) => Some(tick.min(sdo)),
+            (tick, sdo) => tick.or(sdo),
+        }
     }
 }
 
@@ -390,4 +739,94 @@ impl LogMetadata for ControlledNode<'_> {
             self.context.nmt_state_machine.node_id.0
         )
     }
-}
\ No newline at end of file
+}
+#[cfg(all(test, feature = "sdo-udp"))]
+mod tests {
+    use super::*;
+    use crate::nmt::udp::serialize_nmt_udp_payload;
+    use crate::od::ObjectEntry;
+    use alloc::vec;
+
+    fn create_node() -> ControlledNode<'static> {
+        let mut od = crate::od::utils::new_cn_default(NodeId(1)).unwrap();
+        od.insert(
+            0x1000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Unsigned32(0x12345678)),
+                ..Default::default()
+            },
+        );
+        let mut node = ControlledNode::new(od, MacAddress([0x02, 0, 0, 0, 0, 1])).unwrap();
+        node.context.nmt_state_machine.current_state = NmtState::NmtPreOperational2;
+        node
+    }
+
+    fn stop_node_datagram() -> Vec<u8> {
+        let mut buffer = vec![0u8; 64];
+        let len = serialize_nmt_udp_payload(
+            &NmtUdpService::Command {
+                command_id: NmtStateCommand::StopNode as u8,
+                data: &[],
+            },
+            &mut buffer,
+        )
+        .unwrap();
+        buffer.truncate(len);
+        buffer
+    }
+
+    #[test]
+    fn test_nmt_command_over_udp_requires_mn_source() {
+        let mut node = create_node();
+        node.context
+            .nmt_state_machine
+            .feature_flags
+            .insert(FeatureFlags::NMT_SERVICE_UDP);
+        let datagram = stop_node_datagram();
+
+        // The host part matches the MN, but the network does not.
+        node.process_udp_datagram(&datagram, [10, 0, 0, C_ADR_MN_DEF_NODE_ID], 3819, 0);
+        assert_eq!(node.nmt_state(), NmtState::NmtPreOperational2);
+
+        node.process_udp_datagram(&datagram, ip_from_node_id(NodeId(5)), 3819, 0);
+        assert_eq!(node.nmt_state(), NmtState::NmtPreOperational2);
+
+        let mn_ip = ip_from_node_id(NodeId(C_ADR_MN_DEF_NODE_ID));
+        node.process_udp_datagram(&datagram, mn_ip, 3819, 0);
+        assert_eq!(node.nmt_state(), NmtState::NmtCsStopped);
+    }
+
+    #[test]
+    fn test_nmt_command_over_udp_requires_feature_flag() {
+        let mut node = create_node();
+        node.context
+            .nmt_state_machine
+            .feature_flags
+            .remove(FeatureFlags::NMT_SERVICE_UDP);
+
+        let mn_ip = ip_from_node_id(NodeId(C_ADR_MN_DEF_NODE_ID));
+        node.process_udp_datagram(&stop_node_datagram(), mn_ip, 3819, 0);
+        assert_eq!(node.nmt_state(), NmtState::NmtPreOperational2);
+    }
+
+    #[test]
+    fn test_malformed_frame_is_not_reported_below_threshold() {
+        let mut node = create_node();
+        // A SoC with the POWERLINK EtherType, cut off after its message type.
+        let mut buffer = vec![0u8; 15];
+        buffer[12..14].copy_from_slice(&0x88ABu16.to_be_bytes());
+        buffer[14] = MessageType::SoC as u8;
+        node.process_ethernet_frame(&buffer, 1_000_000);
+
+        // The CN does not count invalid frames, so nothing is signalled.
+        assert!(node.context.emergency_queue.is_empty());
+        assert!(!node.context.error_status_changed);
+        assert_eq!(
+            node.context.core.od.read_u32(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE,
+            ),
+            Some(0)
+        );
+    }
+}
@@ -1,7 +1,8 @@
 // crates/powerlink-rs/src/node/cn/payload.rs
+use crate::frame::PRFlag;
 use crate::frame::basic::MacAddress;
 use crate::frame::control::{IdentResponsePayload, StaticErrorBitField, StatusResponsePayload};
-use crate::frame::error::ErrorEntry;
+use crate::frame::error::{DeviceErrors, ErrorEntry};
 use crate::frame::poll::{PResFlags, RSFlag};
 use crate::frame::{ASndFrame, PResFrame, PowerlinkFrame, ServiceId};
 use crate::nmt::NmtStateMachine;
@@ -9,8 +10,8 @@ use crate::nmt::events::CnNmtRequest;
 use crate::nmt::states::NmtState;
 use crate::od::constants;
 use crate::pdo::PDOVersion;
-use crate::sdo::SdoClient;
-use crate::types::C_ADR_MN_DEF_NODE_ID;
+use crate::sdo::asnd::serialize_sdo_asnd_payload;
+use crate::types::{C_ADR_MN_DEF_NODE_ID, C_DLL_MULTICAST_ASND};
 use crate::{od::ObjectDictionary, types::NodeId};
 use alloc::collections::VecDeque;
 use alloc::vec;
@@ -24,7 +25,7 @@ pub(super) fn build_ident_response(
     node_id: NodeId,
     od: &ObjectDictionary,
     soa: &crate::frame::SoAFrame,
-    sdo_client: &SdoClient,
+    sdo_requests: (u8, PRFlag),
     pending_nmt_requests: &[(CnNmtRequest, NodeId)],
 ) -> PowerlinkFrame {
     debug!("Building IdentResponse for SoA from node {}", soa.source.0);
@@ -37,7 +38,7 @@ pub(super) fn build_ident_response(
             crate::frame::PRFlag::PrioNmtRequest,
         )
     } else {
-        sdo_client.pending_request_count_and_priority()
+        sdo_requests
     };
     payload_struct.pr = pr_flag;
     payload_struct.rs = RSFlag::new(rs_count);
@@ -75,8 +76,9 @@ pub(super) fn build_status_response(
     en_flag: bool,
     ec_flag: bool,
     emergency_queue: &mut VecDeque<ErrorEntry>,
+    device_errors: &DeviceErrors,
     soa: &crate::frame::SoAFrame,
-    sdo_client: &SdoClient,
+    sdo_requests: (u8, PRFlag),
     pending_nmt_requests: &[(CnNmtRequest, NodeId)],
 ) -> PowerlinkFrame {
     debug!("Building StatusResponse for SoA from node {}", soa.source.0);
@@ -86,7 +88,7 @@ pub(super) fn build_status_response(
         .and_then(|val| NmtState::try_from(val).ok())
         .unwrap_or(NmtState::NmtNotActive);
 
-    let static_errors = StaticErrorBitField::new(od);
+    let static_errors = StaticErrorBitField::new(od, device_errors);
 
     let (rs_count, pr_flag) = if !pending_nmt_requests.is_empty() {
         (
@@ -94,7 +96,7 @@ pub(super) fn build_status_response(
             crate::frame::PRFlag::PrioNmtRequest,
         )
     } else {
-        sdo_client.pending_request_count_and_priority()
+        sdo_requests
     };
 
     let mtu = od
@@ -178,6 +180,48 @@ pub(super) fn build_nmt_request(
     PowerlinkFrame::ASnd(asnd)
 }
 
+/// Builds the SDO/ASnd frame sent in response to an UnspecifiedInvite.
+///
+/// Frames of the stateful SDO client take precedence over raw payloads
+/// queued with `queue_sdo_request`. Requests to the MN are addressed to the
+/// SoA sender; requests to other CNs use the ASnd multicast address.
+pub(super) fn build_sdo_client_request(
+    context: &mut CnContext,
+    soa: &crate::frame::SoAFrame,
+    current_time_us: u64,
+) -> Option<PowerlinkFrame> {
+    let node_id = context.nmt_state_machine.node_id();
+    let (target, sdo_payload) = if let Some((target, seq, cmd)) = context
+        .sdo_client_manager
+        .get_pending_request(current_time_us, &context.core.od)
+    {
+        match serialize_sdo_asnd_payload(seq, cmd) {
+            Ok(sdo_payload) => (target, sdo_payload),
+            Err(e) => {
+                error!("Failed to serialize SDO client request: {:?}", e);
+                return None;
+            }
+        }
+    } else {
+        context.core.sdo_client.pop_pending_request()?
+    };
+
+    let dest_mac = if target == NodeId(C_ADR_MN_DEF_NODE_ID) {
+        soa.eth_header.source_mac
+    } else {
+        MacAddress(C_DLL_MULTICAST_ASND)
+    };
+    debug!("Building SDO ASnd request for Node {}", target.0);
+    Some(PowerlinkFrame::ASnd(ASndFrame::new(
+        context.core.mac_address,
+        dest_mac,
+        target,
+        node_id,
+        ServiceId::Sdo,
+        sdo_payload,
+    )))
+}
+
 pub(super) fn build_pres_response(context: &mut CnContext, en_flag: bool) -> PowerlinkFrame {
     let node_id = context.nmt_state_machine.node_id();
     let nmt_state = context.nmt_state_machine.current_state();
@@ -212,7 +256,7 @@ pub(super) fn build_pres_response(context: &mut CnContext, en_flag: bool) -> Pow
             crate::frame::PRFlag::PrioNmtRequest,
         )
     } else {
-        context.core.sdo_client.pending_request_count_and_priority()
+        context.pending_sdo_request_count_and_priority()
     };
 
     let flags = PResFlags {
@@ -235,7 +279,6 @@ mod tests {
     use crate::frame::{RequestedServiceId, ServiceId, SoAFrame};
     use crate::nmt::states::NmtState;
     use crate::od::{Object, ObjectDictionary, ObjectEntry, ObjectValue};
-    use crate::sdo::SdoClient;
     use crate::types::{EPLVersion, NodeId};
     use alloc::collections::VecDeque;
     use alloc::vec;
@@ -322,7 +365,6 @@ mod tests {
     #[test]
     fn test_build_ident_response() {
         let od = setup_od();
-        let sdo_client = SdoClient::new();
         let pending_nmt = Vec::new();
 
         let soa = SoAFrame::new(
@@ -344,7 +386,7 @@ mod tests {
             NodeId(10),
             &od,
             soa_ref,
-            &sdo_client,
+            (0, PRFlag::default()),
             &pending_nmt,
         );
 
@@ -372,10 +414,114 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_build_pres_packs_narrow_types() {
+        use crate::frame::cs_state_machine::DllCsStateMachine;
+        use crate::frame::error::{CnErrorCounters, DllErrorManager, LoggingErrorHandler};
+        use crate::nmt::cn_state_machine::CnNmtStateMachine;
+        use crate::node::CoreNodeContext;
+        use crate::pdo::PdoMappingEntry;
+        use crate::sdo::transport::AsndTransport;
+        #[cfg(feature = "sdo-udp")]
+        use crate::sdo::transport::UdpTransport;
+        use crate::sdo::{
+            EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoClientManager, SdoServer,
+        };
+        use crate::types::BitString;
+        use alloc::boxed::Box;
+        use alloc::collections::BTreeMap;
+
+        let mut od = setup_od();
+        od.insert(
+            0x2000,
+            ObjectEntry {
+                object: Object::Record(vec![
+                    ObjectValue::Integer24(-2),
+                    ObjectValue::Unsigned40(0x12_3456_789A),
+                    ObjectValue::BitString(BitString::new(12, 0xABC)),
+                ]),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            constants::IDX_TPDO_COMM_PARAM_REC_START,
+            ObjectEntry {
+                object: Object::Record(vec![ObjectValue::Unsigned8(0), ObjectValue::Unsigned8(0)]),
+                ..Default::default()
+            },
+        );
+        let mapping = |sub_index: u8, offset_bits: u16, length_bits: u16| {
+            ObjectValue::Unsigned64(
+                PdoMappingEntry {
+                    index: 0x2000,
+                    sub_index,
+                    offset_bits,
+                    length_bits,
+                }
+                .to_u64(),
+            )
+        };
+        od.insert(
+            constants::IDX_TPDO_MAPPING_PARAM_REC_START,
+            ObjectEntry {
+                object: Object::Array(vec![
+                    mapping(1, 0, 24),
+                    mapping(2, 24, 40),
+                    mapping(3, 64, 16),
+                ]),
+                ..Default::default()
+            },
+        );
+
+        let mut context = CnContext {
+            core: CoreNodeContext {
+                od,
+                mac_address: MacAddress::default(),
+                sdo_server: SdoServer::new(),
+                sdo_client: SdoClient::new(),
+                embedded_sdo_server: EmbeddedSdoServer::new(),
+                embedded_sdo_client: EmbeddedSdoClient::new(),
+                process_image: None,
+                events: Default::default(),
+            },
+            nmt_state_machine: CnNmtStateMachine::new(NodeId(1), Default::default(), 0),
+            dll_state_machine: DllCsStateMachine::default(),
+            dll_error_manager: DllErrorManager::new(
+                CnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
+            sdo_client_manager: SdoClientManager::new(),
+            asnd_transport: AsndTransport,
+            #[cfg(feature = "sdo-udp")]
+            udp_transport: UdpTransport,
+            pending_nmt_requests: Vec::new(),
+            emergency_queue: VecDeque::new(),
+            device_errors: DeviceErrors::new(),
+            heartbeat_consumers: BTreeMap::new(),
+            last_soc_reception_time_us: 0,
+            soc_timeout_check_active: false,
+            next_tick_us: None,
+            en_flag: false,
+            ec_flag: false,
+            error_status_changed: false,
+        };
+
+        let PowerlinkFrame::PRes(pres) = build_pres_response(&mut context, false) else {
+            panic!("Wrong frame type");
+        };
+        assert_eq!(
+            pres.payload[..10],
+            [
+                0xFE, 0xFF, 0xFF, // INTEGER24 -2
+                0x9A, 0x78, 0x56, 0x34, 0x12, // UNSIGNED40
+                0xBC, 0x0A, // 12-bit BIT string
+            ]
+        );
+    }
+
     #[test]
     fn test_build_status_response_flags() {
         let mut od = setup_od();
-        let sdo_client = SdoClient::new();
         let mut emergency_queue = VecDeque::new();
         let pending_nmt = Vec::new();
 
@@ -400,8 +546,9 @@ mod tests {
             true,
             false,
             &mut emergency_queue,
+            &DeviceErrors::new(),
             soa_ref,
-            &sdo_client,
+            (0, PRFlag::default()),
             &pending_nmt,
         );
 
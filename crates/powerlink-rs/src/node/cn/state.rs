@@ -2,19 +2,24 @@
 use crate::ErrorHandler;
 use crate::PowerlinkError;
 use crate::frame::DllCsStateMachine;
+use crate::common::NetTime;
 use crate::frame::error::{
-    CnErrorCounters, DllErrorManager, ErrorCounters, ErrorEntry, LoggingErrorHandler,
+    CnErrorCounters, DeviceError, DeviceErrors, DllError, DllErrorManager, EntryType,
+    ErrorCounters, ErrorEntry, ErrorEntryMode, NmtAction,
 };
 use crate::log::LogMetadata;
 use crate::nmt::cn_state_machine::CnNmtStateMachine;
 use crate::nmt::events::{CnNmtRequest, NmtServiceRequest}; // Import NmtServiceRequest
 use crate::node::{CoreNodeContext, NodeContext, PdoHandler};
-use crate::od::{ObjectValue, constants};
+use crate::od::{ObjectValue, constants, error_history};
 use crate::pdo::{PDOVersion, PdoMappingEntry, error::PdoError};
+use crate::frame::PRFlag;
+use crate::sdo::SdoClientManager;
 use crate::sdo::transport::AsndTransport;
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::transport::UdpTransport;
 use crate::types::NodeId;
+use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, VecDeque};
 use alloc::vec;
 use alloc::vec::Vec;
@@ -26,7 +31,9 @@ pub struct CnContext<'s> {
     pub nmt_state_machine: CnNmtStateMachine,
     pub dll_state_machine: DllCsStateMachine,
     // dll_error_manager is separated due to its generic parameters
-    pub dll_error_manager: DllErrorManager<CnErrorCounters, LoggingErrorHandler>,
+    pub dll_error_manager: DllErrorManager<CnErrorCounters, Box<dyn ErrorHandler>>,
+    /// Stateful SDO client for accessing the OD of the MN or of other CNs.
+    pub sdo_client_manager: SdoClientManager,
     /// SDO transport handler for ASnd.
     pub asnd_transport: AsndTransport,
     /// SDO transport handler for UDP.
@@ -36,6 +43,8 @@ pub struct CnContext<'s> {
     pub pending_nmt_requests: Vec<(CnNmtRequest, NodeId)>,
     /// Queue for detailed error/event entries to be reported in StatusResponse.
     pub emergency_queue: VecDeque<ErrorEntry>,
+    /// Device profile and vendor-specific errors raised by the application.
+    pub device_errors: DeviceErrors,
     /// Map of nodes to monitor via heartbeat, mapping NodeId -> (Timeout in us, LastSeen time in us).
     pub heartbeat_consumers: BTreeMap<NodeId, (u64, u64)>,
     /// Timestamp of the last successfully received SoC frame (microseconds).
@@ -87,6 +96,175 @@ impl<'s> CnContext<'s> {
             .push((CnNmtRequest::Service(service), target));
     }
 
+    /// Writes an entry to the Error History (0x1003) and adds it to the
+    /// emergency queue. If the queue is full, the entry is only recorded in
+    /// the history and the overflow counter incremented.
+    pub(super) fn queue_error_entry(&mut self, error_entry: ErrorEntry) {
+        error_history::write_error_to_history(&mut self.core.od, &error_entry);
+        self.core.od.increment_counter(
+            constants::IDX_DIAG_ERR_STATISTICS_REC,
+            constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE,
+        );
+        if self.emergency_queue.len() < self.emergency_queue.capacity() {
+            self.emergency_queue.push_back(error_entry);
+
+            info!("[CN] New error queued: {:?}", error_entry);
+            // Increment emergency write counter
+            self.core.od.increment_counter(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_EMCY_WRITE,
+            );
+        } else {
+            warn!(
+                "[CN] Emergency queue full, dropping error: {:?}",
+                error_entry
+            );
+            // Increment emergency overflow counter
+            self.core.od.increment_counter(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_EMCY_OVERFLOW,
+            );
+        }
+    }
+
+    /// Raises (`ErrorActive`), clears (`ErrorCleared`) or reports
+    /// (`EventOccurred`) a device-specific error.
+    ///
+    /// Status changes update the static error bits and the Error Register
+    /// (0x1001). Every change queues an error entry and toggles the EN flag
+    /// with the next frame. Raising an active error or clearing an inactive
+    /// one does nothing.
+    pub(super) fn signal_device_error(
+        &mut self,
+        error: &DeviceError,
+        mode: ErrorEntryMode,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        let register_before = self.device_errors.error_register();
+        let changed = match mode {
+            ErrorEntryMode::ErrorActive => self.device_errors.activate(error)?,
+            ErrorEntryMode::ErrorCleared => self.device_errors.deactivate(error)?,
+            ErrorEntryMode::EventOccurred => true,
+            ErrorEntryMode::Terminator => {
+                return Err(PowerlinkError::InvalidEnumValue);
+            }
+        };
+        if !changed {
+            return Ok(());
+        }
+
+        if mode != ErrorEntryMode::EventOccurred {
+            let current_err_reg = self
+                .core
+                .od
+                .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
+                .unwrap_or(0);
+            let mut new_err_reg =
+                (current_err_reg & !register_before) | self.device_errors.error_register();
+            if self.device_errors.any_active() {
+                new_err_reg |= 0b1; // Generic Error
+            } else if !self.dll_error_manager.counters.is_any_active() {
+                new_err_reg &= !0b1;
+            }
+            if new_err_reg != current_err_reg
+                && let Err(e) = self.core.od.write_internal(
+                    constants::IDX_NMT_ERROR_REGISTER_U8,
+                    0,
+                    ObjectValue::Unsigned8(new_err_reg),
+                    false,
+                )
+            {
+                error!("[CN] Failed to update Error Register: {:?}", e);
+            }
+            // The static error bit field changes with every status change.
+            self.core.od.increment_counter(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_STATIC_ERR_CHG,
+            );
+        }
+
+        self.queue_error_entry(ErrorEntry {
+            entry_type: EntryType {
+                is_status_entry: false,
+                send_to_queue: true,
+                mode,
+                profile: error.profile,
+            },
+            error_code: error.error_code,
+            timestamp: NetTime {
+                seconds: (current_time_us / 1_000_000) as u32,
+                nanoseconds: ((current_time_us % 1_000_000) * 1000) as u32,
+            },
+            additional_information: error.additional_information,
+        });
+        self.error_status_changed = true;
+        Ok(())
+    }
+
+    /// Passes a DLL error to the error manager and, if it is signallable,
+    /// sets the generic bit of the Error Register (0x1001) and reports an
+    /// "event occurred" entry. Returns the NMT action the error calls for.
+    pub(super) fn signal_dll_error(&mut self, error: DllError, current_time_us: u64) -> NmtAction {
+        let (nmt_action, signaled) = self.dll_error_manager.handle_error(error);
+        if !signaled {
+            return nmt_action;
+        }
+        self.error_status_changed = true;
+        let current_err_reg = self
+            .core
+            .od
+            .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
+            .unwrap_or(0);
+        let new_err_reg = current_err_reg | 0b1;
+        if new_err_reg != current_err_reg {
+            self.core.od.increment_counter(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_STATIC_ERR_CHG,
+            );
+            if let Err(e) = self.core.od.write_internal(
+                constants::IDX_NMT_ERROR_REGISTER_U8,
+                0,
+                ObjectValue::Unsigned8(new_err_reg),
+                false,
+            ) {
+                error!("[CN] Failed to update Error Register: {:?}", e);
+            }
+        }
+        self.queue_error_entry(ErrorEntry {
+            entry_type: EntryType {
+                is_status_entry: false,
+                send_to_queue: true,
+                mode: ErrorEntryMode::EventOccurred,
+                profile: 0x002,
+            },
+            error_code: error.to_error_code(),
+            timestamp: NetTime {
+                seconds: (current_time_us / 1_000_000) as u32,
+                nanoseconds: ((current_time_us % 1_000_000) * 1000) as u32,
+            },
+            additional_information: match error {
+                DllError::LossOfPres { node_id }
+                | DllError::LatePres { node_id }
+                | DllError::LossOfStatusRes { node_id } => node_id.0 as u64,
+                _ => 0,
+            },
+        });
+        nmt_action
+    }
+
+    /// Returns the number of pending SDO client frames and their priority,
+    /// covering both raw queued payloads and stateful client connections.
+    /// This is used to set the RS/PR flags in PRes and StatusResponse frames.
+    pub(super) fn pending_sdo_request_count_and_priority(&self) -> (u8, PRFlag) {
+        let count = self.core.sdo_client.pending_request_count_and_priority().0 as usize
+            + self.sdo_client_manager.pending_request_count();
+        if count > 0 {
+            (count.min(7) as u8, PRFlag::PrioGenericRequest)
+        } else {
+            (0, PRFlag::default())
+        }
+    }
+
     /// Fills a buffer with the CN's TPDO payload.
     ///
     /// This implementation is for a CN, which can only have one
@@ -98,6 +276,7 @@ impl<'s> CnContext<'s> {
         // 1. Get the TPDO mapping (1A00h for a CN's PRes).
         let mapping_index = constants::IDX_TPDO_MAPPING_PARAM_REC_START; // 0x1A00
         let comm_param_index = constants::IDX_TPDO_COMM_PARAM_REC_START; // 0x1800
+        self.core.refresh_process_image();
 
         // 2. Get Mapping Version from 0x1800/2
         let pdo_version = PDOVersion(
@@ -254,6 +433,13 @@ impl<'s> CnContext<'s> {
         }
         // --- END SDO-in-PDO LOGIC ---
 
+        // Mapped objects held in the process image are sent from the image.
+        if let Some(image) = &self.core.process_image
+            && image.load_output(entry.index, entry.sub_index, data_slice)
+        {
+            return Ok(());
+        }
+
         // Read the value from the OD
         let Some(value) = self.core.od.read(entry.index, entry.sub_index) else {
             warn!(
@@ -3,15 +3,12 @@
 //! Includes SDO timeouts, Heartbeat monitoring, and NMT state timeouts.
 
 use super::state::CnContext;
-use crate::common::NetTime;
-use crate::frame::error::{EntryType, ErrorEntry, ErrorEntryMode};
 use crate::frame::{DllCsEvent, DllError, NmtAction};
 use crate::nmt::events::NmtEvent;
 use crate::nmt::state_machine::NmtStateMachine;
 use crate::nmt::states::NmtState;
-use crate::node::NodeAction;
+use crate::node::{NodeAction, NodeEvent};
 use crate::od::constants;
-use crate::od::error_history; 
 use crate::sdo::server::SdoClientInfo;
 use crate::sdo::transport::SdoTransport;
 use alloc::vec::Vec;
@@ -57,6 +54,17 @@ pub(crate) fn process_tick(context: &mut CnContext, current_time_us: u64) -> Nod
         _ => {} 
     }
 
+    // --- SDO Client Timeouts ---
+    // A CN may only transmit when invited, so retransmissions and aborts are
+    // kept by the client manager and sent on the next UnspecifiedInvite.
+    context
+        .sdo_client_manager
+        .poll_timeouts(current_time_us, &context.core.od);
+    context
+        .core
+        .embedded_sdo_client
+        .tick(current_time_us, &context.core.od);
+
     let current_nmt_state = context.nmt_state_machine.current_state();
 
     // --- Heartbeat Consumer Check ---
@@ -82,34 +90,14 @@ pub(crate) fn process_tick(context: &mut CnContext, current_time_us: u64) -> Nod
 
         // Handle errors outside the mutable borrow
         for node_id in timed_out_nodes {
+            context
+                .core
+                .events
+                .push(NodeEvent::HeartbeatTimeout { node_id });
             // Log error as HeartbeatTimeout (Custom DLL Error)
-            let (nmt_action, signaled) = context
-                .dll_error_manager
-                .handle_error(DllError::HeartbeatTimeout { node_id });
-
-            // Spec 6.5: Error Signaling (Update Error Register, Queue Emergency)
-            if signaled {
-                context.error_status_changed = true;
-                let current_err_reg = context
-                    .core
-                    .od
-                    .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
-                    .unwrap_or(0);
-                let new_err_reg = current_err_reg | 0b1; 
-                if current_err_reg != new_err_reg {
-                     context.core.od.increment_counter(
-                        constants::IDX_DIAG_ERR_STATISTICS_REC,
-                        constants::SUBIDX_DIAG_ERR_STATS_STATIC_ERR_CHG,
-                    );
-                    let _ = context.core.od.write_internal(
-                        constants::IDX_NMT_ERROR_REGISTER_U8,
-                        0,
-                        crate::od::ObjectValue::Unsigned8(new_err_reg),
-                        false,
-                    );
-                }
-            }
-            
+            let nmt_action =
+                context.signal_dll_error(DllError::HeartbeatTimeout { node_id }, current_time_us);
+
             // Check if error triggers NMT state transition (e.g., to PreOp1)
             if nmt_action != NmtAction::None {
                 context
@@ -182,77 +170,7 @@ pub(crate) fn process_tick(context: &mut CnContext, current_time_us: u64) -> Nod
             .process_event(DllCsEvent::SocTimeout, current_nmt_state)
         {
             for error in errors {
-                context.core.od.increment_counter(
-                    constants::IDX_DIAG_ERR_STATISTICS_REC,
-                    constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE,
-                );
-                let (nmt_action, signaled) = context.dll_error_manager.handle_error(error);
-                
-                if signaled {
-                    context.error_status_changed = true;
-                    // Update Error Register (0x1001)
-                    let current_err_reg = context
-                        .core
-                        .od
-                        .read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0)
-                        .unwrap_or(0);
-                    let new_err_reg = current_err_reg | 0b1; // Set Generic Error
-                    if current_err_reg != new_err_reg {
-                        context.core.od.increment_counter(
-                            constants::IDX_DIAG_ERR_STATISTICS_REC,
-                            constants::SUBIDX_DIAG_ERR_STATS_STATIC_ERR_CHG,
-                        );
-                    }
-                    let _ = context.core.od.write_internal(
-                        constants::IDX_NMT_ERROR_REGISTER_U8,
-                        0,
-                        crate::od::ObjectValue::Unsigned8(new_err_reg),
-                        false,
-                    );
-
-                    // Queue Emergency
-                    let error_entry = ErrorEntry {
-                        entry_type: EntryType {
-                            is_status_entry: false,
-                            send_to_queue: true,
-                            mode: ErrorEntryMode::EventOccurred,
-                            profile: 0x002,
-                        },
-                        error_code: error.to_error_code(),
-                        timestamp: NetTime {
-                            seconds: (current_time_us / 1_000_000) as u32,
-                            nanoseconds: ((current_time_us % 1_000_000) * 1000) as u32,
-                        },
-                        additional_information: match error {
-                            DllError::LossOfPres { node_id }
-                            | DllError::LatePres { node_id }
-                            | DllError::LossOfStatusRes { node_id } => node_id.0 as u64,
-                            _ => 0,
-                        },
-                    };
-                    if context.emergency_queue.len() < context.emergency_queue.capacity() {
-                        context.emergency_queue.push_back(error_entry.clone());
-                        error_history::write_error_to_history(&mut context.core.od, &error_entry);
-                        
-                        my_trace!("[CN] New error queued: {:?}", error_entry);
-                        // Increment emergency write counter
-                        context.core.od.increment_counter(
-                            constants::IDX_DIAG_ERR_STATISTICS_REC,
-                            constants::SUBIDX_DIAG_ERR_STATS_EMCY_WRITE,
-                        );
-                    } else {
-                        my_warn!(
-                            "[CN] Emergency queue full, dropping error: {:?}",
-                            error_entry
-                        );
-                        // Increment emergency overflow counter
-                        context.core.od.increment_counter(
-                            constants::IDX_DIAG_ERR_STATISTICS_REC,
-                            constants::SUBIDX_DIAG_ERR_STATS_EMCY_OVERFLOW,
-                        );
-                    }
-                }
-                
+                let nmt_action = context.signal_dll_error(error, current_time_us);
                 // Handle NMT State Transition (ResetCommunication -> PreOp1)
                 if nmt_action != NmtAction::None {
                     context
@@ -310,9 +228,12 @@ pub(crate) fn process_tick(context: &mut CnContext, current_time_us: u64) -> Nod
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::NetTime;
     use crate::frame::DllCsEvent;
     use crate::frame::cs_state_machine::DllCsStateMachine;
-    use crate::frame::error::{CnErrorCounters, DllErrorManager, LoggingErrorHandler};
+    use crate::frame::error::{
+        CnErrorCounters, DeviceErrors, DllErrorManager, ErrorEntryMode, LoggingErrorHandler,
+    };
     use crate::nmt::cn_state_machine::CnNmtStateMachine;
     use crate::node::CoreNodeContext;
     use crate::node::cn::state::CnContext;
@@ -320,8 +241,11 @@ mod tests {
     use crate::sdo::transport::AsndTransport;
     #[cfg(feature = "sdo-udp")]
     use crate::sdo::transport::UdpTransport;
-    use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
+    use crate::sdo::{
+        EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoClientManager, SdoServer,
+    };
     use crate::types::NodeId;
+    use alloc::boxed::Box;
     use alloc::collections::{BTreeMap, VecDeque};
     use alloc::vec;
     use alloc::vec::Vec;
@@ -364,18 +288,25 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         CnContext {
             core,
             nmt_state_machine: CnNmtStateMachine::new(NodeId(1), Default::default(), 0),
             dll_state_machine: DllCsStateMachine::default(),
-            dll_error_manager: DllErrorManager::new(CnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(
+                CnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
+            sdo_client_manager: SdoClientManager::new(),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
             pending_nmt_requests: Vec::new(),
             emergency_queue: VecDeque::new(),
+            device_errors: DeviceErrors::new(),
             heartbeat_consumers: BTreeMap::new(),
             last_soc_reception_time_us: 0,
             soc_timeout_check_active: false,
@@ -386,9 +317,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sdo_client_retransmit_waits_for_invite() {
+        let mut context = create_context();
+        context
+            .sdo_client_manager
+            .read_object_by_index(NodeId(240), 0x1000, 0, 0, &context.core.od)
+            .unwrap();
+        assert_eq!(
+            context.pending_sdo_request_count_and_priority(),
+            (1, crate::frame::PRFlag::PrioGenericRequest)
+        );
+
+        // The MN invites the CN and the connection request goes out.
+        let (target, first, _) = context
+            .sdo_client_manager
+            .get_pending_request(0, &context.core.od)
+            .unwrap();
+        assert_eq!(target, NodeId(240));
+        assert_eq!(context.pending_sdo_request_count_and_priority().0, 0);
+
+        // No response within the SDO timeout: the retransmission is not sent
+        // from the tick, but signalled through RS until the next invite.
+        let action = process_tick(&mut context, 20_000_000);
+        assert_eq!(action, NodeAction::NoAction);
+        assert_eq!(context.pending_sdo_request_count_and_priority().0, 1);
+        let (_, resent, _) = context
+            .sdo_client_manager
+            .get_pending_request(20_000_000, &context.core.od)
+            .unwrap();
+        assert_eq!(resent, first);
+    }
+
     #[test]
     fn test_heartbeat_timeout() {
         let mut context = create_context();
+        context.emergency_queue = VecDeque::with_capacity(4);
         context.heartbeat_consumers.insert(NodeId(240), (1000, 0));
         context
             .nmt_state_machine
@@ -407,6 +371,34 @@ mod tests {
             context.error_status_changed,
             "Heartbeat timeout failed to signal error"
         );
+        assert_eq!(
+            context.core.events.poll().map(|e| e.event),
+            Some(NodeEvent::HeartbeatTimeout {
+                node_id: NodeId(240)
+            })
+        );
+        assert!(matches!(
+            context.dll_error_manager.take_reported()[..],
+            [(DllError::HeartbeatTimeout { .. }, _), ..]
+        ));
+        let entry = context.emergency_queue.pop_front().unwrap();
+        assert_eq!(entry.entry_type.mode, ErrorEntryMode::EventOccurred);
+        assert_eq!(entry.entry_type.profile, 0x002);
+        assert_eq!(
+            entry.error_code,
+            DllError::HeartbeatTimeout {
+                node_id: NodeId(240)
+            }
+            .to_error_code()
+        );
+        assert!(context.emergency_queue.is_empty());
+        assert_eq!(
+            context.core.od.read_u32(
+                constants::IDX_DIAG_ERR_STATISTICS_REC,
+                constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE,
+            ),
+            Some(1)
+        );
     }
 
     #[test]
@@ -460,6 +452,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_each_soc_rearms_the_timeout() {
+        use crate::common::RelativeTime;
+        use crate::frame::PowerlinkFrame;
+        use crate::frame::control::SocFrame;
+        use crate::node::cn::events;
+
+        let mut context = create_context();
+        context
+            .core
+            .od
+            .write(
+                constants::IDX_NMT_CYCLE_LEN_U32,
+                0,
+                ObjectValue::Unsigned32(1000),
+            )
+            .unwrap();
+        context
+            .nmt_state_machine
+            .set_state(NmtState::NmtOperational);
+        let soc = || {
+            PowerlinkFrame::Soc(SocFrame::new(
+                Default::default(),
+                Default::default(),
+                NetTime {
+                    seconds: 0,
+                    nanoseconds: 0,
+                },
+                RelativeTime {
+                    seconds: 0,
+                    nanoseconds: 0,
+                },
+            ))
+        };
+
+        events::process_frame(&mut context, soc(), 0);
+        assert_eq!(context.next_tick_us, Some(1000));
+        // The next SoC arrives before the deadline and moves it to the next cycle.
+        events::process_frame(&mut context, soc(), 900);
+        assert_eq!(context.next_tick_us, Some(1900));
+        // Ignore the missing SoA between the two SoCs.
+        context.dll_error_manager.take_reported();
+
+        process_tick(&mut context, 1000);
+        assert_eq!(context.dll_error_manager.take_reported(), []);
+    }
+
     #[test]
     fn test_heartbeat_alive() {
         let mut context = create_context();
@@ -472,4 +511,137 @@ mod tests {
         process_tick(&mut context, 1200);
         assert!(!context.error_status_changed);
     }
+
+    #[test]
+    fn test_device_error_raise_and_clear() {
+        let mut context = create_context();
+        context.emergency_queue = VecDeque::with_capacity(4);
+        context.device_errors.define_bit("OverTemperature", 20).unwrap();
+        let error = crate::frame::error::DeviceError {
+            error_code: 0x4210,
+            profile: 0x001,
+            static_bit: context.device_errors.offset_of("OverTemperature"),
+            error_register: 1 << 3,
+            additional_information: 85,
+        };
+
+        context
+            .signal_device_error(&error, ErrorEntryMode::ErrorActive, 1_500_000)
+            .unwrap();
+        assert!(context.error_status_changed);
+        assert_eq!(
+            context.core.od.read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0),
+            Some(0b1001)
+        );
+        assert_eq!(context.device_errors.specific_errors()[1], 1 << 4);
+        let entry = context.emergency_queue.pop_front().unwrap();
+        assert_eq!(entry.entry_type.mode, ErrorEntryMode::ErrorActive);
+        assert_eq!(entry.entry_type.profile, 0x001);
+        assert_eq!(entry.error_code, 0x4210);
+        assert_eq!(entry.timestamp.seconds, 1);
+
+        // Raising it again does not queue another entry.
+        context.error_status_changed = false;
+        context
+            .signal_device_error(&error, ErrorEntryMode::ErrorActive, 1_600_000)
+            .unwrap();
+        assert!(!context.error_status_changed);
+        assert!(context.emergency_queue.is_empty());
+
+        context
+            .signal_device_error(&error, ErrorEntryMode::ErrorCleared, 2_000_000)
+            .unwrap();
+        assert!(context.error_status_changed);
+        assert_eq!(
+            context.core.od.read_u8(constants::IDX_NMT_ERROR_REGISTER_U8, 0),
+            Some(0)
+        );
+        assert_eq!(context.device_errors.specific_errors(), [0; 7]);
+        assert_eq!(
+            context.emergency_queue.pop_front().unwrap().entry_type.mode,
+            ErrorEntryMode::ErrorCleared
+        );
+    }
+
+    #[test]
+    fn test_full_emergency_queue_still_writes_history() {
+        let mut context = create_context();
+        context.core.od.insert(
+            0x1003,
+            ObjectEntry {
+                object: Object::Array(Vec::new()),
+                ..Default::default()
+            },
+        );
+        let error = crate::frame::error::DeviceError {
+            error_code: 0x4210,
+            profile: 0x001,
+            ..Default::default()
+        };
+
+        // The queue of the test context has no capacity.
+        context
+            .signal_device_error(&error, ErrorEntryMode::EventOccurred, 1_000_000)
+            .unwrap();
+        assert!(context.emergency_queue.is_empty());
+        assert!(matches!(
+            context.core.od.read_object(0x1003),
+            Some(Object::Array(entries)) if entries.len() == 1
+        ));
+        let counter = |sub| {
+            context
+                .core
+                .od
+                .read_u32(constants::IDX_DIAG_ERR_STATISTICS_REC, sub)
+        };
+        assert_eq!(
+            counter(constants::SUBIDX_DIAG_ERR_STATS_HIST_WRITE),
+            Some(1)
+        );
+        assert_eq!(
+            counter(constants::SUBIDX_DIAG_ERR_STATS_EMCY_OVERFLOW),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_extended_nmt_command_honors_node_list() {
+        use crate::frame::{ASndFrame, PowerlinkFrame, ServiceId};
+        use crate::nmt::events::NmtStateCommand;
+        use crate::nmt::node_list::NodeList;
+        use crate::types::C_ADR_BROADCAST_NODE_ID;
+
+        let stop_node_ex = |nodes: NodeList| {
+            let mut payload = vec![NmtStateCommand::StopNode.extended_id(), 0];
+            payload.extend_from_slice(nodes.as_bytes());
+            PowerlinkFrame::ASnd(ASndFrame::new(
+                Default::default(),
+                Default::default(),
+                NodeId(C_ADR_BROADCAST_NODE_ID),
+                NodeId(240),
+                ServiceId::NmtCommand,
+                payload,
+            ))
+        };
+        let mut context = create_context();
+        context
+            .nmt_state_machine
+            .set_state(NmtState::NmtOperational);
+
+        // Not in the list: ignored.
+        let others: NodeList = [NodeId(2), NodeId(3)].into_iter().collect();
+        super::super::events::process_frame(&mut context, stop_node_ex(others), 0);
+        assert_eq!(
+            context.nmt_state_machine.current_state(),
+            NmtState::NmtOperational
+        );
+
+        // Listed: the command applies.
+        let ours: NodeList = [NodeId(1), NodeId(3)].into_iter().collect();
+        super::super::events::process_frame(&mut context, stop_node_ex(ours), 0);
+        assert_eq!(
+            context.nmt_state_machine.current_state(),
+            NmtState::NmtCsStopped
+        );
+    }
 }
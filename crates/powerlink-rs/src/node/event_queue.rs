@@ -0,0 +1,203 @@
+// crates/powerlink-rs/src/node/event_queue.rs
+//! A timestamped stream of the notable events of a node.
+//!
+//! Both node types record NMT state changes, DLL errors, heartbeat timeouts
+//! and SDO completions here, and the MN its boot-up milestones. The
+//! application polls the stream, while the monitor and tests may inspect the
+//! most recent events without consuming them.
+
+use super::mn::{CnState, MnBootEvent};
+use crate::PowerlinkError;
+use crate::frame::error::{DllError, NmtAction};
+use crate::nmt::states::NmtState;
+use crate::types::NodeId;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Default number of events kept by a node.
+pub const DEFAULT_EVENT_QUEUE_CAPACITY: usize = 64;
+
+/// An event reported by a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeEvent {
+    /// The local NMT state changed.
+    NmtStateChanged { from: NmtState, to: NmtState },
+    /// The MN saw a CN change its state.
+    CnStateChanged {
+        node_id: NodeId,
+        from: CnState,
+        to: CnState,
+    },
+    /// A boot-up milestone of the MN or the result of a check on a CN.
+    Boot(MnBootEvent),
+    /// A DLL error was detected, with the action taken in response.
+    DllError { error: DllError, action: NmtAction },
+    /// A node monitored by the heartbeat consumer (0x1016) timed out.
+    HeartbeatTimeout { node_id: NodeId },
+    /// An SDO client transfer finished. The result itself is collected with
+    /// `poll_sdo_result`.
+    SdoTransferComplete {
+        target: NodeId,
+        transaction_id: u8,
+        outcome: Result<(), PowerlinkError>,
+    },
+}
+
+/// A `NodeEvent` with the time at which it was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedEvent {
+    /// Time (microseconds) of the `run_cycle` call in which the event occurred.
+    pub time_us: u64,
+    pub event: NodeEvent,
+}
+
+/// A bounded queue of `TimedEvent`s. The oldest events are dropped when full.
+#[derive(Debug, Clone)]
+pub struct NodeEventQueue {
+    events: VecDeque<TimedEvent>,
+    /// Number of events at the back of `events` not yet polled.
+    unread: usize,
+    capacity: usize,
+    /// Number of events dropped because the queue was full.
+    dropped: u32,
+    /// Time of the current `run_cycle` call.
+    now_us: u64,
+}
+
+impl Default for NodeEventQueue {
+    fn default() -> Self {
+        Self {
+            events: VecDeque::new(),
+            unread: 0,
+            capacity: DEFAULT_EVENT_QUEUE_CAPACITY,
+            dropped: 0,
+            now_us: 0,
+        }
+    }
+}
+
+impl NodeEventQueue {
+    /// Sets the maximum number of events kept.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.events.len() > capacity {
+            self.drop_oldest();
+        }
+    }
+
+    /// Returns the oldest event not yet polled.
+    pub fn poll(&mut self) -> Option<TimedEvent> {
+        if self.unread == 0 {
+            return None;
+        }
+        let event = self.events[self.events.len() - self.unread];
+        self.unread -= 1;
+        Some(event)
+    }
+
+    /// Iterates over all kept events, oldest first, whether polled or not.
+    pub fn recent(&self) -> impl Iterator<Item = &TimedEvent> {
+        self.events.iter()
+    }
+
+    /// Returns the number of events dropped because the queue was full.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Records an event at the time of the current cycle.
+    pub fn push(&mut self, event: NodeEvent) {
+        if self.capacity == 0 {
+            self.dropped = self.dropped.saturating_add(1);
+            return;
+        }
+        while self.events.len() >= self.capacity {
+            self.drop_oldest();
+        }
+        self.events.push_back(TimedEvent {
+            time_us: self.now_us,
+            event,
+        });
+        self.unread += 1;
+    }
+
+    /// Sets the time stamped on the events recorded from now on.
+    pub(crate) fn set_time(&mut self, time_us: u64) {
+        self.now_us = time_us;
+    }
+
+    /// Records the events gathered by the node's components during a cycle.
+    pub(crate) fn collect(
+        &mut self,
+        nmt_transitions: Vec<(NmtState, NmtState)>,
+        dll_errors: Vec<(DllError, NmtAction)>,
+        sdo_transfers: Vec<(NodeId, u8, Result<(), PowerlinkError>)>,
+    ) {
+        for (from, to) in nmt_transitions {
+            self.push(NodeEvent::NmtStateChanged { from, to });
+        }
+        for (error, action) in dll_errors {
+            self.push(NodeEvent::DllError { error, action });
+        }
+        for (target, transaction_id, outcome) in sdo_transfers {
+            self.push(NodeEvent::SdoTransferComplete {
+                target,
+                transaction_id,
+                outcome,
+            });
+        }
+    }
+
+    fn drop_oldest(&mut self) {
+        if self.events.pop_front().is_some() {
+            self.unread = self.unread.min(self.events.len());
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_poll_and_recent_share_the_stream() {
+        let mut queue = NodeEventQueue::default();
+        queue.set_capacity(2);
+        queue.set_time(100);
+        queue.collect(
+            vec![(NmtState::NmtPreOperational1, NmtState::NmtPreOperational2)],
+            Vec::new(),
+            Vec::new(),
+        );
+        queue.push(NodeEvent::HeartbeatTimeout { node_id: NodeId(1) });
+
+        assert_eq!(
+            queue.poll(),
+            Some(TimedEvent {
+                time_us: 100,
+                event: NodeEvent::NmtStateChanged {
+                    from: NmtState::NmtPreOperational1,
+                    to: NmtState::NmtPreOperational2,
+                },
+            })
+        );
+        // Polled events stay visible to other consumers.
+        assert_eq!(queue.recent().count(), 2);
+
+        // A full queue drops the oldest events, read or not.
+        queue.push(NodeEvent::HeartbeatTimeout { node_id: NodeId(2) });
+        queue.push(NodeEvent::HeartbeatTimeout { node_id: NodeId(3) });
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(
+            queue.poll().map(|e| e.event),
+            Some(NodeEvent::HeartbeatTimeout { node_id: NodeId(2) })
+        );
+        assert_eq!(
+            queue.poll().map(|e| e.event),
+            Some(NodeEvent::HeartbeatTimeout { node_id: NodeId(3) })
+        );
+        assert_eq!(queue.poll(), None);
+    }
+}
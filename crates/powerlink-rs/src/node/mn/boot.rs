@@ -0,0 +1,130 @@
+// crates/powerlink-rs/src/node/mn/boot.rs
+//! Boot-up milestones of the MN and application control of its transitions.
+//!
+//! With bit 8 (ReadyToOperate) or bit 2 (Operational) of NMT_StartUp_U32 (0x1F80)
+//! set, the MN holds its boot-up until the application releases the next state
+//! (EPSG DS 301, Section 7.4.1). The milestones are reported as `NodeEvent::Boot`
+//! in the node's event stream, so supervisory code can follow and gate the start-up.
+
+use super::state::MnContext;
+use crate::nmt::states::NmtState;
+use crate::node::NodeEvent;
+use crate::types::NodeId;
+
+/// Error code logged when a CN fails CHECK_COMMUNICATION (EPSG DS 301, Appendix 3.9).
+pub const E_NMT_BRO: u16 = 0xF030;
+
+/// A check performed on a CN during boot-up (EPSG DS 301, Section 7.4.2.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootCheck {
+    /// CHECK_IDENTIFICATION: device type, vendor, product code and revision.
+    Identification,
+    /// CHECK_SOFTWARE: application software date and time.
+    Software,
+    /// CHECK_CONFIGURATION: configuration date and time.
+    Configuration,
+    /// CHECK_COMMUNICATION: PRes reception and payload size.
+    Communication,
+}
+
+/// A boot-up milestone of the MN or the result of a check on a CN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MnBootEvent {
+    /// All mandatory CNs are identified and the MN entered PreOperational2.
+    BootStep1Complete,
+    /// All mandatory CNs reached PreOperational2 or ReadyToOperate.
+    BootStep2Complete,
+    /// The MN entered ReadyToOperate.
+    ReadyToOperate,
+    /// All mandatory CNs passed CHECK_COMMUNICATION.
+    CheckCommunicationComplete,
+    /// The MN entered Operational.
+    Operational,
+    /// The boot-up is held until the application releases the given state
+    /// with `ManagingNode::enter_ready_to_operate` or `enter_operational`.
+    AwaitingApplication(NmtState),
+    /// A CN passed all BOOT_STEP1 checks.
+    NodeIdentified(NodeId),
+    /// A CN passed CHECK_COMMUNICATION.
+    NodeCommunicationOk(NodeId),
+    /// A CN failed a boot-up check.
+    NodeCheckFailed { node_id: NodeId, check: BootCheck },
+    /// A CN reported a serial number other than the one expected in
+    /// NMT_MNSerialNoList_AU32 (0x1F88), i.e. the unit was swapped.
+    SerialNumberMismatch {
+        node_id: NodeId,
+        expected: u32,
+        received: u32,
+    },
+}
+
+/// How the MN reacts to a CN failing CHECK_COMMUNICATION.
+///
+/// An optional CN is marked `Missing` and the boot-up continues without it.
+/// A mandatory CN is reset with NMTResetNode; once the retries are used up,
+/// the MN resets its own communication. A CN joining while the MN is
+/// Operational is left `Missing` instead, so the running network is not disturbed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckCommunicationPolicy {
+    /// Number of NMTResetNode commands sent to a failing mandatory CN before
+    /// the MN resets its communication.
+    pub max_node_resets: u8,
+}
+
+impl Default for CheckCommunicationPolicy {
+    fn default() -> Self {
+        Self { max_node_resets: 3 }
+    }
+}
+
+/// Tracks the boot-up progress reported to the application and its releases.
+#[derive(Debug, Clone, Default)]
+pub struct BootControl {
+    /// The last MN state whose boot step was reported as complete.
+    completed_in: Option<NmtState>,
+    /// The state the application has released the MN to enter.
+    released: Option<NmtState>,
+    /// Remediation of CHECK_COMMUNICATION failures.
+    pub(super) check_communication_policy: CheckCommunicationPolicy,
+}
+
+impl BootControl {
+    /// Marks the boot step of `state` as complete.
+    /// Returns true the first time this is called for the state.
+    pub(super) fn complete_step(&mut self, state: NmtState) -> bool {
+        if self.completed_in == Some(state) {
+            return false;
+        }
+        self.completed_in = Some(state);
+        true
+    }
+
+    /// Records the application's release of `target`.
+    pub(super) fn release(&mut self, target: NmtState) {
+        self.released = Some(target);
+    }
+
+    /// Returns true if the application has released `target`.
+    pub(super) fn is_released(&self, target: NmtState) -> bool {
+        self.released == Some(target)
+    }
+
+    /// Forgets a release once it has been used or the boot-up restarted.
+    pub(super) fn clear_release(&mut self) {
+        self.released = None;
+    }
+}
+
+/// Reports a boot event in the node's event stream.
+pub(super) fn report_boot_event(context: &mut MnContext, event: MnBootEvent) {
+    context.core.events.push(NodeEvent::Boot(event));
+}
+
+/// Returns the next boot event from `events`, skipping other events.
+#[cfg(test)]
+pub(super) fn poll_boot_event(events: &mut crate::node::NodeEventQueue) -> Option<MnBootEvent> {
+    core::iter::from_fn(|| events.poll()).find_map(|e| match e.event {
+        NodeEvent::Boot(event) => Some(event),
+        _ => None,
+    })
+}
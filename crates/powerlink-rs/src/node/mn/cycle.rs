@@ -1,8 +1,9 @@
 // src/node/mn/cycle.rs
-use super::state::{CyclePhase, MnContext};
+use super::state::{CnState, CyclePhase, MnContext};
 use crate::frame::{DllMsEvent, PowerlinkFrame};
 use crate::nmt::NmtStateMachine;
 use crate::nmt::events::{MnNmtCommandRequest, NmtStateCommand};
+use crate::nmt::node_list::NodeList;
 use crate::nmt::states::NmtState;
 use crate::node::{NodeAction, serialize_frame_action};
 use crate::od::constants;
@@ -177,6 +178,8 @@ pub(super) fn start_cycle(context: &mut MnContext, current_time_us: u64) -> Node
             (context.current_multiplex_cycle + 1) % context.multiplex_cycle_len;
     }
     context.next_isoch_node_idx = 0; // Reset for this cycle's polling
+    // Each cycle decrements the DLL error threshold counters (EPSG DS 301, 4.7.4.1).
+    context.dll_error_manager.on_cycle_complete();
 
     // 2. Build the SoC frame
     let soc_frame = payload::build_soc_frame(
@@ -238,13 +241,15 @@ pub(super) fn tick(context: &mut MnContext, current_time_us: u64) -> NodeAction
             .unwrap_or(
                 NodeAction::NoAction,
             );
-        } else if let Some(&node_id) = context.mandatory_nodes.first() {
-            info!("[MN] Queuing NMTStartNode (Unicast).");
-            context.pending_nmt_commands.push((
-                MnNmtCommandRequest::State(NmtStateCommand::StartNode),
-                node_id,
-                NmtCommandData::None,
-            ));
+        } else {
+            let ready: NodeList = context
+                .node_info
+                .iter()
+                .filter(|(_, info)| info.state == CnState::PreOperational)
+                .map(|(node_id, _)| *node_id)
+                .collect();
+            info!("[MN] Queuing NMTStartNode for {} node(s).", ready.len());
+            scheduler::queue_group_state_command(context, NmtStateCommand::StartNode, &ready);
         }
     } else if current_nmt_state < NmtState::NmtOperational {
         context.initial_operational_actions_done = false;
@@ -257,9 +262,11 @@ pub(super) fn tick(context: &mut MnContext, current_time_us: u64) -> NodeAction
             // Priority: NMT Commands > SDO Client > Generic Queue
             context.current_phase = CyclePhase::Idle; // Consume the phase
 
-            if let Some((command_req, target_node_id, command_data)) =
-                context.pending_nmt_commands.pop()
-            {
+            // Commands are sent in the order they were queued, so a CN
+            // receives e.g. EnableReadyToOperate before StartNode.
+            if !context.pending_nmt_commands.is_empty() {
+                let (command_req, target_node_id, command_data) =
+                    context.pending_nmt_commands.remove(0);
                 // *** INCREMENT ASYNC TX COUNTER (NMT Command) ***
                 context.core.od.increment_counter(
                     constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
@@ -344,6 +351,7 @@ mod tests {
     use crate::sdo::transport::UdpTransport;
     use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
     use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
+    use alloc::boxed::Box;
     use alloc::collections::{BTreeMap, BinaryHeap};
     use alloc::vec::Vec;
 
@@ -356,6 +364,8 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         MnContext {
@@ -368,10 +378,15 @@ mod tests {
                 0,
             ),
             dll_state_machine: DllMsStateMachine::default(),
-            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(
+                MnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
             cycle_time_us: 10000,
             multiplex_cycle_len: 0,
             multiplex_assign: BTreeMap::new(),
@@ -388,6 +403,7 @@ mod tests {
             async_request_queue: BinaryHeap::new(),
             pending_er_requests: Vec::new(),
             pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
             pending_nmt_commands: Vec::new(),
             mn_async_send_queue: Vec::new(),
             sdo_client_manager: SdoClientManager::new(),
@@ -397,6 +413,7 @@ mod tests {
             pending_timeout_event: None,
             current_cycle_start_time_us: 0,
             initial_operational_actions_done: false,
+            boot: Default::default(),
         }
     }
 
@@ -464,6 +481,117 @@ mod tests {
         assert_eq!(context.current_phase, CyclePhase::AsynchronousSoA);
     }
 
+    #[test]
+    fn test_last_pres_is_followed_by_soa() {
+        use crate::frame::PResFrame;
+        use crate::frame::poll::PResFlags;
+        use crate::node::mn::events;
+        use crate::pdo::PDOVersion;
+
+        let mut context = create_test_context();
+        context.nmt_state_machine.set_state(NmtState::NmtPreOperational2);
+        context.isochronous_nodes.push(NodeId(1));
+        context.node_info.insert(
+            NodeId(1),
+            CnInfo {
+                state: CnState::PreOperational,
+                ..Default::default()
+            },
+        );
+        context.current_phase = CyclePhase::SoCSent;
+        assert!(matches!(
+            advance_cycle_phase(&mut context, 100),
+            NodeAction::SendFrame(_)
+        ));
+
+        // The PRes of the last polled CN is answered with the SoA right away.
+        let pres = PResFrame::new(
+            Default::default(),
+            NodeId(1),
+            NmtState::NmtPreOperational2,
+            PResFlags::default(),
+            PDOVersion(0),
+            Vec::new(),
+        );
+        match events::process_frame(&mut context, PowerlinkFrame::PRes(pres), 200) {
+            NodeAction::SendFrame(bytes) => {
+                assert!(matches!(deserialize_frame(&bytes), Ok(PowerlinkFrame::SoA(_))));
+            }
+            other => panic!("Expected SoA, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nmt_commands_are_sent_in_queued_order() {
+        use crate::nmt::events::{MnNmtCommandRequest, NmtStateCommand};
+        use crate::node::mn::state::NmtCommandData;
+        use crate::types::C_ADR_BROADCAST_NODE_ID;
+
+        let mut context = create_test_context();
+        for command in [
+            NmtStateCommand::EnableReadyToOperate,
+            NmtStateCommand::StartNode,
+        ] {
+            context.pending_nmt_commands.push((
+                MnNmtCommandRequest::State(command),
+                NodeId(C_ADR_BROADCAST_NODE_ID),
+                NmtCommandData::None,
+            ));
+        }
+
+        for expected in [
+            NmtStateCommand::EnableReadyToOperate,
+            NmtStateCommand::StartNode,
+        ] {
+            context.current_phase = CyclePhase::AwaitingMnAsyncSend;
+            let NodeAction::SendFrame(bytes) = tick(&mut context, 100) else {
+                panic!("Expected an NMT command frame");
+            };
+            let Ok(PowerlinkFrame::ASnd(asnd)) = deserialize_frame(&bytes) else {
+                panic!("Expected an ASnd frame");
+            };
+            assert_eq!(asnd.payload[0], expected as u8);
+        }
+        assert!(context.pending_nmt_commands.is_empty());
+    }
+
+    #[test]
+    fn test_entering_operational_starts_ready_cns() {
+        use crate::nmt::events::{MnNmtCommandRequest, NmtStateCommand};
+        use crate::node::mn::state::NmtCommandData;
+
+        let mut context = create_test_context();
+        context.nmt_state_machine.set_state(NmtState::NmtOperational);
+        for (node_id, state) in [
+            (1, CnState::PreOperational),
+            (2, CnState::PreOperational),
+            (3, CnState::Missing),
+        ] {
+            context.node_info.insert(
+                NodeId(node_id),
+                CnInfo {
+                    state,
+                    ..Default::default()
+                },
+            );
+        }
+
+        tick(&mut context, 100);
+        assert_eq!(
+            context.pending_nmt_commands,
+            [1, 2].map(|node_id| (
+                MnNmtCommandRequest::State(NmtStateCommand::StartNode),
+                NodeId(node_id),
+                NmtCommandData::None,
+            ))
+        );
+
+        // The StartNode is only sent once.
+        context.pending_nmt_commands.clear();
+        tick(&mut context, 200);
+        assert!(context.pending_nmt_commands.is_empty());
+    }
+
     #[test]
     fn test_advance_cycle_empty_isochronous() {
         let mut context = create_test_context();
@@ -0,0 +1,194 @@
+// crates/powerlink-rs/src/node/mn/error_log.rs
+//! Per-CN logs of the error entries reported in StatusResponse frames.
+//!
+//! A CN clears its emergency queue when the MN resets its exception signaling
+//! (ER flag, EPSG DS 301, Section 6.5.5), so the MN keeps its own copy of the
+//! entries for later inspection by the application and the monitor.
+
+use crate::frame::control::StaticErrorBitField;
+use crate::frame::error::ErrorEntry;
+use crate::types::NodeId;
+use alloc::collections::{BTreeMap, VecDeque};
+
+/// Default number of entries kept per CN.
+pub const DEFAULT_CN_ERROR_LOG_CAPACITY: usize = 32;
+
+/// An error entry reported by a CN, as recorded by the MN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CnErrorRecord {
+    /// The entry as sent by the CN, including the CN's own timestamp.
+    pub entry: ErrorEntry,
+    /// MN time (microseconds) at which the StatusResponse was received.
+    pub received_time_us: u64,
+    /// Set once the application has acknowledged the entry.
+    pub acknowledged: bool,
+}
+
+/// A bounded log of the error entries reported by a single CN.
+/// The oldest entries are dropped when the log is full.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CnErrorLog {
+    records: VecDeque<CnErrorRecord>,
+    /// Number of entries dropped because the log was full.
+    dropped: u32,
+    /// The Static Error Bit Field of the last StatusResponse.
+    static_errors: StaticErrorBitField,
+}
+
+impl CnErrorLog {
+    /// Returns the recorded entries, oldest first.
+    pub fn records(&self) -> impl Iterator<Item = &CnErrorRecord> {
+        self.records.iter()
+    }
+
+    /// Returns the entries the application has not yet acknowledged.
+    pub fn unacknowledged(&self) -> impl Iterator<Item = &CnErrorRecord> {
+        self.records.iter().filter(|r| !r.acknowledged)
+    }
+
+    /// Returns the number of entries dropped because the log was full.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Returns the Static Error Bit Field of the last StatusResponse.
+    pub fn static_errors(&self) -> StaticErrorBitField {
+        self.static_errors
+    }
+
+    /// Marks all entries as acknowledged and returns how many were newly acknowledged.
+    pub fn acknowledge_all(&mut self) -> usize {
+        let mut count = 0;
+        for record in self.records.iter_mut().filter(|r| !r.acknowledged) {
+            record.acknowledged = true;
+            count += 1;
+        }
+        count
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.dropped = 0;
+    }
+
+    fn push(&mut self, record: CnErrorRecord, capacity: usize) {
+        if capacity == 0 {
+            self.dropped = self.dropped.saturating_add(1);
+            return;
+        }
+        while self.records.len() >= capacity {
+            self.records.pop_front();
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        self.records.push_back(record);
+    }
+}
+
+/// The error logs of all CNs known to the MN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CnErrorLogs {
+    logs: BTreeMap<NodeId, CnErrorLog>,
+    capacity: usize,
+}
+
+impl Default for CnErrorLogs {
+    fn default() -> Self {
+        Self {
+            logs: BTreeMap::new(),
+            capacity: DEFAULT_CN_ERROR_LOG_CAPACITY,
+        }
+    }
+}
+
+impl CnErrorLogs {
+    /// Sets the maximum number of entries kept per CN.
+    /// Existing logs are trimmed on their next update.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    /// Returns the log of a CN, if it has reported anything.
+    pub fn get(&self, node_id: NodeId) -> Option<&CnErrorLog> {
+        self.logs.get(&node_id)
+    }
+
+    /// Returns the log of a CN for acknowledging or clearing entries.
+    pub fn get_mut(&mut self, node_id: NodeId) -> Option<&mut CnErrorLog> {
+        self.logs.get_mut(&node_id)
+    }
+
+    /// Iterates over all CN logs in Node ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (&NodeId, &CnErrorLog)> {
+        self.logs.iter()
+    }
+
+    /// Records the contents of a StatusResponse from a CN.
+    pub(super) fn record(
+        &mut self,
+        node_id: NodeId,
+        static_errors: StaticErrorBitField,
+        entries: &[ErrorEntry],
+        received_time_us: u64,
+    ) {
+        let log = self.logs.entry(node_id).or_default();
+        log.static_errors = static_errors;
+        for entry in entries {
+            log.push(
+                CnErrorRecord {
+                    entry: *entry,
+                    received_time_us,
+                    acknowledged: false,
+                },
+                self.capacity,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::NetTime;
+    use crate::frame::error::{EntryType, ErrorEntryMode};
+
+    fn entry(error_code: u16) -> ErrorEntry {
+        ErrorEntry {
+            entry_type: EntryType {
+                is_status_entry: false,
+                send_to_queue: true,
+                mode: ErrorEntryMode::ErrorActive,
+                profile: 0x001,
+            },
+            error_code,
+            timestamp: NetTime {
+                seconds: 0,
+                nanoseconds: 0,
+            },
+            additional_information: 0,
+        }
+    }
+
+    #[test]
+    fn test_log_is_bounded_and_acknowledged() {
+        let mut logs = CnErrorLogs::default();
+        logs.set_capacity(2);
+        let node = NodeId(5);
+        logs.record(
+            node,
+            StaticErrorBitField::default(),
+            &[entry(1), entry(2), entry(3)],
+            100,
+        );
+
+        let log = logs.get(node).unwrap();
+        let codes: alloc::vec::Vec<u16> = log.records().map(|r| r.entry.error_code).collect();
+        assert_eq!(codes, [2, 3]);
+        assert_eq!(log.dropped(), 1);
+        assert_eq!(log.unacknowledged().count(), 2);
+
+        assert_eq!(logs.get_mut(node).unwrap().acknowledge_all(), 2);
+        assert_eq!(logs.get(node).unwrap().unacknowledged().count(), 0);
+        assert!(logs.get(NodeId(6)).is_none());
+    }
+}
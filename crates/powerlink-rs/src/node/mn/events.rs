@@ -1,4 +1,5 @@
 // crates/powerlink-rs/src/node/mn/events.rs
+use super::boot::{BootCheck, E_NMT_BRO, MnBootEvent, report_boot_event};
 use super::scheduler;
 use super::state::{AsyncRequest, CnState, CyclePhase, MnContext};
 use super::validation; // <-- ADDED import
@@ -10,17 +11,28 @@ use crate::frame::{
 use crate::nmt::NmtStateMachine;
 use crate::nmt::events::{MnNmtCommandRequest, NmtStateCommand};
 use crate::nmt::{events::NmtEvent, states::NmtState};
-use crate::node::PdoHandler;
-use crate::node::mn::ip_from_node_id;
+use crate::node::ip_from_node_id;
+use crate::node::{NodeAction, NodeEvent, PdoHandler};
 use crate::node::mn::state::NmtCommandData;
 use crate::od::constants;
 use crate::types::NodeId;
+#[cfg(feature = "sdo-udp")]
+use crate::{
+    nmt::{flags::FeatureFlags, node_list::NodeList, udp::NmtUdpService},
+    types::{C_ADR_BROADCAST_NODE_ID, C_ADR_MN_DEF_NODE_ID, IpAddress},
+};
 use log::{debug, error, info, trace, warn};
 
 /// Processes a `PowerlinkFrame` after it has been identified as
 /// non-SDO or not for the MN. This handles NMT state changes and
 /// DLL state progression based on received frames.
-pub(super) fn process_frame(context: &mut MnContext, frame: PowerlinkFrame, current_time_us: u64) {
+///
+/// Returns the next frame of the cycle when an awaited PRes completes a poll.
+pub(super) fn process_frame(
+    context: &mut MnContext,
+    frame: PowerlinkFrame,
+    current_time_us: u64,
+) -> NodeAction {
     // 1. Update NMT state machine based on the frame type.
     if let Some(event) = frame.nmt_event() {
         if context.nmt_state_machine.current_state() != NmtState::NmtNotActive {
@@ -70,16 +82,16 @@ pub(super) fn process_frame(context: &mut MnContext, frame: PowerlinkFrame, curr
                     pres_frame.flags.rd,
                 );
                 // Handle async and error signaling flags in PRes
-                handle_pres_frame(context, &pres_frame);
-                // PRes received, advance to the next action in the cycle.
-                // The action is returned by `tick` in the scheduler, so we don't need to capture it here.
-                let _action = super::cycle::advance_cycle_phase(context, current_time_us);
+                handle_pres_frame(context, &pres_frame, current_time_us);
+                // PRes received, advance to the next action in the cycle
+                // (the next PReq or the SoA).
+                return super::cycle::advance_cycle_phase(context, current_time_us);
             } else {
                 warn!(
                     "[MN] Received unexpected PRes from Node {}.",
                     pres_frame.source.0
                 );
-                handle_pres_frame(context, &pres_frame);
+                handle_pres_frame(context, &pres_frame, current_time_us);
             }
         }
         PowerlinkFrame::ASnd(asnd_frame) => {
@@ -108,6 +120,7 @@ pub(super) fn process_frame(context: &mut MnContext, frame: PowerlinkFrame, curr
             // SDO ASnd is handled in main.rs
         }
     }
+    NodeAction::NoAction
 }
 
 /// Passes an event to the DLL state machine and processes any resulting errors.
@@ -151,6 +164,13 @@ pub(super) fn handle_dll_event(
                         node_id.0
                     );
                     if let Some(info) = context.node_info.get_mut(&node_id) {
+                        if info.state != CnState::Missing {
+                            context.core.events.push(NodeEvent::CnStateChanged {
+                                node_id,
+                                from: info.state,
+                                to: CnState::Missing,
+                            });
+                        }
                         info.state = CnState::Missing;
                     }
                     context.pending_nmt_commands.push((
@@ -185,6 +205,23 @@ fn handle_asnd_frame(context: &mut MnContext, frame: &ASndFrame, current_time_us
                 if state == CnState::Unknown || state == CnState::Missing {
                     match IdentResponsePayload::deserialize(&frame.payload) {
                         Ok(payload) => {
+                            // --- ARP CACHE LOGIC ---
+                            // Passively populate the ARP cache based on the frame
+                            // (Spec 5.1.3). A CN failing the checks may still be
+                            // sent an SDO download or NMT command.
+                            let cn_ip = ip_from_node_id(node_id);
+                            let cn_mac = frame.eth_header.source_mac;
+                            context.arp_cache.insert(cn_ip, cn_mac);
+                            info!(
+                                "[MN-ARP] Cached MAC {} for Node {} (IP {}).",
+                                cn_mac,
+                                node_id.0,
+                                core::net::Ipv4Addr::from(cn_ip)
+                            );
+                            // The CN has booted (again), so the SDO segment
+                            // size agreed before its reset may no longer hold.
+                            context.sdo_client_manager.forget_segment_size(node_id);
+
                             // Perform Boot Step 1 Checks (ID, SW, Config) using the extracted validation module
                             if validation::validate_boot_step1_checks(
                                 context,
@@ -197,19 +234,6 @@ fn handle_asnd_frame(context: &mut MnContext, frame: &ASndFrame, current_time_us
                                     node_id.0
                                 );
 
-                                // --- ARP CACHE LOGIC ---
-                                // Passively populate the ARP cache based on the frame
-                                // (Spec 5.1.3)
-                                let cn_ip = ip_from_node_id(node_id);
-                                let cn_mac = frame.eth_header.source_mac;
-                                context.arp_cache.insert(cn_ip, cn_mac);
-                                info!(
-                                    "[MN-ARP] Cached MAC {} for Node {} (IP {}).",
-                                    cn_mac,
-                                    node_id.0,
-                                    core::net::Ipv4Addr::from(cn_ip)
-                                );
-
                                 // Re-acquire mutable borrow to update state
                                 if let Some(info_mut) = context.node_info.get_mut(&node_id) {
                                     info_mut.state = CnState::Identified;
@@ -220,8 +244,11 @@ fn handle_asnd_frame(context: &mut MnContext, frame: &ASndFrame, current_time_us
                                         product_code: payload.product_code,
                                         revision_no: payload.revision_number,
                                         serial_no: payload.serial_number,
+                                        feature_flags: payload.feature_flags,
                                     });
                                 }
+                                report_boot_event(context, MnBootEvent::NodeIdentified(node_id));
+                                scheduler::continue_late_boot(context, node_id);
                                 // Check if this identification allows the MN to transition
                                 scheduler::check_bootup_state(context);
                             } else {
@@ -252,40 +279,61 @@ fn handle_asnd_frame(context: &mut MnContext, frame: &ASndFrame, current_time_us
         ServiceId::StatusResponse => {
             let node_id = frame.source;
             trace!("[MN] Received StatusResponse from CN {}.", frame.source.0);
-            if let Some(info) = context.node_info.get_mut(&node_id) {
-                // The handshake is complete. Update the MN's EA flag to match the CN's EN flag.
-                // This new EA value will be sent in the next PReq.
-                info.ea_flag = info.en_flag;
-                info!(
-                    "[MN] StatusResponse from Node {} processed. Updated EA flag to {}.",
-                    node_id.0, info.ea_flag
-                );
+            if !context.node_info.contains_key(&node_id) {
+                return;
+            }
+            match StatusResponsePayload::deserialize(&frame.payload) {
+                Ok(payload) => {
+                    info!(
+                        "[MN] StatusResponse from Node {}: ErrorRegister = {:#04x}, SpecificErrors = {:02X?}",
+                        node_id.0,
+                        payload.static_error_bit_field.error_register,
+                        payload.static_error_bit_field.specific_errors
+                    );
+                    // Update the CN's state in the MN's tracker
+                    update_cn_state(context, node_id, payload.nmt_state);
 
-                match StatusResponsePayload::deserialize(&frame.payload) {
-                    Ok(payload) => {
-                        info!(
-                            "[MN] StatusResponse from Node {}: ErrorRegister = {:#04x}, SpecificErrors = {:02X?}",
-                            node_id.0,
-                            payload.static_error_bit_field.error_register,
-                            payload.static_error_bit_field.specific_errors
+                    for entry in &payload.error_entries {
+                        warn!(
+                            "[MN] StatusResponse from Node {}: Received Error/Event Entry: {:?}",
+                            node_id.0, entry
                         );
-                        // Update the CN's state in the MN's tracker
-                        update_cn_state(context, node_id, payload.nmt_state);
+                    }
+                    context.cn_error_logs.record(
+                        node_id,
+                        payload.static_error_bit_field,
+                        &payload.error_entries,
+                        current_time_us,
+                    );
 
-                        for entry in payload.error_entries {
-                            warn!(
-                                "[MN] StatusResponse from Node {}: Received Error/Event Entry: {:?}",
-                                node_id.0, entry
-                            );
-                        }
+                    // The entries are recorded, so the handshake can be completed.
+                    // Update the MN's EA flag to match the CN's EN flag.
+                    // This new EA value will be sent in the next PReq.
+                    if let Some(info) = context.node_info.get_mut(&node_id) {
+                        info.ea_flag = info.en_flag;
+                        info!(
+                            "[MN] StatusResponse from Node {} processed. Updated EA flag to {}.",
+                            node_id.0, info.ea_flag
+                        );
                     }
-                    Err(e) => {
-                        error!(
-                            "[MN] Failed to deserialize StatusResponse from Node {}: {:?}",
-                            node_id.0, e
+                    // EC clear means the CN has not acknowledged an exception reset
+                    // yet (Spec 6.5.5). Keep resetting until it answers with EC set,
+                    // now that its entries are safe.
+                    if !payload.ec_flag && !context.pending_er_requests.contains(&node_id) {
+                        info!(
+                            "[MN] Node {} reports EC clear. Queuing exception reset (ER).",
+                            node_id.0
                         );
+                        context.pending_er_requests.push(node_id);
                     }
                 }
+                Err(e) => {
+                    // Not acknowledged: the EN/EA mismatch triggers another StatusRequest.
+                    error!(
+                        "[MN] Failed to deserialize StatusResponse from Node {}: {:?}",
+                        node_id.0, e
+                    );
+                }
             }
         }
         _ => {
@@ -297,8 +345,120 @@ fn handle_asnd_frame(context: &mut MnContext, frame: &ASndFrame, current_time_us
     }
 }
 
+/// Handles an NMTCommand or NMTRequest received over UDP/IP.
+///
+/// The services are only accepted if the MN advertises NMT_SERVICE_UDP
+/// (0x1F82 bit 7) and the sender's address was allowed by the application.
+#[cfg(feature = "sdo-udp")]
+pub(super) fn handle_nmt_udp_service(
+    context: &mut MnContext,
+    service: NmtUdpService,
+    source_ip: IpAddress,
+) {
+    if !context
+        .nmt_state_machine
+        .feature_flags
+        .contains(FeatureFlags::NMT_SERVICE_UDP)
+    {
+        warn!("[MN] Ignoring NMT service over UDP: NMT_SERVICE_UDP is not enabled.");
+        return;
+    }
+    if !context.nmt_udp_sources.contains(&source_ip) {
+        warn!(
+            "[MN] Rejected NMT service over UDP from unauthorized address {}.",
+            core::net::Ipv4Addr::from(source_ip)
+        );
+        return;
+    }
+    context.core.od.increment_counter(
+        constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
+        constants::SUBIDX_DIAG_NMT_COUNT_ASYNC_RX,
+    );
+
+    match service {
+        // A command sent to the MN's own address applies to the MN.
+        NmtUdpService::Command { command_id, .. } => match NmtStateCommand::try_from(command_id) {
+            Ok(command) => apply_state_command_to_mn(context, command),
+            Err(_) => warn!(
+                "[MN] Ignoring unsupported NMT command {:#04x} over UDP.",
+                command_id
+            ),
+        },
+        NmtUdpService::Request {
+            command_id,
+            target,
+            data,
+        } => {
+            if let Ok(command) = NmtStateCommand::try_from(command_id) {
+                info!(
+                    "[MN] NMTRequest over UDP: {:?} for Node {}.",
+                    command, target.0
+                );
+                if target.0 == C_ADR_MN_DEF_NODE_ID {
+                    apply_state_command_to_mn(context, command);
+                } else if target.0 == C_ADR_BROADCAST_NODE_ID
+                    || context.node_info.contains_key(&target)
+                {
+                    context.pending_nmt_commands.push((
+                        MnNmtCommandRequest::State(command),
+                        target,
+                        NmtCommandData::None,
+                    ));
+                } else {
+                    warn!(
+                        "[MN] Ignoring NMTRequest over UDP for unconfigured Node {}.",
+                        target.0
+                    );
+                }
+            } else if let Ok(command) = NmtStateCommand::try_from_extended(command_id) {
+                // The node list is the requested command data (Spec 7.3.1.2.3).
+                match NodeList::from_bytes(data) {
+                    Ok(nodes) => {
+                        info!(
+                            "[MN] NMTRequest over UDP: {:?} for {} node(s).",
+                            command,
+                            nodes.len()
+                        );
+                        scheduler::queue_group_state_command(context, command, &nodes);
+                    }
+                    Err(_) => warn!(
+                        "[MN] Ignoring extended NMTRequest over UDP without a node list ({} bytes).",
+                        data.len()
+                    ),
+                }
+            } else {
+                warn!(
+                    "[MN] Ignoring unsupported NMTRequest {:#04x} over UDP.",
+                    command_id
+                );
+            }
+        }
+    }
+}
+
+/// Applies a state command addressed to the MN itself. Only the reset
+/// commands are meaningful for the MN; it drives its other states itself.
+#[cfg(feature = "sdo-udp")]
+fn apply_state_command_to_mn(context: &mut MnContext, command: NmtStateCommand) {
+    let event = match command {
+        NmtStateCommand::ResetNode => NmtEvent::ResetNode,
+        NmtStateCommand::ResetCommunication => NmtEvent::ResetCommunication,
+        NmtStateCommand::ResetConfiguration => NmtEvent::ResetConfiguration,
+        NmtStateCommand::SwReset => NmtEvent::SwReset,
+        _ => {
+            warn!("[MN] Ignoring {:?} addressed to the MN.", command);
+            return;
+        }
+    };
+    info!("[MN] Applying {:?} requested over UDP.", command);
+    context
+        .nmt_state_machine
+        .process_event(event, &mut context.core.od);
+    context.current_phase = CyclePhase::Idle;
+}
+
 /// Checks the flags in a received PRes frame for async requests and error signals.
-fn handle_pres_frame(context: &mut MnContext, pres: &PResFrame) {
+fn handle_pres_frame(context: &mut MnContext, pres: &PResFrame, current_time_us: u64) {
     // 1. Handle async requests flagged by RS.
     if pres.flags.rs.get() > 0 {
         debug!("[MN] Node {} requesting async transmission.", pres.source.0);
@@ -329,8 +489,12 @@ fn handle_pres_frame(context: &mut MnContext, pres: &PResFrame) {
 
         // --- Phase 1.5: CHECK_COMMUNICATION ---
         // This check is performed when the MN is in ReadyToOperate, before moving to Operational.
-        if context.nmt_state_machine.current_state() == NmtState::NmtReadyToOperate
-            && !info.communication_ok
+        // A CN joining a running network is checked in Operational.
+        let mn_state = context.nmt_state_machine.current_state();
+        if matches!(
+            mn_state,
+            NmtState::NmtReadyToOperate | NmtState::NmtOperational
+        ) && !info.communication_ok
         {
             let expected_payload_size = context
                 .core
@@ -348,6 +512,8 @@ fn handle_pres_frame(context: &mut MnContext, pres: &PResFrame) {
                     expected_payload_size
                 );
                 info.communication_ok = true;
+                info.communication_resets = 0;
+                report_boot_event(context, MnBootEvent::NodeCommunicationOk(pres.source));
                 // Immediately check if this was the last node needed for NMT transition
                 scheduler::check_bootup_state(context);
             } else {
@@ -357,10 +523,89 @@ fn handle_pres_frame(context: &mut MnContext, pres: &PResFrame) {
                     expected_payload_size,
                     pres.payload.len()
                 );
-                // TODO: Handle error (e.g., E_NMT_BRO)
+                validation::report_check_failed(context, pres.source, BootCheck::Communication);
+                handle_check_communication_failure(context, pres.source, current_time_us);
+                return;
             }
         }
     }
+
+    // A CN that finished its boot-up while the MN is Operational is started
+    // on its own.
+    if context.nmt_state_machine.current_state() == NmtState::NmtOperational
+        && pres.nmt_state == NmtState::NmtReadyToOperate
+        && context
+            .node_info
+            .get(&pres.source)
+            .is_some_and(|info| info.communication_ok)
+    {
+        scheduler::queue_start_node(context, pres.source);
+    }
+}
+
+/// Handles a CN that failed CHECK_COMMUNICATION (EPSG DS 301, Section 7.4.2.2.3).
+///
+/// The failure is logged as E_NMT_BRO in the Error History (0x1003). The CN is
+/// marked `Missing`, which takes it out of the isochronous cycle, and is then
+/// treated according to the `CheckCommunicationPolicy`.
+fn handle_check_communication_failure(
+    context: &mut MnContext,
+    node_id: NodeId,
+    current_time_us: u64,
+) {
+    context.log_error_event(E_NMT_BRO, node_id.0 as u64, current_time_us);
+
+    let is_mandatory = context.mandatory_nodes.contains(&node_id);
+    let max_resets = context.boot.check_communication_policy.max_node_resets;
+    let is_operational = context.nmt_state_machine.current_state() == NmtState::NmtOperational;
+    let Some(info) = context.node_info.get_mut(&node_id) else {
+        return;
+    };
+    if info.state != CnState::Missing {
+        context.core.events.push(NodeEvent::CnStateChanged {
+            node_id,
+            from: info.state,
+            to: CnState::Missing,
+        });
+    }
+    info.state = CnState::Missing;
+    info.communication_ok = false;
+
+    if !is_mandatory {
+        warn!(
+            "[MN] Optional Node {} failed CHECK_COMMUNICATION. Continuing without it.",
+            node_id.0
+        );
+        return;
+    }
+    if info.communication_resets < max_resets {
+        info.communication_resets += 1;
+        warn!(
+            "[MN] Mandatory Node {} failed CHECK_COMMUNICATION. Resetting it (attempt {}/{}).",
+            node_id.0, info.communication_resets, max_resets
+        );
+        context.pending_nmt_commands.push((
+            MnNmtCommandRequest::State(NmtStateCommand::ResetNode),
+            node_id,
+            NmtCommandData::None,
+        ));
+    } else if is_operational {
+        // The CN joined a running network; the other CNs keep running.
+        error!(
+            "[MN] Mandatory Node {} failed CHECK_COMMUNICATION after {} resets. Leaving it Missing.",
+            node_id.0, max_resets
+        );
+    } else {
+        error!(
+            "[MN] Mandatory Node {} failed CHECK_COMMUNICATION after {} resets. Resetting communication.",
+            node_id.0, max_resets
+        );
+        info.communication_resets = 0;
+        context
+            .nmt_state_machine
+            .process_event(NmtEvent::ResetCommunication, &mut context.core.od);
+        context.current_phase = CyclePhase::Idle;
+    }
 }
 
 /// Updates the MN's internal state tracker for a CN based on its reported NMT state.
@@ -378,6 +623,11 @@ fn update_cn_state(context: &mut MnContext, node_id: NodeId, reported_state: Nmt
                 "[MN] Node {} state changed: {:?} -> {:?}",
                 node_id.0, current_info.state, new_state
             );
+            context.core.events.push(NodeEvent::CnStateChanged {
+                node_id,
+                from: current_info.state,
+                to: new_state,
+            });
             current_info.state = new_state;
 
             // If a node resets (e.g., error) or stops, its communication
@@ -389,3 +639,188 @@ fn update_cn_state(context: &mut MnContext, node_id: NodeId, reported_state: Nmt
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::error::{DllErrorManager, LoggingErrorHandler, MnErrorCounters};
+    use crate::frame::ms_state_machine::DllMsStateMachine;
+    use crate::nmt::mn_state_machine::MnNmtStateMachine;
+    use crate::node::CoreNodeContext;
+    use crate::od::ObjectDictionary;
+    use crate::sdo::client_manager::SdoClientManager;
+    use crate::sdo::transport::AsndTransport;
+    #[cfg(feature = "sdo-udp")]
+    use crate::sdo::transport::UdpTransport;
+    use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
+    use crate::types::C_ADR_MN_DEF_NODE_ID;
+    use alloc::boxed::Box;
+    use alloc::collections::{BTreeMap, BinaryHeap};
+    use alloc::vec::Vec;
+
+    fn create_test_context<'a>() -> MnContext<'a> {
+        let od = ObjectDictionary::new(None);
+        let core = CoreNodeContext {
+            od,
+            mac_address: Default::default(),
+            sdo_server: SdoServer::new(),
+            sdo_client: SdoClient::new(),
+            embedded_sdo_server: EmbeddedSdoServer::new(),
+            embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
+        };
+
+        MnContext {
+            core,
+            configuration_interface: None,
+            nmt_state_machine: MnNmtStateMachine::new(
+                NodeId(C_ADR_MN_DEF_NODE_ID),
+                Default::default(),
+                0,
+                0,
+            ),
+            dll_state_machine: DllMsStateMachine::default(),
+            dll_error_manager: DllErrorManager::new(
+                MnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
+            asnd_transport: AsndTransport,
+            #[cfg(feature = "sdo-udp")]
+            udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
+            cycle_time_us: 1000,
+            multiplex_cycle_len: 0,
+            multiplex_assign: BTreeMap::new(),
+            publish_config: BTreeMap::new(),
+            current_multiplex_cycle: 0,
+            node_info: BTreeMap::new(),
+            mandatory_nodes: Vec::new(),
+            isochronous_nodes: Vec::new(),
+            async_only_nodes: Vec::new(),
+            arp_cache: BTreeMap::new(),
+            next_isoch_node_idx: 0,
+            current_phase: CyclePhase::Idle,
+            current_polled_cn: None,
+            async_request_queue: BinaryHeap::new(),
+            pending_er_requests: Vec::new(),
+            pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
+            pending_nmt_commands: Vec::new(),
+            mn_async_send_queue: Vec::new(),
+            sdo_client_manager: SdoClientManager::new(),
+            last_ident_poll_node_id: NodeId(0),
+            last_status_poll_node_id: NodeId(0),
+            next_tick_us: None,
+            pending_timeout_event: None,
+            current_cycle_start_time_us: 0,
+            initial_operational_actions_done: false,
+            boot: Default::default(),
+        }
+    }
+
+    #[cfg(feature = "sdo-udp")]
+    #[test]
+    fn test_nmt_request_over_udp_requires_allowed_source() {
+        use crate::nmt::udp::{deserialize_nmt_udp_payload, serialize_nmt_udp_payload};
+        use crate::node::mn::state::CnInfo;
+
+        let scada = [10, 0, 0, 1];
+        let mut buffer = [0u8; 16];
+        let len = serialize_nmt_udp_payload(
+            &NmtUdpService::Request {
+                command_id: NmtStateCommand::StartNode as u8,
+                target: NodeId(5),
+                data: &[],
+            },
+            &mut buffer,
+        )
+        .unwrap();
+        let request = deserialize_nmt_udp_payload(&buffer[..len]).unwrap();
+
+        let mut context = create_test_context();
+        context.node_info.insert(NodeId(5), CnInfo::default());
+
+        // The feature is disabled: dropped even from an allowed address.
+        context.nmt_udp_sources.push(scada);
+        handle_nmt_udp_service(&mut context, request, scada);
+        assert!(context.pending_nmt_commands.is_empty());
+
+        // Enabled, but from an unknown address: dropped.
+        context.nmt_state_machine.feature_flags = FeatureFlags::NMT_SERVICE_UDP;
+        handle_nmt_udp_service(&mut context, request, [10, 0, 0, 2]);
+        assert!(context.pending_nmt_commands.is_empty());
+
+        handle_nmt_udp_service(&mut context, request, scada);
+        assert_eq!(
+            context.pending_nmt_commands,
+            [(
+                MnNmtCommandRequest::State(NmtStateCommand::StartNode),
+                NodeId(5),
+                NmtCommandData::None
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_communication_failure_resets_then_gives_up() {
+        use crate::frame::poll::PResFlags;
+        use crate::node::mn::state::CnInfo;
+        use crate::pdo::PDOVersion;
+        use alloc::vec;
+
+        let mut context = create_test_context();
+        context.boot.check_communication_policy.max_node_resets = 1;
+        context.mandatory_nodes.push(NodeId(1));
+        for node in [1, 2] {
+            context.node_info.insert(
+                NodeId(node),
+                CnInfo {
+                    state: CnState::PreOperational,
+                    ..Default::default()
+                },
+            );
+        }
+        context.nmt_state_machine.current_state = NmtState::NmtReadyToOperate;
+        // No PRes payload limit (0x1F8D) is configured, so any payload fails.
+        let receive_pres = |context: &mut MnContext, node| {
+            context.current_phase = CyclePhase::IsochronousPReq;
+            context.current_polled_cn = Some(NodeId(node));
+            let pres = PResFrame::new(
+                Default::default(),
+                NodeId(node),
+                NmtState::NmtReadyToOperate,
+                PResFlags::default(),
+                PDOVersion(0),
+                vec![0; 4],
+            );
+            process_frame(context, PowerlinkFrame::PRes(pres), 0);
+        };
+
+        // An optional CN is skipped.
+        receive_pres(&mut context, 2);
+        assert_eq!(context.node_info[&NodeId(2)].state, CnState::Missing);
+        assert!(context.pending_nmt_commands.is_empty());
+
+        // A mandatory CN is reset while retries remain...
+        receive_pres(&mut context, 1);
+        assert_eq!(context.node_info[&NodeId(1)].state, CnState::Missing);
+        assert_eq!(
+            context.pending_nmt_commands,
+            [(
+                MnNmtCommandRequest::State(NmtStateCommand::ResetNode),
+                NodeId(1),
+                NmtCommandData::None
+            )]
+        );
+
+        // ...after which the MN resets its communication.
+        context.node_info.get_mut(&NodeId(1)).unwrap().state = CnState::PreOperational;
+        receive_pres(&mut context, 1);
+        assert_eq!(
+            context.nmt_state_machine.current_state(),
+            NmtState::NmtNotActive
+        );
+    }
+}
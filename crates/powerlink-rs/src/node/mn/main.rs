@@ -1,35 +1,47 @@
 // crates/powerlink-rs/src/node/mn/main.rs
+use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::format;
+use super::boot::CheckCommunicationPolicy;
+use super::error_log::{CnErrorLog, CnErrorLogs};
 use super::events;
+use super::scheduler;
 use super::state::{CyclePhase, MnContext};
+use super::validation;
 use crate::PowerlinkError;
 use crate::frame::basic::MacAddress;
-use crate::frame::error::{DllErrorManager, LoggingErrorHandler, MnErrorCounters};
+use crate::frame::error::{DllErrorManager, ErrorHandler, LoggingErrorHandler, MnErrorCounters};
 use crate::frame::ms_state_machine::DllMsStateMachine;
 use crate::frame::{PowerlinkFrame, ServiceId, deserialize_frame};
-use crate::hal::ConfigurationInterface;
+use crate::hal::{ConfigurationInterface, LinkStatus};
 use crate::log::LogMetadata;
 use crate::nmt::mn_state_machine::MnNmtStateMachine;
+use crate::nmt::node_list::NodeList;
 use crate::nmt::state_machine::NmtStateMachine;
 use crate::nmt::states::NmtState;
 use crate::node::mn::config;
-use crate::node::{CoreNodeContext, Node, NodeAction};
+use crate::node::{CoreNodeContext, Node, NodeAction, NodeEventQueue, TimedEvent};
 use crate::od::{ObjectDictionary, constants};
+use crate::pdo::ProcessImage;
+use crate::sdo::asnd::restore_trimmed_padding;
 use crate::sdo::client_manager::SdoClientManager;
-use crate::sdo::command::SdoCommand;
+use crate::sdo::command::{MultipleParamEntry, SdoCommand, WriteMultipleParamEntry};
 use crate::sdo::sequence::SequenceLayerHeader;
 use crate::sdo::server::SdoClientInfo;
 use crate::sdo::transport::AsndTransport;
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::transport::UdpTransport;
-use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoServer, SdoTransport};
+use crate::sdo::{
+    EmbeddedSdoClient, EmbeddedSdoServer, SdoServer, SdoTransferResult, SdoTransport,
+};
 use crate::types::{C_ADR_BROADCAST_NODE_ID, C_ADR_MN_DEF_NODE_ID, MessageType, NodeId};
 use alloc::collections::BinaryHeap;
 use alloc::vec::Vec;
 use log::{error, info, trace, warn};
 
+#[cfg(feature = "sdo-udp")]
+use crate::nmt::udp::{deserialize_nmt_udp_payload, is_nmt_udp_payload};
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::udp::deserialize_sdo_udp_payload;
 #[cfg(feature = "sdo-udp")]
@@ -45,16 +57,37 @@ pub struct ManagingNode<'s> {
 }
 
 impl<'s> ManagingNode<'s> {
-    /// Creates a new Managing Node.
+    /// Creates a new Managing Node. DLL errors are logged with `LoggingErrorHandler`.
     ///
     /// # Arguments
     /// * `od` - The Object Dictionary containing the node's configuration.
     /// * `mac_address` - The physical MAC address of the node.
     /// * `configuration_interface` - An optional interface to an external Configuration Manager (CFM).
     pub fn new(
+        od: ObjectDictionary<'s>,
+        mac_address: MacAddress,
+        configuration_interface: Option<&'s dyn ConfigurationInterface>,
+    ) -> Result<Self, PowerlinkError> {
+        Self::with_error_handler(
+            od,
+            mac_address,
+            configuration_interface,
+            Box::new(LoggingErrorHandler),
+        )
+    }
+
+    /// Creates a new Managing Node that reports DLL errors to `error_handler`.
+    ///
+    /// # Arguments
+    /// * `od` - The Object Dictionary containing the node's configuration.
+    /// * `mac_address` - The physical MAC address of the node.
+    /// * `configuration_interface` - An optional interface to an external Configuration Manager (CFM).
+    /// * `error_handler` - Receives every DLL error, e.g. to raise alarms.
+    pub fn with_error_handler(
         mut od: ObjectDictionary<'s>,
         mac_address: MacAddress,
         configuration_interface: Option<&'s dyn ConfigurationInterface>,
+        error_handler: Box<dyn ErrorHandler>,
     ) -> Result<Self, PowerlinkError> {
         info!("Creating new Managing Node.");
         od.init()?;
@@ -83,6 +116,8 @@ impl<'s> ManagingNode<'s> {
             mac_address,
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         // --- Initialize MnContext ---
@@ -91,10 +126,12 @@ impl<'s> ManagingNode<'s> {
             configuration_interface,
             nmt_state_machine,
             dll_state_machine: DllMsStateMachine::new(),
-            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), error_handler),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
             cycle_time_us,
             multiplex_cycle_len: 8, // Default, TODO: Read from 0x1F98
             multiplex_assign,
@@ -111,6 +148,7 @@ impl<'s> ManagingNode<'s> {
             async_request_queue: BinaryHeap::new(),
             pending_er_requests: Vec::new(),
             pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
             pending_nmt_commands: Vec::new(),
             mn_async_send_queue: Vec::new(),
             sdo_client_manager: SdoClientManager::new(),
@@ -120,6 +158,7 @@ impl<'s> ManagingNode<'s> {
             pending_timeout_event: None,
             current_cycle_start_time_us: 0,
             initial_operational_actions_done: false,
+            boot: Default::default(),
         };
 
         let mut node = Self { context };
@@ -131,6 +170,7 @@ impl<'s> ManagingNode<'s> {
         node.context
             .nmt_state_machine
             .run_internal_initialisation(&mut node.context.core.od);
+        node.apply_communication_reset();
 
         Ok(node)
     }
@@ -143,7 +183,7 @@ impl<'s> ManagingNode<'s> {
     ) -> NodeAction {
         match frame {
             PowerlinkFrame::PRes(pres_frame) => {
-                events::process_frame(
+                return events::process_frame(
                     &mut self.context,
                     PowerlinkFrame::PRes(pres_frame),
                     current_time_us,
@@ -216,7 +256,7 @@ impl<'s> ManagingNode<'s> {
                     asnd_source_node_id.0
                 );
                 let payload = match &asnd_frame {
-                    PowerlinkFrame::ASnd(f) => &f.payload,
+                    PowerlinkFrame::ASnd(f) => restore_trimmed_padding(&f.payload),
                     _ => unreachable!(),
                 };
                 if payload.len() < 8 {
@@ -265,12 +305,15 @@ impl<'s> ManagingNode<'s> {
                     source_node_id: asnd_source_node_id,
                     source_mac,
                 };
-                return self.handle_sdo_server_request(&payload, client_info, current_time_us);
+                return self.handle_sdo_server_request(
+                    &restore_trimmed_padding(&payload),
+                    client_info,
+                    current_time_us,
+                );
             }
         }
 
-        events::process_frame(&mut self.context, asnd_frame, current_time_us);
-        NodeAction::NoAction
+        events::process_frame(&mut self.context, asnd_frame, current_time_us)
     }
 
     fn handle_sdo_server_request(
@@ -326,6 +369,20 @@ impl<'s> ManagingNode<'s> {
         ));
     }
 
+    /// Queues an NMT state command for a group of CNs.
+    ///
+    /// When the MN and all listed CNs advertise extended NMT commands
+    /// (0x1F82 bit 5), one broadcast of the extended command (e.g.
+    /// NMTStartNodeEx) is sent. Otherwise the command is sent to each CN.
+    pub fn queue_nmt_group_command(&mut self, command: NmtStateCommand, nodes: &NodeList) {
+        info!(
+            "Queueing NMT State Command: {:?} for {} node(s)",
+            command,
+            nodes.len()
+        );
+        scheduler::queue_group_state_command(&mut self.context, command, nodes);
+    }
+
     /// Queues an NMTNetHostNameSet command to be sent to a target CN.
     pub fn set_hostname(
         &mut self,
@@ -376,6 +433,7 @@ impl<'s> ManagingNode<'s> {
             "Queueing SDO Read from Node {} for 0x{:04X}/{}",
             target.0, index, sub_index
         );
+        scheduler::update_sdo_transport(&mut self.context, target);
         self.context.sdo_client_manager.read_object_by_index(
             target,
             index,
@@ -401,6 +459,7 @@ impl<'s> ManagingNode<'s> {
             sub_index,
             data.len()
         );
+        scheduler::update_sdo_transport(&mut self.context, target);
         self.context.sdo_client_manager.write_object_by_index(
             target,
             index,
@@ -411,6 +470,261 @@ impl<'s> ManagingNode<'s> {
         )
     }
 
+    /// Initiates an SDO ReadByName transfer from a target CN.
+    pub fn read_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: &str,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadByName from Node {} for '{}'",
+            target.0, name
+        );
+        scheduler::update_sdo_transport(&mut self.context, target);
+        self.context.sdo_client_manager.read_object_by_name(
+            target,
+            name.into(),
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO WriteByName transfer to a target CN.
+    pub fn write_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: &str,
+        data: Vec<u8>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO WriteByName to Node {} for '{}' ({} bytes)",
+            target.0,
+            name,
+            data.len()
+        );
+        scheduler::update_sdo_transport(&mut self.context, target);
+        self.context.sdo_client_manager.write_object_by_name(
+            target,
+            name.into(),
+            data,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO ReadAllByIndex transfer, reading all sub-indices of an object.
+    pub fn read_all_by_index(
+        &mut self,
+        target: NodeId,
+        index: u16,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadAllByIndex from Node {} for 0x{:04X}",
+            target.0, index
+        );
+        scheduler::update_sdo_transport(&mut self.context, target);
+        self.context.sdo_client_manager.read_all_by_index(
+            target,
+            index,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO ReadMultipleParamByIndex transfer from a target CN.
+    pub fn read_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<MultipleParamEntry>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO ReadMultipleParamByIndex from Node {} ({} entries)",
+            target.0,
+            entries.len()
+        );
+        scheduler::update_sdo_transport(&mut self.context, target);
+        self.context.sdo_client_manager.read_multiple_params(
+            target,
+            entries,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Initiates an SDO WriteMultipleParamByIndex transfer to a target CN.
+    pub fn write_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<WriteMultipleParamEntry>,
+        current_time_us: u64,
+    ) -> Result<(), PowerlinkError> {
+        info!(
+            "Queueing SDO WriteMultipleParamByIndex to Node {} ({} entries)",
+            target.0,
+            entries.len()
+        );
+        scheduler::update_sdo_transport(&mut self.context, target);
+        self.context.sdo_client_manager.write_multiple_params(
+            target,
+            entries,
+            current_time_us,
+            &self.context.core.od,
+        )
+    }
+
+    /// Sets the SDO sequence layer window used for transfers to `target`:
+    /// the number of download segments sent before waiting for an ACK.
+    pub fn set_sdo_window_size(&mut self, target: NodeId, window_size: u8) {
+        self.context
+            .sdo_client_manager
+            .set_window_size(target, window_size);
+    }
+
+    /// Returns the outcome of the oldest finished SDO client transfer, if any.
+    pub fn poll_sdo_result(&mut self) -> Option<SdoTransferResult> {
+        self.context.sdo_client_manager.pop_completed()
+    }
+
+    /// Enables the process image: a contiguous input/output buffer laid out
+    /// from the active RPDO/TPDO mappings. Mapped objects are then exchanged
+    /// through the image instead of the Object Dictionary, and the image is
+    /// rebuilt automatically when the mappings change.
+    pub fn enable_process_image(&mut self) -> &mut ProcessImage {
+        self.context.core.enable_process_image()
+    }
+
+    /// Returns the process image, if enabled.
+    pub fn process_image(&mut self) -> Option<&mut ProcessImage> {
+        self.context.core.refresh_process_image();
+        self.context.core.process_image.as_mut()
+    }
+
+    /// Releases the MN to enter ReadyToOperate when bit 8 of NMT_StartUp_U32
+    /// (0x1F80) holds the boot-up in PreOperational2.
+    ///
+    /// The transition is made as soon as all mandatory CNs are PreOperational2;
+    /// until then the release is kept. Fails if the MN is not in PreOperational2.
+    pub fn enter_ready_to_operate(&mut self) -> Result<(), PowerlinkError> {
+        self.release_boot_state(NmtState::NmtPreOperational2, NmtState::NmtReadyToOperate)
+    }
+
+    /// Releases the MN to enter Operational when bit 2 of NMT_StartUp_U32
+    /// (0x1F80) holds the boot-up in ReadyToOperate.
+    ///
+    /// The transition is made as soon as all mandatory CNs have passed
+    /// CHECK_COMMUNICATION. Fails if the MN is not in ReadyToOperate.
+    pub fn enter_operational(&mut self) -> Result<(), PowerlinkError> {
+        self.release_boot_state(NmtState::NmtReadyToOperate, NmtState::NmtOperational)
+    }
+
+    /// Processes the link state queried from the `NetworkInterface`.
+    ///
+    /// Raises `E_DLL_LOSS_OF_LINK` when the link goes down, `E_DLL_BAD_PHYS_MODE`
+    /// when it comes up in the wrong mode and `E_DLL_MAC_BUFFER` when frames were
+    /// dropped. Each is counted and logged in the Error History (0x1003).
+    /// (Reference: EPSG DS 301, Section 4.7.4)
+    pub fn report_link_status(&mut self, status: LinkStatus, current_time_us: u64) {
+        let context = &mut self.context;
+        for error in context.dll_error_manager.link_errors(status) {
+            warn!("[MN] Link status reported error: {:?}", error);
+            let (_, signaled) = context.dll_error_manager.handle_error(error);
+            if signaled {
+                context.log_error_event(error.to_error_code(), 0, current_time_us);
+            }
+        }
+        context.dll_error_manager.sync_od(&mut context.core.od);
+    }
+
+    /// Sets how the MN reacts to CNs failing CHECK_COMMUNICATION.
+    pub fn set_check_communication_policy(&mut self, policy: CheckCommunicationPolicy) {
+        self.context.boot.check_communication_policy = policy;
+    }
+
+    fn release_boot_state(
+        &mut self,
+        required: NmtState,
+        target: NmtState,
+    ) -> Result<(), PowerlinkError> {
+        if self.context.nmt_state_machine.current_state() != required {
+            return Err(PowerlinkError::NotReady);
+        }
+        info!("[MN] Application released transition to {:?}.", target);
+        self.context.boot.release(target);
+        scheduler::check_bootup_state(&mut self.context);
+        Ok(())
+    }
+
+    /// Allows a host outside the POWERLINK segment (e.g. a SCADA system) to
+    /// send NMTCommand and NMTRequest services to the MN over UDP. Requires
+    /// NMT_SERVICE_UDP in the MN's feature flags (0x1F82 bit 7). Services
+    /// from addresses not allowed here are dropped.
+    #[cfg(feature = "sdo-udp")]
+    pub fn allow_nmt_udp_source(&mut self, source_ip: IpAddress) {
+        if !self.context.nmt_udp_sources.contains(&source_ip) {
+            info!(
+                "[MN] Allowing NMT services over UDP from {}.",
+                core::net::Ipv4Addr::from(source_ip)
+            );
+            self.context.nmt_udp_sources.push(source_ip);
+        }
+    }
+
+    /// Withdraws an address allowed with `allow_nmt_udp_source`.
+    #[cfg(feature = "sdo-udp")]
+    pub fn revoke_nmt_udp_source(&mut self, source_ip: IpAddress) {
+        self.context.nmt_udp_sources.retain(|ip| *ip != source_ip);
+    }
+
+    /// Returns the error entries reported by a CN in its StatusResponses.
+    pub fn cn_error_log(&self, node_id: NodeId) -> Option<&CnErrorLog> {
+        self.context.cn_error_logs.get(node_id)
+    }
+
+    /// Returns the error logs of all CNs, e.g. to acknowledge or clear entries
+    /// or to change the number of entries kept per CN.
+    pub fn cn_error_logs(&mut self) -> &mut CnErrorLogs {
+        &mut self.context.cn_error_logs
+    }
+
+    /// Returns the oldest node event not yet polled.
+    pub fn poll_event(&mut self) -> Option<TimedEvent> {
+        self.context.core.events.poll()
+    }
+
+    /// Returns the node's event queue, e.g. to inspect recent events without
+    /// consuming them or to change its capacity.
+    pub fn events(&mut self) -> &mut NodeEventQueue {
+        &mut self.context.core.events
+    }
+
+    /// Reloads the DLL error thresholds and counts from the OD after a reset
+    /// passed through NMT_GS_RESET_COMMUNICATION.
+    fn apply_communication_reset(&mut self) {
+        let context = &mut self.context;
+        if context.nmt_state_machine.take_communication_reset() {
+            context.dll_error_manager.load_from_od(&context.core.od);
+        }
+    }
+
+    /// Moves the events gathered during a cycle into the node's event queue.
+    fn finish_cycle(&mut self, action: NodeAction) -> NodeAction {
+        self.apply_communication_reset();
+        let context = &mut self.context;
+        context.dll_error_manager.sync_od(&mut context.core.od);
+        let finished = context.sdo_client_manager.take_finished();
+        validation::finish_configuration_downloads(context, &finished);
+        context.core.events.collect(
+            context.nmt_state_machine.take_transitions(),
+            context.dll_error_manager.take_reported(),
+            finished,
+        );
+        action
+    }
+
     #[cfg(feature = "sdo-udp")]
     fn process_udp_datagram(
         &mut self,
@@ -426,6 +740,16 @@ impl<'s> ManagingNode<'s> {
             payload.len()
         );
 
+        if is_nmt_udp_payload(payload) {
+            match deserialize_nmt_udp_payload(payload) {
+                Ok(service) => {
+                    events::handle_nmt_udp_service(&mut self.context, service, source_ip)
+                }
+                Err(e) => warn!("Failed to deserialize NMT/UDP payload: {:?}", e),
+            }
+            return NodeAction::NoAction;
+        }
+
         match deserialize_sdo_udp_payload(payload) {
             Ok((seq_header, cmd)) => {
                 self.context.core.od.increment_counter(
@@ -433,6 +757,22 @@ impl<'s> ManagingNode<'s> {
                     constants::SUBIDX_DIAG_NMT_COUNT_SDO_RX,
                 );
 
+                // Responses belong to our own SDO/UDP client connections.
+                // The CN's node ID is the host part of its IP address.
+                if cmd.header.is_response {
+                    match NodeId::try_from(source_ip[3]) {
+                        Ok(source) => self
+                            .context
+                            .sdo_client_manager
+                            .handle_response(source, seq_header, cmd),
+                        Err(_) => warn!(
+                            "Ignoring SDO/UDP response from unexpected address {}.",
+                            core::net::Ipv4Addr::from(source_ip)
+                        ),
+                    }
+                    return NodeAction::NoAction;
+                }
+
                 let client_info = SdoClientInfo::Udp {
                     source_ip,
                     source_port,
@@ -484,13 +824,14 @@ impl<'s> Node for ManagingNode<'s> {
         udp_datagram: Option<(&[u8], IpAddress, u16)>,
         current_time_us: u64,
     ) -> NodeAction {
+        self.context.core.events.set_time(current_time_us);
         if let Some(buffer) = ethernet_frame {
             if buffer.len() >= 14
                 && buffer[12..14] == crate::types::C_DLL_ETHERTYPE_EPL.to_be_bytes()
             {
                 let action = self.process_ethernet_frame(buffer, current_time_us);
                 if action != NodeAction::NoAction {
-                    return action;
+                    return self.finish_cycle(action);
                 }
             }
         }
@@ -504,29 +845,32 @@ impl<'s> Node for ManagingNode<'s> {
                 );
             }
             if action != NodeAction::NoAction {
-                return action;
+                return self.finish_cycle(action);
             }
         }
 
         // Call the new tick module
-        super::tick::handle_tick(&mut self.context, current_time_us)
+        let action = super::tick::handle_tick(&mut self.context, current_time_us);
+        self.finish_cycle(action)
     }
 
     #[cfg(not(feature = "sdo-udp"))]
     fn run_cycle(&mut self, ethernet_frame: Option<&[u8]>, current_time_us: u64) -> NodeAction {
+        self.context.core.events.set_time(current_time_us);
         if let Some(buffer) = ethernet_frame {
             if buffer.len() >= 14
                 && buffer[12..14] == crate::types::C_DLL_ETHERTYPE_EPL.to_be_bytes()
             {
                 let action = self.process_ethernet_frame(buffer, current_time_us);
                 if action != NodeAction::NoAction {
-                    return action;
+                    return self.finish_cycle(action);
                 }
             }
         }
 
         // Call the new tick module
-        super::tick::handle_tick(&mut self.context, current_time_us)
+        let action = super::tick::handle_tick(&mut self.context, current_time_us);
+        self.finish_cycle(action)
     }
 
     fn nmt_state(&self) -> NmtState {
@@ -543,6 +887,10 @@ impl<'s> Node for ManagingNode<'s> {
         if self.nmt_state() == NmtState::NmtNotActive && self.context.next_tick_us.is_none() {
             return Some(0);
         }
+        #[cfg(feature = "sdo-udp")]
+        if self.context.sdo_client_manager.has_pending_udp_request() {
+            return Some(0);
+        }
 
         let sdo_server_time = self.context.core.sdo_server.next_action_time();
         let sdo_client_time = self
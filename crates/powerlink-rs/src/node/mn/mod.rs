@@ -1,6 +1,8 @@
 // crates/powerlink-rs/src/node/mn/mod.rs
+mod boot;
 pub(crate) mod config;
 mod cycle;
+mod error_log;
 mod events;
 mod main;
 mod payload;
@@ -9,13 +11,7 @@ mod state;
 mod tick; // <-- ADDED
 pub mod validation;
 
+pub use boot::{BootCheck, CheckCommunicationPolicy, E_NMT_BRO, MnBootEvent};
+pub use error_log::{CnErrorLog, CnErrorLogs, CnErrorRecord, DEFAULT_CN_ERROR_LOG_CAPACITY};
 pub use main::ManagingNode;
 pub use state::{CnInfo, CnState, MnContext};
-
-use crate::{NodeId, types::IpAddress};
-
-/// Helper to derive a CN's IP Address from its Node ID.
-/// (Per EPSG DS 301, Section 5.1.2)
-fn ip_from_node_id(node_id: NodeId) -> IpAddress {
-    [192, 168, 100, node_id.0]
-}
@@ -11,12 +11,20 @@ use crate::frame::{
 };
 use crate::nmt::NmtStateMachine;
 use crate::nmt::events::MnNmtCommandRequest;
+use crate::nmt::node_list::NodeList;
 use crate::od::ObjectValue;
 use crate::pdo::{PDOVersion, PdoMappingEntry};
 use crate::sdo::asnd::serialize_sdo_asnd_payload;
 use crate::sdo::command::SdoCommand;
 use crate::sdo::sequence::SequenceLayerHeader;
+#[cfg(feature = "sdo-udp")]
+use crate::sdo::udp::serialize_sdo_udp_payload;
 use crate::types::{C_ADR_BROADCAST_NODE_ID, C_ADR_MN_DEF_NODE_ID, EPLVersion, NodeId};
+#[cfg(feature = "sdo-udp")]
+use crate::{
+    node::{NodeAction, ip_from_node_id},
+    types::C_SDO_EPL_PORT,
+};
 use alloc::vec;
 use alloc::vec::Vec;
 use log::{debug, error, trace, warn};
@@ -169,6 +177,7 @@ pub(super) fn build_tpdo_payload(
 ) -> Result<(Vec<u8>, PDOVersion), PowerlinkError> {
     let comm_param_index = OD_IDX_TPDO_COMM_PARAM_BASE + channel_index as u16;
     let mapping_index = OD_IDX_TPDO_MAPP_PARAM_BASE + channel_index as u16;
+    context.core.refresh_process_image();
     let od = &context.core.od;
 
     let target_node_id = od
@@ -255,6 +264,10 @@ pub(super) fn build_tpdo_payload(
                             .get_pending_request(entry.index, length);
                         data_slice.copy_from_slice(&request_payload);
                     }
+                    // Sent from the process image if it holds the object.
+                    _ if context.core.process_image.as_ref().is_some_and(|image| {
+                        image.load_output(entry.index, entry.sub_index, data_slice)
+                    }) => {}
                     // Standard Data Object
                     _ => {
                         // --- This is the OLD logic, now in the 'else' branch ---
@@ -388,6 +401,14 @@ pub(super) fn build_nmt_command_frame(
         NmtCommandData::FlushArp(flush_target_node) => {
             vec![command.as_u8(), 0u8, flush_target_node.0]
         }
+        NmtCommandData::NodeList(nodes) => {
+            // Extended NMT State Command (34 bytes: CommandID + Reserved + NodeList[32])
+            let mut payload = Vec::with_capacity(34);
+            payload.push(command.as_u8());
+            payload.push(0u8);
+            payload.extend_from_slice(nodes.as_bytes());
+            payload
+        }
     };
     // --- End of Payload Build ---
 
@@ -478,26 +499,13 @@ fn build_node_list_payload<F>(context: &MnContext, filter: F) -> Vec<u8>
 where
     F: Fn(&super::state::CnInfo) -> bool,
 {
-    let mut payload_bytes = [0u8; 32];
-
-    let mut set_bit = |node_id: u8| {
-        if node_id == 0 || node_id > 254 {
-            return;
-        }
-        let node_idx_0_based = (node_id - 1) as usize;
-        let byte_index = node_idx_0_based / 8;
-        let bit_index = node_idx_0_based % 8;
-        if let Some(byte) = payload_bytes.get_mut(byte_index) {
-            *byte |= 1 << bit_index;
-        }
-    };
-
     // Check all CNs
-    for (node_id, info) in &context.node_info {
-        if filter(info) {
-            set_bit(node_id.0);
-        }
-    }
+    let mut node_list: NodeList = context
+        .node_info
+        .iter()
+        .filter(|(_, info)| filter(info))
+        .map(|(node_id, _)| *node_id)
+        .collect();
 
     // Check if MN itself should be included (Node 240)
     // This is context-dependent, but generally, if the MN is part of the active set, include it.
@@ -515,11 +523,11 @@ where
             state: CnState::Operational, // Dummy success state
             ..Default::default()
         }) {
-            set_bit(context.nmt_state_machine.node_id().0);
+            node_list.insert(context.nmt_state_machine.node_id());
         }
     }
 
-    payload_bytes.to_vec()
+    node_list.as_bytes().to_vec()
 }
 
 /// Builds an ASnd(SDO Request) frame for the SdoClientManager.
@@ -551,4 +559,27 @@ pub(super) fn build_sdo_asnd_request(
         ServiceId::Sdo,
         sdo_payload,
     )))
-}
\ No newline at end of file
+}
+
+/// Builds the UDP datagram for an SDO client request to a CN.
+///
+/// The CN is addressed by its node-derived IP on the POWERLINK SDO port.
+#[cfg(feature = "sdo-udp")]
+pub(super) fn build_sdo_udp_request(
+    target_node_id: NodeId,
+    seq_header: SequenceLayerHeader,
+    cmd: SdoCommand,
+) -> Result<NodeAction, PowerlinkError> {
+    trace!(
+        "Building SDO UDP request for Node {} (TID {})",
+        target_node_id.0, cmd.header.transaction_id
+    );
+    let mut udp_buffer = vec![0u8; 1500];
+    let len = serialize_sdo_udp_payload(seq_header, cmd, &mut udp_buffer)?;
+    udp_buffer.truncate(len);
+    Ok(NodeAction::SendUdp {
+        dest_ip: ip_from_node_id(target_node_id),
+        dest_port: C_SDO_EPL_PORT,
+        data: udp_buffer,
+    })
+}
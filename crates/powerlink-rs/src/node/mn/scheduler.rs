@@ -1,15 +1,20 @@
 // crates/powerlink-rs/src/node/mn/scheduler.rs
+use super::boot::{MnBootEvent, report_boot_event};
 use super::payload;
 use super::state::{CnInfo, CnState, MnContext};
 use super::validation; // <-- Use the new validation module
 use crate::frame::basic::MacAddress;
 use crate::frame::{DllMsEvent, PowerlinkFrame, RequestedServiceId, ServiceId};
 use crate::nmt::events::{MnNmtCommandRequest, NmtEvent, NmtStateCommand};
+use crate::nmt::flags::FeatureFlags;
+use crate::nmt::node_list::NodeList;
 use crate::nmt::{NmtStateMachine, states::NmtState};
-use crate::node::mn::ip_from_node_id;
+use crate::node::ip_from_node_id;
 use crate::node::mn::state::NmtCommandData;
-use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
-use log::{debug, info, trace};
+use crate::sdo::SdoClientTransport;
+use crate::sdo::asnd::serialize_sdo_asnd_payload;
+use crate::types::{C_ADR_BROADCAST_NODE_ID, C_ADR_MN_DEF_NODE_ID, NodeId};
+use log::{debug, error, info, trace};
 
 /// Looks up a CN's MAC address from the dynamic ARP cache.
 // ... [get_cn_mac_address unchanged] ...
@@ -33,6 +38,35 @@ pub(super) fn get_cn_mac_address(context: &MnContext, node_id: NodeId) -> Option
     }
 }
 
+/// Selects the SDO client transport for a CN and applies it to the SDO
+/// client manager.
+///
+/// SDO/UDP is used if the CN advertised it in its IdentResponse feature flags
+/// and its IP address is resolved in the ARP cache; otherwise ASnd is used.
+pub(super) fn update_sdo_transport(context: &mut MnContext, node_id: NodeId) {
+    let transport = select_sdo_transport(context, node_id);
+    context.sdo_client_manager.set_transport(node_id, transport);
+}
+
+#[cfg(feature = "sdo-udp")]
+fn select_sdo_transport(context: &MnContext, node_id: NodeId) -> SdoClientTransport {
+    let supports_udp = context
+        .node_info
+        .get(&node_id)
+        .and_then(|info| info.identity)
+        .is_some_and(|identity| identity.feature_flags.contains(FeatureFlags::SDO_UDP));
+    if supports_udp && get_cn_mac_address(context, node_id).is_some() {
+        SdoClientTransport::Udp
+    } else {
+        SdoClientTransport::Asnd
+    }
+}
+
+#[cfg(not(feature = "sdo-udp"))]
+fn select_sdo_transport(_context: &MnContext, _node_id: NodeId) -> SdoClientTransport {
+    SdoClientTransport::Asnd
+}
+
 /// Determines the highest priority asynchronous action to be taken.
 // ... [determine_next_async_action unchanged] ...
 pub(super) fn determine_next_async_action(
@@ -41,6 +75,10 @@ pub(super) fn determine_next_async_action(
     // 1. Check for pending Exception Reset requests (highest priority).
     if let Some(node_to_reset) = context.pending_er_requests.pop() {
         info!("[MN] Prioritizing ER for Node {}.", node_to_reset.0);
+        // The CN resets its EN flag on ER, so the acknowledgement starts over.
+        if let Some(info) = context.node_info.get_mut(&node_to_reset) {
+            info.ea_flag = false;
+        }
         return (RequestedServiceId::StatusRequest, node_to_reset, true);
     }
 
@@ -85,11 +123,16 @@ pub(super) fn determine_next_async_action(
         );
 
         // Construct the ASnd frame here and push it to the generic queue.
-        let sdo_payload = {
-            let mut buf = alloc::vec::Vec::with_capacity(128);
-            let _ = seq.serialize(&mut buf);
-            let _ = cmd.serialize(&mut buf);
-            buf
+        let sdo_payload = match serialize_sdo_asnd_payload(seq, cmd) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("[MN] Failed to serialize SDO request: {:?}", e);
+                return (
+                    RequestedServiceId::UnspecifiedInvite,
+                    NodeId(C_ADR_MN_DEF_NODE_ID),
+                    false,
+                );
+            }
         };
 
         let dest_mac = get_cn_mac_address(context, target_node_id)
@@ -171,28 +214,40 @@ pub(super) fn check_bootup_state(context: &mut MnContext) {
             context
                 .nmt_state_machine
                 .process_event(NmtEvent::AllCnsIdentified, &mut context.core.od);
+            // A new boot-up starts: releases given before a reset no longer apply.
+            context.boot.clear_release();
+            context.boot.complete_step(current_mn_state);
+            report_boot_event(context, MnBootEvent::BootStep1Complete);
 
             // --- Phase 1.4: BOOT_STEP2 ---
             // Queue NMTEnableReadyToOperate for all identified CNs.
             // (Iterate all nodes, not just mandatory)
-            for (node_id, info) in context.node_info.iter() {
-                if info.state == CnState::Identified {
-                    info!(
-                        "[MN] BOOT_STEP2: Queuing NMTEnableReadyToOperate for Node {}.",
-                        node_id.0
-                    );
-                    context.pending_nmt_commands.push((
-                        MnNmtCommandRequest::State(NmtStateCommand::EnableReadyToOperate),
-                        *node_id,
-                        NmtCommandData::None,
-                    ));
-                }
-            }
+            let identified: NodeList = context
+                .node_info
+                .iter()
+                .filter(|(_, info)| info.state == CnState::Identified)
+                .map(|(node_id, _)| *node_id)
+                .collect();
+            info!(
+                "[MN] BOOT_STEP2: Queuing NMTEnableReadyToOperate for {} node(s).",
+                identified.len()
+            );
+            queue_group_state_command(
+                context,
+                NmtStateCommand::EnableReadyToOperate,
+                &identified,
+            );
         }
     } else if current_mn_state == NmtState::NmtPreOperational2 {
         // Check if all mandatory nodes are PreOperational or further
         if validation::check_all_mandatory_preop(&context.mandatory_nodes, &context.node_info) {
-            if context.nmt_state_machine.startup_flags & (1 << 8) == 0 {
+            let first_report = context.boot.complete_step(current_mn_state);
+            if first_report {
+                report_boot_event(context, MnBootEvent::BootStep2Complete);
+            }
+            if context.nmt_state_machine.startup_flags & (1 << 8) == 0
+                || context.boot.is_released(NmtState::NmtReadyToOperate)
+            {
                 info!(
                     "[MN] All mandatory nodes PreOperational/ReadyToOp. Triggering NMT transition to ReadyToOp."
                 );
@@ -200,10 +255,16 @@ pub(super) fn check_bootup_state(context: &mut MnContext) {
                     NmtEvent::ConfigurationCompleteCnsReady,
                     &mut context.core.od,
                 );
-            } else {
+                context.boot.clear_release();
+                report_boot_event(context, MnBootEvent::ReadyToOperate);
+            } else if first_report {
                 debug!(
                     "[MN] All mandatory nodes PreOperational/ReadyToOp, but waiting for application trigger to enter ReadyToOp."
                 );
+                report_boot_event(
+                    context,
+                    MnBootEvent::AwaitingApplication(NmtState::NmtReadyToOperate),
+                );
             }
         }
     } else if current_mn_state == NmtState::NmtReadyToOperate {
@@ -212,22 +273,158 @@ pub(super) fn check_bootup_state(context: &mut MnContext) {
             &context.mandatory_nodes,
             &context.node_info,
         ) {
-            if context.nmt_state_machine.startup_flags & (1 << 2) == 0 {
+            let first_report = context.boot.complete_step(current_mn_state);
+            if first_report {
+                report_boot_event(context, MnBootEvent::CheckCommunicationComplete);
+            }
+            if context.nmt_state_machine.startup_flags & (1 << 2) == 0
+                || context.boot.is_released(NmtState::NmtOperational)
+            {
                 info!(
                     "[MN] CHECK_COMMUNICATION passed for all mandatory nodes. Triggering NMT transition to Operational."
                 );
                 context
                     .nmt_state_machine
                     .process_event(NmtEvent::AllMandatoryCnsOperational, &mut context.core.od);
-            } else {
+                context.boot.clear_release();
+                report_boot_event(context, MnBootEvent::Operational);
+            } else if first_report {
                 debug!(
                     "[MN] All mandatory nodes passed CHECK_COMMUNICATION, but waiting for application trigger to enter Operational."
                 );
+                report_boot_event(
+                    context,
+                    MnBootEvent::AwaitingApplication(NmtState::NmtOperational),
+                );
             }
         }
     }
 }
 
+/// Continues the boot-up of a CN identified after the MN has left
+/// PreOperational1, e.g. an optional CN plugged into a running network.
+///
+/// The CN is enabled for ReadyToOperate on its own. It is then polled in the
+/// isochronous phase, checked with CHECK_COMMUNICATION and started once it
+/// reports ReadyToOperate, without affecting the CNs already running.
+pub(super) fn continue_late_boot(context: &mut MnContext, node_id: NodeId) {
+    if !matches!(
+        context.nmt_state_machine.current_state(),
+        NmtState::NmtPreOperational2 | NmtState::NmtReadyToOperate | NmtState::NmtOperational
+    ) {
+        // BOOT_STEP2 enables all identified CNs when the MN enters PreOperational2.
+        return;
+    }
+    info!(
+        "[MN] Node {} joined late. Queuing NMTEnableReadyToOperate.",
+        node_id.0
+    );
+    if let Some(info) = context.node_info.get_mut(&node_id) {
+        info.communication_ok = false;
+    }
+    context.pending_nmt_commands.push((
+        MnNmtCommandRequest::State(NmtStateCommand::EnableReadyToOperate),
+        node_id,
+        NmtCommandData::None,
+    ));
+}
+
+/// Queues NMTResetConfiguration for a CN whose configuration was downloaded.
+///
+/// The CN applies the configuration and boots again. Its next IdentResponse
+/// repeats CHECK_CONFIGURATION, during boot-up as well as for a late CN.
+pub(super) fn reset_configuration(context: &mut MnContext, node_id: NodeId) {
+    info!(
+        "[MN-CFM] Queuing NMTResetConfiguration for Node {}.",
+        node_id.0
+    );
+    context.pending_nmt_commands.push((
+        MnNmtCommandRequest::State(NmtStateCommand::ResetConfiguration),
+        node_id,
+        NmtCommandData::None,
+    ));
+}
+
+/// Queues NMTStartNode for a CN that reached ReadyToOperate while the MN is
+/// Operational, unless one is already pending.
+pub(super) fn queue_start_node(context: &mut MnContext, node_id: NodeId) {
+    if is_command_pending(context, NmtStateCommand::StartNode, node_id) {
+        return;
+    }
+    info!(
+        "[MN] Node {} is ReadyToOperate. Queuing NMTStartNode.",
+        node_id.0
+    );
+    context.pending_nmt_commands.push((
+        MnNmtCommandRequest::State(NmtStateCommand::StartNode),
+        node_id,
+        NmtCommandData::None,
+    ));
+}
+
+/// Returns true if an NMT state command for `node_id` is waiting to be sent.
+pub(super) fn is_command_pending(
+    context: &MnContext,
+    command: NmtStateCommand,
+    node_id: NodeId,
+) -> bool {
+    let command = MnNmtCommandRequest::State(command);
+    context
+        .pending_nmt_commands
+        .iter()
+        .any(|(req, target, _)| *req == command && *target == node_id)
+}
+
+/// Queues an NMT state command for a group of CNs.
+///
+/// If the MN and every addressed CN support extended NMT commands (feature
+/// flag bit 5, Spec 7.3.1.2.3), a single broadcast of the extended command with
+/// the node list is queued. Otherwise the command is queued for each CN.
+pub(super) fn queue_group_state_command(
+    context: &mut MnContext,
+    command: NmtStateCommand,
+    nodes: &NodeList,
+) {
+    let supports_extended = |node_id: NodeId| {
+        context
+            .node_info
+            .get(&node_id)
+            .and_then(|info| info.identity.as_ref())
+            .is_some_and(|identity| {
+                identity
+                    .feature_flags
+                    .contains(FeatureFlags::EXTENDED_NMT_CMDS)
+            })
+    };
+    let use_extended = nodes.len() > 1
+        && context
+            .nmt_state_machine
+            .feature_flags
+            .contains(FeatureFlags::EXTENDED_NMT_CMDS)
+        && nodes.iter().all(supports_extended);
+
+    if use_extended {
+        debug!(
+            "[MN] Queuing extended {:?} for {} nodes.",
+            command,
+            nodes.len()
+        );
+        context.pending_nmt_commands.push((
+            MnNmtCommandRequest::StateEx(command),
+            NodeId(C_ADR_BROADCAST_NODE_ID),
+            NmtCommandData::NodeList(*nodes),
+        ));
+    } else {
+        for node_id in nodes.iter() {
+            context.pending_nmt_commands.push((
+                MnNmtCommandRequest::State(command),
+                node_id,
+                NmtCommandData::None,
+            ));
+        }
+    }
+}
+
 /// Finds the next configured CN that has not been identified yet for polling.
 // ... [find_next_node_to_identify unchanged] ...
 pub(super) fn find_next_node_to_identify(context: &mut MnContext) -> Option<NodeId> {
@@ -334,7 +531,7 @@ pub(super) fn get_next_isochronous_node_to_poll(
                 .map_or(CnState::Unknown, |info| info.state);
             // Poll nodes from Identified onwards, excluding Missing.
             // CRITICAL FIX: Explicitly exclude Stopped nodes (EPSG 301, 7.1.4.1.2.5)
-            if state >= CnState::PreOperational && state != CnState::Stopped {
+            if is_isochronously_polled(state) {
                 // Found a valid node to poll in this cycle
                 trace!(
                     "[MN] Polling Node {} (State: {:?}, MuxCycle: {}) in mux cycle {}",
@@ -357,6 +554,16 @@ pub(super) fn get_next_isochronous_node_to_poll(
     None // No more nodes left to poll in this cycle
 }
 
+/// Returns true if a CN in `state` takes part in the isochronous phase.
+/// An identified CN enters PreOperational2 on the next SoC and then expects
+/// PReqs, which is also how its PRes reports the state it has reached.
+fn is_isochronously_polled(state: CnState) -> bool {
+    matches!(
+        state,
+        CnState::Identified | CnState::PreOperational | CnState::Operational
+    )
+}
+
 /// Helper to check if there are more isochronous nodes to poll in the current cycle.
 // ... [has_more_isochronous_nodes unchanged] ...
 pub(super) fn has_more_isochronous_nodes(context: &MnContext, current_multiplex_cycle: u8) -> bool {
@@ -374,7 +581,7 @@ pub(super) fn has_more_isochronous_nodes(context: &MnContext, current_multiplex_
                 .get(&node_id)
                 .map_or(CnState::Unknown, |info| info.state);
             // CRITICAL FIX: Explicitly exclude Stopped nodes here too
-            if state >= CnState::PreOperational && state != CnState::Stopped {
+            if is_isochronously_polled(state) {
                 return true; // Found at least one more node to poll
             }
         }
@@ -408,6 +615,7 @@ mod tests {
     use alloc::collections::{BTreeMap, BinaryHeap};
 
     use super::*;
+    use crate::node::mn::boot::poll_boot_event;
     use crate::frame::error::{DllErrorManager, LoggingErrorHandler, MnErrorCounters};
     use crate::frame::ms_state_machine::DllMsStateMachine;
     use crate::nmt::mn_state_machine::MnNmtStateMachine;
@@ -419,6 +627,7 @@ mod tests {
     use crate::sdo::transport::UdpTransport;
     use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
     use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
+    use alloc::boxed::Box;
     use alloc::vec::Vec;
     use crate::node::mn::state::{CnInfo, CnState};
 
@@ -432,6 +641,8 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         MnContext {
@@ -444,10 +655,15 @@ mod tests {
                 0,
             ),
             dll_state_machine: DllMsStateMachine::default(),
-            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(
+                MnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
             cycle_time_us: 10000,
             multiplex_cycle_len: 10,
             multiplex_assign: BTreeMap::new(),
@@ -464,6 +680,7 @@ mod tests {
             async_request_queue: BinaryHeap::new(),
             pending_er_requests: Vec::new(),
             pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
             pending_nmt_commands: Vec::new(),
             mn_async_send_queue: Vec::new(),
             sdo_client_manager: SdoClientManager::new(),
@@ -473,6 +690,7 @@ mod tests {
             pending_timeout_event: None,
             current_cycle_start_time_us: 0,
             initial_operational_actions_done: false,
+            boot: Default::default(),
         }
     }
 
@@ -481,8 +699,15 @@ mod tests {
         let mut context = create_test_context();
         let node_id = NodeId(10);
 
-        // Setup: Add a pending ER request
+        // Setup: Add a pending ER request for a CN whose exception was acknowledged
         context.pending_er_requests.push(node_id);
+        context.node_info.insert(
+            node_id,
+            CnInfo {
+                ea_flag: true,
+                ..Default::default()
+            },
+        );
 
         // Act
         let (service, target, er_flag) = determine_next_async_action(&mut context);
@@ -492,6 +717,8 @@ mod tests {
         assert_eq!(target, node_id);
         assert_eq!(er_flag, true);
         assert!(context.pending_er_requests.is_empty());
+        // The acknowledgement starts over with the reset
+        assert!(!context.node_info[&node_id].ea_flag);
     }
 
     #[test]
@@ -677,4 +904,130 @@ mod tests {
         let n_end = get_next_isochronous_node_to_poll(&mut context, 0);
         assert_eq!(n_end, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_isochronous_scheduler_polls_identified_nodes() {
+        let mut context = create_test_context();
+        for (node, state) in [
+            (1, CnState::Unknown),
+            (2, CnState::Identified),
+            (3, CnState::Missing),
+        ] {
+            context.isochronous_nodes.push(NodeId(node));
+            context.node_info.insert(
+                NodeId(node),
+                CnInfo {
+                    state,
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Only the identified node is polled; unknown and missing nodes are not.
+        context.next_isoch_node_idx = 0;
+        assert_eq!(
+            get_next_isochronous_node_to_poll(&mut context, 0),
+            Some(NodeId(2))
+        );
+        assert_eq!(get_next_isochronous_node_to_poll(&mut context, 0), None);
+    }
+
+    #[test]
+    fn test_bootup_waits_for_application_release() {
+        let mut context = create_test_context();
+        let node_id = NodeId(1);
+        context.mandatory_nodes.push(node_id);
+        context.node_info.insert(
+            node_id,
+            CnInfo {
+                state: CnState::PreOperational,
+                ..Default::default()
+            },
+        );
+        context.nmt_state_machine.current_state = NmtState::NmtPreOperational2;
+        context.nmt_state_machine.startup_flags = 1 << 8;
+
+        // Held in PreOp2; the wait is reported once.
+        check_bootup_state(&mut context);
+        check_bootup_state(&mut context);
+        assert_eq!(
+            context.nmt_state_machine.current_state(),
+            NmtState::NmtPreOperational2
+        );
+        assert_eq!(poll_boot_event(&mut context.core.events), Some(MnBootEvent::BootStep2Complete));
+        assert_eq!(
+            poll_boot_event(&mut context.core.events),
+            Some(MnBootEvent::AwaitingApplication(NmtState::NmtReadyToOperate))
+        );
+        assert_eq!(poll_boot_event(&mut context.core.events), None);
+
+        // The application's release lets the boot-up continue.
+        context.boot.release(NmtState::NmtReadyToOperate);
+        check_bootup_state(&mut context);
+        assert_eq!(
+            context.nmt_state_machine.current_state(),
+            NmtState::NmtReadyToOperate
+        );
+        assert_eq!(poll_boot_event(&mut context.core.events), Some(MnBootEvent::ReadyToOperate));
+        assert!(!context.boot.is_released(NmtState::NmtReadyToOperate));
+    }
+
+    #[test]
+    fn test_group_command_uses_extended_command_when_supported() {
+        use crate::node::mn::state::CnIdentity;
+
+        let mut context = create_test_context();
+        context.nmt_state_machine.feature_flags = FeatureFlags::EXTENDED_NMT_CMDS;
+        let with_flags = |feature_flags| CnInfo {
+            identity: Some(CnIdentity {
+                device_type: 0,
+                vendor_id: 0,
+                product_code: 0,
+                revision_no: 0,
+                serial_no: 0,
+                feature_flags,
+            }),
+            ..Default::default()
+        };
+        context
+            .node_info
+            .insert(NodeId(1), with_flags(FeatureFlags::EXTENDED_NMT_CMDS));
+        context
+            .node_info
+            .insert(NodeId(2), with_flags(FeatureFlags::EXTENDED_NMT_CMDS));
+        context
+            .node_info
+            .insert(NodeId(3), with_flags(FeatureFlags::empty()));
+
+        let supported: NodeList = [NodeId(1), NodeId(2)].into_iter().collect();
+        queue_group_state_command(&mut context, NmtStateCommand::StartNode, &supported);
+        assert_eq!(
+            context.pending_nmt_commands,
+            [(
+                MnNmtCommandRequest::StateEx(NmtStateCommand::StartNode),
+                NodeId(C_ADR_BROADCAST_NODE_ID),
+                NmtCommandData::NodeList(supported),
+            )]
+        );
+
+        // One CN without support: fall back to one command per CN.
+        context.pending_nmt_commands.clear();
+        let mixed: NodeList = [NodeId(1), NodeId(3)].into_iter().collect();
+        queue_group_state_command(&mut context, NmtStateCommand::StartNode, &mixed);
+        assert_eq!(
+            context.pending_nmt_commands,
+            [
+                (
+                    MnNmtCommandRequest::State(NmtStateCommand::StartNode),
+                    NodeId(1),
+                    NmtCommandData::None,
+                ),
+                (
+                    MnNmtCommandRequest::State(NmtStateCommand::StartNode),
+                    NodeId(3),
+                    NmtCommandData::None,
+                ),
+            ]
+        );
+    }
+}
@@ -1,19 +1,28 @@
 // crates/powerlink-rs/src/node/mn/state.rs
 use crate::ErrorHandler;
+use crate::common::NetTime;
 use crate::frame::basic::MacAddress;
-use crate::frame::error::{DllErrorManager, ErrorCounters, LoggingErrorHandler, MnErrorCounters};
+use crate::frame::error::{
+    DllErrorManager, EntryType, ErrorCounters, ErrorEntry, ErrorEntryMode, MnErrorCounters,
+};
 use crate::frame::{DllMsEvent, DllMsStateMachine, PowerlinkFrame, ServiceId}; // Import ServiceId
 use crate::hal::ConfigurationInterface; use crate::log::LogMetadata;
 // <-- ADDED: Import ConfigurationInterface
 use crate::nmt::events::MnNmtCommandRequest;
+use super::boot::BootControl;
+use super::error_log::CnErrorLogs;
+use crate::nmt::flags::FeatureFlags;
 use crate::nmt::mn_state_machine::MnNmtStateMachine;
+use crate::nmt::node_list::NodeList;
 use crate::nmt::states::NmtState;
 use crate::node::{CoreNodeContext, NodeContext, PdoHandler};
+use crate::od::error_history;
 use crate::sdo::client_manager::SdoClientManager;
 use crate::sdo::transport::AsndTransport;
 #[cfg(feature = "sdo-udp")]
 use crate::sdo::transport::UdpTransport;
 use crate::types::{IpAddress, NodeId};
+use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BinaryHeap};
 use alloc::string::String; // Import String
 use alloc::vec::Vec;
@@ -29,6 +38,8 @@ pub enum NmtCommandData {
     HostName(String),
     /// Payload for NMTFlushArpEntry (Spec 7.3.2.1.2).
     FlushArp(NodeId),
+    /// Node list of an extended NMT state command (Spec 7.3.1.2.3).
+    NodeList(NodeList),
 }
 
 /// Holds the complete state for a Managing Node.
@@ -42,12 +53,15 @@ pub struct MnContext<'s> {
     pub nmt_state_machine: MnNmtStateMachine,
     pub dll_state_machine: DllMsStateMachine,
     // dll_error_manager is separated due to its generic parameters
-    pub dll_error_manager: DllErrorManager<MnErrorCounters, LoggingErrorHandler>,
+    pub dll_error_manager: DllErrorManager<MnErrorCounters, Box<dyn ErrorHandler>>,
     /// SDO transport handler for ASnd.
     pub asnd_transport: AsndTransport,
     /// SDO transport handler for UDP.
     #[cfg(feature = "sdo-udp")]
     pub udp_transport: UdpTransport,
+    /// Source addresses allowed to send NMT services to the MN over UDP.
+    #[cfg(feature = "sdo-udp")]
+    pub nmt_udp_sources: Vec<IpAddress>,
     pub cycle_time_us: u64,
     pub multiplex_cycle_len: u8,
     pub multiplex_assign: BTreeMap<NodeId, u8>,
@@ -69,6 +83,8 @@ pub struct MnContext<'s> {
     /// A high-priority queue for sending StatusRequests to CNs that need an ER flag.
     pub pending_er_requests: Vec<NodeId>,
     pub pending_status_requests: Vec<NodeId>,
+    /// Error entries reported by each CN in its StatusResponses.
+    pub cn_error_logs: CnErrorLogs,
     /// Queue for NMT commands (State and Managing) to be sent by the MN.
     /// (Command Type, Target Node ID, Command-specific Data)
     pub pending_nmt_commands: Vec<(MnNmtCommandRequest, NodeId, NmtCommandData)>,
@@ -81,6 +97,34 @@ pub struct MnContext<'s> {
     pub pending_timeout_event: Option<DllMsEvent>,
     pub current_cycle_start_time_us: u64,
     pub initial_operational_actions_done: bool,
+    /// Boot-up milestones for the application and its releases of the next state.
+    pub boot: BootControl,
+}
+
+impl<'s> MnContext<'s> {
+    /// Logs an "event occurred" entry in the MN's Error History (0x1003).
+    pub(super) fn log_error_event(
+        &mut self,
+        error_code: u16,
+        additional_information: u64,
+        current_time_us: u64,
+    ) {
+        let entry = ErrorEntry {
+            entry_type: EntryType {
+                is_status_entry: false,
+                send_to_queue: false,
+                mode: ErrorEntryMode::EventOccurred,
+                profile: 0x002,
+            },
+            error_code,
+            timestamp: NetTime {
+                seconds: (current_time_us / 1_000_000) as u32,
+                nanoseconds: ((current_time_us % 1_000_000) * 1000) as u32,
+            },
+            additional_information,
+        };
+        error_history::write_error_to_history(&mut self.core.od, &entry);
+    }
 }
 
 impl<'s> PdoHandler<'s> for MnContext<'s> {
@@ -130,6 +174,8 @@ pub struct CnIdentity {
     pub product_code: u32,
     pub revision_no: u32,
     pub serial_no: u32,
+    /// The CN's supported features (e.g. SDO over UDP).
+    pub feature_flags: FeatureFlags,
 }
 
 /// A struct holding all state information for a single CN, as tracked by the MN.
@@ -146,6 +192,10 @@ pub struct CnInfo {
     pub ea_flag: bool,
     /// Flag indicating the `CHECK_COMMUNICATION` step has passed.
     pub communication_ok: bool,
+    /// NMTResetNode commands sent since the CN last passed `CHECK_COMMUNICATION`.
+    pub communication_resets: u8,
+    /// Set when the serial number of the last IdentResponse differed from 0x1F88.
+    pub serial_no_mismatch: bool,
     /// Timestamp of the last successful PRes reception.
     pub last_pres_time_us: u64,
     /// Number of consecutive DLL errors (e.g., PRes timeouts).
@@ -164,6 +214,8 @@ impl Default for CnInfo {
             en_flag: false,
             ea_flag: false,
             communication_ok: false,
+            communication_resets: 0,
+            serial_no_mismatch: false,
             last_pres_time_us: 0,
             dll_errors: 0,
             identity: None, // Starts as None
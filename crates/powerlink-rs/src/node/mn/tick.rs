@@ -6,7 +6,7 @@ use super::events;
 use super::payload;
 use super::state::{CyclePhase, MnContext};
 use crate::common::{NetTime, RelativeTime};
-use crate::frame::PowerlinkFrame;
+use crate::frame::{DllMsEvent, PowerlinkFrame};
 use crate::frame::control::SocFrame;
 use crate::nmt::NmtStateMachine;
 use crate::nmt::events::NmtEvent;
@@ -15,6 +15,11 @@ use crate::node::{NodeAction, serialize_frame_action};
 use crate::od::constants;
 use crate::sdo::SdoTransport;
 use crate::sdo::server::SdoClientInfo;
+#[cfg(feature = "sdo-udp")]
+use crate::{
+    sdo::{SdoClientTransport, command::SdoCommand, sequence::SequenceLayerHeader},
+    types::NodeId,
+};
 use crate::log::{my_error, my_info, my_trace, my_warn};
 
 /// Handles periodic timer events for the node.
@@ -42,6 +47,10 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
     }
 
     // --- 1. Check for SDO Client Timeouts ---
+    context
+        .core
+        .embedded_sdo_client
+        .tick(current_time_us, &context.core.od);
     if let Some((target_node_id, seq, cmd)) = context
         .sdo_client_manager
         .tick(current_time_us, &context.core.od)
@@ -50,6 +59,10 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
             "SDO Client tick generated frame (timeout/abort) for Node {}.",
             target_node_id.0
         );
+        #[cfg(feature = "sdo-udp")]
+        if context.sdo_client_manager.transport(target_node_id) == SdoClientTransport::Udp {
+            return send_sdo_udp_request(context, target_node_id, seq, cmd);
+        }
         match payload::build_sdo_asnd_request(context, target_node_id, seq, cmd) {
             Ok(frame) => {
                 context.core.od.increment_counter(
@@ -62,6 +75,16 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
         }
     }
 
+    // --- 1b. Send SDO/UDP Client Frames ---
+    // UDP transfers bypass the asynchronous phase and go out immediately.
+    #[cfg(feature = "sdo-udp")]
+    if let Some((target_node_id, seq, cmd)) = context
+        .sdo_client_manager
+        .get_pending_udp_request(current_time_us, &context.core.od)
+    {
+        return send_sdo_udp_request(context, target_node_id, seq, cmd);
+    }
+
     // --- 2. Check for SDO Server Timeouts ---
     if let Some(deadline) = context.core.sdo_server.next_action_time() {
         if current_time_us >= deadline {
@@ -129,9 +152,9 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
             return NodeAction::NoAction;
         }
         
-        // Handle PRes Timeout
+        // Handle PRes and ASnd Timeouts
         if let Some(event) = context.pending_timeout_event.take() {
-            my_warn!("[MN] PRes timeout for Node {:?}.", context.current_polled_cn);
+            my_warn!("[MN] {:?} for Node {:?}.", event, context.current_polled_cn);
             events::handle_dll_event(
                 context,
                 event,
@@ -143,6 +166,11 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
                     RelativeTime { seconds: 0, nanoseconds: 0 },
                 )),
             );
+            if event == DllMsEvent::AsndTimeout {
+                // The asynchronous phase ends the cycle, answered or not.
+                context.current_phase = CyclePhase::Idle;
+                return NodeAction::NoAction;
+            }
             return cycle::advance_cycle_phase(context, current_time_us);
         }
     }
@@ -173,6 +201,29 @@ pub(crate) fn handle_tick(context: &mut MnContext, current_time_us: u64) -> Node
     cycle::tick(context, current_time_us)
 }
 
+/// Builds the action for an SDO client frame sent over UDP.
+#[cfg(feature = "sdo-udp")]
+fn send_sdo_udp_request(
+    context: &mut MnContext,
+    target_node_id: NodeId,
+    seq: SequenceLayerHeader,
+    cmd: SdoCommand,
+) -> NodeAction {
+    match payload::build_sdo_udp_request(target_node_id, seq, cmd) {
+        Ok(action) => {
+            context.core.od.increment_counter(
+                constants::IDX_DIAG_NMT_TELEGR_COUNT_REC,
+                constants::SUBIDX_DIAG_NMT_COUNT_SDO_TX,
+            );
+            action
+        }
+        Err(e) => {
+            my_error!("Failed to build SDO/UDP client frame: {:?}", e);
+            NodeAction::NoAction
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,6 +240,7 @@ mod tests {
     use crate::sdo::transport::UdpTransport;
     use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
     use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
+    use alloc::boxed::Box;
     use alloc::collections::{BTreeMap, BinaryHeap};
     use alloc::vec::Vec;
 
@@ -201,6 +253,8 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         MnContext {
@@ -213,10 +267,15 @@ mod tests {
                 0,
             ),
             dll_state_machine: DllMsStateMachine::default(),
-            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(
+                MnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
             cycle_time_us: 1000,
             multiplex_cycle_len: 0,
             multiplex_assign: BTreeMap::new(),
@@ -233,6 +292,7 @@ mod tests {
             async_request_queue: BinaryHeap::new(),
             pending_er_requests: Vec::new(),
             pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
             pending_nmt_commands: Vec::new(),
             mn_async_send_queue: Vec::new(),
             sdo_client_manager: SdoClientManager::new(),
@@ -242,6 +302,7 @@ mod tests {
             pending_timeout_event: None,
             current_cycle_start_time_us: 0,
             initial_operational_actions_done: false,
+            boot: Default::default(),
         }
     }
 
@@ -306,4 +367,98 @@ mod tests {
             "Should advance to next phase"
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_handle_tick_asnd_timeout_ends_cycle() {
+        let mut context = create_test_context();
+        context
+            .nmt_state_machine
+            .set_state(NmtState::NmtOperational);
+        context.current_phase = CyclePhase::AsynchronousSoA;
+        context.pending_timeout_event = Some(DllMsEvent::AsndTimeout);
+        context.current_polled_cn = Some(NodeId(5));
+        context.next_tick_us = Some(1500);
+
+        // Another request is queued, but it has to wait for the next cycle.
+        context
+            .async_request_queue
+            .push(crate::node::mn::state::AsyncRequest {
+                node_id: NodeId(1),
+                priority: 1,
+            });
+
+        let action = handle_tick(&mut context, 1500);
+        assert!(matches!(action, NodeAction::NoAction));
+        assert_eq!(context.current_phase, CyclePhase::Idle);
+        assert_eq!(context.async_request_queue.len(), 1);
+    }
+
+    #[cfg(feature = "sdo-udp")]
+    #[test]
+    fn test_sdo_client_transport_follows_feature_flags_and_arp() {
+        use crate::frame::basic::MacAddress;
+        use crate::nmt::flags::FeatureFlags;
+        use crate::node::mn::scheduler;
+        use crate::node::mn::state::{CnIdentity, CnInfo};
+        use crate::sdo::sequence::SendConnState;
+        use crate::sdo::udp::deserialize_sdo_udp_payload;
+        use crate::types::C_SDO_EPL_PORT;
+
+        let mut context = create_test_context();
+        let identity = CnIdentity {
+            device_type: 0,
+            vendor_id: 0,
+            product_code: 0,
+            revision_no: 0,
+            serial_no: 0,
+            feature_flags: FeatureFlags::SDO_ASND | FeatureFlags::SDO_UDP,
+        };
+        for node in [5, 6] {
+            context.node_info.insert(
+                NodeId(node),
+                CnInfo {
+                    identity: Some(identity),
+                    ..Default::default()
+                },
+            );
+        }
+        // Only node 5 has been resolved in the ARP cache.
+        context
+            .arp_cache
+            .insert([192, 168, 100, 5], MacAddress([0, 1, 2, 3, 4, 5]));
+
+        scheduler::update_sdo_transport(&mut context, NodeId(6));
+        assert_eq!(
+            context.sdo_client_manager.transport(NodeId(6)),
+            SdoClientTransport::Asnd
+        );
+
+        scheduler::update_sdo_transport(&mut context, NodeId(5));
+        context
+            .sdo_client_manager
+            .read_object_by_index(NodeId(5), 0x1000, 0, 0, &context.core.od)
+            .unwrap();
+
+        // The connection request goes out immediately, outside any async slot.
+        match handle_tick(&mut context, 100) {
+            NodeAction::SendUdp {
+                dest_ip,
+                dest_port,
+                data,
+            } => {
+                assert_eq!(dest_ip, [192, 168, 100, 5]);
+                assert_eq!(dest_port, C_SDO_EPL_PORT);
+                let (seq, _) = deserialize_sdo_udp_payload(&data).unwrap();
+                assert_eq!(seq.send_con, SendConnState::Initialization);
+            }
+            other => panic!("Expected SendUdp, got {:?}", other),
+        }
+        // Nothing is left for the ASnd path.
+        assert!(
+            context
+                .sdo_client_manager
+                .get_pending_request(100, &context.core.od)
+                .is_none()
+        );
+    }
+}
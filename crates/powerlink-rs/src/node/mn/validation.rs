@@ -2,8 +2,12 @@
 //! Contains logic for verifying Controlled Node (CN) identity, software, and configuration.
 //! (EPSG DS 301, Section 7.4.2.2)
 
+use super::boot::{BootCheck, MnBootEvent, report_boot_event};
+use super::scheduler;
 use super::state::{MnContext, SdoState};
+use crate::PowerlinkError;
 use crate::frame::control::IdentResponsePayload;
+use crate::nmt::events::NmtStateCommand;
 use crate::od::constants;
 use crate::types::NodeId;
 use log::{error, info, trace, warn};
@@ -12,6 +16,10 @@ use log::{error, info, trace, warn};
 use super::state::{CnInfo, CnState};
 use alloc::collections::BTreeMap;
 
+/// NMT_StartUp_U32 (0x1F80) bit 9: a serial number mismatch fails
+/// CHECK_IDENTIFICATION. Otherwise the mismatch is only reported.
+const STARTUP_CHECK_SERIAL_NO: u32 = 1 << 9;
+
 /// Validates a CN's IdentResponse payload against the MN's OD configuration.
 /// (EPSG DS 301, Section 7.4.2.2.1.1, 7.4.2.2.1.2, 7.4.2.2.1.3)
 ///
@@ -59,6 +67,11 @@ pub(super) fn validate_boot_step1_checks(
         .od
         .read_u32(constants::IDX_NMT_MN_REVISION_NO_LIST_AU32, node_id.0)
         .unwrap_or(0);
+    let expected_serial_no = context
+        .core
+        .od
+        .read_u32(constants::IDX_NMT_MN_SERIAL_NO_LIST_AU32, node_id.0)
+        .unwrap_or(0);
     let expected_sw_date = context
         .core
         .od
@@ -93,6 +106,7 @@ pub(super) fn validate_boot_step1_checks(
             "[MN] CHECK_IDENTIFICATION failed Node {}: DeviceType mismatch.",
             node_id.0
         );
+        report_check_failed(context, node_id, BootCheck::Identification);
         return false;
     }
     if expected_vendor_id != 0 && received_vendor_id != expected_vendor_id {
@@ -100,6 +114,7 @@ pub(super) fn validate_boot_step1_checks(
             "[MN] CHECK_IDENTIFICATION failed Node {}: VendorId mismatch.",
             node_id.0
         );
+        report_check_failed(context, node_id, BootCheck::Identification);
         return false;
     }
     if expected_product_code != 0 && received_product_code != expected_product_code {
@@ -107,6 +122,7 @@ pub(super) fn validate_boot_step1_checks(
             "[MN] CHECK_IDENTIFICATION failed Node {}: ProductCode mismatch.",
             node_id.0
         );
+        report_check_failed(context, node_id, BootCheck::Identification);
         return false;
     }
     if expected_revision_no != 0 && received_revision_no != expected_revision_no {
@@ -114,9 +130,39 @@ pub(super) fn validate_boot_step1_checks(
             "[MN] CHECK_IDENTIFICATION failed Node {}: RevisionNo mismatch.",
             node_id.0
         );
+        report_check_failed(context, node_id, BootCheck::Identification);
         return false;
     }
 
+    // The serial number tells apart units of the same product, so a mismatch
+    // means the CN was swapped.
+    let serial_no_mismatch = expected_serial_no != 0 && payload.serial_number != expected_serial_no;
+    if let Some(info) = context.node_info.get_mut(&node_id) {
+        info.serial_no_mismatch = serial_no_mismatch;
+    }
+    if serial_no_mismatch {
+        report_boot_event(
+            context,
+            MnBootEvent::SerialNumberMismatch {
+                node_id,
+                expected: expected_serial_no,
+                received: payload.serial_number,
+            },
+        );
+        if startup_flags & STARTUP_CHECK_SERIAL_NO != 0 {
+            error!(
+                "[MN] CHECK_IDENTIFICATION failed Node {}: SerialNo mismatch. Expected {:#010x}, got {:#010x}.",
+                node_id.0, expected_serial_no, payload.serial_number
+            );
+            report_check_failed(context, node_id, BootCheck::Identification);
+            return false;
+        }
+        warn!(
+            "[MN] Node {} reports SerialNo {:#010x}, expected {:#010x}. The device may have been replaced.",
+            node_id.0, payload.serial_number, expected_serial_no
+        );
+    }
+
     trace!("[MN] CHECK_IDENTIFICATION passed for Node {}.", node_id.0);
 
     // --- CHECK_SOFTWARE (7.4.2.2.1.2) ---
@@ -138,6 +184,7 @@ pub(super) fn validate_boot_step1_checks(
             );
             // TODO: Trigger Program Download (PDL) here if supported.
             // For now, we just fail, as PDL is a separate complex process.
+            report_check_failed(context, node_id, BootCheck::Software);
             return false;
         }
         trace!("[MN] CHECK_SOFTWARE passed for Node {}.", node_id.0);
@@ -149,6 +196,27 @@ pub(super) fn validate_boot_step1_checks(
             && (received_conf_date != expected_conf_date
                 || received_conf_time != expected_conf_time);
 
+        let sdo_state = context.node_info.get(&node_id).map(|info| info.sdo_state);
+        if config_mismatch && sdo_state == Some(SdoState::InProgress) {
+            // The download is still running.
+            return false;
+        }
+        if sdo_state == Some(SdoState::Done)
+            && scheduler::is_command_pending(context, NmtStateCommand::ResetConfiguration, node_id)
+        {
+            // The CN has not been reset since the download.
+            return false;
+        }
+        if config_mismatch && sdo_state == Some(SdoState::Done) {
+            // The CN was reset after the download but did not take the
+            // configuration. The download is not repeated.
+            error!(
+                "[MN-CFM] Node {} still reports configuration {}/{} after the download.",
+                node_id.0, received_conf_date, received_conf_time
+            );
+            report_check_failed(context, node_id, BootCheck::Configuration);
+            return false;
+        }
         if config_mismatch {
             warn!(
                 "[MN] CHECK_CONFIGURATION failed for Node {}. Expected {}/{}, Got {}/{}.",
@@ -160,6 +228,7 @@ pub(super) fn validate_boot_step1_checks(
             );
 
             // --- REMEDIATION LOGIC ---
+            // The check only fails if the configuration cannot be downloaded.
             // If we have a configuration interface, try to fetch the configuration and start download.
             if let Some(cfg_if) = context.configuration_interface {
                 info!(
@@ -175,6 +244,7 @@ pub(super) fn validate_boot_step1_checks(
                         );
 
                         // Trigger the SdoClientManager to start the sequence
+                        scheduler::update_sdo_transport(context, node_id);
                         if let Err(e) = context.sdo_client_manager.start_configuration_download(
                             node_id,
                             concise_dcf.to_vec(),
@@ -182,6 +252,7 @@ pub(super) fn validate_boot_step1_checks(
                             &context.core.od,
                         ) {
                             error!("[MN-CFM] Failed to start configuration download: {:?}", e);
+                            report_check_failed(context, node_id, BootCheck::Configuration);
                         } else {
                             // Update internal state to indicate SDO is in progress
                             if let Some(info) = context.node_info.get_mut(&node_id) {
@@ -197,6 +268,7 @@ pub(super) fn validate_boot_step1_checks(
                             "[MN-CFM] Application failed to provide configuration for Node {}: {:?}",
                             node_id.0, e
                         );
+                        report_check_failed(context, node_id, BootCheck::Configuration);
                         return false;
                     }
                 }
@@ -204,16 +276,62 @@ pub(super) fn validate_boot_step1_checks(
                 error!(
                     "[MN] Configuration mismatch, but no Configuration Interface provided to fix it."
                 );
+                report_check_failed(context, node_id, BootCheck::Configuration);
                 return false;
             }
         }
+        if let Some(info) = context.node_info.get_mut(&node_id) {
+            info.sdo_state = SdoState::Idle;
+        }
         trace!("[MN] CHECK_CONFIGURATION passed for Node {}.", node_id.0);
     }
 
-    // TODO: Add SerialNo check (0x1F88) as a warning-only check
     true
 }
 
+/// Completes the configuration downloads started by CHECK_CONFIGURATION.
+///
+/// `finished` holds the SDO client transfers finished during the cycle. A
+/// completed download resets the configuration of its CN, which is checked
+/// again when it re-identifies. A failed download fails the configuration
+/// check of its CN.
+pub(super) fn finish_configuration_downloads(
+    context: &mut MnContext,
+    finished: &[(NodeId, u8, Result<(), PowerlinkError>)],
+) {
+    for (node_id, _, outcome) in finished {
+        let Some(info) = context.node_info.get_mut(node_id) else {
+            continue;
+        };
+        if info.sdo_state != SdoState::InProgress {
+            continue;
+        }
+        match outcome {
+            Ok(()) => {
+                info!(
+                    "[MN-CFM] Configuration download for Node {} complete.",
+                    node_id.0
+                );
+                info.sdo_state = SdoState::Done;
+                scheduler::reset_configuration(context, *node_id);
+            }
+            Err(e) => {
+                error!(
+                    "[MN-CFM] Configuration download for Node {} failed: {:?}",
+                    node_id.0, e
+                );
+                info.sdo_state = SdoState::Idle;
+                report_check_failed(context, *node_id, BootCheck::Configuration);
+            }
+        }
+    }
+}
+
+/// Queues a boot event for a CN that failed a boot-up check.
+pub(super) fn report_check_failed(context: &mut MnContext, node_id: NodeId, check: BootCheck) {
+    report_boot_event(context, MnBootEvent::NodeCheckFailed { node_id, check });
+}
+
 // --- New Boot-Up Check Functions ---
 
 /// Checks if all mandatory nodes have successfully completed identification (BOOT_STEP1).
@@ -265,6 +383,7 @@ pub fn check_all_mandatory_comm_verified(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::node::mn::boot::poll_boot_event;
     use crate::frame::error::{DllErrorManager, LoggingErrorHandler, MnErrorCounters};
     use crate::frame::ms_state_machine::DllMsStateMachine;
     use crate::frame::poll::{PRFlag, RSFlag};
@@ -279,6 +398,7 @@ mod tests {
     use crate::sdo::transport::UdpTransport;
     use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
     use crate::types::{C_ADR_MN_DEF_NODE_ID, EPLVersion};
+    use alloc::boxed::Box;
     use alloc::collections::BTreeMap;
     use alloc::vec;
     use alloc::vec::Vec;
@@ -382,6 +502,8 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
         MnContext {
             core,
@@ -393,10 +515,15 @@ mod tests {
                 0,
             ),
             dll_state_machine: DllMsStateMachine::default(),
-            dll_error_manager: DllErrorManager::new(MnErrorCounters::new(), LoggingErrorHandler),
+            dll_error_manager: DllErrorManager::new(
+                MnErrorCounters::new(),
+                Box::new(LoggingErrorHandler),
+            ),
             asnd_transport: AsndTransport,
             #[cfg(feature = "sdo-udp")]
             udp_transport: UdpTransport,
+            #[cfg(feature = "sdo-udp")]
+            nmt_udp_sources: Vec::new(),
             cycle_time_us: 10000,
             multiplex_cycle_len: 0,
             multiplex_assign: BTreeMap::new(),
@@ -413,6 +540,7 @@ mod tests {
             async_request_queue: Default::default(),
             pending_er_requests: Vec::new(),
             pending_status_requests: Vec::new(),
+            cn_error_logs: Default::default(),
             pending_nmt_commands: Vec::new(),
             mn_async_send_queue: Vec::new(),
             sdo_client_manager: SdoClientManager::new(),
@@ -422,6 +550,7 @@ mod tests {
             pending_timeout_event: None,
             current_cycle_start_time_us: 0,
             initial_operational_actions_done: false,
+            boot: Default::default(),
         }
     }
 
@@ -457,6 +586,68 @@ mod tests {
         assert!(!result, "Validation should fail for mismatched DeviceType");
     }
 
+    #[test]
+    fn test_serial_number_mismatch_is_fatal_only_with_startup_flag() {
+        let mut od = ObjectDictionary::new(None);
+        let node_id = NodeId(1);
+        setup_od(&mut od, node_id.0);
+        od.insert(
+            constants::IDX_NMT_MN_SERIAL_NO_LIST_AU32,
+            ObjectEntry {
+                object: crate::od::Object::Array(vec![ObjectValue::Unsigned32(0); 255]),
+                ..Default::default()
+            },
+        );
+        od.write(
+            constants::IDX_NMT_MN_SERIAL_NO_LIST_AU32,
+            node_id.0,
+            ObjectValue::Unsigned32(0x1111),
+        )
+        .unwrap();
+        let mut context = create_context(od);
+        context.node_info.insert(node_id, CnInfo::default());
+        let payload = create_valid_payload(); // Serial number 0x9999
+        let mismatch = MnBootEvent::SerialNumberMismatch {
+            node_id,
+            expected: 0x1111,
+            received: 0x9999,
+        };
+
+        // Warning only: the CN still boots, but the swap is reported.
+        assert!(validate_boot_step1_checks(
+            &mut context,
+            node_id,
+            &payload,
+            0
+        ));
+        assert!(context.node_info[&node_id].serial_no_mismatch);
+        assert_eq!(poll_boot_event(&mut context.core.events), Some(mismatch));
+
+        context
+            .core
+            .od
+            .write(
+                constants::IDX_NMT_START_UP_U32,
+                0,
+                ObjectValue::Unsigned32(STARTUP_CHECK_SERIAL_NO),
+            )
+            .unwrap();
+        assert!(!validate_boot_step1_checks(
+            &mut context,
+            node_id,
+            &payload,
+            0
+        ));
+        assert_eq!(poll_boot_event(&mut context.core.events), Some(mismatch));
+        assert_eq!(
+            poll_boot_event(&mut context.core.events),
+            Some(MnBootEvent::NodeCheckFailed {
+                node_id,
+                check: BootCheck::Identification
+            })
+        );
+    }
+
     #[test]
     fn test_config_check_pass_when_disabled() {
         let mut od = ObjectDictionary::new(None);
@@ -540,6 +731,7 @@ mod tests {
             config_data: vec![0x00, 0x00, 0x00, 0x00],
         }; // Empty Concise DCF
         context.configuration_interface = Some(&mock_interface);
+        context.node_info.insert(node_id, CnInfo::default());
 
         let payload = create_valid_payload(); // Has date=0, mismatch!
 
@@ -548,6 +740,161 @@ mod tests {
 
         // Assert
         assert!(!result, "Should return false to pause boot-up");
+        assert_eq!(
+            context.node_info.get(&node_id).unwrap().sdo_state,
+            SdoState::InProgress
+        );
+        // The mismatch can be remediated, so the check has not failed yet.
+        assert_eq!(poll_boot_event(&mut context.core.events), None);
+
+        // The download fails.
+        let finished = [(node_id, 0, Err(PowerlinkError::SdoAborted(0x0800_0000)))];
+        finish_configuration_downloads(&mut context, &finished);
+        assert_eq!(
+            poll_boot_event(&mut context.core.events),
+            Some(MnBootEvent::NodeCheckFailed {
+                node_id,
+                check: BootCheck::Configuration
+            })
+        );
+        assert_eq!(
+            context.node_info.get(&node_id).unwrap().sdo_state,
+            SdoState::Idle
+        );
+    }
+
+    #[test]
+    fn test_config_check_fails_without_configuration_interface() {
+        let mut od = ObjectDictionary::new(None);
+        let node_id = NodeId(1);
+        setup_od(&mut od, node_id.0);
+        od.write(
+            constants::IDX_NMT_START_UP_U32,
+            0,
+            ObjectValue::Unsigned32(1 << 11),
+        )
+        .unwrap();
+        let mut context = create_context(od);
+        context.core.od.insert(
+            constants::IDX_NMT_MN_EXP_CONF_DATE_LIST_AU32,
+            ObjectEntry {
+                object: crate::od::Object::Array(vec![ObjectValue::Unsigned32(500); 255]),
+                ..Default::default()
+            },
+        );
+
+        let payload = create_valid_payload(); // Has date=0, mismatch!
+        assert!(!validate_boot_step1_checks(
+            &mut context,
+            node_id,
+            &payload,
+            0
+        ));
+        assert_eq!(
+            poll_boot_event(&mut context.core.events),
+            Some(MnBootEvent::NodeCheckFailed {
+                node_id,
+                check: BootCheck::Configuration
+            })
+        );
+    }
+
+    #[test]
+    fn test_finished_configuration_download_completes_sdo_state() {
+        let mut od = ObjectDictionary::new(None);
+        setup_od(&mut od, 1);
+        let mut context = create_context(od);
+        let node_id = NodeId(1);
+        context.node_info.insert(
+            node_id,
+            CnInfo {
+                sdo_state: SdoState::InProgress,
+                ..Default::default()
+            },
+        );
+
+        finish_configuration_downloads(&mut context, &[(node_id, 0, Ok(()))]);
+        assert_eq!(
+            context.node_info.get(&node_id).unwrap().sdo_state,
+            SdoState::Done
+        );
+        assert_eq!(poll_boot_event(&mut context.core.events), None);
+        assert_eq!(
+            context.pending_nmt_commands,
+            vec![(
+                crate::nmt::events::MnNmtCommandRequest::State(
+                    crate::nmt::events::NmtStateCommand::ResetConfiguration
+                ),
+                node_id,
+                crate::node::mn::state::NmtCommandData::None,
+            )]
+        );
+    }
+
+    #[test]
+    fn test_config_check_after_download_does_not_repeat_it() {
+        let mut od = ObjectDictionary::new(None);
+        let node_id = NodeId(1);
+        setup_od(&mut od, node_id.0);
+        od.write(
+            constants::IDX_NMT_START_UP_U32,
+            0,
+            ObjectValue::Unsigned32(1 << 11),
+        )
+        .unwrap();
+        let mut context = create_context(od);
+        context.core.od.insert(
+            constants::IDX_NMT_MN_EXP_CONF_DATE_LIST_AU32,
+            ObjectEntry {
+                object: crate::od::Object::Array(vec![ObjectValue::Unsigned32(500); 255]),
+                ..Default::default()
+            },
+        );
+        let mock_interface = MockConfigInterface {
+            should_update_sw: false,
+            config_data: vec![0x00, 0x00, 0x00, 0x00],
+        };
+        context.configuration_interface = Some(&mock_interface);
+        context.node_info.insert(
+            node_id,
+            CnInfo {
+                sdo_state: SdoState::Done,
+                ..Default::default()
+            },
+        );
+
+        // The CN re-identifies with the old configuration.
+        let mut payload = create_valid_payload();
+        assert!(!validate_boot_step1_checks(
+            &mut context,
+            node_id,
+            &payload,
+            0
+        ));
+        assert_eq!(
+            poll_boot_event(&mut context.core.events),
+            Some(MnBootEvent::NodeCheckFailed {
+                node_id,
+                check: BootCheck::Configuration
+            })
+        );
+        assert_eq!(
+            context.node_info.get(&node_id).unwrap().sdo_state,
+            SdoState::Done
+        );
+
+        // It re-identifies with the downloaded configuration.
+        payload.verify_conf_date = 500;
+        assert!(validate_boot_step1_checks(
+            &mut context,
+            node_id,
+            &payload,
+            0
+        ));
+        assert_eq!(
+            context.node_info.get(&node_id).unwrap().sdo_state,
+            SdoState::Idle
+        );
     }
 
     // --- TABLE DRIVEN TESTS for Boot Logic ---
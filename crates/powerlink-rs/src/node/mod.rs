@@ -1,9 +1,11 @@
 // crates/powerlink-rs/src/node/mod.rs
 pub mod cn;
+mod event_queue;
 pub mod mn;
 pub mod pdo_handler;
 
 pub use cn::ControlledNode;
+pub use event_queue::{DEFAULT_EVENT_QUEUE_CAPACITY, NodeEvent, NodeEventQueue, TimedEvent};
 use log::{error, info, trace};
 pub use mn::ManagingNode;
 pub use mn::{CnInfo, CnState, MnContext};
@@ -14,8 +16,10 @@ use crate::frame::basic::MacAddress;
 use crate::frame::codec::CodecHelpers;
 use crate::nmt::states::NmtState;
 use crate::od::ObjectDictionary;
-use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer}; // Added embedded managers
-#[cfg(feature = "sdo-udp")]
+use crate::pdo::ProcessImage;
+use crate::sdo::{
+    EmbeddedSdoClient, EmbeddedSdoResult, EmbeddedSdoServer, SdoClient, SdoServer,
+}; // Added embedded managers
 use crate::types::IpAddress;
 use crate::{NodeId, PowerlinkError};
 use alloc::vec;
@@ -29,6 +33,10 @@ pub struct CoreNodeContext<'s> {
     pub sdo_client: SdoClient,
     pub embedded_sdo_server: EmbeddedSdoServer,
     pub embedded_sdo_client: EmbeddedSdoClient,
+    /// Contiguous image of the mapped PDO data, if enabled by the application.
+    pub process_image: Option<ProcessImage>,
+    /// Timestamped events for the application and the monitor.
+    pub events: NodeEventQueue,
 }
 
 impl<'s> CoreNodeContext<'s> {
@@ -38,27 +46,51 @@ impl<'s> CoreNodeContext<'s> {
     }
 
     /// Queues an embedded SDO Read request to be sent via a TPDO container.
+    /// Returns the transaction ID of the request.
     pub fn queue_embedded_sdo_read(
         &mut self,
         channel_index: u16,
         index: u16,
         sub_index: u8,
-    ) -> Result<(), PowerlinkError> {
+    ) -> Result<u8, PowerlinkError> {
         self.embedded_sdo_client
             .queue_read(channel_index, index, sub_index)
     }
 
     /// Queues an embedded SDO Write to be sent via a TPDO container.
+    /// Returns the transaction ID of the request.
     pub fn queue_embedded_sdo_write(
         &mut self,
         channel_index: u16,
         index: u16,
         sub_index: u8,
         data: Vec<u8>,
-    ) -> Result<(), PowerlinkError> {
+    ) -> Result<u8, PowerlinkError> {
         self.embedded_sdo_client
             .queue_write(channel_index, index, sub_index, data)
     }
+
+    /// Builds the process image from the current PDO mappings. From now on,
+    /// mapped objects are exchanged through the image instead of the OD.
+    pub fn enable_process_image(&mut self) -> &mut ProcessImage {
+        self.process_image.insert(ProcessImage::build(&self.od))
+    }
+
+    /// Rebuilds the process image if the PDO mappings changed since it was built.
+    /// Output values written by the application carry over to the new layout.
+    pub(crate) fn refresh_process_image(&mut self) {
+        if let Some(image) = &mut self.process_image
+            && image.is_stale(&self.od)
+        {
+            info!("PDO mappings changed, rebuilding process image.");
+            *image = image.rebuild(&self.od);
+        }
+    }
+
+    /// Returns the oldest finished embedded SDO transfer on a client channel, if any.
+    pub fn poll_embedded_sdo_result(&mut self, channel_index: u16) -> Option<EmbeddedSdoResult> {
+        self.embedded_sdo_client.pop_completed(channel_index)
+    }
 }
 
 /// Represents the possible actions a POWERLINK node might need to perform
@@ -132,6 +164,12 @@ pub trait NodeContext<'s> {
     fn nmt_state_machine(&self) -> &dyn crate::nmt::NmtStateMachine;
 }
 
+/// Helper to derive a node's IP Address from its Node ID.
+/// (Per EPSG DS 301, Section 5.1.2)
+pub(super) fn ip_from_node_id(node_id: NodeId) -> IpAddress {
+    [192, 168, 100, node_id.0]
+}
+
 /// Helper to serialize a PowerlinkFrame (Ethernet) and prepare the NodeAction.
 /// This function is now shared by both CN and MN logic.
 pub(super) fn serialize_frame_action<'a>(
@@ -1,7 +1,7 @@
 // crates/powerlink-rs/src/node/pdo_handler.rs
 use crate::frame::error::{DllError, DllErrorManager, ErrorCounters, ErrorHandler};
 use crate::node::NodeContext;
-use crate::od::{ObjectValue, constants};
+use crate::od::{ObjectValue, WriteSource, constants};
 use crate::pdo::{PDOVersion, PdoMappingEntry};
 use crate::types::{C_ADR_MN_DEF_NODE_ID, NodeId};
 use alloc::vec::Vec;
@@ -83,6 +83,7 @@ pub trait PdoHandler<'s>: NodeContext<'s> {
             payload.len(),
             source_node_id.0
         );
+        self.core_mut().refresh_process_image();
 
         // Find the correct mapping for this source node by searching RPDO Comm Params (0x14xx)
         let mut mapping_index_opt = None;
@@ -264,6 +265,17 @@ pub trait PdoHandler<'s>: NodeContext<'s> {
         }
         // --- END SDO-in-PDO LOGIC ---
 
+        // Objects in the process image are stored there, not in the OD, so
+        // their observers are notified from the image data.
+        let core = self.core_mut();
+        if let Some(image) = core.process_image.as_mut()
+            && image.store_input(entry.index, entry.sub_index, data_slice)
+        {
+            core.od
+                .notify_image_write(entry.index, entry.sub_index, data_slice);
+            return Ok(());
+        }
+
         // Get an immutable reference to the OD first
         let type_template_option = self
             .core()
@@ -298,11 +310,13 @@ pub trait PdoHandler<'s>: NodeContext<'s> {
                     value, entry.index, entry.sub_index
                 );
                 // Now get a mutable reference to write
-                if let Err(e) =
-                    self.core_mut()
-                        .od
-                        .write_internal(entry.index, entry.sub_index, value, false)
-                {
+                if let Err(e) = self.core_mut().od.write_internal_from(
+                    entry.index,
+                    entry.sub_index,
+                    value,
+                    false,
+                    WriteSource::Rpdo,
+                ) {
                     error!(
                         "Critical Error: Failed to write RPDO data to existing OD entry 0x{:04X}/{}: {:?}",
                         entry.index, entry.sub_index, e
@@ -334,12 +348,17 @@ mod tests {
     };
     use crate::nmt::cn_state_machine::CnNmtStateMachine;
     use crate::node::{CoreNodeContext, NodeContext};
+    use crate::od::ObjectObserver;
     use crate::od::{Object, ObjectDictionary, ObjectEntry, ObjectValue};
     use crate::pdo::PDOVersion;
+    use crate::pdo::PdoMappingEntry;
     use crate::sdo::{EmbeddedSdoClient, EmbeddedSdoServer, SdoClient, SdoServer};
-    use crate::types::NodeId;
+    use crate::types::{BitString, NodeId};
     use alloc::borrow::Cow;
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
     use alloc::vec;
+    use core::cell::RefCell;
 
     struct TestNode {
         core: CoreNodeContext<'static>,
@@ -443,6 +462,8 @@ mod tests {
             sdo_client: SdoClient::new(),
             embedded_sdo_server: EmbeddedSdoServer::new(),
             embedded_sdo_client: EmbeddedSdoClient::new(),
+            process_image: None,
+            events: Default::default(),
         };
 
         TestNode {
@@ -509,4 +530,139 @@ mod tests {
             "Data written despite version mismatch"
         );
     }
+
+    #[test]
+    fn test_consume_pdo_into_process_image() {
+        let mut node = setup_node();
+        node.core.enable_process_image();
+
+        node.consume_pdo_payload(NodeId(1), &[0xAA, 0xBB, 0xCC], PDOVersion(0), true);
+
+        let image = node.core.process_image.as_ref().unwrap();
+        assert_eq!(image.read_input::<u8>(0), Some(0xAA));
+        assert_eq!(image.read_input::<u16>(1), Some(0xCCBB));
+        // The OD is bypassed while the image is active.
+        assert_eq!(node.core.od.read_u8(0x2000, 1), Some(0));
+    }
+
+    #[test]
+    fn test_consume_pdo_with_narrow_types() {
+        let mut node = setup_node();
+        let od = &mut node.core.od;
+        od.insert(
+            0x2001,
+            ObjectEntry {
+                object: Object::Record(vec![
+                    ObjectValue::Integer24(0),
+                    ObjectValue::Unsigned40(0),
+                    ObjectValue::BitString(BitString::new(12, 0)),
+                ]),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x1401,
+            ObjectEntry {
+                object: Object::Array(vec![ObjectValue::Unsigned8(2), ObjectValue::Unsigned8(0)]),
+                ..Default::default()
+            },
+        );
+        let mapping = |sub_index: u8, offset_bits: u16, length_bits: u16| {
+            ObjectValue::Unsigned64(
+                PdoMappingEntry {
+                    index: 0x2001,
+                    sub_index,
+                    offset_bits,
+                    length_bits,
+                }
+                .to_u64(),
+            )
+        };
+        od.insert(
+            0x1601,
+            ObjectEntry {
+                object: Object::Array(vec![
+                    mapping(1, 0, 24),
+                    mapping(2, 24, 40),
+                    mapping(3, 64, 16),
+                ]),
+                ..Default::default()
+            },
+        );
+
+        let payload = [
+            0xFE, 0xFF, 0xFF, // INTEGER24 -2
+            0x9A, 0x78, 0x56, 0x34, 0x12, // UNSIGNED40
+            0xBC, 0xFA, // 12-bit BIT string, unused bits set
+        ];
+        node.consume_pdo_payload(NodeId(2), &payload, PDOVersion(0), true);
+
+        let od = &node.core.od;
+        assert_eq!(
+            od.read(0x2001, 1).unwrap().as_ref(),
+            &ObjectValue::Integer24(-2)
+        );
+        assert_eq!(
+            od.read(0x2001, 2).unwrap().as_ref(),
+            &ObjectValue::Unsigned40(0x12_3456_789A)
+        );
+        assert_eq!(
+            od.read(0x2001, 3).unwrap().as_ref(),
+            &ObjectValue::BitString(BitString::new(12, 0xABC))
+        );
+    }
+
+    /// Records the post-write notifications of an observed object.
+    struct Recorder(Rc<RefCell<Vec<(u8, ObjectValue, WriteSource)>>>);
+    impl ObjectObserver for Recorder {
+        fn post_write(
+            &mut self,
+            _index: u16,
+            sub_index: u8,
+            value: &ObjectValue,
+            source: WriteSource,
+        ) {
+            self.0.borrow_mut().push((sub_index, value.clone(), source));
+        }
+    }
+
+    #[test]
+    fn test_consume_pdo_notifies_observer_with_rpdo_source() {
+        let mut node = setup_node();
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        node.core
+            .od
+            .register_observer(0x2000, Box::new(Recorder(writes.clone())));
+
+        node.consume_pdo_payload(NodeId(1), &[0xAA, 0xBB, 0xCC], PDOVersion(0), true);
+
+        assert_eq!(
+            *writes.borrow(),
+            vec![
+                (1, ObjectValue::Unsigned8(0xAA), WriteSource::Rpdo),
+                (2, ObjectValue::Unsigned16(0xCCBB), WriteSource::Rpdo)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consume_pdo_into_process_image_notifies_observer() {
+        let mut node = setup_node();
+        node.core.enable_process_image();
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        node.core
+            .od
+            .register_observer(0x2000, Box::new(Recorder(writes.clone())));
+
+        node.consume_pdo_payload(NodeId(1), &[0xAA, 0xBB, 0xCC], PDOVersion(0), true);
+
+        assert_eq!(
+            *writes.borrow(),
+            vec![
+                (1, ObjectValue::Unsigned8(0xAA), WriteSource::Rpdo),
+                (2, ObjectValue::Unsigned16(0xCCBB), WriteSource::Rpdo)
+            ]
+        );
+        assert_eq!(node.core.od.read_u8(0x2000, 1), Some(0));
+    }
 }
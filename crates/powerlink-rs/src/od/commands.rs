@@ -1,69 +1,228 @@
 // crates/powerlink-rs/src/od/commands.rs
-use super::{AccessType, Object, ObjectDictionary};
+use super::{AccessType, Object, ObjectDictionary, ObjectValue, constants, storage};
 use crate::PowerlinkError;
 use alloc::collections::BTreeMap;
-use log::{error, trace};
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+use log::{error, trace, warn};
 
-/// Collects all storable parameters and tells the storage backend to save them.
+/// Sub-index of the "Save All Parameters" list.
+const LIST_ALL: u8 = 1;
+/// First and last sub-index available for manufacturer-specific lists.
+pub const MANUFACTURER_LISTS: RangeInclusive<u8> = 4..=127;
+
+/// Index ranges covered by the standard lists 1 to 3.
+const STANDARD_LISTS: [&[RangeInclusive<u16>]; 3] = [
+    &[0x0000..=0xFFFF], // 1: All parameters
+    &[0x1000..=0x1FFF], // 2: Communication parameters
+    &[0x6000..=0x9FFF], // 3: Application parameters
+];
+
+/// How the parameters of a store list reach non-volatile memory.
+/// Reported in the read-back value of the list's sub-index in 0x1010.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreMode {
+    /// Saved only when the "save" signature is written (bit 0).
+    OnCommand,
+    /// Saved by the device after every change (bit 1).
+    Autonomous,
+}
+
+impl StoreMode {
+    /// The 0x1010 read-back capability bits for this mode.
+    fn capability(self) -> u32 {
+        match self {
+            StoreMode::OnCommand => 1 << 0,
+            StoreMode::Autonomous => 1 << 1,
+        }
+    }
+}
+
+/// A manufacturer-specific store list (0x1010/0x1011 sub-index 4..127).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreList {
+    /// The OD index ranges whose storable objects belong to this list.
+    pub ranges: Vec<RangeInclusive<u16>>,
+    pub mode: StoreMode,
+}
+
+impl<'a> ObjectDictionary<'a> {
+    /// Defines a manufacturer-specific store list at the given sub-index of
+    /// 0x1010 and 0x1011. Objects in `ranges` with a storable access type are
+    /// saved and restored together when the list's sub-index is written.
+    pub fn define_store_list(
+        &mut self,
+        sub_index: u8,
+        ranges: Vec<RangeInclusive<u16>>,
+        mode: StoreMode,
+    ) -> Result<(), PowerlinkError> {
+        if !MANUFACTURER_LISTS.contains(&sub_index) {
+            error!(
+                "Store list sub-index {} is outside the manufacturer-specific range 4..=127.",
+                sub_index
+            );
+            return Err(PowerlinkError::SubObjectNotFound);
+        }
+        self.store_lists
+            .insert(sub_index, StoreList { ranges, mode });
+        update_store_capabilities(self);
+        Ok(())
+    }
+}
+
+/// Returns the index ranges covered by a list, or `None` if it is not defined.
+fn list_ranges(od: &ObjectDictionary, list: u8) -> Option<Vec<RangeInclusive<u16>>> {
+    match list {
+        1..=3 => Some(STANDARD_LISTS[list as usize - 1].to_vec()),
+        _ => od.store_lists.get(&list).map(|l| l.ranges.clone()),
+    }
+}
+
+fn in_ranges(ranges: &[RangeInclusive<u16>], index: u16) -> bool {
+    ranges.iter().any(|r| r.contains(&index))
+}
+
+/// Writes the capability bits of all manufacturer-specific lists into the
+/// read-back values of 0x1010 and 0x1011, growing the records if needed.
+pub(super) fn update_store_capabilities(od: &mut ObjectDictionary) {
+    let capabilities: Vec<(u8, u32)> = od
+        .store_lists
+        .iter()
+        .map(|(&sub_index, list)| (sub_index, list.mode.capability()))
+        .collect();
+    for (index, restore) in [
+        (constants::IDX_NMT_STORE_PARAM_CMD_REC, false),
+        (constants::IDX_NMT_RESTORE_PARAM_CMD_REC, true),
+    ] {
+        let Some(entry) = od.entries.get_mut(&index) else {
+            continue;
+        };
+        let (Object::Record(values) | Object::Array(values)) = &mut entry.object else {
+            continue;
+        };
+        for &(sub_index, capability) in &capabilities {
+            if values.len() < sub_index as usize {
+                values.resize(sub_index as usize, ObjectValue::Unsigned32(0));
+            }
+            // 0x1011 only reports whether restoring is supported (bit 0).
+            let value = if restore { 1 } else { capability };
+            values[sub_index as usize - 1] = ObjectValue::Unsigned32(value);
+        }
+    }
+}
+
+/// Collects all storable parameters of the list and tells the storage backend
+/// to save them. Parameters of other lists already in storage are kept.
 pub fn store_parameters(od: &mut ObjectDictionary, list_to_save: u8) -> Result<(), PowerlinkError> {
     if list_to_save == 0 {
         error!("Attempted to store parameters with invalid sub-index 0.");
         return Err(PowerlinkError::StorageError("Cannot save to sub-index 0"));
     }
-    if let Some(s) = &mut od.storage {
-        trace!("Storing parameters for sub-index {}", list_to_save);
-        let mut storable_params = BTreeMap::new();
-        for (&index, entry) in &od.entries {
-            // Determine if this object's group (Comm, App, etc.) should be saved based on list_to_save.
-            let should_save = match list_to_save {
-                1 => true,                               // Save All
-                2 => (0x1000..=0x1FFF).contains(&index), // Save Communication
-                3 => (0x6000..=0x9FFF).contains(&index), // Save Application
-                _ => false, // Other sub-indices are manufacturer-specific (ignore for now)
-            };
-
-            if should_save {
-                // Check if the entry itself has a storable access type
-                if let Some(access) = entry.access {
-                    if matches!(
-                        access,
-                        AccessType::ReadWriteStore | AccessType::WriteOnlyStore
-                    ) {
-                        // Extract values based on object type
-                        match &entry.object {
-                            Object::Variable(val) => {
-                                // Store Variable at sub-index 0
-                                storable_params.insert((index, 0), val.clone());
-                            }
-                            Object::Record(vals) | Object::Array(vals) => {
-                                // Store Array/Record elements at sub-indices 1..N
-                                for (i, val) in vals.iter().enumerate() {
-                                    storable_params.insert((index, (i + 1) as u8), val.clone());
-                                }
-                            }
-                        }
-                    }
+    let Some(ranges) = list_ranges(od, list_to_save) else {
+        error!(
+            "Store parameters failed: list {} is not defined.",
+            list_to_save
+        );
+        return Err(PowerlinkError::SubObjectNotFound);
+    };
+    trace!("Storing parameters for sub-index {}", list_to_save);
+    let mut storable_params = BTreeMap::new();
+    for (&index, entry) in &od.entries {
+        // Determine if this object's group (Comm, App, etc.) should be saved based on list_to_save.
+        if !in_ranges(&ranges, index) {
+            continue;
+        }
+        // Check if the entry itself has a storable access type
+        if !matches!(
+            entry.access,
+            Some(AccessType::ReadWriteStore | AccessType::WriteOnlyStore)
+        ) {
+            continue;
+        }
+        // Extract values based on object type
+        match &entry.object {
+            Object::Variable(val) => {
+                // Store Variable at sub-index 0
+                storable_params.insert((index, 0), val.clone());
+            }
+            Object::Record(vals) | Object::Array(vals) => {
+                // Store Array/Record elements at sub-indices 1..N
+                for (i, val) in vals.iter().enumerate() {
+                    storable_params.insert((index, (i + 1) as u8), val.clone());
                 }
             }
         }
-        if storable_params.is_empty() {
-            trace!(
-                "No storable parameters found for sub-index {}",
-                list_to_save
-            );
-        } else {
-            trace!("Saving {} parameters.", storable_params.len());
-        }
-        s.save(&storable_params)
+    }
+    if storable_params.is_empty() {
+        trace!(
+            "No storable parameters found for sub-index {}",
+            list_to_save
+        );
     } else {
+        trace!("Saving {} parameters.", storable_params.len());
+    }
+
+    let schema_version = od.storage_schema_version;
+    let Some(s) = &mut od.storage else {
         error!("Store parameters failed: No storage backend configured.");
-        Err(PowerlinkError::StorageError(
+        return Err(PowerlinkError::StorageError(
             "No storage backend configured",
-        ))
+        ));
+    };
+    // Keep the parameters of other lists that are already in storage.
+    let mut merged = if list_to_save == LIST_ALL {
+        BTreeMap::new()
+    } else {
+        let mut existing = s.load()?;
+        // Parameters of another schema must not be re-tagged as current.
+        if !storage::take_compatible(schema_version, &mut existing) {
+            warn!(
+                "Discarding {} stored parameters from an incompatible schema.",
+                existing.len()
+            );
+            existing.clear();
+        }
+        existing.retain(|&(index, _), _| !in_ranges(&ranges, index));
+        existing
+    };
+    merged.extend(storable_params);
+    merged.insert(
+        storage::SCHEMA_VERSION_KEY,
+        ObjectValue::Unsigned32(schema_version),
+    );
+    s.save(&merged)
+}
+
+/// Saves every autonomous list that contains the given storable object.
+/// Called after a successful write through the public OD interface.
+pub(super) fn store_autonomous(od: &mut ObjectDictionary, index: u16) {
+    let is_storable = od.entries.get(&index).is_some_and(|e| {
+        matches!(
+            e.access,
+            Some(AccessType::ReadWriteStore | AccessType::WriteOnlyStore)
+        )
+    });
+    if !is_storable || od.storage.is_none() {
+        return;
+    }
+    let lists: Vec<u8> = od
+        .store_lists
+        .iter()
+        .filter(|(_, l)| l.mode == StoreMode::Autonomous && in_ranges(&l.ranges, index))
+        .map(|(&sub_index, _)| sub_index)
+        .collect();
+    for list in lists {
+        if let Err(e) = store_parameters(od, list) {
+            warn!("Autonomous save of store list {} failed: {:?}", list, e);
+        }
     }
 }
 
-/// Tells the storage backend to set a flag to restore defaults on the next boot.
+/// Restores the default values of a list.
+///
+/// For list 1 the storage backend sets a flag that wipes all stored parameters
+/// on the next boot. For other lists, their parameters are removed from
+/// storage immediately, so the defaults apply after the next reset.
 pub fn restore_defaults(
     od: &mut ObjectDictionary,
     list_to_restore: u8,
@@ -74,18 +233,29 @@ pub fn restore_defaults(
             "Cannot restore from sub-index 0",
         ));
     }
-    if let Some(s) = &mut od.storage {
-        trace!(
-            "Requesting restore defaults for sub-index {}",
+    let Some(ranges) = list_ranges(od, list_to_restore) else {
+        error!(
+            "Restore defaults failed: list {} is not defined.",
             list_to_restore
         );
-        s.request_restore_defaults()
-    } else {
+        return Err(PowerlinkError::SubObjectNotFound);
+    };
+    let Some(s) = &mut od.storage else {
         error!("Restore defaults failed: No storage backend configured.");
-        Err(PowerlinkError::StorageError(
+        return Err(PowerlinkError::StorageError(
             "No storage backend configured",
-        ))
+        ));
+    };
+    trace!(
+        "Requesting restore defaults for sub-index {}",
+        list_to_restore
+    );
+    if list_to_restore == LIST_ALL {
+        return s.request_restore_defaults();
     }
+    let mut stored = s.load()?;
+    stored.retain(|&key, _| key == storage::SCHEMA_VERSION_KEY || !in_ranges(&ranges, key.0));
+    s.save(&stored)
 }
 
 #[cfg(test)]
@@ -178,7 +348,8 @@ mod tests {
         // Directly test the store_parameters function for Application Params (sub-index 3)
         store_parameters(&mut od, 3).unwrap();
 
-        assert_eq!(storage.saved_data.len(), 1);
+        // The application variable plus the schema version tag.
+        assert_eq!(storage.saved_data.len(), 2);
         assert_eq!(
             storage.saved_data.get(&(0x6000, 0)),
             Some(&ObjectValue::Unsigned32(123))
@@ -201,4 +372,154 @@ mod tests {
         // Test invalid sub-index
         assert!(restore_defaults(&mut od, 0).is_err());
     }
+
+    fn storable(object: Object) -> ObjectEntry {
+        ObjectEntry {
+            object,
+            access: Some(AccessType::ReadWriteStore),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_store_list_drops_parameters_of_another_schema() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(7));
+        storage
+            .saved_data
+            .insert(storage::SCHEMA_VERSION_KEY, ObjectValue::Unsigned32(1));
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.set_storage_schema_version(2);
+        od.insert(
+            0x1800,
+            storable(Object::Record(vec![ObjectValue::Unsigned8(10)])),
+        );
+
+        store_parameters(&mut od, 2).unwrap();
+
+        // The v1 application parameter is gone; only list 2 is tagged as v2.
+        assert!(!storage.saved_data.contains_key(&(0x6000, 0)));
+        assert_eq!(
+            storage.saved_data.get(&(0x1800, 1)),
+            Some(&ObjectValue::Unsigned8(10))
+        );
+        assert_eq!(
+            storage.saved_data.get(&storage::SCHEMA_VERSION_KEY),
+            Some(&ObjectValue::Unsigned32(2))
+        );
+    }
+
+    #[test]
+    fn test_manufacturer_list_capabilities_read_back() {
+        let mut od = ObjectDictionary::new(None);
+        od.init().unwrap();
+        od.define_store_list(5, vec![0x2000..=0x20FF], StoreMode::Autonomous)
+            .unwrap();
+        od.define_store_list(4, vec![0x2100..=0x21FF], StoreMode::OnCommand)
+            .unwrap();
+
+        assert_eq!(od.read_u32(0x1010, 1), Some(1));
+        assert_eq!(od.read_u32(0x1010, 4), Some(0b01));
+        assert_eq!(od.read_u32(0x1010, 5), Some(0b10));
+        assert_eq!(od.read_u32(0x1011, 5), Some(1));
+        assert!(
+            od.define_store_list(3, vec![0x2000..=0x20FF], StoreMode::OnCommand)
+                .is_err()
+        );
+        assert!(
+            od.define_store_list(128, vec![0x2000..=0x20FF], StoreMode::OnCommand)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_store_list_keeps_other_lists() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(7));
+        storage
+            .saved_data
+            .insert((0x2000, 0), ObjectValue::Unsigned32(1));
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.insert(
+            0x2000,
+            storable(Object::Variable(ObjectValue::Unsigned32(2))),
+        );
+        od.insert(
+            0x2001,
+            storable(Object::Variable(ObjectValue::Unsigned32(3))),
+        );
+        od.define_store_list(4, vec![0x2000..=0x2FFF], StoreMode::OnCommand)
+            .unwrap();
+
+        od.write(0x1010, 4, ObjectValue::VisibleString("save".into()))
+            .unwrap();
+        // Writing to an undefined list is rejected.
+        assert!(
+            od.write(0x1010, 6, ObjectValue::VisibleString("save".into()))
+                .is_err()
+        );
+
+        assert_eq!(
+            storage.saved_data.get(&(0x6000, 0)),
+            Some(&ObjectValue::Unsigned32(7))
+        );
+        assert_eq!(
+            storage.saved_data.get(&(0x2000, 0)),
+            Some(&ObjectValue::Unsigned32(2))
+        );
+        assert_eq!(
+            storage.saved_data.get(&(0x2001, 0)),
+            Some(&ObjectValue::Unsigned32(3))
+        );
+    }
+
+    #[test]
+    fn test_autonomous_list_saves_on_write() {
+        let mut storage = MockStorage::new();
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.insert(
+            0x2000,
+            storable(Object::Variable(ObjectValue::Unsigned16(0))),
+        );
+        od.insert(
+            0x2100,
+            storable(Object::Variable(ObjectValue::Unsigned16(0))),
+        );
+        od.define_store_list(4, vec![0x2000..=0x20FF], StoreMode::Autonomous)
+            .unwrap();
+
+        od.write(0x2100, 0, ObjectValue::Unsigned16(9)).unwrap();
+        od.write(0x2000, 0, ObjectValue::Unsigned16(5)).unwrap();
+        drop(od);
+
+        assert!(storage.save_called);
+        assert_eq!(
+            storage.saved_data.get(&(0x2000, 0)),
+            Some(&ObjectValue::Unsigned16(5))
+        );
+        assert!(!storage.saved_data.contains_key(&(0x2100, 0)));
+    }
+
+    #[test]
+    fn test_restore_single_list_removes_only_its_parameters() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x1300, 0), ObjectValue::Unsigned32(500));
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(7));
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+
+        restore_defaults(&mut od, 3).unwrap();
+        assert!(!od.storage.as_ref().unwrap().restore_defaults_requested());
+        drop(od);
+
+        assert!(storage.saved_data.contains_key(&(0x1300, 0)));
+        assert!(!storage.saved_data.contains_key(&(0x6000, 0)));
+    }
 }
@@ -68,6 +68,27 @@ pub const IDX_TPDO_MAPPING_PARAM_REC_END: u16 = 0x1AFF;
 pub const IDX_TPDO_MAPPING_PARAM_REC_1: u16 = 0x1A01;
 
 // 0x1Cxx: DLL Parameters
+pub const IDX_DLL_MN_CRC_ERROR_REC: u16 = 0x1C00;
+pub const IDX_DLL_MN_COLLISION_REC: u16 = 0x1C01;
+pub const IDX_DLL_MN_CYC_TIME_EXCEED_REC: u16 = 0x1C02;
+pub const IDX_DLL_MN_LOSS_OF_LINK_CUM_U32: u16 = 0x1C03;
+pub const IDX_DLL_MN_CN_LATE_PRES_CUM_CNT_AU32: u16 = 0x1C04;
+pub const IDX_DLL_MN_CN_LATE_PRES_THR_CNT_AU32: u16 = 0x1C05;
+pub const IDX_DLL_MN_CN_LATE_PRES_THRESHOLD_AU32: u16 = 0x1C06;
+pub const IDX_DLL_MN_CN_LOSS_PRES_CUM_CNT_AU32: u16 = 0x1C07;
+pub const IDX_DLL_MN_CN_LOSS_PRES_THR_CNT_AU32: u16 = 0x1C08;
+pub const IDX_DLL_MN_CN_LOSS_PRES_THRESHOLD_AU32: u16 = 0x1C09;
+pub const IDX_DLL_CN_COLLISION_REC: u16 = 0x1C0A;
+pub const IDX_DLL_CN_LOSS_SOC_REC: u16 = 0x1C0B;
+pub const IDX_DLL_CN_LOSS_SOA_REC: u16 = 0x1C0C;
+pub const IDX_DLL_CN_LOSS_PREQ_REC: u16 = 0x1C0D;
+pub const IDX_DLL_CN_SOC_JITTER_REC: u16 = 0x1C0E;
+pub const IDX_DLL_CN_CRC_ERROR_REC: u16 = 0x1C0F;
+pub const IDX_DLL_CN_LOSS_OF_LINK_CUM_U32: u16 = 0x1C10;
+// Sub-indices of the DLL error counter records (DLL_ErrorCntRec_TYPE)
+pub const SUBIDX_DLL_ERROR_CUM_CNT_U32: u8 = 1;
+pub const SUBIDX_DLL_ERROR_THR_CNT_U32: u8 = 2;
+pub const SUBIDX_DLL_ERROR_THRESHOLD_U32: u8 = 3;
 pub const IDX_DLL_CN_LOSS_OF_SOC_TOL_U32: u16 = 0x1C14;
 pub const IDX_PDO_ERR_MAP_VERS_OSTR: u16 = 0x1C80;
 pub const IDX_PDO_ERR_SHORT_RX_OSTR: u16 = 0x1C81;
@@ -84,6 +105,7 @@ pub const IDX_NMT_MN_DEVICE_TYPE_ID_LIST_AU32: u16 = 0x1F84;
 pub const IDX_NMT_MN_VENDOR_ID_LIST_AU32: u16 = 0x1F85;
 pub const IDX_NMT_MN_PRODUCT_CODE_LIST_AU32: u16 = 0x1F86;
 pub const IDX_NMT_MN_REVISION_NO_LIST_AU32: u16 = 0x1F87;
+pub const IDX_NMT_MN_SERIAL_NO_LIST_AU32: u16 = 0x1F88;
 pub const IDX_NMT_BOOT_TIME_REC: u16 = 0x1F89;
 pub const IDX_NMT_MN_CYCLE_TIMING_REC: u16 = 0x1F8A; // MN-specific cycle timing
 pub const SUBIDX_NMT_MN_CYCLE_TIMING_ASYNC_SLOT_U32: u8 = 2;
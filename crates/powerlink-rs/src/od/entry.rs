@@ -1,5 +1,7 @@
 use super::value::ObjectValue;
+use crate::PowerlinkError;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 /// Represents a single entry in the Object Dictionary.
 #[derive(Debug, Clone, PartialEq)]
@@ -51,6 +53,29 @@ pub enum PdoMapping {
 pub struct ValueRange {
     pub min: ObjectValue,
     pub max: ObjectValue,
+    /// Enumerated values allowed within the range (e.g. from an XDC
+    /// `<allowedValues>` list). Empty if every value in `min..=max` is allowed.
+    pub values: Vec<ObjectValue>,
+}
+
+impl ValueRange {
+    /// Checks a value against the range and the enumerated values.
+    /// Values of a different type than the range are not checked here.
+    pub fn check(&self, value: &ObjectValue) -> Result<(), PowerlinkError> {
+        if core::mem::discriminant(value) != core::mem::discriminant(&self.min) {
+            return Ok(());
+        }
+        if value.numeric_cmp(&self.min) == Some(Ordering::Less) {
+            return Err(PowerlinkError::ValueTooLow);
+        }
+        if value.numeric_cmp(&self.max) == Some(Ordering::Greater) {
+            return Err(PowerlinkError::ValueTooHigh);
+        }
+        if !self.values.is_empty() && !self.values.contains(value) {
+            return Err(PowerlinkError::InvalidValue);
+        }
+        Ok(())
+    }
 }
 
 /// A complete entry in the Object Dictionary, containing both the data and its metadata.
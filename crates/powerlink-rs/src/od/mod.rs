@@ -3,25 +3,42 @@ mod commands;
 pub mod constants;
 pub mod error_history;
 mod entry;
+mod observer;
 mod pdo_validator;
 mod predefined;
 mod storage;
+mod typed;
 pub mod utils;
 mod value;
 
+pub use commands::{StoreList, StoreMode};
 pub use entry::{AccessType, Category, Object, ObjectEntry, PdoMapping, ValueRange};
+pub use observer::{ObjectObserver, ObserverId, WriteSource};
+pub use typed::{ObjectType, PowerlinkObjects};
 pub use value::ObjectValue;
 
 use crate::hal::ObjectDictionaryStorage;
 use crate::{NodeId, PowerlinkError};
-use alloc::{borrow::Cow, collections::BTreeMap, vec::Vec};
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, vec::Vec};
 use core::fmt;
+use core::ops::RangeInclusive;
 use log::{error, info, trace, warn};
+use observer::Observers;
 
 /// The main Object Dictionary structure.
 pub struct ObjectDictionary<'a> {
     pub(super) entries: BTreeMap<u16, ObjectEntry>,
     storage: Option<&'a mut dyn ObjectDictionaryStorage>,
+    observers: Observers,
+    /// Incremented whenever a PDO communication or mapping object
+    /// (0x1400 - 0x14FF, 0x1600 - 0x16FF, 0x1800 - 0x18FF, 0x1A00 - 0x1AFF) changes.
+    pdo_mapping_revision: u32,
+    /// Manufacturer-specific store lists (0x1010/0x1011 sub-index 4..127).
+    store_lists: BTreeMap<u8, StoreList>,
+    /// Version tag written with stored parameters, see `set_storage_schema_version`.
+    storage_schema_version: u32,
+    /// Stored parameters that could not be applied during `init`.
+    orphaned_parameters: Vec<(u16, u8)>,
 }
 
 impl<'a> fmt::Debug for ObjectDictionary<'a> {
@@ -36,6 +53,7 @@ impl<'a> fmt::Debug for ObjectDictionary<'a> {
                     "None"
                 },
             )
+            .field("observers", &self.observers.len())
             .finish()
     }
 }
@@ -48,6 +66,11 @@ impl<'a> ObjectDictionary<'a> {
         Self {
             entries: BTreeMap::new(),
             storage,
+            observers: Observers::default(),
+            pdo_mapping_revision: 0,
+            store_lists: BTreeMap::new(),
+            storage_schema_version: 0,
+            orphaned_parameters: Vec::new(),
         }
     }
 
@@ -89,8 +112,84 @@ impl<'a> ObjectDictionary<'a> {
         self.entries.insert(index, entry);
     }
 
+    /// Returns a counter that changes whenever a PDO communication or mapping
+    /// object is written.
+    /// Used to detect when layouts derived from the mappings must be rebuilt.
+    pub fn pdo_mapping_revision(&self) -> u32 {
+        self.pdo_mapping_revision
+    }
+
+    /// Sets the schema version stored alongside saved parameters. Stored data
+    /// with a different version is not applied by `init`. Bump this when a
+    /// firmware update changes the meaning or layout of storable objects.
+    pub fn set_storage_schema_version(&mut self, version: u32) {
+        self.storage_schema_version = version;
+    }
+
+    /// Returns the stored parameters that could not be applied by `init` or a
+    /// reset, either because of a schema version mismatch or because the
+    /// object no longer exists or has changed type.
+    pub fn orphaned_parameters(&self) -> &[(u16, u8)] {
+        &self.orphaned_parameters
+    }
+
+    /// Registers an observer for a single object index.
+    pub fn register_observer(
+        &mut self,
+        index: u16,
+        observer: Box<dyn ObjectObserver>,
+    ) -> ObserverId {
+        self.observers.register(index..=index, observer)
+    }
+
+    /// Registers an observer for a range of object indices (e.g. 0x2000..=0x5FFF).
+    pub fn register_range_observer(
+        &mut self,
+        indices: RangeInclusive<u16>,
+        observer: Box<dyn ObjectObserver>,
+    ) -> ObserverId {
+        self.observers.register(indices, observer)
+    }
+
+    /// Removes a previously registered observer. Returns false if it was not found.
+    pub fn unregister_observer(&mut self, id: ObserverId) -> bool {
+        self.observers.unregister(id)
+    }
+
+    /// Runs the post-write hooks for RPDO data stored in the process image
+    /// instead of the OD. The data is already in the image, so it cannot be
+    /// vetoed.
+    pub(crate) fn notify_image_write(&mut self, index: u16, sub_index: u8, data: &[u8]) {
+        if !self.observers.observes(index) {
+            return;
+        }
+        let Some(template) = self.read(index, sub_index).map(Cow::into_owned) else {
+            return;
+        };
+        match ObjectValue::deserialize(data, &template) {
+            Ok(value) => self
+                .observers
+                .post_write(index, sub_index, &value, WriteSource::Rpdo),
+            Err(e) => warn!(
+                "Cannot decode process image data of 0x{:04X}/{} for observers: {:?}",
+                index, sub_index, e
+            ),
+        }
+    }
+
     /// Reads a value from the Object Dictionary by index and sub-index.
+    ///
+    /// For existing objects, a registered read provider takes precedence over
+    /// the stored value.
     pub fn read<'s>(&'s self, index: u16, sub_index: u8) -> Option<Cow<'s, ObjectValue>> {
+        if let Some(value) = self
+            .entries
+            .contains_key(&index)
+            .then(|| self.observers.read(index, sub_index))
+            .flatten()
+        {
+            return Some(Cow::Owned(value));
+        }
         self.entries
             .get(&index)
             .and_then(|entry| match &entry.object {
@@ -169,6 +268,17 @@ impl<'a> ObjectDictionary<'a> {
         index: u16,
         sub_index: u8,
         value: ObjectValue,
+    ) -> Result<(), PowerlinkError> {
+        self.write_with_source(index, sub_index, value, WriteSource::Internal)
+    }
+
+    /// Like `write`, but reports the given source to registered observers.
+    pub fn write_with_source(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: ObjectValue,
+        source: WriteSource,
     ) -> Result<(), PowerlinkError> {
         trace!(
             "Attempting OD write: {:#06X}/{}, Value: {:?}",
@@ -222,7 +332,41 @@ impl<'a> ObjectDictionary<'a> {
         }
 
         // Normal write for other objects/sub-indices.
-        self.write_internal(index, sub_index, value, true)
+        self.write_internal_from(index, sub_index, value, true, source)?;
+        commands::store_autonomous(self, index);
+        Ok(())
+    }
+
+    /// Checks whether `write` would accept the value for a data object,
+    /// without writing it. Command objects (1010h/1011h), PDO mapping counts
+    /// and write observers are not consulted.
+    pub fn check_write(
+        &self,
+        index: u16,
+        sub_index: u8,
+        value: &ObjectValue,
+    ) -> Result<(), PowerlinkError> {
+        let entry = self
+            .entries
+            .get(&index)
+            .ok_or(PowerlinkError::ObjectNotFound)?;
+        if matches!(
+            entry.access,
+            Some(AccessType::ReadOnly | AccessType::Constant)
+        ) {
+            return Err(PowerlinkError::StorageError("Object is read-only"));
+        }
+        let current = match (&entry.object, sub_index) {
+            (Object::Variable(v), 0) => v,
+            (Object::Array(values) | Object::Record(values), 1..) => values
+                .get(sub_index as usize - 1)
+                .ok_or(PowerlinkError::SubObjectNotFound)?,
+            _ => return Err(PowerlinkError::SubObjectNotFound),
+        };
+        if !current.same_type(value) {
+            return Err(PowerlinkError::TypeMismatch);
+        }
+        check_value_range(entry.value_range.as_ref(), index, sub_index, value)
     }
 
     /// Finds an object by its string name.
@@ -252,7 +396,14 @@ impl<'a> ObjectDictionary<'a> {
         // 1. Try to load stored parameters from the backend (if available)
         let stored_params = if let Some(s) = &mut self.storage {
             match s.load() {
-                Ok(params) => Some(params),
+                Ok(mut params) => {
+                    if storage::take_compatible(self.storage_schema_version, &mut params) {
+                        Some(params)
+                    } else {
+                        self.orphaned_parameters = params.into_keys().collect();
+                        None
+                    }
+                }
                 Err(e) => {
                     warn!("Failed to load stored parameters during reset: {:?}", e);
                     None
@@ -297,6 +448,12 @@ impl<'a> ObjectDictionary<'a> {
                 }
             }
         }
+
+        if start_index <= constants::IDX_TPDO_MAPPING_PARAM_REC_END
+            && end_index >= constants::IDX_RPDO_COMM_PARAM_REC_START
+        {
+            self.pdo_mapping_revision = self.pdo_mapping_revision.wrapping_add(1);
+        }
     }
 
     /// Atomically increments an UNSIGNED32 counter in the Object Dictionary.
@@ -338,7 +495,22 @@ impl<'a> ObjectDictionary<'a> {
         value: ObjectValue,
         check_access: bool,
     ) -> Result<(), PowerlinkError> {
-        self.entries
+        self.write_internal_from(index, sub_index, value, check_access, WriteSource::Internal)
+    }
+
+    /// Internal write function that reports the given source to observers.
+    pub(super) fn write_internal_from(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: ObjectValue,
+        check_access: bool,
+        source: WriteSource,
+    ) -> Result<(), PowerlinkError> {
+        // Keep a copy for the post-write notification only if someone listens.
+        let notify_value = self.observers.observes(index).then(|| value.clone());
+        let result = self
+            .entries
             .get_mut(&index)
             .ok_or(PowerlinkError::ObjectNotFound)
             .and_then(|entry| {
@@ -358,10 +530,14 @@ impl<'a> ObjectDictionary<'a> {
                 match &mut entry.object {
                     Object::Variable(v) => {
                         if sub_index == 0 {
-                            if core::mem::discriminant(v) != core::mem::discriminant(&value) {
+                            if !v.same_type(&value) {
                                 error!("Type mismatch writing Variable {:#06X}/0. Expected {:?}, got {:?}", index, v, value);
                                 return Err(PowerlinkError::TypeMismatch);
                             }
+                            check_value_range(entry.value_range.as_ref(), index, sub_index, &value)?;
+                            self.observers
+                                .pre_write(index, sub_index, &value, source)
+                                .map_err(PowerlinkError::WriteRejected)?;
                             *v = value;
                             Ok(())
                         } else {
@@ -395,13 +571,22 @@ impl<'a> ObjectDictionary<'a> {
                                 ))
                             }
                         } else if let Some(v) = values.get_mut(sub_index as usize - 1) {
-                            if core::mem::discriminant(v) != core::mem::discriminant(&value) {
+                            if !v.same_type(&value) {
                                 error!(
                                     "Type mismatch writing {:#06X}/{}. Expected {:?}, got {:?}",
                                     index, sub_index, v, value
                                 );
                                 return Err(PowerlinkError::TypeMismatch);
                             }
+                            check_value_range(
+                                entry.value_range.as_ref(),
+                                index,
+                                sub_index,
+                                &value,
+                            )?;
+                            self.observers
+                                .pre_write(index, sub_index, &value, source)
+                                .map_err(PowerlinkError::WriteRejected)?;
                             *v = value;
                             Ok(())
                         } else {
@@ -409,10 +594,57 @@ impl<'a> ObjectDictionary<'a> {
                         }
                     }
                 }
-            })
+            });
+        if result.is_ok() && is_pdo_layout(index) {
+            self.pdo_mapping_revision = self.pdo_mapping_revision.wrapping_add(1);
+        }
+        if let (Ok(()), Some(value)) = (&result, notify_value) {
+            self.observers.post_write(index, sub_index, &value, source);
+        }
+        result
     }
 }
 
+/// Returns true if the index is an RPDO or TPDO communication or mapping
+/// parameter object. The communication parameters carry the NodeID that
+/// selects which channels make up the process image.
+fn is_pdo_layout(index: u16) -> bool {
+    (constants::IDX_RPDO_COMM_PARAM_REC_START..=constants::IDX_RPDO_COMM_PARAM_REC_END)
+        .contains(&index)
+        || (constants::IDX_RPDO_MAPPING_PARAM_REC_START..=constants::IDX_RPDO_MAPPING_PARAM_REC_END)
+            .contains(&index)
+        || (constants::IDX_TPDO_COMM_PARAM_REC_START..=constants::IDX_TPDO_COMM_PARAM_REC_END)
+            .contains(&index)
+        || (constants::IDX_TPDO_MAPPING_PARAM_REC_START..=constants::IDX_TPDO_MAPPING_PARAM_REC_END)
+            .contains(&index)
+}
+
+/// Checks a value against the bit width of its data type and the entry's
+/// declared value range, if any.
+fn check_value_range(
+    range: Option<&ValueRange>,
+    index: u16,
+    sub_index: u8,
+    value: &ObjectValue,
+) -> Result<(), PowerlinkError> {
+    if let Err(e) = value.check_bit_width() {
+        error!(
+            "Rejected write to {:#06X}/{}: {:?} does not fit its data type ({:?})",
+            index, sub_index, value, e
+        );
+        return Err(e);
+    }
+    let Some(range) = range else {
+        return Ok(());
+    };
+    range.check(value).inspect_err(|e| {
+        error!(
+            "Rejected write to {:#06X}/{}: {:?} ({:?}, range {:?}..={:?})",
+            index, sub_index, value, e, range.min, range.max
+        );
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -557,6 +789,88 @@ mod tests {
         assert_eq!(od.read_u32(0x6000, 0).unwrap(), 21);
     }
 
+    #[test]
+    fn test_write_checks_value_range_and_allowed_values() {
+        let mut od = ObjectDictionary::new(None);
+        od.insert(
+            0x2000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Integer16(0)),
+                access: Some(AccessType::ReadWrite),
+                value_range: Some(ValueRange {
+                    min: ObjectValue::Integer16(-10),
+                    max: ObjectValue::Integer16(100),
+                    values: Vec::new(),
+                }),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x2001,
+            ObjectEntry {
+                object: Object::Array(vec![ObjectValue::Unsigned8(1); 2]),
+                access: Some(AccessType::ReadWrite),
+                value_range: Some(ValueRange {
+                    min: ObjectValue::Unsigned8(1),
+                    max: ObjectValue::Unsigned8(4),
+                    values: vec![ObjectValue::Unsigned8(1), ObjectValue::Unsigned8(4)],
+                }),
+                ..Default::default()
+            },
+        );
+
+        assert!(od.write(0x2000, 0, ObjectValue::Integer16(100)).is_ok());
+        assert_eq!(
+            od.write(0x2000, 0, ObjectValue::Integer16(101)),
+            Err(PowerlinkError::ValueTooHigh)
+        );
+        assert_eq!(
+            od.write(0x2000, 0, ObjectValue::Integer16(-11)),
+            Err(PowerlinkError::ValueTooLow)
+        );
+        assert_eq!(
+            od.write(0x2000, 0, ObjectValue::Unsigned16(5)),
+            Err(PowerlinkError::TypeMismatch)
+        );
+        assert_eq!(
+            od.read(0x2000, 0).unwrap().as_ref(),
+            &ObjectValue::Integer16(100)
+        );
+
+        assert!(od.write(0x2001, 2, ObjectValue::Unsigned8(4)).is_ok());
+        assert_eq!(
+            od.write(0x2001, 2, ObjectValue::Unsigned8(3)),
+            Err(PowerlinkError::InvalidValue)
+        );
+        assert_eq!(
+            od.read(0x2001, 2).unwrap().as_ref(),
+            &ObjectValue::Unsigned8(4)
+        );
+    }
+
+    #[test]
+    fn test_write_checks_narrow_integer_width() {
+        let mut od = ObjectDictionary::new(None);
+        od.insert(
+            0x2002,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Unsigned24(0)),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+
+        assert!(od.write(0x2002, 0, ObjectValue::Unsigned24(0xFF_FFFF)).is_ok());
+        assert_eq!(
+            od.write(0x2002, 0, ObjectValue::Unsigned24(0x100_0000)),
+            Err(PowerlinkError::ValueTooHigh)
+        );
+        assert_eq!(
+            od.read(0x2002, 0).unwrap().as_ref(),
+            &ObjectValue::Unsigned24(0xFF_FFFF)
+        );
+    }
+
     // Default implementation for ObjectEntry to simplify test setup.
     impl Default for ObjectEntry {
         fn default() -> Self {
@@ -0,0 +1,255 @@
+// crates/powerlink-rs/src/od/observer.rs
+//! Application hooks for Object Dictionary reads and writes.
+//!
+//! An `ObjectObserver` is registered for a single index or a range of indices.
+//! It is called on every write to those objects, whether it comes from an SDO
+//! server, an RPDO or the local stack and application, and may provide the
+//! value of computed objects on read.
+
+use super::value::ObjectValue;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+/// The origin of an Object Dictionary write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteSource {
+    /// A write from a remote SDO client (ASnd, UDP or embedded in a PDO).
+    Sdo,
+    /// Data consumed from a received RPDO.
+    Rpdo,
+    /// A write by the local application or the stack itself.
+    Internal,
+}
+
+/// Callbacks for objects owned by the application. All methods have no-op
+/// defaults, so an observer only implements the hooks it needs.
+pub trait ObjectObserver {
+    /// Called before a value is stored. Returning `Err(abort_code)` vetoes the
+    /// write; SDO writes are then aborted with that code.
+    fn pre_write(
+        &mut self,
+        _index: u16,
+        _sub_index: u8,
+        _value: &ObjectValue,
+        _source: WriteSource,
+    ) -> Result<(), u32> {
+        Ok(())
+    }
+
+    /// Called after a value has been stored.
+    fn post_write(
+        &mut self,
+        _index: u16,
+        _sub_index: u8,
+        _value: &ObjectValue,
+        _source: WriteSource,
+    ) {
+    }
+
+    /// Provides the current value of a computed object. Returning `None`
+    /// falls back to the value stored in the Object Dictionary.
+    fn read(&self, _index: u16, _sub_index: u8) -> Option<ObjectValue> {
+        None
+    }
+}
+
+/// Identifies a registered observer, used to unregister it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObserverId(u32);
+
+/// An observer together with the indices it is registered for.
+pub(super) struct Registration {
+    id: ObserverId,
+    indices: RangeInclusive<u16>,
+    observer: Box<dyn ObjectObserver>,
+}
+
+/// The set of observers registered on an Object Dictionary.
+#[derive(Default)]
+pub(super) struct Observers {
+    registrations: Vec<Registration>,
+    next_id: u32,
+}
+
+impl Observers {
+    pub(super) fn register(
+        &mut self,
+        indices: RangeInclusive<u16>,
+        observer: Box<dyn ObjectObserver>,
+    ) -> ObserverId {
+        let id = ObserverId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        self.registrations.push(Registration {
+            id,
+            indices,
+            observer,
+        });
+        id
+    }
+
+    pub(super) fn unregister(&mut self, id: ObserverId) -> bool {
+        let before = self.registrations.len();
+        self.registrations.retain(|r| r.id != id);
+        self.registrations.len() != before
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub(super) fn observes(&self, index: u16) -> bool {
+        self.registrations
+            .iter()
+            .any(|r| r.indices.contains(&index))
+    }
+
+    /// Runs all pre-write hooks for the index. The first veto wins.
+    pub(super) fn pre_write(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: &ObjectValue,
+        source: WriteSource,
+    ) -> Result<(), u32> {
+        self.registrations
+            .iter_mut()
+            .filter(|r| r.indices.contains(&index))
+            .try_for_each(|r| r.observer.pre_write(index, sub_index, value, source))
+    }
+
+    pub(super) fn post_write(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: &ObjectValue,
+        source: WriteSource,
+    ) {
+        for r in self
+            .registrations
+            .iter_mut()
+            .filter(|r| r.indices.contains(&index))
+        {
+            r.observer.post_write(index, sub_index, value, source);
+        }
+    }
+
+    /// Returns the value of the first provider that supplies one.
+    pub(super) fn read(&self, index: u16, sub_index: u8) -> Option<ObjectValue> {
+        self.registrations
+            .iter()
+            .filter(|r| r.indices.contains(&index))
+            .find_map(|r| r.observer.read(index, sub_index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PowerlinkError;
+    use crate::od::{AccessType, Object, ObjectDictionary, ObjectEntry};
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    type WriteLog = Rc<RefCell<Vec<(u16, u8, ObjectValue, WriteSource)>>>;
+
+    struct TestObserver {
+        writes: WriteLog,
+    }
+
+    impl ObjectObserver for TestObserver {
+        fn pre_write(
+            &mut self,
+            _index: u16,
+            _sub_index: u8,
+            value: &ObjectValue,
+            _source: WriteSource,
+        ) -> Result<(), u32> {
+            if *value == ObjectValue::Unsigned16(13) {
+                Err(0x0800_0022)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn post_write(
+            &mut self,
+            index: u16,
+            sub_index: u8,
+            value: &ObjectValue,
+            source: WriteSource,
+        ) {
+            self.writes
+                .borrow_mut()
+                .push((index, sub_index, value.clone(), source));
+        }
+
+        fn read(&self, index: u16, _sub_index: u8) -> Option<ObjectValue> {
+            (index == 0x2001).then_some(ObjectValue::Unsigned16(0xBEEF))
+        }
+    }
+
+    fn create_od() -> ObjectDictionary<'static> {
+        let mut od = ObjectDictionary::new(None);
+        for index in [0x2000, 0x2001, 0x3000] {
+            od.insert(
+                index,
+                ObjectEntry {
+                    object: Object::Variable(ObjectValue::Unsigned16(0)),
+                    access: Some(AccessType::ReadWrite),
+                    ..Default::default()
+                },
+            );
+        }
+        od
+    }
+
+    #[test]
+    fn test_observer_veto_and_notification() {
+        let writes = WriteLog::default();
+        let mut od = create_od();
+        od.register_range_observer(
+            0x2000..=0x2FFF,
+            Box::new(TestObserver {
+                writes: writes.clone(),
+            }),
+        );
+
+        od.write(0x2000, 0, ObjectValue::Unsigned16(5)).unwrap();
+        od.write_with_source(0x2000, 0, ObjectValue::Unsigned16(6), WriteSource::Sdo)
+            .unwrap();
+        assert_eq!(
+            od.write(0x2000, 0, ObjectValue::Unsigned16(13)),
+            Err(PowerlinkError::WriteRejected(0x0800_0022))
+        );
+        assert_eq!(od.read_u16(0x2000, 0), Some(6));
+        // Objects outside the range are not observed.
+        od.write(0x3000, 0, ObjectValue::Unsigned16(13)).unwrap();
+
+        assert_eq!(
+            *writes.borrow(),
+            vec![
+                (0x2000, 0, ObjectValue::Unsigned16(5), WriteSource::Internal),
+                (0x2000, 0, ObjectValue::Unsigned16(6), WriteSource::Sdo),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_provider_and_unregister() {
+        let mut od = create_od();
+        let id = od.register_observer(
+            0x2001,
+            Box::new(TestObserver {
+                writes: WriteLog::default(),
+            }),
+        );
+        assert_eq!(od.read_u16(0x2001, 0), Some(0xBEEF));
+        assert_eq!(od.read_u16(0x2000, 0), Some(0));
+
+        assert!(od.unregister_observer(id));
+        assert!(!od.unregister_observer(id));
+        assert_eq!(od.read_u16(0x2001, 0), Some(0));
+    }
+}
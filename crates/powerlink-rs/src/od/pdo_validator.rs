@@ -43,6 +43,20 @@ pub fn validate_pdo_mapping(
         for i in 0..(new_num_entries as usize) {
             if let Some(ObjectValue::Unsigned64(raw_mapping)) = entries.get(i) {
                 let entry = PdoMappingEntry::from_u64(*raw_mapping);
+                if entry.byte_offset().is_none() || entry.byte_length().is_none() {
+                    error!(
+                        "PDO mapping validation error for {:#06X}: entry {} maps {:#06X}/{} at bit offset {} with {} bits, which is not byte-aligned.",
+                        index,
+                        i + 1,
+                        entry.index,
+                        entry.sub_index,
+                        entry.offset_bits,
+                        entry.length_bits
+                    );
+                    return Err(PowerlinkError::ValidationError(
+                        "Non-byte-aligned PDO mappings are not supported",
+                    ));
+                }
                 let end_pos_bits = entry.offset_bits as u32 + entry.length_bits as u32;
                 max_bits_required = max_bits_required.max(end_pos_bits);
             } else {
@@ -211,4 +225,41 @@ mod tests {
             ))
         ));
     }
+
+    #[test]
+    fn test_pdo_mapping_validation_failure_bit_aligned() {
+        let mut od = ObjectDictionary::new(None);
+        // A 12-bit BIT string packed right after a single bit.
+        let mapping1 = PdoMappingEntry {
+            index: 0x6000,
+            sub_index: 1,
+            offset_bits: 0,
+            length_bits: 1,
+        };
+        let mapping2 = PdoMappingEntry {
+            index: 0x6001,
+            sub_index: 0,
+            offset_bits: 1,
+            length_bits: 12,
+        };
+        od.insert(
+            0x1A00,
+            ObjectEntry {
+                object: Object::Array(vec![
+                    ObjectValue::Unsigned64(mapping1.to_u64()),
+                    ObjectValue::Unsigned64(mapping2.to_u64()),
+                ]),
+                access: Some(AccessType::ReadWriteStore),
+                ..Default::default()
+            },
+        );
+
+        let result = validate_pdo_mapping(&od, 0x1A00, 2);
+        assert!(matches!(
+            result,
+            Err(PowerlinkError::ValidationError(
+                "Non-byte-aligned PDO mappings are not supported"
+            ))
+        ));
+    }
 }
@@ -1,8 +1,34 @@
 // crates/powerlink-rs/src/od/storage.rs
-use super::{ObjectDictionary, predefined};
+use super::{ObjectDictionary, ObjectValue, commands, constants, predefined};
 use crate::PowerlinkError;
+use alloc::collections::BTreeMap;
 use log::{info, warn};
 
+/// Storage key holding the schema version the parameters were saved with.
+/// Sub-index 0 of 0x1010 is never a storable parameter, so it cannot collide.
+pub(super) const SCHEMA_VERSION_KEY: (u16, u8) = (constants::IDX_NMT_STORE_PARAM_CMD_REC, 0);
+
+/// Removes the schema version tag from loaded parameters and returns whether
+/// they were saved with the expected schema version. Untagged data is
+/// treated as version 0.
+pub(super) fn take_compatible(
+    expected_version: u32,
+    params: &mut BTreeMap<(u16, u8), ObjectValue>,
+) -> bool {
+    let stored_version = match params.remove(&SCHEMA_VERSION_KEY) {
+        Some(ObjectValue::Unsigned32(v)) => v,
+        _ => 0,
+    };
+    if stored_version != expected_version {
+        warn!(
+            "Stored parameters have schema version {}, expected {}.",
+            stored_version, expected_version
+        );
+        return false;
+    }
+    true
+}
+
 /// Initialises the Object Dictionary.
 ///
 /// This function implements a "Layered Initialization" strategy:
@@ -11,11 +37,15 @@ use log::{info, warn};
 /// 3. **Persistence Layer**:
 ///    - If a **Restore Defaults** is pending: The storage is wiped, the flag is cleared, and the OD remains at the Firmware/Protocol default state.
 ///    - If **Normal Boot**: Parameters are loaded from storage and applied as an overlay, overwriting the defaults.
+///      Parameters saved with a different schema version, or that no longer fit the OD,
+///      are not applied and are reported by `ObjectDictionary::orphaned_parameters`.
 pub fn init(od: &mut ObjectDictionary) -> Result<(), PowerlinkError> {
     // 1. & 2. Ensure the OD has a valid structure (Firmware + Protocol Defaults).
     // This must happen BEFORE loading from storage, so that storage has valid
     // objects to write into.
     predefined::populate_protocol_objects(od);
+    commands::update_store_capabilities(od);
+    od.orphaned_parameters.clear();
 
     // 3. Handle Persistence
     if let Some(s) = &mut od.storage {
@@ -37,9 +67,19 @@ pub fn init(od: &mut ObjectDictionary) -> Result<(), PowerlinkError> {
             info!("Loading parameters from persistent storage.");
             
             // 1. Load parameters from storage.
-            let stored_params = s.load()?;
+            let mut stored_params = s.load()?;
             
-            // 2. Apply them to the OD (Overlay).
+            // 2. Data written by a different firmware schema is not applied at all.
+            if !take_compatible(od.storage_schema_version, &mut stored_params) {
+                od.orphaned_parameters = stored_params.into_keys().collect();
+                warn!(
+                    "Ignoring {} stored parameters from an incompatible schema.",
+                    od.orphaned_parameters.len()
+                );
+                return Ok(());
+            }
+
+            // 3. Apply them to the OD (Overlay).
             let mut loaded_count = 0;
             for ((index, sub_index), value) in stored_params {
                 // We use write_internal with check_access=false.
@@ -48,9 +88,9 @@ pub fn init(od: &mut ObjectDictionary) -> Result<(), PowerlinkError> {
                 match od.write_internal(index, sub_index, value, false) {
                     Ok(_) => loaded_count += 1,
                     Err(e) => {
-                        // This is not critical: it might be an orphaned parameter from 
-                        // an old firmware version that no longer exists in the OD.
-                        warn!("Failed to restore stored parameter {:#06X}/{}: {:?}", index, sub_index, e);
+                        // The parameter no longer exists in the OD or has changed type.
+                        warn!("Orphaned stored parameter {:#06X}/{}: {:?}", index, sub_index, e);
+                        od.orphaned_parameters.push((index, sub_index));
                     }
                 }
             }
@@ -176,4 +216,83 @@ mod tests {
         // Note: In a real mock, clear() clears the map, but our flag logic is boolean.
         // The important part is the method calls.
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_init_reports_orphaned_parameters() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(999));
+        // No longer present in the OD.
+        storage
+            .saved_data
+            .insert((0x6001, 0), ObjectValue::Unsigned32(1));
+
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.insert(
+            0x6000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Unsigned32(0)),
+                access: Some(AccessType::ReadWriteStore),
+                ..Default::default()
+            },
+        );
+        init(&mut od).unwrap();
+
+        assert_eq!(od.read_u32(0x6000, 0).unwrap(), 999);
+        assert_eq!(od.orphaned_parameters(), &[(0x6001, 0)]);
+    }
+
+    #[test]
+    fn test_init_ignores_incompatible_schema_version() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(999));
+        storage
+            .saved_data
+            .insert(SCHEMA_VERSION_KEY, ObjectValue::Unsigned32(1));
+
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.set_storage_schema_version(2);
+        od.insert(
+            0x6000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Unsigned32(0)),
+                access: Some(AccessType::ReadWriteStore),
+                ..Default::default()
+            },
+        );
+        init(&mut od).unwrap();
+
+        assert_eq!(od.read_u32(0x6000, 0).unwrap(), 0);
+        assert_eq!(od.orphaned_parameters(), &[(0x6000, 0)]);
+    }
+
+    #[test]
+    fn test_reset_ignores_incompatible_schema_version() {
+        let mut storage = MockStorage::new();
+        storage
+            .saved_data
+            .insert((0x6000, 0), ObjectValue::Unsigned32(999));
+        storage
+            .saved_data
+            .insert(SCHEMA_VERSION_KEY, ObjectValue::Unsigned32(1));
+
+        let mut od = ObjectDictionary::new(Some(&mut storage));
+        od.set_storage_schema_version(2);
+        od.insert(
+            0x6000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Unsigned32(5)),
+                default_value: Some(ObjectValue::Unsigned32(0)),
+                access: Some(AccessType::ReadWriteStore),
+                ..Default::default()
+            },
+        );
+        od.restore_power_on_values(0x6000, 0x9FFF);
+
+        assert_eq!(od.read_u32(0x6000, 0).unwrap(), 0);
+        assert_eq!(od.orphaned_parameters(), &[(0x6000, 0)]);
+    }
+}
@@ -0,0 +1,156 @@
+// crates/powerlink-rs/src/od/typed.rs
+//! Typed access to Object Dictionary entries.
+//!
+//! `ObjectType` converts plain Rust values to and from `ObjectValue`.
+//! `PowerlinkObjects` is implemented by application structs (usually through
+//! `#[derive(PowerlinkObjects)]` from the `derive` feature) whose fields are
+//! bound to OD entries.
+
+use super::{ObjectDictionary, ObjectValue};
+use crate::PowerlinkError;
+use crate::frame::basic::MacAddress;
+use crate::types::{BitString, IpAddress};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A Rust type that maps to exactly one `ObjectValue` variant.
+///
+/// The 24, 40, 48 and 56-bit integer types have no implementation: their
+/// aliases (`INTEGER24` = `i32`, ...) are the same Rust types as the 32 and
+/// 64-bit ones, which already map to `Integer32`/`Integer64` and so on. Read
+/// and write those objects as `ObjectValue`s.
+pub trait ObjectType: Sized {
+    /// Wraps the value in its `ObjectValue` variant.
+    fn into_object_value(self) -> ObjectValue;
+
+    /// Extracts the value, or returns `None` if the variant does not match.
+    fn from_object_value(value: &ObjectValue) -> Option<Self>;
+}
+
+macro_rules! impl_object_type {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl ObjectType for $t {
+                fn into_object_value(self) -> ObjectValue {
+                    ObjectValue::$variant(self)
+                }
+
+                fn from_object_value(value: &ObjectValue) -> Option<Self> {
+                    match value {
+                        ObjectValue::$variant(v) => Some(v.clone()),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_object_type!(
+    i8 => Integer8,
+    i16 => Integer16,
+    i32 => Integer32,
+    i64 => Integer64,
+    u8 => Unsigned8,
+    u16 => Unsigned16,
+    u32 => Unsigned32,
+    u64 => Unsigned64,
+    f32 => Real32,
+    f64 => Real64,
+    String => VisibleString,
+    Vec<u8> => OctetString,
+    MacAddress => MacAddress,
+    IpAddress => IpAddress,
+    BitString => BitString,
+);
+
+impl ObjectType for bool {
+    fn into_object_value(self) -> ObjectValue {
+        ObjectValue::Boolean(self as u8)
+    }
+
+    fn from_object_value(value: &ObjectValue) -> Option<Self> {
+        match value {
+            ObjectValue::Boolean(v) => Some(*v != 0),
+            _ => None,
+        }
+    }
+}
+
+/// A set of application objects bound to fixed OD indices.
+pub trait PowerlinkObjects {
+    /// Inserts the OD entries for all fields, using the current field values
+    /// as initial and default values.
+    fn register(&self, od: &mut ObjectDictionary);
+
+    /// Copies the current OD values into the fields.
+    fn load(&mut self, od: &ObjectDictionary);
+
+    /// Writes all writable fields to the OD, respecting value ranges. Fields of
+    /// read-only and constant objects are skipped. The derived implementation
+    /// checks every field before writing any, so a rejected value leaves the
+    /// OD unchanged; only a write observer may still reject a field after
+    /// earlier ones were written.
+    fn store(&self, od: &mut ObjectDictionary) -> Result<(), PowerlinkError>;
+}
+
+impl<'a> ObjectDictionary<'a> {
+    /// Reads a value and converts it to a Rust type.
+    /// Returns `None` if the object does not exist or has a different type.
+    pub fn read_as<T: ObjectType>(&self, index: u16, sub_index: u8) -> Option<T> {
+        self.read(index, sub_index)
+            .and_then(|cow| T::from_object_value(&cow))
+    }
+
+    /// Converts a Rust value to its `ObjectValue` and writes it.
+    pub fn write_as<T: ObjectType>(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        value: T,
+    ) -> Result<(), PowerlinkError> {
+        self.write(index, sub_index, value.into_object_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::od::{AccessType, Object, ObjectEntry};
+
+    #[test]
+    fn test_round_trip_conversions() {
+        assert_eq!(7u16.into_object_value(), ObjectValue::Unsigned16(7));
+        assert_eq!(true.into_object_value(), ObjectValue::Boolean(1));
+        assert_eq!(
+            i32::from_object_value(&ObjectValue::Integer32(-4)),
+            Some(-4)
+        );
+        assert_eq!(u32::from_object_value(&ObjectValue::Integer32(-4)), None);
+        assert_eq!(
+            BitString::new(12, 0xABC).into_object_value(),
+            ObjectValue::BitString(BitString::new(12, 0xABC))
+        );
+        assert_eq!(
+            String::from_object_value(&ObjectValue::VisibleString("io".into())),
+            Some(String::from("io"))
+        );
+    }
+
+    #[test]
+    fn test_typed_read_write() {
+        let mut od = ObjectDictionary::new(None);
+        od.insert(
+            0x2000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Real32(0.0)),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.write_as(0x2000, 0, 1.5f32).unwrap();
+        assert_eq!(od.read_as::<f32>(0x2000, 0), Some(1.5));
+        assert_eq!(od.read_as::<u8>(0x2000, 0), None);
+        assert!(od.write_as(0x2000, 0, 1u8).is_err());
+    }
+}
@@ -6,6 +6,7 @@ use super::{
     ObjectDictionary,
     {AccessType, Category, Object, ObjectValue, PdoMapping},
 };
+use crate::frame::error::DEFAULT_DLL_ERROR_THRESHOLD;
 use crate::{nmt::flags::FeatureFlags, types::NodeId, PowerlinkError};
 use alloc::vec;
 
@@ -198,6 +199,7 @@ pub fn new_cn_default(node_id: NodeId) -> Result<ObjectDictionary<'static>, Powe
     );
 
     add_diagnostic_objects(&mut od)?;
+    add_cn_dll_error_objects(&mut od)?;
 
     Ok(od)
 }
@@ -367,6 +369,8 @@ pub fn new_mn_default(node_id: NodeId) -> Result<ObjectDictionary<'static>, Powe
         },
     );
 
+    add_mn_dll_error_objects(&mut od)?;
+
     Ok(od)
 }
 
@@ -420,4 +424,100 @@ fn add_diagnostic_objects(od: &mut ObjectDictionary<'static>) -> Result<(), Powe
     );
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Builds a DLL_ErrorCntRec_TYPE record with zeroed counters and the default threshold.
+fn dll_error_record(name: &'static str) -> ObjectEntry {
+    ObjectEntry {
+        object: Object::Record(vec![
+            // Sub-index 0 (Count) is implicit.
+            ObjectValue::Unsigned32(0), // 1: CumulativeCnt_U32
+            ObjectValue::Unsigned32(0), // 2: ThresholdCnt_U32
+            ObjectValue::Unsigned32(DEFAULT_DLL_ERROR_THRESHOLD), // 3: Threshold_U32
+        ]),
+        name,
+        category: Category::Optional,
+        access: Some(AccessType::ReadWrite),
+        default_value: None,
+        value_range: None,
+        pdo_mapping: Some(PdoMapping::No),
+    }
+}
+
+/// Builds a per-CN DLL error array (sub-index = Node ID) with every entry set to `value`.
+fn dll_error_array(name: &'static str, value: u32) -> ObjectEntry {
+    ObjectEntry {
+        object: Object::Array(vec![ObjectValue::Unsigned32(value); 254]),
+        name,
+        category: Category::Optional,
+        access: Some(AccessType::ReadWrite),
+        default_value: None,
+        value_range: None,
+        pdo_mapping: Some(PdoMapping::No),
+    }
+}
+
+/// Helper to add the CN's DLL error counter objects (0x1C0A - 0x1C10) to an OD.
+/// (DS 301, 4.7.8)
+fn add_cn_dll_error_objects(od: &mut ObjectDictionary<'static>) -> Result<(), PowerlinkError> {
+    od.insert(0x1C0A, dll_error_record("DLL_CNCollision_REC"));
+    od.insert(0x1C0B, dll_error_record("DLL_CNLossSoC_REC"));
+    od.insert(0x1C0C, dll_error_record("DLL_CNLossSoA_REC"));
+    od.insert(0x1C0D, dll_error_record("DLL_CNLossPReq_REC"));
+    od.insert(0x1C0E, dll_error_record("DLL_CNSoCJitter_REC"));
+    od.insert(0x1C0F, dll_error_record("DLL_CNCRCError_REC"));
+    od.insert(
+        0x1C10,
+        ObjectEntry {
+            object: Object::Variable(ObjectValue::Unsigned32(0)),
+            name: "DLL_CNLossOfLinkCum_U32",
+            category: Category::Optional,
+            access: Some(AccessType::ReadWrite),
+            default_value: None,
+            value_range: None,
+            pdo_mapping: Some(PdoMapping::No),
+        },
+    );
+
+    Ok(())
+}
+
+/// Helper to add the MN's DLL error counter objects (0x1C00 - 0x1C09) to an OD.
+/// (DS 301, 4.7.8)
+fn add_mn_dll_error_objects(od: &mut ObjectDictionary<'static>) -> Result<(), PowerlinkError> {
+    od.insert(0x1C00, dll_error_record("DLL_MNCRCError_REC"));
+    od.insert(0x1C01, dll_error_record("DLL_MNCollision_REC"));
+    od.insert(0x1C02, dll_error_record("DLL_MNCycTimeExceed_REC"));
+    od.insert(
+        0x1C03,
+        ObjectEntry {
+            object: Object::Variable(ObjectValue::Unsigned32(0)),
+            name: "DLL_MNLossOfLinkCum_U32",
+            category: Category::Optional,
+            access: Some(AccessType::ReadWrite),
+            default_value: None,
+            value_range: None,
+            pdo_mapping: Some(PdoMapping::No),
+        },
+    );
+    od.insert(0x1C04, dll_error_array("DLL_MNCNLatePResCumCnt_AU32", 0));
+    od.insert(0x1C05, dll_error_array("DLL_MNCNLatePResThrCnt_AU32", 0));
+    od.insert(
+        0x1C06,
+        dll_error_array(
+            "DLL_MNCNLatePResThreshold_AU32",
+            DEFAULT_DLL_ERROR_THRESHOLD,
+        ),
+    );
+    od.insert(0x1C07, dll_error_array("DLL_MNCNLossPResCumCnt_AU32", 0));
+    od.insert(0x1C08, dll_error_array("DLL_MNCNLossPResThrCnt_AU32", 0));
+    od.insert(
+        0x1C09,
+        dll_error_array(
+            "DLL_MNCNLossPResThreshold_AU32",
+            DEFAULT_DLL_ERROR_THRESHOLD,
+        ),
+    );
+
+    Ok(())
+}
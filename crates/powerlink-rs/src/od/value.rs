@@ -5,10 +5,12 @@ use crate::PowerlinkError;
 use crate::common::{NetTime, TimeDifference, TimeOfDay};
 use crate::frame::basic::MacAddress;
 use crate::types::{
-    BOOLEAN, INTEGER8, INTEGER16, INTEGER32, INTEGER64, IpAddress, REAL32, REAL64, UNSIGNED8,
-    UNSIGNED16, UNSIGNED32, UNSIGNED64,
+    BOOLEAN, BitString, INTEGER8, INTEGER16, INTEGER24, INTEGER32, INTEGER40, INTEGER48, INTEGER56,
+    INTEGER64, IpAddress, REAL32, REAL64, UNSIGNED8, UNSIGNED16, UNSIGNED24, UNSIGNED32,
+    UNSIGNED40, UNSIGNED48, UNSIGNED56, UNSIGNED64,
 };
 use alloc::{string::String, vec::Vec};
+use core::cmp::Ordering;
 use core::convert::TryInto; // Required for try_into()
 
 /// Represents any value that can be stored in an Object Dictionary entry.
@@ -17,11 +19,19 @@ pub enum ObjectValue {
     Boolean(BOOLEAN), // Actually u8
     Integer8(INTEGER8),
     Integer16(INTEGER16),
+    Integer24(INTEGER24), // Stored in an i32, 3 bytes on the wire
     Integer32(INTEGER32),
+    Integer40(INTEGER40), // Stored in an i64, 5 bytes on the wire
+    Integer48(INTEGER48), // Stored in an i64, 6 bytes on the wire
+    Integer56(INTEGER56), // Stored in an i64, 7 bytes on the wire
     Integer64(INTEGER64),
     Unsigned8(UNSIGNED8),
     Unsigned16(UNSIGNED16),
+    Unsigned24(UNSIGNED24), // Stored in a u32, 3 bytes on the wire
     Unsigned32(UNSIGNED32),
+    Unsigned40(UNSIGNED40), // Stored in a u64, 5 bytes on the wire
+    Unsigned48(UNSIGNED48), // Stored in a u64, 6 bytes on the wire
+    Unsigned56(UNSIGNED56), // Stored in a u64, 7 bytes on the wire
     Unsigned64(UNSIGNED64),
     Real32(REAL32),
     Real64(REAL64),
@@ -34,9 +44,90 @@ pub enum ObjectValue {
     NetTime(NetTime),
     MacAddress(MacAddress), // Array [u8; 6]
     IpAddress(IpAddress),   // Array [u8; 4]
+    BitString(BitString),   // 1-8 bytes on the wire, depending on the length
 }
 
 impl ObjectValue {
+    /// Returns the encoded size in bytes of fixed-size types, or `None` for
+    /// strings and domains.
+    pub fn fixed_size(&self) -> Option<usize> {
+        match self {
+            ObjectValue::VisibleString(_)
+            | ObjectValue::OctetString(_)
+            | ObjectValue::UnicodeString(_)
+            | ObjectValue::Domain(_) => None,
+            other => Some(other.serialize().len()),
+        }
+    }
+
+    /// Returns true if both values have the same data type. BIT strings must
+    /// also have the same length.
+    pub fn same_type(&self, other: &ObjectValue) -> bool {
+        match (self, other) {
+            (ObjectValue::BitString(a), ObjectValue::BitString(b)) => a.len == b.len,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+
+    /// Compares two numeric values of the same type.
+    /// Returns `None` if the types differ or are not numeric.
+    pub fn numeric_cmp(&self, other: &ObjectValue) -> Option<Ordering> {
+        match (self, other) {
+            (ObjectValue::Boolean(a), ObjectValue::Boolean(b)) => a.partial_cmp(b),
+            (ObjectValue::Integer8(a), ObjectValue::Integer8(b)) => a.partial_cmp(b),
+            (ObjectValue::Integer16(a), ObjectValue::Integer16(b)) => a.partial_cmp(b),
+            (ObjectValue::Integer24(a), ObjectValue::Integer24(b)) => a.partial_cmp(b),
+            (ObjectValue::Integer32(a), ObjectValue::Integer32(b)) => a.partial_cmp(b),
+            (ObjectValue::Integer40(a), ObjectValue::Integer40(b))
+            | (ObjectValue::Integer48(a), ObjectValue::Integer48(b))
+            | (ObjectValue::Integer56(a), ObjectValue::Integer56(b))
+            | (ObjectValue::Integer64(a), ObjectValue::Integer64(b)) => a.partial_cmp(b),
+            (ObjectValue::Unsigned8(a), ObjectValue::Unsigned8(b)) => a.partial_cmp(b),
+            (ObjectValue::Unsigned16(a), ObjectValue::Unsigned16(b)) => a.partial_cmp(b),
+            (ObjectValue::Unsigned24(a), ObjectValue::Unsigned24(b)) => a.partial_cmp(b),
+            (ObjectValue::Unsigned32(a), ObjectValue::Unsigned32(b)) => a.partial_cmp(b),
+            (ObjectValue::Unsigned40(a), ObjectValue::Unsigned40(b))
+            | (ObjectValue::Unsigned48(a), ObjectValue::Unsigned48(b))
+            | (ObjectValue::Unsigned56(a), ObjectValue::Unsigned56(b))
+            | (ObjectValue::Unsigned64(a), ObjectValue::Unsigned64(b)) => a.partial_cmp(b),
+            (ObjectValue::Real32(a), ObjectValue::Real32(b)) => a.partial_cmp(b),
+            (ObjectValue::Real64(a), ObjectValue::Real64(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+
+    /// Checks that a value of a non-native width (e.g. INTEGER24, UNSIGNED40,
+    /// BIT strings) fits into the number of bits of its data type.
+    pub fn check_bit_width(&self) -> Result<(), PowerlinkError> {
+        let (value, bits, signed) = match *self {
+            ObjectValue::BitString(v) if v.len == 0 || v.len > 64 => {
+                return Err(PowerlinkError::TypeMismatch);
+            }
+            ObjectValue::BitString(v) => (v.bits as i128, v.len as u32, false),
+            ObjectValue::Integer24(v) => (v as i128, 24, true),
+            ObjectValue::Integer40(v) => (v as i128, 40, true),
+            ObjectValue::Integer48(v) => (v as i128, 48, true),
+            ObjectValue::Integer56(v) => (v as i128, 56, true),
+            ObjectValue::Unsigned24(v) => (v as i128, 24, false),
+            ObjectValue::Unsigned40(v) => (v as i128, 40, false),
+            ObjectValue::Unsigned48(v) => (v as i128, 48, false),
+            ObjectValue::Unsigned56(v) => (v as i128, 56, false),
+            _ => return Ok(()),
+        };
+        let (min, max) = if signed {
+            (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+        } else {
+            (0, (1i128 << bits) - 1)
+        };
+        if value < min {
+            Err(PowerlinkError::ValueTooLow)
+        } else if value > max {
+            Err(PowerlinkError::ValueTooHigh)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Serializes the inner value into a little-endian byte vector.
     /// Suitable for PDO payload construction.
     pub fn serialize(&self) -> Vec<u8> {
@@ -45,11 +136,19 @@ impl ObjectValue {
             ObjectValue::Boolean(v) => v.to_le_bytes().to_vec(), // Serialize as u8
             ObjectValue::Integer8(v) => v.to_le_bytes().to_vec(),
             ObjectValue::Integer16(v) => v.to_le_bytes().to_vec(),
+            ObjectValue::Integer24(v) => v.to_le_bytes()[..3].to_vec(),
             ObjectValue::Integer32(v) => v.to_le_bytes().to_vec(),
+            ObjectValue::Integer40(v) => v.to_le_bytes()[..5].to_vec(),
+            ObjectValue::Integer48(v) => v.to_le_bytes()[..6].to_vec(),
+            ObjectValue::Integer56(v) => v.to_le_bytes()[..7].to_vec(),
             ObjectValue::Integer64(v) => v.to_le_bytes().to_vec(),
             ObjectValue::Unsigned8(v) => v.to_le_bytes().to_vec(),
             ObjectValue::Unsigned16(v) => v.to_le_bytes().to_vec(),
+            ObjectValue::Unsigned24(v) => v.to_le_bytes()[..3].to_vec(),
             ObjectValue::Unsigned32(v) => v.to_le_bytes().to_vec(),
+            ObjectValue::Unsigned40(v) => v.to_le_bytes()[..5].to_vec(),
+            ObjectValue::Unsigned48(v) => v.to_le_bytes()[..6].to_vec(),
+            ObjectValue::Unsigned56(v) => v.to_le_bytes()[..7].to_vec(),
             ObjectValue::Unsigned64(v) => v.to_le_bytes().to_vec(),
             ObjectValue::Real32(v) => v.to_le_bytes().to_vec(),
             ObjectValue::Real64(v) => v.to_le_bytes().to_vec(),
@@ -77,6 +176,7 @@ impl ObjectValue {
             .concat(), // Total 8 bytes
             ObjectValue::MacAddress(v) => v.0.to_vec(), // 6 bytes
             ObjectValue::IpAddress(v) => v.to_vec(),    // 4 bytes
+            ObjectValue::BitString(v) => v.bits.to_le_bytes()[..v.byte_len().min(8)].to_vec(),
 
             // UnicodeString needs special handling (each u16 to LE bytes)
             ObjectValue::UnicodeString(v) => v.iter().flat_map(|c| c.to_le_bytes()).collect(),
@@ -106,15 +206,48 @@ impl ObjectValue {
             }};
         }
 
+        // Helper macro for integers narrower than their storage type. The value
+        // is read into the low bytes and sign-extended for signed types.
+        macro_rules! deserialize_narrow {
+            ($data:expr, $variant:path, $type:ty, $len:expr) => {{
+                if $data.len() < $len {
+                    Err(PowerlinkError::BufferTooShort)
+                } else {
+                    let mut bytes = [0u8; core::mem::size_of::<$type>()];
+                    bytes[..$len].copy_from_slice(&$data[..$len]);
+                    let shift = (core::mem::size_of::<$type>() - $len) * 8;
+                    // Shifting left then right sign-extends signed types and
+                    // is a no-op for unsigned ones.
+                    Ok($variant((<$type>::from_le_bytes(bytes) << shift) >> shift))
+                }
+            }};
+        }
+
         match type_template {
             ObjectValue::Boolean(_) => deserialize_fixed!(data, ObjectValue::Boolean, u8),
             ObjectValue::Integer8(_) => deserialize_fixed!(data, ObjectValue::Integer8, i8),
             ObjectValue::Integer16(_) => deserialize_fixed!(data, ObjectValue::Integer16, i16),
+            ObjectValue::Integer24(_) => deserialize_narrow!(data, ObjectValue::Integer24, i32, 3),
             ObjectValue::Integer32(_) => deserialize_fixed!(data, ObjectValue::Integer32, i32),
+            ObjectValue::Integer40(_) => deserialize_narrow!(data, ObjectValue::Integer40, i64, 5),
+            ObjectValue::Integer48(_) => deserialize_narrow!(data, ObjectValue::Integer48, i64, 6),
+            ObjectValue::Integer56(_) => deserialize_narrow!(data, ObjectValue::Integer56, i64, 7),
             ObjectValue::Integer64(_) => deserialize_fixed!(data, ObjectValue::Integer64, i64),
             ObjectValue::Unsigned8(_) => deserialize_fixed!(data, ObjectValue::Unsigned8, u8),
             ObjectValue::Unsigned16(_) => deserialize_fixed!(data, ObjectValue::Unsigned16, u16),
+            ObjectValue::Unsigned24(_) => {
+                deserialize_narrow!(data, ObjectValue::Unsigned24, u32, 3)
+            }
             ObjectValue::Unsigned32(_) => deserialize_fixed!(data, ObjectValue::Unsigned32, u32),
+            ObjectValue::Unsigned40(_) => {
+                deserialize_narrow!(data, ObjectValue::Unsigned40, u64, 5)
+            }
+            ObjectValue::Unsigned48(_) => {
+                deserialize_narrow!(data, ObjectValue::Unsigned48, u64, 6)
+            }
+            ObjectValue::Unsigned56(_) => {
+                deserialize_narrow!(data, ObjectValue::Unsigned56, u64, 7)
+            }
             ObjectValue::Unsigned64(_) => deserialize_fixed!(data, ObjectValue::Unsigned64, u64),
             ObjectValue::Real32(_) => deserialize_fixed!(data, ObjectValue::Real32, f32),
             ObjectValue::Real64(_) => deserialize_fixed!(data, ObjectValue::Real64, f64),
@@ -173,6 +306,21 @@ impl ObjectValue {
                 }
             }
 
+            ObjectValue::BitString(template) => {
+                let len = template.byte_len().min(8);
+                if data.len() < len {
+                    Err(PowerlinkError::BufferTooShort)
+                } else {
+                    let mut bytes = [0u8; 8];
+                    bytes[..len].copy_from_slice(&data[..len]);
+                    // Unused bits in the last byte are ignored.
+                    Ok(ObjectValue::BitString(BitString::new(
+                        template.len,
+                        u64::from_le_bytes(bytes),
+                    )))
+                }
+            }
+
             // UnicodeString needs special handling (LE bytes pairs to u16)
             ObjectValue::UnicodeString(_) => {
                 if data.len() % 2 != 0 {
@@ -231,6 +379,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_narrow_integer_roundtrip() {
+        let val_u24 = ObjectValue::Unsigned24(0x00AB_CDEF);
+        assert_eq!(val_u24.serialize(), vec![0xEF, 0xCD, 0xAB]);
+        assert_eq!(
+            ObjectValue::deserialize(&val_u24.serialize(), &val_u24),
+            Ok(val_u24)
+        );
+
+        let val_i24 = ObjectValue::Integer24(-2);
+        assert_eq!(val_i24.serialize(), vec![0xFE, 0xFF, 0xFF]);
+        assert_eq!(
+            ObjectValue::deserialize(&val_i24.serialize(), &val_i24),
+            Ok(val_i24)
+        );
+
+        let val_u40 = ObjectValue::Unsigned40(0xFF_1234_5678);
+        assert_eq!(val_u40.serialize().len(), 5);
+        assert_eq!(
+            ObjectValue::deserialize(&val_u40.serialize(), &val_u40),
+            Ok(val_u40)
+        );
+
+        let val_i56 = ObjectValue::Integer56(-(1 << 55));
+        assert_eq!(val_i56.serialize().len(), 7);
+        assert_eq!(
+            ObjectValue::deserialize(&val_i56.serialize(), &val_i56),
+            Ok(val_i56)
+        );
+
+        assert_eq!(
+            ObjectValue::deserialize(&[0x01, 0x02], &ObjectValue::Integer48(0)),
+            Err(PowerlinkError::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_narrow_integer_bit_width() {
+        assert_eq!(ObjectValue::Unsigned24(0xFF_FFFF).check_bit_width(), Ok(()));
+        assert_eq!(
+            ObjectValue::Unsigned24(0x100_0000).check_bit_width(),
+            Err(PowerlinkError::ValueTooHigh)
+        );
+        assert_eq!(
+            ObjectValue::Integer40(-(1 << 39) - 1).check_bit_width(),
+            Err(PowerlinkError::ValueTooLow)
+        );
+        assert_eq!(ObjectValue::Integer40(-(1 << 39)).check_bit_width(), Ok(()));
+        assert_eq!(ObjectValue::Unsigned32(u32::MAX).check_bit_width(), Ok(()));
+    }
+
+    #[test]
+    fn test_bit_string() {
+        let val = ObjectValue::BitString(BitString::new(12, 0xABC));
+        assert_eq!(val.serialize(), vec![0xBC, 0x0A]);
+        assert_eq!(val.fixed_size(), Some(2));
+        // Bits beyond the length are dropped when receiving.
+        assert_eq!(
+            ObjectValue::deserialize(&[0xBC, 0xFA], &val),
+            Ok(val.clone())
+        );
+        assert_eq!(
+            ObjectValue::deserialize(&[0xBC], &val),
+            Err(PowerlinkError::BufferTooShort)
+        );
+
+        let val_40 = ObjectValue::BitString(BitString::new(40, 0xFF_0000_0001));
+        assert_eq!(val_40.serialize().len(), 5);
+        assert_eq!(
+            ObjectValue::deserialize(&val_40.serialize(), &val_40),
+            Ok(val_40.clone())
+        );
+
+        assert_eq!(val.check_bit_width(), Ok(()));
+        assert_eq!(
+            ObjectValue::BitString(BitString {
+                len: 12,
+                bits: 0x1000
+            })
+            .check_bit_width(),
+            Err(PowerlinkError::ValueTooHigh)
+        );
+        assert_eq!(
+            ObjectValue::BitString(BitString::new(0, 0)).check_bit_width(),
+            Err(PowerlinkError::TypeMismatch)
+        );
+        // BIT strings of different lengths are different types.
+        assert!(val.same_type(&ObjectValue::BitString(BitString::new(12, 0))));
+        assert!(!val.same_type(&val_40));
+    }
+
     #[test]
     fn test_string_roundtrip() {
         let original = ObjectValue::VisibleString("Powerlink".into());
@@ -0,0 +1,510 @@
+// crates/powerlink-rs/src/pdo/image.rs
+//! Process image for cyclic application data.
+//!
+//! The process image holds two contiguous byte buffers laid out from the
+//! active PDO mappings: the input image receives the objects mapped in RPDOs
+//! (0x1600 - 0x16FF), the output image supplies the objects mapped in TPDOs
+//! (0x1A00 - 0x1AFF). Each mapped object gets a fixed offset, so the
+//! application accesses its data without going through `ObjectValue`, and the
+//! stack copies raw bytes between the image and the PReq/PRes payloads.
+//!
+//! While a process image is active, mapped objects are exchanged through the
+//! image only; their Object Dictionary values are not updated cyclically.
+//! Observers of mapped objects still get `post_write` for received RPDO data,
+//! but `pre_write` cannot veto it and `read` is not asked for TPDO data.
+
+use crate::PowerlinkError;
+use crate::od::{Object, ObjectDictionary, ObjectValue, constants};
+use crate::pdo::PdoMappingEntry;
+use alloc::vec;
+use alloc::vec::Vec;
+use log::{info, warn};
+
+/// The location of a mapped object within the process image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageField {
+    pub index: u16,
+    pub sub_index: u8,
+    /// Byte offset of the object within its image buffer.
+    pub offset: usize,
+    /// Length of the object in bytes.
+    pub length: usize,
+}
+
+/// A fixed-size value that can be read from or written to the process image.
+/// All values are stored little-endian, as in the PDO payload.
+pub trait ImageValue: Sized {
+    const SIZE: usize;
+    fn from_le_slice(bytes: &[u8]) -> Self;
+    fn write_le_slice(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_image_value {
+    ($($t:ty),*) => {
+        $(
+            impl ImageValue for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let mut raw = [0u8; core::mem::size_of::<$t>()];
+                    raw.copy_from_slice(bytes);
+                    <$t>::from_le_bytes(raw)
+                }
+                fn write_le_slice(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_image_value!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Contiguous input and output buffers laid out from the PDO mappings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessImage {
+    inputs: Vec<u8>,
+    outputs: Vec<u8>,
+    input_fields: Vec<ImageField>,
+    output_fields: Vec<ImageField>,
+    /// The OD mapping revision this layout was built from.
+    mapping_revision: u32,
+}
+
+impl ProcessImage {
+    /// Builds the image layout from the RPDO and TPDO mappings in the OD.
+    ///
+    /// Only configured channels are laid out: the communication parameter
+    /// record must exist, and its NodeID must not be claimed by an earlier
+    /// channel, which would take precedence in the PDO exchange. Objects are
+    /// placed in mapping order; an object mapped in more than one channel
+    /// shares a single field. The buffers are initialised with the current OD
+    /// values.
+    pub fn build(od: &ObjectDictionary) -> Self {
+        let input_fields = Self::layout(
+            od,
+            constants::IDX_RPDO_COMM_PARAM_REC_START,
+            constants::IDX_RPDO_MAPPING_PARAM_REC_START,
+        );
+        let output_fields = Self::layout(
+            od,
+            constants::IDX_TPDO_COMM_PARAM_REC_START,
+            constants::IDX_TPDO_MAPPING_PARAM_REC_START,
+        );
+        let mut image = Self {
+            inputs: vec![0; Self::size_of(&input_fields)],
+            outputs: vec![0; Self::size_of(&output_fields)],
+            input_fields,
+            output_fields,
+            mapping_revision: od.pdo_mapping_revision(),
+        };
+        Self::load_from_od(od, &image.input_fields, &mut image.inputs);
+        Self::load_from_od(od, &image.output_fields, &mut image.outputs);
+        info!(
+            "Process image built: {} input bytes ({} objects), {} output bytes ({} objects)",
+            image.inputs.len(),
+            image.input_fields.len(),
+            image.outputs.len(),
+            image.output_fields.len()
+        );
+        image
+    }
+
+    /// Returns true if the PDO mappings in the OD have changed since the image was built.
+    pub fn is_stale(&self, od: &ObjectDictionary) -> bool {
+        self.mapping_revision != od.pdo_mapping_revision()
+    }
+
+    /// Builds a new layout from the current mappings. Output objects that keep
+    /// their length retain the values the application wrote to this image.
+    pub fn rebuild(&self, od: &ObjectDictionary) -> Self {
+        let mut image = Self::build(od);
+        for field in &image.output_fields {
+            if let Some(old) = Self::find(&self.output_fields, field.index, field.sub_index)
+                && old.length == field.length
+            {
+                image.outputs[field.offset..field.offset + field.length]
+                    .copy_from_slice(&self.outputs[old.offset..old.offset + old.length]);
+            }
+        }
+        image
+    }
+
+    fn layout(od: &ObjectDictionary, comm_param_start: u16, mapping_start: u16) -> Vec<ImageField> {
+        let mut fields: Vec<ImageField> = Vec::new();
+        let mut claimed_node_ids: Vec<u8> = Vec::new();
+        let mut next_offset = 0;
+        for channel in 0..=0xFFu16 {
+            let mapping_index = mapping_start + channel;
+            let Some(node_id) = od.read_u8(
+                comm_param_start + channel,
+                constants::SUBIDX_PDO_COMM_PARAM_NODEID_U8,
+            ) else {
+                continue;
+            };
+            let num_entries = od.read_u8(mapping_index, 0).unwrap_or(0) as usize;
+            let Some(Object::Array(entries) | Object::Record(entries)) =
+                od.read_object(mapping_index)
+            else {
+                continue;
+            };
+            if num_entries == 0 {
+                continue;
+            }
+            if claimed_node_ids.contains(&node_id) {
+                warn!(
+                    "PDO channel 0x{:04X} shares NodeID {} with an earlier channel and is not used.",
+                    mapping_index, node_id
+                );
+                continue;
+            }
+            claimed_node_ids.push(node_id);
+            for raw in entries.iter().take(num_entries) {
+                let ObjectValue::Unsigned64(raw) = raw else {
+                    continue;
+                };
+                let entry = PdoMappingEntry::from_u64(*raw);
+                // Skip unused entries and embedded SDO containers (0x1200 - 0x12FF).
+                if entry.index == 0 || (0x1200..=0x12FF).contains(&entry.index) {
+                    continue;
+                }
+                let Some(length) = entry.byte_length().filter(|&len| len > 0) else {
+                    warn!(
+                        "Bit-level mapping of 0x{:04X}/{} is not supported in the process image.",
+                        entry.index, entry.sub_index
+                    );
+                    continue;
+                };
+                if fields
+                    .iter()
+                    .any(|f| f.index == entry.index && f.sub_index == entry.sub_index)
+                {
+                    continue;
+                }
+                fields.push(ImageField {
+                    index: entry.index,
+                    sub_index: entry.sub_index,
+                    offset: next_offset,
+                    length,
+                });
+                next_offset += length;
+            }
+        }
+        fields
+    }
+
+    fn size_of(fields: &[ImageField]) -> usize {
+        fields
+            .iter()
+            .map(|f| f.offset + f.length)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn load_from_od(od: &ObjectDictionary, fields: &[ImageField], buffer: &mut [u8]) {
+        for field in fields {
+            if let Some(value) = od.read(field.index, field.sub_index) {
+                let bytes = value.serialize();
+                let len = bytes.len().min(field.length);
+                buffer[field.offset..field.offset + len].copy_from_slice(&bytes[..len]);
+            }
+        }
+    }
+
+    /// The input image (data received via RPDOs).
+    pub fn inputs(&self) -> &[u8] {
+        &self.inputs
+    }
+
+    /// The output image (data sent via TPDOs).
+    pub fn outputs(&self) -> &[u8] {
+        &self.outputs
+    }
+
+    /// Mutable access to the output image.
+    pub fn outputs_mut(&mut self) -> &mut [u8] {
+        &mut self.outputs
+    }
+
+    /// The layout of the input image.
+    pub fn input_fields(&self) -> &[ImageField] {
+        &self.input_fields
+    }
+
+    /// The layout of the output image.
+    pub fn output_fields(&self) -> &[ImageField] {
+        &self.output_fields
+    }
+
+    /// Finds the input field of a mapped object.
+    pub fn input_field(&self, index: u16, sub_index: u8) -> Option<ImageField> {
+        Self::find(&self.input_fields, index, sub_index)
+    }
+
+    /// Finds the output field of a mapped object.
+    pub fn output_field(&self, index: u16, sub_index: u8) -> Option<ImageField> {
+        Self::find(&self.output_fields, index, sub_index)
+    }
+
+    fn find(fields: &[ImageField], index: u16, sub_index: u8) -> Option<ImageField> {
+        fields
+            .iter()
+            .find(|f| f.index == index && f.sub_index == sub_index)
+            .copied()
+    }
+
+    /// Reads a typed value from the input image at a byte offset.
+    pub fn read_input<T: ImageValue>(&self, offset: usize) -> Option<T> {
+        self.inputs
+            .get(offset..offset + T::SIZE)
+            .map(T::from_le_slice)
+    }
+
+    /// Reads back a typed value from the output image at a byte offset.
+    pub fn read_output<T: ImageValue>(&self, offset: usize) -> Option<T> {
+        self.outputs
+            .get(offset..offset + T::SIZE)
+            .map(T::from_le_slice)
+    }
+
+    /// Writes a typed value into the output image at a byte offset.
+    pub fn write_output<T: ImageValue>(
+        &mut self,
+        offset: usize,
+        value: T,
+    ) -> Result<(), PowerlinkError> {
+        let slot = self
+            .outputs
+            .get_mut(offset..offset + T::SIZE)
+            .ok_or(PowerlinkError::BufferTooShort)?;
+        value.write_le_slice(slot);
+        Ok(())
+    }
+
+    /// Copies a received RPDO object into the input image.
+    /// Returns false if the object is not part of the image.
+    pub(crate) fn store_input(&mut self, index: u16, sub_index: u8, data: &[u8]) -> bool {
+        let Some(field) = Self::find(&self.input_fields, index, sub_index) else {
+            return false;
+        };
+        let len = data.len().min(field.length);
+        self.inputs[field.offset..field.offset + len].copy_from_slice(&data[..len]);
+        true
+    }
+
+    /// Copies an object from the output image into a TPDO payload slice.
+    /// Returns false if the object is not part of the image.
+    pub(crate) fn load_output(&self, index: u16, sub_index: u8, data: &mut [u8]) -> bool {
+        let Some(field) = Self::find(&self.output_fields, index, sub_index) else {
+            return false;
+        };
+        let len = data.len().min(field.length);
+        data[..len].copy_from_slice(&self.outputs[field.offset..field.offset + len]);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::od::{AccessType, ObjectEntry};
+
+    fn mapping(index: u16, sub_index: u8, offset_bits: u16, length_bits: u16) -> ObjectValue {
+        ObjectValue::Unsigned64(
+            PdoMappingEntry {
+                index,
+                sub_index,
+                offset_bits,
+                length_bits,
+            }
+            .to_u64(),
+        )
+    }
+
+    fn create_od() -> ObjectDictionary<'static> {
+        let mut od = ObjectDictionary::new(None);
+        od.insert(
+            0x6000,
+            ObjectEntry {
+                object: Object::Array(vec![
+                    ObjectValue::Unsigned8(0x11),
+                    ObjectValue::Unsigned32(0x2233_4455),
+                ]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x6200,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Integer16(-2)),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x1600,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6000, 2, 0, 32), mapping(0x1200, 0, 32, 64)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x1601,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6000, 1, 8, 8), mapping(0x6000, 2, 16, 32)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.insert(
+            0x1A00,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6200, 0, 0, 16)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        for (index, node_id) in [(0x1400, 0), (0x1401, 2), (0x1800, 0)] {
+            insert_comm_param(&mut od, index, node_id);
+        }
+        od
+    }
+
+    fn insert_comm_param(od: &mut ObjectDictionary, index: u16, node_id: u8) {
+        od.insert(
+            index,
+            ObjectEntry {
+                object: Object::Record(vec![
+                    ObjectValue::Unsigned8(node_id),
+                    ObjectValue::Unsigned8(0),
+                ]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_layout_from_mappings() {
+        let od = create_od();
+        let image = ProcessImage::build(&od);
+
+        // 0x6000/2 first (4 bytes), then 0x6000/1; the SDO container and the
+        // duplicate mapping of 0x6000/2 are skipped.
+        assert_eq!(image.inputs().len(), 5);
+        assert_eq!(image.input_field(0x6000, 2).unwrap().offset, 0);
+        assert_eq!(image.input_field(0x6000, 1).unwrap().offset, 4);
+        assert!(image.input_field(0x1200, 0).is_none());
+        assert_eq!(image.read_input::<u32>(0), Some(0x2233_4455));
+        assert_eq!(image.read_input::<u8>(4), Some(0x11));
+
+        assert_eq!(image.outputs().len(), 2);
+        assert_eq!(image.read_output::<i16>(0), Some(-2));
+        assert!(!image.is_stale(&od));
+    }
+
+    #[test]
+    fn test_typed_access_and_copy() {
+        let od = create_od();
+        let mut image = ProcessImage::build(&od);
+
+        image.write_output::<i16>(0, -300).unwrap();
+        assert!(image.write_output::<u32>(0, 1).is_err());
+        let mut payload = [0u8; 2];
+        assert!(image.load_output(0x6200, 0, &mut payload));
+        assert_eq!(payload, (-300i16).to_le_bytes());
+
+        assert!(image.store_input(0x6000, 1, &[0x99]));
+        assert!(!image.store_input(0x6200, 0, &[0x99, 0x00]));
+        assert_eq!(image.read_input::<u8>(4), Some(0x99));
+        assert_eq!(image.read_input::<u16>(4), None);
+    }
+
+    #[test]
+    fn test_layout_skips_unconfigured_channels() {
+        let mut od = create_od();
+        // A mapping without a communication parameter record is not in use.
+        od.insert(
+            0x1602,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6200, 0, 0, 16)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        // A second channel for NodeID 0 is shadowed by 0x1600.
+        od.insert(
+            0x1603,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6200, 0, 0, 16)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        insert_comm_param(&mut od, 0x1403, 0);
+        // A configured channel without mapped objects contributes nothing.
+        od.insert(
+            0x1A01,
+            ObjectEntry {
+                object: Object::Array(Vec::new()),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        insert_comm_param(&mut od, 0x1801, 3);
+
+        let image = ProcessImage::build(&od);
+        assert_eq!(image.inputs().len(), 5);
+        assert!(image.input_field(0x6200, 0).is_none());
+        assert_eq!(image.output_fields().len(), 1);
+    }
+
+    #[test]
+    fn test_rebuild_keeps_output_values() {
+        let mut od = create_od();
+        let mut image = ProcessImage::build(&od);
+        image.write_output::<i16>(0, 1234).unwrap();
+
+        // Map 0x6000/1 in front of 0x6200; the latter moves to offset 1.
+        od.write(0x1A00, 1, mapping(0x6000, 1, 0, 8)).unwrap();
+        od.insert(
+            0x1A01,
+            ObjectEntry {
+                object: Object::Array(vec![mapping(0x6200, 0, 0, 16)]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        insert_comm_param(&mut od, 0x1801, 3);
+        let image = image.rebuild(&od);
+
+        let field = image.output_field(0x6200, 0).unwrap();
+        assert_eq!(field.offset, 1);
+        assert_eq!(image.read_output::<i16>(field.offset), Some(1234));
+        // New objects start from their OD value.
+        assert_eq!(image.read_output::<u8>(0), Some(0x11));
+    }
+
+    #[test]
+    fn test_stale_after_mapping_change() {
+        let mut od = create_od();
+        let image = ProcessImage::build(&od);
+        od.write(0x1A00, 1, mapping(0x6000, 1, 0, 8)).unwrap();
+        assert!(image.is_stale(&od));
+        let image = ProcessImage::build(&od);
+        assert_eq!(image.output_field(0x6000, 1).unwrap().length, 1);
+    }
+
+    #[test]
+    fn test_stale_after_node_id_change() {
+        let mut od = create_od();
+        let image = ProcessImage::build(&od);
+        assert!(image.input_field(0x6000, 1).is_some());
+        // 0x1601 now shares NodeID 0 with 0x1600 and drops out of the image.
+        od.write(0x1401, 1, ObjectValue::Unsigned8(0)).unwrap();
+        assert!(image.is_stale(&od));
+        let image = ProcessImage::build(&od);
+        assert!(image.input_field(0x6000, 1).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 // crates/powerlink-rs/src/pdo/mod.rs
 
 pub mod error;
+pub mod image;
 pub mod mapping;
 
 pub use error::PdoError;
+pub use image::{ImageField, ImageValue, ProcessImage};
 pub use mapping::{PDOVersion, PayloadSize, PayloadSizeError, PdoMappingEntry};
@@ -2,8 +2,9 @@
 //! Handles serialization of SDO data for ASnd frames.
 
 use crate::PowerlinkError;
-use crate::sdo::command::SdoCommand;
+use crate::sdo::command::{SdoCommand, Segmentation};
 use crate::sdo::sequence::SequenceLayerHeader;
+use alloc::borrow::Cow;
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -14,7 +15,7 @@ pub fn serialize_sdo_asnd_payload(
 ) -> Result<Vec<u8>, PowerlinkError> {
     // Allocate buffer based on command payload size + headers
     let estimated_size = 4 // Sequence Header
-                       + 4 // Command Header Fixed Part
+                       + 8 // Command Header Fixed Part
                        + if cmd.data_size.is_some() { 4 } else { 0 } // Optional Data Size
                        + cmd.payload.len();
     // Use Vec directly instead of pre-allocating large buffer
@@ -33,3 +34,95 @@ pub fn serialize_sdo_asnd_payload(
 
     Ok(buffer)
 }
+
+/// Puts back the zero bytes an SDO payload lost to ASnd padding removal.
+///
+/// ASnd has no length field, so the frame decoder trims trailing zeros from
+/// minimum-size frames. The command layer states its own length, which tells
+/// how many of those zeros were data. Payloads that were not trimmed are
+/// returned unchanged.
+pub fn restore_trimmed_padding(sdo_payload: &[u8]) -> Cow<'_, [u8]> {
+    const SEQ_HEADER_SIZE: usize = 4;
+    const CMD_HEADER_SIZE: usize = 8;
+    // Minimum Ethernet payload (46) less the ASnd header (4).
+    const MIN_SDO_PAYLOAD: usize = 42;
+
+    if sdo_payload.len() <= SEQ_HEADER_SIZE || sdo_payload.len() >= MIN_SDO_PAYLOAD {
+        return Cow::Borrowed(sdo_payload);
+    }
+    let mut payload = sdo_payload.to_vec();
+    payload.resize(payload.len().max(SEQ_HEADER_SIZE + CMD_HEADER_SIZE), 0);
+    let command = &payload[SEQ_HEADER_SIZE..];
+    let header_size = if command[2] >> 6 == Segmentation::Initiate as u8 {
+        CMD_HEADER_SIZE + 4
+    } else {
+        CMD_HEADER_SIZE
+    };
+    let segment_size = u16::from_le_bytes([command[4], command[5]]) as usize;
+    let length = SEQ_HEADER_SIZE + header_size + segment_size;
+    if length <= MIN_SDO_PAYLOAD {
+        payload.resize(payload.len().max(length), 0);
+    }
+    Cow::Owned(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdo::command::{CommandId, CommandLayerHeader, Segmentation};
+    use crate::sdo::sequence::{ReceiveConnState, SendConnState};
+
+    #[test]
+    fn test_serialize_sdo_asnd_payload_full_segment() {
+        let seq = SequenceLayerHeader {
+            receive_sequence_number: 1,
+            receive_con: ReceiveConnState::ConnectionValid,
+            send_sequence_number: 2,
+            send_con: SendConnState::ConnectionValidAckRequest,
+        };
+        let cmd = SdoCommand {
+            header: CommandLayerHeader {
+                transaction_id: 3,
+                segmentation: Segmentation::Initiate,
+                command_id: CommandId::WriteByIndex,
+                segment_size: 1480,
+                ..Default::default()
+            },
+            data_size: Some(5000),
+            payload: vec![0x55; 1480],
+        };
+        let payload = serialize_sdo_asnd_payload(seq, cmd.clone()).unwrap();
+        // Sequence (4) + command header (8) + data size (4) + data
+        assert_eq!(payload.len(), 1496);
+        assert_eq!(SequenceLayerHeader::deserialize(&payload[..4]), Ok(seq));
+        assert_eq!(SdoCommand::deserialize(&payload[4..]), Ok(cmd));
+    }
+
+    #[test]
+    fn test_restore_trimmed_padding() {
+        let seq = SequenceLayerHeader {
+            send_con: SendConnState::ConnectionValidAckRequest,
+            ..Default::default()
+        };
+        let cmd = SdoCommand {
+            header: CommandLayerHeader {
+                command_id: CommandId::WriteByIndex,
+                segment_size: 8,
+                ..Default::default()
+            },
+            data_size: None,
+            // 0x1020/1 = 500
+            payload: vec![0x20, 0x10, 0x01, 0x00, 0xF4, 0x01, 0x00, 0x00],
+        };
+        let payload = serialize_sdo_asnd_payload(seq, cmd).unwrap();
+        let trimmed_len = payload.iter().rposition(|&b| b != 0).unwrap() + 1;
+        let trimmed = &payload[..trimmed_len];
+        assert!(trimmed.len() < payload.len());
+        assert_eq!(restore_trimmed_padding(trimmed), payload);
+
+        // Frames without a command layer and full frames are left alone.
+        assert_eq!(restore_trimmed_padding(&payload[..4]), &payload[..4]);
+        let long = vec![0x55; 64];
+        assert_eq!(restore_trimmed_padding(&long), long);
+    }
+}
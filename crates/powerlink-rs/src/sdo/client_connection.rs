@@ -7,17 +7,219 @@
 
 use crate::PowerlinkError;
 use crate::od::ObjectDictionary;
-use crate::sdo::command::{CommandId, CommandLayerHeader, SdoCommand, Segmentation};
-use crate::sdo::sequence::{ReceiveConnState, SendConnState, SequenceLayerHeader};
+use crate::sdo::command::{
+    CommandId, CommandLayerHeader, MaxSegmentSizePayload, MultipleParamEntry,
+    ReadAllByIndexRequest, ReadAllByIndexResponse, ReadByIndexRequest, ReadByNameRequest,
+    ReadMultipleParamRequest, ReadMultipleParamResponse, SdoCommand, Segmentation,
+    WriteByNameRequest, WriteMultipleParamEntry, WriteMultipleParamRequest,
+    WriteMultipleParamResponse,
+};
+use crate::sdo::segment::{self, SDO_UDP_MAX_SEGMENT_SIZE};
+use crate::sdo::sequence::{
+    MAX_SEQUENCE_WINDOW, ReceiveConnState, SendConnState, SequenceLayerHeader,
+};
 use crate::sdo::{OD_IDX_SDO_RETRIES, OD_IDX_SDO_TIMEOUT};
 use crate::types::NodeId;
+use alloc::collections::VecDeque;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::convert::TryInto;
 use log::{debug, error, info, warn};
 
-/// Maximum payload size for an SDO segment sent by the client.
-/// (1456 max SDO payload - 4 byte command header = 1452)
-const MAX_CLIENT_PAYLOAD: usize = 1452;
+/// Default number of download segments that may be sent before the server
+/// has acknowledged the first of them.
+pub const DEFAULT_SDO_WINDOW_SIZE: u8 = 4;
+
+/// The transport an SDO client connection runs over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SdoClientTransport {
+    /// SDO embedded in ASnd frames, sent in asynchronous slots.
+    #[default]
+    Asnd,
+    /// SDO over UDP/IP (port `C_SDO_EPL_PORT`), sent without waiting for a slot.
+    #[cfg(feature = "sdo-udp")]
+    Udp,
+}
+
+/// A request issued by an SDO client.
+/// (Reference: EPSG DS 301, Section 6.3.2.4.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SdoClientRequest {
+    ReadByIndex {
+        index: u16,
+        sub_index: u8,
+    },
+    WriteByIndex {
+        index: u16,
+        sub_index: u8,
+        data: Vec<u8>,
+    },
+    ReadByName {
+        name: String,
+    },
+    WriteByName {
+        name: String,
+        data: Vec<u8>,
+    },
+    ReadAllByIndex {
+        index: u16,
+    },
+    ReadMultipleParamByIndex {
+        entries: Vec<MultipleParamEntry>,
+    },
+    WriteMultipleParamByIndex {
+        entries: Vec<WriteMultipleParamEntry>,
+    },
+}
+
+impl SdoClientRequest {
+    /// Returns the SDO command used to carry this request.
+    pub fn command_id(&self) -> CommandId {
+        match self {
+            Self::ReadByIndex { .. } => CommandId::ReadByIndex,
+            Self::WriteByIndex { .. } => CommandId::WriteByIndex,
+            Self::ReadByName { .. } => CommandId::ReadByName,
+            Self::WriteByName { .. } => CommandId::WriteByName,
+            Self::ReadAllByIndex { .. } => CommandId::ReadAllByIndex,
+            Self::ReadMultipleParamByIndex { .. } => CommandId::ReadMultipleParamByIndex,
+            Self::WriteMultipleParamByIndex { .. } => CommandId::WriteMultipleParamByIndex,
+        }
+    }
+
+    /// Returns true if the request transfers data to the server (download).
+    pub fn is_download(&self) -> bool {
+        matches!(
+            self,
+            Self::WriteByIndex { .. }
+                | Self::WriteByName { .. }
+                | Self::WriteMultipleParamByIndex { .. }
+        )
+    }
+
+    /// Builds the command layer payload for this request.
+    pub fn to_payload(&self) -> Vec<u8> {
+        match self {
+            Self::ReadByIndex { index, sub_index } => ReadByIndexRequest {
+                index: *index,
+                sub_index: *sub_index,
+            }
+            .to_payload(),
+            Self::WriteByIndex {
+                index,
+                sub_index,
+                data,
+            } => {
+                // [index(2), sub(1), reserved(1), data...]
+                let mut payload = Vec::with_capacity(4 + data.len());
+                payload.extend_from_slice(&index.to_le_bytes());
+                payload.push(*sub_index);
+                payload.push(0);
+                payload.extend_from_slice(data);
+                payload
+            }
+            Self::ReadByName { name } => ReadByNameRequest { name: name.clone() }.to_payload(),
+            Self::WriteByName { name, data } => WriteByNameRequest {
+                name: name.clone(),
+                data,
+            }
+            .to_payload(),
+            Self::ReadAllByIndex { index } => ReadAllByIndexRequest { index: *index }.to_payload(),
+            Self::ReadMultipleParamByIndex { entries } => ReadMultipleParamRequest {
+                entries: entries.clone(),
+            }
+            .to_payload(),
+            Self::WriteMultipleParamByIndex { entries } => WriteMultipleParamRequest {
+                entries: entries.clone(),
+            }
+            .to_payload(),
+        }
+    }
+
+    /// Number of leading payload bytes that address the object rather than
+    /// carry data. These are excluded from the DataSize of a segmented download.
+    fn download_header_len(&self) -> usize {
+        match self {
+            Self::WriteByIndex { .. } => 4,
+            _ => 0,
+        }
+    }
+
+    /// Decodes the server's final response into a typed result.
+    fn decode_response(&self, data: Vec<u8>) -> Result<SdoClientResponse, PowerlinkError> {
+        match self {
+            Self::ReadByIndex { index, sub_index } => Ok(SdoClientResponse::ReadByIndex {
+                index: *index,
+                sub_index: *sub_index,
+                data,
+            }),
+            Self::WriteByIndex {
+                index, sub_index, ..
+            } => Ok(SdoClientResponse::WriteByIndex {
+                index: *index,
+                sub_index: *sub_index,
+            }),
+            Self::ReadByName { name } => Ok(SdoClientResponse::ReadByName {
+                name: name.clone(),
+                data,
+            }),
+            Self::WriteByName { name, .. } => {
+                Ok(SdoClientResponse::WriteByName { name: name.clone() })
+            }
+            Self::ReadAllByIndex { index } => {
+                Ok(SdoClientResponse::ReadAllByIndex(ReadAllByIndexResponse {
+                    index: *index,
+                    data,
+                }))
+            }
+            Self::ReadMultipleParamByIndex { entries } => {
+                ReadMultipleParamResponse::from_payload(&data, entries)
+                    .map(SdoClientResponse::ReadMultipleParamByIndex)
+            }
+            Self::WriteMultipleParamByIndex { .. } => {
+                WriteMultipleParamResponse::from_payload(&data)
+                    .map(SdoClientResponse::WriteMultipleParamByIndex)
+            }
+        }
+    }
+}
+
+/// The typed, decoded response to a completed SDO client request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SdoClientResponse {
+    ReadByIndex {
+        index: u16,
+        sub_index: u8,
+        data: Vec<u8>,
+    },
+    WriteByIndex {
+        index: u16,
+        sub_index: u8,
+    },
+    ReadByName {
+        name: String,
+        data: Vec<u8>,
+    },
+    WriteByName {
+        name: String,
+    },
+    ReadAllByIndex(ReadAllByIndexResponse),
+    ReadMultipleParamByIndex(ReadMultipleParamResponse),
+    WriteMultipleParamByIndex(WriteMultipleParamResponse),
+    /// All entries of a Concise DCF were written.
+    ConfigurationDownload,
+}
+
+/// The outcome of a finished SDO client transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdoTransferResult {
+    /// The node the transfer was addressed to.
+    pub target: NodeId,
+    /// The transaction ID used for the transfer.
+    pub transaction_id: u8,
+    /// The decoded response, or `PowerlinkError::SdoAborted` carrying the
+    /// abort code if the server or a local timeout aborted the transfer.
+    pub outcome: Result<SdoClientResponse, PowerlinkError>,
+}
 
 /// Internal state of a single SDO client-side transfer.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +228,8 @@ pub(crate) enum SdoClientConnectionState {
     Idle,
     /// Waiting for SDO Init response from server.
     Opening,
+    /// Agreeing on the maximum segment size with the server.
+    Negotiating,
     /// Connection established, ready to send main command.
     Established,
     /// Sent an upload (read) request, awaiting first segment/response.
@@ -57,6 +261,8 @@ pub(crate) enum SdoJob {
 pub(crate) struct SdoClientConnection {
     /// The Node ID of the server (CN) we are talking to.
     target_node_id: NodeId,
+    /// The transport used to reach the server.
+    transport: SdoClientTransport,
     /// The current state of this connection.
     state: SdoClientConnectionState,
     /// The current transaction ID for this transfer.
@@ -78,20 +284,41 @@ pub(crate) struct SdoClientConnection {
     /// Retries left for the current action.
     retries_left: u32,
 
-    /// The last command we sent, stored for retransmission.
-    last_sent_command: Option<(SequenceLayerHeader, SdoCommand)>,
+    /// Frames sent but not yet acknowledged by the server, oldest first.
+    /// Retransmissions restart from the front of this history.
+    history: VecDeque<(SequenceLayerHeader, SdoCommand)>,
+    /// Position in `history` of the next frame to retransmit, if a
+    /// retransmission is in progress.
+    resend_index: Option<usize>,
+    /// Maximum number of unacknowledged download segments.
+    window_size: u8,
+    /// Largest segment the local transport can carry.
+    local_segment_size: usize,
+    /// Segment size agreed with the server. `None` until negotiated.
+    segment_size: Option<usize>,
 
     /// The current job being processed.
     current_job: Option<SdoJob>,
 
     /// The pending command to send after connection establishment.
     pending_command: Option<SdoCommand>,
+
+    /// The application request being served, used to decode the response.
+    request: Option<SdoClientRequest>,
+    /// The command ID used for download segments.
+    download_command: CommandId,
+    /// Number of addressing bytes at the start of `data_buffer` (see
+    /// `SdoClientRequest::download_header_len`).
+    download_header_len: usize,
+    /// The outcome of the transfer, set once the connection closes.
+    result: Option<Result<SdoClientResponse, PowerlinkError>>,
 }
 
 impl SdoClientConnection {
     pub(crate) fn new(target_node_id: NodeId) -> Self {
         Self {
             target_node_id,
+            transport: SdoClientTransport::Asnd,
             state: SdoClientConnectionState::Idle,
             transaction_id: 0,
             send_sequence_number: 0,
@@ -101,12 +328,60 @@ impl SdoClientConnection {
             total_size: 0,
             deadline_us: None,
             retries_left: 0,
-            last_sent_command: None,
+            history: VecDeque::new(),
+            resend_index: None,
+            window_size: DEFAULT_SDO_WINDOW_SIZE,
+            local_segment_size: SDO_UDP_MAX_SEGMENT_SIZE,
+            segment_size: None,
             current_job: None,
             pending_command: None,
+            request: None,
+            download_command: CommandId::WriteByIndex,
+            download_header_len: 4,
+            result: None,
         }
     }
 
+    /// Sets the maximum number of unacknowledged download segments.
+    /// The value is clamped to `1..=MAX_SEQUENCE_WINDOW`; 1 is stop-and-wait.
+    pub(crate) fn set_window_size(&mut self, window_size: u8) {
+        self.window_size = window_size.clamp(1, MAX_SEQUENCE_WINDOW);
+    }
+
+    pub(crate) fn transport(&self) -> SdoClientTransport {
+        self.transport
+    }
+
+    /// Selects the transport used to reach the server.
+    pub(crate) fn set_transport(&mut self, transport: SdoClientTransport) {
+        self.transport = transport;
+    }
+
+    /// Sets the largest segment the transport to this server can carry.
+    pub(crate) fn set_local_segment_size(&mut self, segment_size: usize) {
+        self.local_segment_size = segment_size;
+    }
+
+    /// Uses a segment size agreed earlier, skipping the MaxSegmentSize exchange.
+    pub(crate) fn set_segment_size(&mut self, segment_size: usize) {
+        self.segment_size = Some(segment_size);
+    }
+
+    /// Returns the segment size agreed with the server, if negotiated.
+    pub(crate) fn negotiated_segment_size(&self) -> Option<usize> {
+        self.segment_size
+    }
+
+    /// Returns to the Opening state to start the connection over, keeping the
+    /// prepared request.
+    fn reopen(&mut self) {
+        self.state = SdoClientConnectionState::Opening;
+        self.send_sequence_number = 0;
+        self.last_received_sequence_number = 63;
+        self.history.clear();
+        self.resend_index = None;
+    }
+
     pub(crate) fn is_idle(&self) -> bool {
         matches!(
             self.state,
@@ -118,13 +393,56 @@ impl SdoClientConnection {
         matches!(self.state, SdoClientConnectionState::Closed)
     }
 
+    /// Returns true if `get_pending_request` would produce a frame now.
+    pub(crate) fn has_pending_request(&self) -> bool {
+        if self
+            .resend_index
+            .is_some_and(|index| index < self.history.len())
+        {
+            return true;
+        }
+        match self.state {
+            SdoClientConnectionState::Idle | SdoClientConnectionState::Closed => false,
+            SdoClientConnectionState::DownloadInProgress => {
+                self.history.len() < self.window_size as usize && self.offset < self.total_size
+            }
+            _ => self.history.is_empty(),
+        }
+    }
+
+    /// Takes the outcome of the finished transfer, if any.
+    pub(crate) fn take_result(&mut self) -> Option<SdoTransferResult> {
+        self.result.take().map(|outcome| SdoTransferResult {
+            target: self.target_node_id,
+            transaction_id: self.transaction_id,
+            outcome,
+        })
+    }
+
+    /// Closes the connection and records the outcome of the transfer.
+    fn finish(&mut self, outcome: Result<SdoClientResponse, PowerlinkError>) {
+        self.state = SdoClientConnectionState::Closed;
+        self.result = Some(outcome);
+    }
+
+    /// Closes the connection after a successful transfer, decoding the
+    /// final response data according to the request.
+    fn complete(&mut self, data: Vec<u8>) {
+        let outcome = match self.request.take() {
+            Some(request) => request.decode_response(data),
+            None => Ok(SdoClientResponse::ConfigurationDownload),
+        };
+        self.finish(outcome);
+    }
+
     fn abort(&mut self, abort_code: u32) -> (SequenceLayerHeader, SdoCommand) {
         error!(
             "Aborting SDO client connection to Node {}, code: {:#010X}",
             self.target_node_id.0, abort_code
         );
-        self.state = SdoClientConnectionState::Closed;
-        self.last_sent_command = None;
+        self.finish(Err(PowerlinkError::SdoAborted(abort_code)));
+        self.history.clear();
+        self.resend_index = None;
         self.deadline_us = None;
         self.current_job = None; // Abort the job
 
@@ -151,7 +469,7 @@ impl SdoClientConnection {
 
     pub(crate) fn handle_response(&mut self, seq_header: &SequenceLayerHeader, cmd: &SdoCommand) {
         // 1. Validate Sequence ACKs
-        if self.last_sent_command.is_none() {
+        if self.history.is_empty() {
             warn!(
                 "SDO Client: Unexpected response from Node {}. Ignoring.",
                 self.target_node_id.0
@@ -159,19 +477,45 @@ impl SdoClientConnection {
             return;
         }
 
-        let (last_seq, _last_cmd) = self.last_sent_command.as_ref().unwrap();
-        if seq_header.receive_sequence_number != last_seq.send_sequence_number {
+        // The server's rsnr acknowledges every frame up to and including it.
+        let acked = self
+            .history
+            .iter()
+            .position(|(seq, _)| seq.send_sequence_number == seq_header.receive_sequence_number);
+        if let Some(acked) = acked {
+            self.history.drain(..=acked);
+            self.resend_index = self.resend_index.map(|i| i.saturating_sub(acked + 1));
+        }
+
+        if seq_header.receive_con == ReceiveConnState::ErrorResponse {
+            // The server lost a frame; resend everything it has not acknowledged.
+            if !self.history.is_empty() {
+                warn!(
+                    "SDO Client: Node {} requested retransmission after seq {}.",
+                    self.target_node_id.0, seq_header.receive_sequence_number
+                );
+                self.resend_index = Some(0);
+            }
+            return;
+        }
+
+        if acked.is_none() {
             warn!(
-                "SDO Client: Server ACK mismatch. Expected {}, got {}. Ignoring.",
-                last_seq.send_sequence_number, seq_header.receive_sequence_number
+                "SDO Client: Server ACK mismatch. Expected one of {:?}, got {}. Ignoring.",
+                self.history
+                    .iter()
+                    .map(|(seq, _)| seq.send_sequence_number)
+                    .collect::<Vec<_>>(),
+                seq_header.receive_sequence_number
             );
             return;
         }
 
         // ACK valid
-        self.last_sent_command = None;
-        self.deadline_us = None;
-        self.retries_left = 0;
+        if self.history.is_empty() {
+            self.deadline_us = None;
+            self.retries_left = 0;
+        }
 
         // 2. Validate Server Sequence Number
         let expected_server_seq = self.last_received_sequence_number.wrapping_add(1) % 64;
@@ -180,7 +524,9 @@ impl SdoClientConnection {
             return;
         } else if seq_header.send_sequence_number != expected_server_seq {
             error!("SDO Client: Sequence mismatch. Aborting.");
-            self.state = SdoClientConnectionState::Closed;
+            self.finish(Err(PowerlinkError::SdoSequenceError(
+                "Server sequence number mismatch",
+            )));
             return;
         }
         self.last_received_sequence_number = seq_header.send_sequence_number;
@@ -188,11 +534,22 @@ impl SdoClientConnection {
         // 3. Handle Server Aborts
         if cmd.header.is_aborted {
             let abort_code = u32::from_le_bytes(cmd.payload[0..4].try_into().unwrap_or_default());
+            if self.state == SdoClientConnectionState::Negotiating {
+                // The server does not support MaxSegmentSize and has closed
+                // the connection. Start over using our own limit.
+                warn!(
+                    "SDO Client: Node {} rejected MaxSegmentSize ({:#010X}). Using {} bytes.",
+                    self.target_node_id.0, abort_code, self.local_segment_size
+                );
+                self.segment_size = Some(self.local_segment_size);
+                self.reopen();
+                return;
+            }
             error!(
                 "SDO Client: Server Node {} aborted (TID {}) with code {:#010X}",
                 self.target_node_id.0, cmd.header.transaction_id, abort_code
             );
-            self.state = SdoClientConnectionState::Closed;
+            self.finish(Err(PowerlinkError::SdoAborted(abort_code)));
             self.current_job = None;
             return;
         }
@@ -207,16 +564,37 @@ impl SdoClientConnection {
                         "SDO Client: Connection to Node {} established.",
                         self.target_node_id.0
                     );
-                    // If we have a pending command (Read), send it.
+                    // Agree on the segment size first, unless it is already known.
+                    // Then, if we have a pending command (Read), send it.
                     // If we have data in buffer (Write), prepare to send it.
-                    self.state = SdoClientConnectionState::Established;
+                    self.state = if self.segment_size.is_some() {
+                        SdoClientConnectionState::Established
+                    } else {
+                        SdoClientConnectionState::Negotiating
+                    };
                 } else {
                     warn!("SDO Client: Invalid response in Opening. Aborting.");
-                    self.state = SdoClientConnectionState::Closed;
+                    self.finish(Err(PowerlinkError::SdoSequenceError(
+                        "Invalid response to connection initialization",
+                    )));
                 }
             }
+            SdoClientConnectionState::Negotiating => {
+                let server = MaxSegmentSizePayload::from_payload(&cmd.payload)
+                    .map(|payload| payload.server)
+                    .unwrap_or(0);
+                let segment_size = segment::negotiate_segment_size(self.local_segment_size, server);
+                info!(
+                    "SDO Client: Segment size for Node {} is {} bytes (server {}).",
+                    self.target_node_id.0, segment_size, server
+                );
+                self.segment_size = Some(segment_size);
+                self.state = SdoClientConnectionState::Established;
+            }
             SdoClientConnectionState::DownloadInProgress => {
-                if self.offset >= self.total_size {
+                // The transfer is done once the last segment is sent and every
+                // segment in the window has been acknowledged.
+                if self.offset >= self.total_size && self.history.is_empty() {
                     info!(
                         "SDO Client: Write to Node {} complete.",
                         self.target_node_id.0
@@ -228,7 +606,7 @@ impl SdoClientConnection {
                         // Stay in Established/DownloadInProgress to send next init frame.
                         self.state = SdoClientConnectionState::Established;
                     } else {
-                        self.state = SdoClientConnectionState::Closed;
+                        self.complete(cmd.payload.clone());
                     }
                 }
             }
@@ -242,7 +620,7 @@ impl SdoClientConnection {
                             cmd.payload.len()
                         );
                         self.data_buffer = cmd.payload.clone();
-                        self.state = SdoClientConnectionState::Closed;
+                        self.complete(cmd.payload.clone());
                     }
                     Segmentation::Initiate => {
                         // Server wants to send segmented data.
@@ -252,13 +630,14 @@ impl SdoClientConnection {
                         } else {
                             info!("SDO Client: Starting segmented upload (unknown size).");
                         }
-                        self.data_buffer.clear();
-                        // Move to UploadInProgress to request the first segment
+                        // The Initiate frame already carries the first chunk of data.
+                        self.data_buffer = cmd.payload.clone();
+                        // Move to UploadInProgress to request the next segment
                         self.state = SdoClientConnectionState::UploadInProgress;
                     }
                     _ => {
                         warn!("SDO Client: Invalid segmentation in UploadInit.");
-                        self.state = SdoClientConnectionState::Closed;
+                        self.finish(Err(PowerlinkError::SdoInvalidCommandPayload));
                     }
                 }
             }
@@ -279,20 +658,23 @@ impl SdoClientConnection {
                                 "SDO Client: Segmented read complete ({} bytes).",
                                 self.data_buffer.len()
                             );
-                            self.state = SdoClientConnectionState::Closed;
+                            let data = core::mem::take(&mut self.data_buffer);
+                            self.complete(data);
                         } else {
                             // Stay in UploadInProgress to request the next segment
                         }
                     }
                     _ => {
                         warn!("SDO Client: Invalid segmentation in UploadInProgress.");
-                        self.state = SdoClientConnectionState::Closed;
+                        self.finish(Err(PowerlinkError::SdoInvalidCommandPayload));
                     }
                 }
             }
             _ => {
                 // For other states or unexpected frames
-                self.state = SdoClientConnectionState::Closed;
+                self.finish(Err(PowerlinkError::SdoSequenceError(
+                    "Unexpected response in current state",
+                )));
             }
         }
     }
@@ -407,7 +789,11 @@ impl SdoClientConnection {
             );
             let timeout_ms = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64;
             self.deadline_us = Some(current_time_us + timeout_ms * 1000);
-            self.last_sent_command.clone()
+            // Go back to the oldest unacknowledged frame; the rest of the
+            // window follows through get_pending_request.
+            let first = self.history.front().cloned();
+            self.resend_index = first.is_some().then_some(1);
+            first
         } else {
             error!(
                 "SDO Client: Timeout Node {}. Aborting.",
@@ -422,13 +808,31 @@ impl SdoClientConnection {
         current_time_us: u64,
         od: &ObjectDictionary,
     ) -> Option<(SequenceLayerHeader, SdoCommand)> {
-        if self.last_sent_command.is_some() {
-            return None;
-        }
-
         let timeout_ms = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64;
         let retries = od.read_u32(OD_IDX_SDO_RETRIES, 0).unwrap_or(2);
 
+        // Retransmissions take precedence over new frames.
+        if let Some(index) = self.resend_index {
+            if let Some(frame) = self.history.get(index).cloned() {
+                self.resend_index = Some(index + 1);
+                self.deadline_us = Some(current_time_us + timeout_ms * 1000);
+                return Some(frame);
+            }
+            self.resend_index = None;
+        }
+
+        // Only download segments are windowed; everything else waits for
+        // the previous frame to be acknowledged.
+        let can_send = match self.state {
+            SdoClientConnectionState::DownloadInProgress => {
+                self.history.len() < self.window_size as usize && self.offset < self.total_size
+            }
+            _ => self.history.is_empty(),
+        };
+        if !can_send {
+            return None;
+        }
+
         let (seq, cmd) = match self.state {
             SdoClientConnectionState::Opening => {
                 let seq = SequenceLayerHeader {
@@ -447,6 +851,31 @@ impl SdoClientConnection {
                 };
                 (seq, cmd)
             }
+            SdoClientConnectionState::Negotiating => {
+                let payload = MaxSegmentSizePayload {
+                    client: self.local_segment_size.min(u16::MAX as usize) as u16,
+                    server: 0,
+                }
+                .to_payload();
+                let cmd = SdoCommand {
+                    header: CommandLayerHeader {
+                        transaction_id: self.transaction_id,
+                        segmentation: Segmentation::Expedited,
+                        command_id: CommandId::MaxSegmentSize,
+                        segment_size: payload.len() as u16,
+                        ..Default::default()
+                    },
+                    data_size: None,
+                    payload,
+                };
+                let seq = SequenceLayerHeader {
+                    send_sequence_number: self.send_sequence_number,
+                    send_con: SendConnState::ConnectionValidAckRequest,
+                    receive_sequence_number: self.last_received_sequence_number,
+                    receive_con: ReceiveConnState::ConnectionValid,
+                };
+                (seq, cmd)
+            }
             SdoClientConnectionState::Established => {
                 // If we have a job command pending (data in buffer), start sending
                 if !self.data_buffer.is_empty() {
@@ -476,7 +905,7 @@ impl SdoClientConnection {
                         return self.get_pending_request(current_time_us, od);
                     }
                     // Job done
-                    self.state = SdoClientConnectionState::Closed;
+                    self.complete(Vec::new());
                     return None;
                 }
             }
@@ -491,18 +920,18 @@ impl SdoClientConnection {
                 (seq, cmd)
             }
             SdoClientConnectionState::UploadInProgress => {
-                // Request next segment
-                // Use ReadByIndex with Segmentation::Segment
+                // Acknowledge the received segment with a NIL command,
+                // which prompts the server to send the next one.
                 let cmd = SdoCommand {
                     header: CommandLayerHeader {
                         transaction_id: self.transaction_id,
                         segmentation: Segmentation::Segment,
-                        command_id: CommandId::ReadByIndex,
+                        command_id: CommandId::Nil,
                         segment_size: 0,
                         ..Default::default()
                     },
                     data_size: None,
-                    payload: Vec::new(), // Payload unused for segment request
+                    payload: Vec::new(),
                 };
                 let seq = SequenceLayerHeader {
                     send_sequence_number: self.send_sequence_number,
@@ -517,7 +946,7 @@ impl SdoClientConnection {
 
         self.deadline_us = Some(current_time_us + timeout_ms * 1000);
         self.retries_left = retries;
-        self.last_sent_command = Some((seq, cmd.clone()));
+        self.history.push_back((seq, cmd.clone()));
         self.send_sequence_number = self.send_sequence_number.wrapping_add(1) % 64;
 
         Some((seq, cmd))
@@ -527,22 +956,26 @@ impl SdoClientConnection {
         let is_initiate = self.offset == 0;
         let remaining = self.total_size.saturating_sub(self.offset);
         let (header_data_len, data_only_len) = if is_initiate {
-            (self.total_size, self.total_size - 4)
+            (
+                self.total_size,
+                self.total_size.saturating_sub(self.download_header_len),
+            )
         } else {
             (remaining, remaining)
         };
 
-        let chunk_size = MAX_CLIENT_PAYLOAD.min(header_data_len);
+        let max_payload = self.segment_size.unwrap_or(self.local_segment_size);
+        let chunk_size = max_payload.min(header_data_len);
         let data_end_offset = self.offset + chunk_size;
         let chunk = &self.data_buffer[self.offset..data_end_offset];
 
         let segmentation = if is_initiate {
-            if self.total_size <= (MAX_CLIENT_PAYLOAD + 4) {
+            if self.total_size <= max_payload {
                 Segmentation::Expedited
             } else {
                 Segmentation::Initiate
             }
-        } else if remaining <= MAX_CLIENT_PAYLOAD {
+        } else if remaining <= max_payload {
             Segmentation::Complete
         } else {
             Segmentation::Segment
@@ -555,7 +988,7 @@ impl SdoClientConnection {
             header: CommandLayerHeader {
                 transaction_id: self.transaction_id,
                 segmentation,
-                command_id: CommandId::WriteByIndex,
+                command_id: self.download_command,
                 segment_size: chunk.len() as u16,
                 ..Default::default()
             },
@@ -606,6 +1039,11 @@ impl SdoClientConnection {
             entries_remaining: entries,
         });
 
+        self.request = None;
+        self.result = None;
+        self.download_command = CommandId::WriteByIndex;
+        self.download_header_len = 4;
+
         // Trigger first command load
         self.data_buffer.clear(); // Clear previous
         self.prepare_next_job_command(); // Load first command into buffer
@@ -617,10 +1055,10 @@ impl SdoClientConnection {
         Ok(())
     }
 
-    pub(crate) fn start_read_job(
+    /// Starts a single request (read or write) on this connection.
+    pub(crate) fn start_request(
         &mut self,
-        index: u16,
-        sub_index: u8,
+        request: SdoClientRequest,
         tid: u8,
         current_time_us: u64,
         od: &ObjectDictionary,
@@ -632,60 +1070,397 @@ impl SdoClientConnection {
         self.transaction_id = tid;
         self.send_sequence_number = 0;
         self.last_received_sequence_number = 63;
-        self.data_buffer.clear(); // Ensure buffer is empty for new read
+        self.data_buffer.clear(); // Ensure buffer is empty for the new request
+        self.result = None;
 
-        // Prepare Read Command
-        let cmd = SdoCommand {
+        let payload = request.to_payload();
+        if request.is_download() {
+            // Downloads are sent (and segmented if needed) from data_buffer.
+            self.download_command = request.command_id();
+            self.download_header_len = request.download_header_len();
+            self.total_size = payload.len();
+            self.offset = 0;
+            self.data_buffer = payload;
+        } else {
+            // Uploads send a single expedited request and collect the response.
+            let cmd = SdoCommand {
+                header: CommandLayerHeader {
+                    transaction_id: tid,
+                    segmentation: Segmentation::Expedited,
+                    command_id: request.command_id(),
+                    segment_size: payload.len() as u16,
+                    ..Default::default()
+                },
+                data_size: None,
+                payload,
+            };
+            self.pending_command = Some(cmd);
+        }
+        self.request = Some(request);
+
+        let timeout_ms = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64;
+        self.deadline_us = Some(current_time_us + timeout_ms * 1000);
+        self.retries_left = od.read_u32(OD_IDX_SDO_RETRIES, 0).unwrap_or(2);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Builds a server response acknowledging the client's last frame.
+    fn server_response(
+        conn: &SdoClientConnection,
+        send_con: SendConnState,
+        server_seq: u8,
+        cmd: SdoCommand,
+    ) -> (SequenceLayerHeader, SdoCommand) {
+        let (last_seq, _) = conn.history.back().cloned().unwrap();
+        let seq = SequenceLayerHeader {
+            send_sequence_number: server_seq,
+            send_con,
+            receive_sequence_number: last_seq.send_sequence_number,
+            receive_con: ReceiveConnState::Initialization,
+        };
+        (seq, cmd)
+    }
+
+    fn response_cmd(tid: u8, command_id: CommandId, payload: Vec<u8>) -> SdoCommand {
+        SdoCommand {
             header: CommandLayerHeader {
                 transaction_id: tid,
+                is_response: true,
                 segmentation: Segmentation::Expedited,
-                command_id: CommandId::ReadByIndex,
-                segment_size: 4,
+                command_id,
+                segment_size: payload.len() as u16,
                 ..Default::default()
             },
             data_size: None,
-            payload: [index.to_le_bytes().as_slice(), &[sub_index, 0u8]].concat(),
+            payload,
+        }
+    }
+
+    /// Drives a connection through initialization and one expedited exchange.
+    fn run_expedited(
+        request: SdoClientRequest,
+        response_payload: Vec<u8>,
+    ) -> (SdoCommand, SdoTransferResult) {
+        let od = ObjectDictionary::new(None);
+        let mut conn = SdoClientConnection::new(NodeId(1));
+        // Segment size known from an earlier connection; no MaxSegmentSize exchange.
+        conn.set_segment_size(1452);
+        conn.start_request(request.clone(), 5, 0, &od).unwrap();
+
+        // Connection initialization
+        conn.get_pending_request(0, &od).unwrap();
+        let (seq, cmd) = server_response(
+            &conn,
+            SendConnState::Initialization,
+            0,
+            response_cmd(5, CommandId::Nil, Vec::new()),
+        );
+        conn.handle_response(&seq, &cmd);
+
+        // The request itself
+        let (_, sent) = conn.get_pending_request(0, &od).unwrap();
+        let (seq, cmd) = server_response(
+            &conn,
+            SendConnState::ConnectionValid,
+            1,
+            response_cmd(5, request.command_id(), response_payload),
+        );
+        conn.handle_response(&seq, &cmd);
+        assert!(conn.is_closed());
+        (sent, conn.take_result().unwrap())
+    }
+
+    #[test]
+    fn test_read_by_name_request_and_response() {
+        let request = SdoClientRequest::ReadByName {
+            name: "NMT_CycleLen_U32".into(),
         };
-        self.pending_command = Some(cmd);
+        let (sent, result) = run_expedited(request, vec![0x10, 0x27, 0x00, 0x00]);
+        assert_eq!(sent.header.command_id, CommandId::ReadByName);
+        assert_eq!(sent.payload, b"NMT_CycleLen_U32\0");
+        assert_eq!(result.target, NodeId(1));
+        assert_eq!(result.transaction_id, 5);
+        assert_eq!(
+            result.outcome,
+            Ok(SdoClientResponse::ReadByName {
+                name: "NMT_CycleLen_U32".into(),
+                data: vec![0x10, 0x27, 0x00, 0x00],
+            })
+        );
+    }
 
-        let timeout_ms = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64;
-        self.deadline_us = Some(current_time_us + timeout_ms * 1000);
-        self.retries_left = od.read_u32(OD_IDX_SDO_RETRIES, 0).unwrap_or(2);
-        Ok(())
+    #[test]
+    fn test_write_multiple_params_reports_sub_aborts() {
+        let entries = vec![WriteMultipleParamEntry {
+            index: 0x1006,
+            sub_index: 0,
+            data: vec![0x10, 0x27, 0x00, 0x00],
+        }];
+        let aborts = WriteMultipleParamResponse {
+            sub_aborts: vec![(
+                MultipleParamEntry {
+                    index: 0x1006,
+                    sub_index: 0,
+                },
+                0x0601_0002,
+            )],
+        };
+        let (sent, result) = run_expedited(
+            SdoClientRequest::WriteMultipleParamByIndex {
+                entries: entries.clone(),
+            },
+            aborts.to_payload(),
+        );
+        assert_eq!(sent.header.command_id, CommandId::WriteMultipleParamByIndex);
+        assert_eq!(
+            sent.payload,
+            WriteMultipleParamRequest { entries }.to_payload()
+        );
+        // The whole payload is data; no index/sub-index prefix is subtracted.
+        assert_eq!(sent.data_size, Some(sent.payload.len() as u32));
+        assert_eq!(
+            result.outcome,
+            Ok(SdoClientResponse::WriteMultipleParamByIndex(aborts))
+        );
     }
 
-    pub(crate) fn start_write_job(
-        &mut self,
-        index: u16,
-        sub_index: u8,
-        data: Vec<u8>,
-        tid: u8,
-        current_time_us: u64,
-        od: &ObjectDictionary,
-    ) -> Result<(), PowerlinkError> {
-        if !self.is_idle() {
-            return Err(PowerlinkError::SdoSequenceError("Client is busy"));
+    #[test]
+    fn test_server_abort_is_reported() {
+        let od = ObjectDictionary::new(None);
+        let mut conn = SdoClientConnection::new(NodeId(2));
+        conn.start_request(
+            SdoClientRequest::ReadAllByIndex { index: 0x1F81 },
+            1,
+            0,
+            &od,
+        )
+        .unwrap();
+        conn.get_pending_request(0, &od).unwrap();
+        let mut abort = response_cmd(1, CommandId::Nil, 0x0602_0000u32.to_le_bytes().to_vec());
+        abort.header.is_aborted = true;
+        let (seq, cmd) = server_response(&conn, SendConnState::Initialization, 0, abort);
+        conn.handle_response(&seq, &cmd);
+        assert!(conn.is_closed());
+        assert_eq!(
+            conn.take_result().unwrap().outcome,
+            Err(PowerlinkError::SdoAborted(0x0602_0000))
+        );
+    }
+
+    /// Runs a download through a real server sequence handler, optionally
+    /// dropping the first transmission of one client frame. Returns the
+    /// largest number of frames sent in one burst and the transfer outcome.
+    fn run_windowed_download(
+        window_size: u8,
+        data: &[u8],
+        drop_seq: Option<u8>,
+    ) -> (usize, SdoTransferResult, ObjectDictionary<'static>) {
+        let (max_burst, result, od, _) = run_download(window_size, data, drop_seq, None);
+        (max_burst, result, od)
+    }
+
+    /// Like `run_windowed_download`, optionally limiting the server's
+    /// AsyncMTU. Also returns the largest segment the client sent.
+    fn run_download(
+        window_size: u8,
+        data: &[u8],
+        drop_seq: Option<u8>,
+        server_async_mtu: Option<u16>,
+    ) -> (usize, SdoTransferResult, ObjectDictionary<'static>, usize) {
+        use crate::frame::basic::MacAddress;
+        use crate::od::{Object, ObjectEntry, ObjectValue};
+        use crate::sdo::command::DefaultSdoHandler;
+        use crate::sdo::sequence_handler::SdoSequenceHandler;
+        use crate::sdo::server::SdoClientInfo;
+
+        let client_od = ObjectDictionary::new(None);
+        let mut server_od = ObjectDictionary::new(None);
+        server_od.insert(
+            0x2000,
+            ObjectEntry {
+                object: Object::Variable(ObjectValue::Domain(Vec::new())),
+                ..Default::default()
+            },
+        );
+        if let Some(mtu) = server_async_mtu {
+            let mut timing = vec![ObjectValue::Unsigned16(0); 9];
+            timing[crate::od::constants::SUBIDX_NMT_CYCLE_TIMING_ASYNC_MTU_U16 as usize - 1] =
+                ObjectValue::Unsigned16(mtu);
+            server_od.insert(
+                crate::od::constants::IDX_NMT_CYCLE_TIMING_REC,
+                ObjectEntry {
+                    object: Object::Record(timing),
+                    ..Default::default()
+                },
+            );
         }
-        self.state = SdoClientConnectionState::Opening;
-        self.transaction_id = tid;
-        self.send_sequence_number = 0;
-        self.last_received_sequence_number = 63;
-        self.data_buffer.clear(); // Ensure buffer is empty
+        let mut server = SdoSequenceHandler::new(SdoClientInfo::Asnd {
+            source_node_id: NodeId(240),
+            source_mac: MacAddress::default(),
+        });
+        let mut cmd_handler = DefaultSdoHandler;
 
-        // Create payload for WriteByIndex [index(2), sub(1), reserved(1), data...]
-        let mut full_payload = Vec::with_capacity(4 + data.len());
-        full_payload.extend_from_slice(&index.to_le_bytes());
-        full_payload.push(sub_index);
-        full_payload.push(0);
-        full_payload.extend_from_slice(&data);
+        let mut conn = SdoClientConnection::new(NodeId(1));
+        conn.set_local_segment_size(segment::asnd_max_segment_size(&client_od));
+        conn.set_window_size(window_size);
+        let request = SdoClientRequest::WriteByIndex {
+            index: 0x2000,
+            sub_index: 0,
+            data: data.to_vec(),
+        };
+        conn.start_request(request, 1, 0, &client_od).unwrap();
 
-        self.data_buffer = full_payload;
-        self.total_size = self.data_buffer.len();
-        self.offset = 0;
+        let mut drop_seq = drop_seq;
+        let mut max_burst = 0;
+        let mut max_segment = 0;
+        for _ in 0..100 {
+            let mut burst = Vec::new();
+            while let Some(frame) = conn.get_pending_request(0, &client_od) {
+                burst.push(frame);
+            }
+            max_burst = max_burst.max(burst.len());
+            for (seq, cmd) in burst {
+                max_segment = max_segment.max(cmd.payload.len());
+                if drop_seq == Some(seq.send_sequence_number) {
+                    drop_seq = None;
+                    continue;
+                }
+                let mut frame = vec![0u8; 1500];
+                let len = seq.serialize(&mut frame).unwrap();
+                let len = len + cmd.serialize(&mut frame[len..]).unwrap();
+                let response = server
+                    .handle_request(&frame[..len], &mut server_od, 0, &mut cmd_handler)
+                    .unwrap();
+                conn.handle_response(&response.seq_header, &response.command);
+            }
+            if conn.is_closed() {
+                return (
+                    max_burst,
+                    conn.take_result().unwrap(),
+                    server_od,
+                    max_segment,
+                );
+            }
+        }
+        panic!("Download did not finish");
+    }
 
-        let timeout_ms = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64;
-        self.deadline_us = Some(current_time_us + timeout_ms * 1000);
-        self.retries_left = od.read_u32(OD_IDX_SDO_RETRIES, 0).unwrap_or(2);
-        Ok(())
+    #[test]
+    fn test_windowed_download_keeps_segments_in_flight() {
+        let data: Vec<u8> = (0..6000u32).map(|i| i as u8).collect();
+        let (max_burst, result, od) = run_windowed_download(3, &data, None);
+        assert_eq!(max_burst, 3);
+        assert_eq!(
+            result.outcome,
+            Ok(SdoClientResponse::WriteByIndex {
+                index: 0x2000,
+                sub_index: 0,
+            })
+        );
+        assert_eq!(
+            od.read(0x2000, 0).unwrap().into_owned(),
+            crate::od::ObjectValue::Domain(data)
+        );
+    }
+
+    #[test]
+    fn test_windowed_download_resends_after_lost_segment() {
+        let data: Vec<u8> = (0..6000u32).map(|i| (i * 7) as u8).collect();
+        // Seq 0 is the Init frame and seq 1 the Initiate; lose the first data segment.
+        let (_, result, od) = run_windowed_download(4, &data, Some(2));
+        assert!(result.outcome.is_ok());
+        assert_eq!(
+            od.read(0x2000, 0).unwrap().into_owned(),
+            crate::od::ObjectValue::Domain(data)
+        );
+    }
+
+    #[test]
+    fn test_segment_size_negotiated_with_server() {
+        let data: Vec<u8> = (0..3000u32).map(|i| i as u8).collect();
+        // Without limits both sides use the full ASnd MTU.
+        let (_, result, _, max_segment) = run_download(4, &data, None, None);
+        assert!(result.outcome.is_ok());
+        assert_eq!(max_segment, 1480);
+
+        // A server with the minimum AsyncMTU forces small segments.
+        let (_, result, od, max_segment) = run_download(4, &data, None, Some(300));
+        assert!(result.outcome.is_ok());
+        assert_eq!(max_segment, segment::SDO_MIN_SEGMENT_SIZE);
+        assert_eq!(
+            od.read(0x2000, 0).unwrap().into_owned(),
+            crate::od::ObjectValue::Domain(data)
+        );
+    }
+
+    #[test]
+    fn test_rejected_negotiation_reopens_with_local_size() {
+        let od = ObjectDictionary::new(None);
+        let mut conn = SdoClientConnection::new(NodeId(1));
+        conn.start_request(
+            SdoClientRequest::ReadByIndex {
+                index: 0x1000,
+                sub_index: 0,
+            },
+            1,
+            0,
+            &od,
+        )
+        .unwrap();
+        conn.get_pending_request(0, &od).unwrap();
+        let (seq, cmd) = server_response(
+            &conn,
+            SendConnState::Initialization,
+            0,
+            response_cmd(1, CommandId::Nil, Vec::new()),
+        );
+        conn.handle_response(&seq, &cmd);
+
+        let (_, sent) = conn.get_pending_request(0, &od).unwrap();
+        assert_eq!(sent.header.command_id, CommandId::MaxSegmentSize);
+        let mut abort = response_cmd(1, CommandId::Nil, 0x0504_0001u32.to_le_bytes().to_vec());
+        abort.header.is_aborted = true;
+        let (seq, cmd) = server_response(&conn, SendConnState::ConnectionValid, 1, abort);
+        conn.handle_response(&seq, &cmd);
+
+        // The connection starts over instead of failing the request.
+        assert!(!conn.is_closed());
+        assert_eq!(
+            conn.negotiated_segment_size(),
+            Some(SDO_UDP_MAX_SEGMENT_SIZE)
+        );
+        let (seq, _) = conn.get_pending_request(0, &od).unwrap();
+        assert_eq!(seq.send_con, SendConnState::Initialization);
+        assert_eq!(seq.send_sequence_number, 0);
+    }
+
+    #[test]
+    fn test_timeout_retransmits_from_oldest_unacked_frame() {
+        let od = ObjectDictionary::new(None);
+        let mut conn = SdoClientConnection::new(NodeId(1));
+        conn.start_request(
+            SdoClientRequest::ReadByIndex {
+                index: 0x1000,
+                sub_index: 0,
+            },
+            1,
+            0,
+            &od,
+        )
+        .unwrap();
+        let (init_seq, _) = conn.get_pending_request(0, &od).unwrap();
+        // Nothing new may be sent while the Init frame is unacknowledged.
+        assert!(conn.get_pending_request(0, &od).is_none());
+
+        let deadline = conn.deadline_us.unwrap();
+        let (seq, _) = conn.tick(deadline, &od).unwrap();
+        assert_eq!(seq, init_seq);
+        assert!(conn.get_pending_request(deadline, &od).is_none());
     }
 }
@@ -1,23 +1,42 @@
 // crates/powerlink-rs/src/sdo/client_manager.rs
 //! Manages multiple, concurrent, stateful SDO client connections.
 //!
-//! This is primarily used by the Managing Node (MN) to perform complex
-//! SDO transfers (like segmented downloads for CFM/PDL) to multiple CNs
-//! simultaneously.
+//! The Managing Node (MN) uses it to perform complex SDO transfers (like
+//! segmented downloads for CFM/PDL) to multiple CNs simultaneously. A
+//! Controlled Node (CN) uses it to access the OD of the MN or of peer CNs,
+//! sending its frames only when invited by the MN.
 
 use crate::PowerlinkError;
 use crate::od::ObjectDictionary;
-use crate::sdo::client_connection::SdoClientConnection;
-use crate::sdo::command::SdoCommand;
+use crate::sdo::client_connection::{
+    SdoClientConnection, SdoClientRequest, SdoClientTransport, SdoTransferResult,
+};
+use crate::sdo::command::{MultipleParamEntry, SdoCommand, WriteMultipleParamEntry};
+use crate::sdo::segment;
 use crate::sdo::sequence::SequenceLayerHeader;
 use crate::types::NodeId;
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::String;
 use alloc::vec::Vec;
 
 #[derive(Debug, Default)]
 pub struct SdoClientManager {
     connections: BTreeMap<NodeId, SdoClientConnection>,
     next_transaction_id: u8,
+    /// Outcomes of finished transfers, waiting to be collected by the application.
+    completed: VecDeque<SdoTransferResult>,
+    /// Transfers finished since the node last collected them, for its event queue.
+    finished: Vec<(NodeId, u8, Result<(), PowerlinkError>)>,
+    /// Per-target sequence layer window sizes, overriding the default.
+    window_sizes: BTreeMap<NodeId, u8>,
+    /// Segment sizes agreed with each target, so MaxSegmentSize is only
+    /// exchanged on the first connection.
+    segment_sizes: BTreeMap<NodeId, usize>,
+    /// Per-target transports, overriding the default (ASnd).
+    transports: BTreeMap<NodeId, SdoClientTransport>,
+    /// Retransmission and abort frames produced by `poll_timeouts`, waiting
+    /// for the next `get_pending_request`.
+    deferred: VecDeque<(NodeId, SequenceLayerHeader, SdoCommand)>,
 }
 
 impl SdoClientManager {
@@ -33,6 +52,75 @@ impl SdoClientManager {
         self.next_transaction_id
     }
 
+    /// Returns the connection to `target`, creating it if needed.
+    fn connection(&mut self, target: NodeId, od: &ObjectDictionary) -> &mut SdoClientConnection {
+        let window_size = self.window_sizes.get(&target).copied();
+        let segment_size = self.segment_sizes.get(&target).copied();
+        let transport = self.transport(target);
+        self.connections.entry(target).or_insert_with(|| {
+            let mut conn = SdoClientConnection::new(target);
+            conn.set_transport(transport);
+            conn.set_local_segment_size(match transport {
+                SdoClientTransport::Asnd => segment::asnd_max_segment_size(od),
+                #[cfg(feature = "sdo-udp")]
+                SdoClientTransport::Udp => segment::SDO_UDP_MAX_SEGMENT_SIZE,
+            });
+            if let Some(window_size) = window_size {
+                conn.set_window_size(window_size);
+            }
+            if let Some(segment_size) = segment_size {
+                conn.set_segment_size(segment_size);
+            }
+            conn
+        })
+    }
+
+    /// Removes a finished connection, keeping its negotiated segment size
+    /// and queueing its result for the application.
+    fn retire(&mut self, target: NodeId) {
+        if let Some(mut conn) = self.connections.remove(&target) {
+            if let Some(segment_size) = conn.negotiated_segment_size() {
+                self.segment_sizes.insert(target, segment_size);
+            }
+            if let Some(result) = conn.take_result() {
+                self.finished.push((
+                    result.target,
+                    result.transaction_id,
+                    result.outcome.as_ref().map(|_| ()).map_err(|e| *e),
+                ));
+                self.completed.push_back(result);
+            }
+        }
+    }
+
+    /// Forgets the segment size agreed with `target`, e.g. after it was reset,
+    /// so the next connection negotiates it again.
+    pub fn forget_segment_size(&mut self, target: NodeId) {
+        self.segment_sizes.remove(&target);
+    }
+
+    /// Sets how many download segments may be in flight unacknowledged on
+    /// the connection to `target` (1 = stop-and-wait). Applies to transfers
+    /// started after this call.
+    pub fn set_window_size(&mut self, target: NodeId, window_size: u8) {
+        self.window_sizes.insert(target, window_size);
+    }
+
+    /// Selects the transport used for connections to `target`. Applies to
+    /// transfers started after this call; a change of transport discards the
+    /// segment size agreed over the previous one.
+    pub fn set_transport(&mut self, target: NodeId, transport: SdoClientTransport) {
+        if self.transport(target) != transport {
+            self.segment_sizes.remove(&target);
+        }
+        self.transports.insert(target, transport);
+    }
+
+    /// Returns the transport used for connections to `target`.
+    pub fn transport(&self, target: NodeId) -> SdoClientTransport {
+        self.transports.get(&target).copied().unwrap_or_default()
+    }
+
     pub fn next_action_time(&self, _od: &ObjectDictionary) -> Option<u64> {
         self.connections
             .values()
@@ -49,13 +137,25 @@ impl SdoClientManager {
         od: &ObjectDictionary,
     ) -> Result<(), PowerlinkError> {
         let tid = self.get_next_tid();
-        let conn = self
-            .connections
-            .entry(target)
-            .or_insert_with(|| SdoClientConnection::new(target));
+        let conn = self.connection(target, od);
         conn.start_concise_dcf_job(dcf_data, tid, current_time_us, od)
     }
 
+    /// Starts a single SDO request to the target node.
+    ///
+    /// The outcome is reported through `pop_completed` once the transfer finishes.
+    pub fn start_request(
+        &mut self,
+        target: NodeId,
+        request: SdoClientRequest,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        let tid = self.get_next_tid();
+        let conn = self.connection(target, od);
+        conn.start_request(request, tid, time, od)
+    }
+
     pub fn read_object_by_index(
         &mut self,
         target: NodeId,
@@ -64,12 +164,12 @@ impl SdoClientManager {
         time: u64,
         od: &ObjectDictionary,
     ) -> Result<(), PowerlinkError> {
-        let tid = self.get_next_tid();
-        let conn = self
-            .connections
-            .entry(target)
-            .or_insert_with(|| SdoClientConnection::new(target));
-        conn.start_read_job(index, sub_index, tid, time, od)
+        self.start_request(
+            target,
+            SdoClientRequest::ReadByIndex { index, sub_index },
+            time,
+            od,
+        )
     }
 
     pub fn write_object_by_index(
@@ -81,19 +181,100 @@ impl SdoClientManager {
         time: u64,
         od: &ObjectDictionary,
     ) -> Result<(), PowerlinkError> {
-        let tid = self.get_next_tid();
-        let conn = self
-            .connections
-            .entry(target)
-            .or_insert_with(|| SdoClientConnection::new(target));
-        conn.start_write_job(index, sub_index, data, tid, time, od)
+        self.start_request(
+            target,
+            SdoClientRequest::WriteByIndex {
+                index,
+                sub_index,
+                data,
+            },
+            time,
+            od,
+        )
+    }
+
+    pub fn read_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: String,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        self.start_request(target, SdoClientRequest::ReadByName { name }, time, od)
+    }
+
+    pub fn write_object_by_name(
+        &mut self,
+        target: NodeId,
+        name: String,
+        data: Vec<u8>,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        self.start_request(
+            target,
+            SdoClientRequest::WriteByName { name, data },
+            time,
+            od,
+        )
+    }
+
+    pub fn read_all_by_index(
+        &mut self,
+        target: NodeId,
+        index: u16,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        self.start_request(target, SdoClientRequest::ReadAllByIndex { index }, time, od)
+    }
+
+    pub fn read_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<MultipleParamEntry>,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        self.start_request(
+            target,
+            SdoClientRequest::ReadMultipleParamByIndex { entries },
+            time,
+            od,
+        )
+    }
+
+    pub fn write_multiple_params(
+        &mut self,
+        target: NodeId,
+        entries: Vec<WriteMultipleParamEntry>,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Result<(), PowerlinkError> {
+        self.start_request(
+            target,
+            SdoClientRequest::WriteMultipleParamByIndex { entries },
+            time,
+            od,
+        )
+    }
+
+    /// Returns the outcome of the oldest finished transfer, if any.
+    pub fn pop_completed(&mut self) -> Option<SdoTransferResult> {
+        self.completed.pop_front()
+    }
+
+    /// Returns the transfers finished since the last call, as
+    /// (target, transaction ID, outcome).
+    pub(crate) fn take_finished(&mut self) -> Vec<(NodeId, u8, Result<(), PowerlinkError>)> {
+        core::mem::take(&mut self.finished)
     }
 
     pub fn handle_response(&mut self, source: NodeId, seq: SequenceLayerHeader, cmd: SdoCommand) {
         if let Some(conn) = self.connections.get_mut(&source) {
             conn.handle_response(&seq, &cmd);
             if conn.is_closed() {
-                self.connections.remove(&source);
+                self.retire(source);
             }
         }
     }
@@ -116,20 +297,74 @@ impl SdoClientManager {
             }
         }
         for id in prune {
-            self.connections.remove(&id);
+            self.retire(id);
         }
         res
     }
 
+    /// Runs the connection timers like `tick`, but keeps the resulting frames
+    /// until the next `get_pending_request` instead of returning them.
+    ///
+    /// Used by nodes that may only transmit when invited (CNs).
+    pub fn poll_timeouts(&mut self, time: u64, od: &ObjectDictionary) {
+        while let Some(frame) = self.tick(time, od) {
+            self.deferred.push_back(frame);
+        }
+    }
+
+    /// Returns the number of frames that are ready to be sent, used to
+    /// request an asynchronous slot through the RS flag.
+    pub fn pending_request_count(&self) -> usize {
+        self.deferred.len()
+            + self
+                .connections
+                .values()
+                .filter(|conn| conn.has_pending_request())
+                .count()
+    }
+
+    /// Returns the next SDO/ASnd frame to send, to be called when the node
+    /// has been granted an asynchronous slot.
     pub fn get_pending_request(
         &mut self,
         time: u64,
         od: &ObjectDictionary,
+    ) -> Option<(NodeId, SequenceLayerHeader, SdoCommand)> {
+        if let Some(frame) = self.deferred.pop_front() {
+            return Some(frame);
+        }
+        self.next_request(SdoClientTransport::Asnd, time, od)
+    }
+
+    /// Returns the next SDO/UDP frame to send. UDP transfers do not wait for
+    /// asynchronous slots, so this can be called at any time.
+    #[cfg(feature = "sdo-udp")]
+    pub fn get_pending_udp_request(
+        &mut self,
+        time: u64,
+        od: &ObjectDictionary,
+    ) -> Option<(NodeId, SequenceLayerHeader, SdoCommand)> {
+        self.next_request(SdoClientTransport::Udp, time, od)
+    }
+
+    /// Returns true if a connection over UDP has a frame ready to send.
+    #[cfg(feature = "sdo-udp")]
+    pub fn has_pending_udp_request(&self) -> bool {
+        self.connections
+            .values()
+            .any(|conn| conn.transport() == SdoClientTransport::Udp && conn.has_pending_request())
+    }
+
+    fn next_request(
+        &mut self,
+        transport: SdoClientTransport,
+        time: u64,
+        od: &ObjectDictionary,
     ) -> Option<(NodeId, SequenceLayerHeader, SdoCommand)> {
         let mut res = None;
         let mut prune = Vec::new();
         for (id, conn) in self.connections.iter_mut() {
-            if res.is_none() {
+            if res.is_none() && conn.transport() == transport {
                 if let Some(out) = conn.get_pending_request(time, od) {
                     res = Some((*id, out.0, out.1));
                 }
@@ -139,7 +374,7 @@ impl SdoClientManager {
             }
         }
         for id in prune {
-            self.connections.remove(&id);
+            self.retire(id);
         }
         res
     }
@@ -1,5 +1,6 @@
 // crates/powerlink-rs/src/sdo/command/base.rs
 use crate::PowerlinkError;
+use crate::od::ObjectValue;
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::convert::TryFrom;
@@ -236,6 +237,15 @@ impl ReadByIndexRequest {
             // Ignore payload[3] (reserved)
         })
     }
+
+    /// Builds the 4-byte request payload (Index, Sub-Index, reserved).
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(4);
+        payload.extend_from_slice(&self.index.to_le_bytes());
+        payload.push(self.sub_index);
+        payload.push(0); // Reserved
+        payload
+    }
 }
 
 /// Payload for a ReadByName command.
@@ -255,6 +265,41 @@ impl ReadByNameRequest {
             .map_err(|_| PowerlinkError::SdoInvalidCommandPayload)?;
         Ok(Self { name })
     }
+
+    /// Builds the request payload (zero-terminated name).
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(self.name.len() + 1);
+        payload.extend_from_slice(self.name.as_bytes());
+        payload.push(0);
+        payload
+    }
+}
+
+/// Payload for a ReadAllByIndex command.
+/// (Reference: EPSG DS 301, Section 6.3.2.4.2.1.4, Table 66)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadAllByIndexRequest {
+    pub index: u16,
+}
+
+impl ReadAllByIndexRequest {
+    pub fn from_payload(payload: &[u8]) -> Result<Self, PowerlinkError> {
+        // Index (2 bytes), reserved (2 bytes)
+        if payload.len() < 2 {
+            return Err(PowerlinkError::SdoInvalidCommandPayload);
+        }
+        Ok(Self {
+            index: u16::from_le_bytes(payload[0..2].try_into()?),
+        })
+    }
+
+    /// Builds the 4-byte request payload (Index, reserved).
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(4);
+        payload.extend_from_slice(&self.index.to_le_bytes());
+        payload.extend_from_slice(&[0, 0]); // Reserved
+        payload
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -327,6 +372,16 @@ impl<'a> WriteByNameRequest<'a> {
         let data = &payload[name_end + 1..];
         Ok(Self { name, data })
     }
+
+    /// Builds the request payload (zero-terminated name followed by the data),
+    /// mirroring the layout accepted by `from_payload`.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(self.name.len() + 1 + self.data.len());
+        payload.extend_from_slice(self.name.as_bytes());
+        payload.push(0);
+        payload.extend_from_slice(self.data);
+        payload
+    }
 }
 
 /// A single entry in a Read/Write Multiple Parameters request.
@@ -360,6 +415,291 @@ impl ReadMultipleParamRequest {
             .collect();
         Ok(Self { entries })
     }
+
+    /// Builds the request payload (4 bytes per entry).
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(self.entries.len() * 4);
+        for entry in &self.entries {
+            payload.extend_from_slice(&entry.index.to_le_bytes());
+            payload.push(entry.sub_index);
+            payload.push(0); // Reserved
+        }
+        payload
+    }
+}
+
+/// A single data set in a WriteMultipleParamByIndex request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteMultipleParamEntry {
+    pub index: u16,
+    pub sub_index: u8,
+    pub data: Vec<u8>,
+}
+
+/// Payload for a WriteMultipleParamByIndex request.
+///
+/// Each data set is laid out as: byte offset of the next data set (4 bytes,
+/// 0 for the last one), Index (2 bytes), Sub-Index (1 byte), padding length
+/// (lower 2 bits of 1 byte), data, padding up to the next 4-byte boundary.
+/// (Reference: EPSG DS 301, Section 6.3.2.4.2.3.4)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteMultipleParamRequest {
+    pub entries: Vec<WriteMultipleParamEntry>,
+}
+
+impl WriteMultipleParamRequest {
+    const DATA_SET_HEADER_SIZE: usize = 8;
+
+    pub fn from_payload(payload: &[u8]) -> Result<Self, PowerlinkError> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset < payload.len() {
+            if offset + Self::DATA_SET_HEADER_SIZE > payload.len() {
+                return Err(PowerlinkError::SdoInvalidCommandPayload);
+            }
+            let next = u32::from_le_bytes(payload[offset..offset + 4].try_into()?) as usize;
+            let index = u16::from_le_bytes(payload[offset + 4..offset + 6].try_into()?);
+            let sub_index = payload[offset + 6];
+            let padding = (payload[offset + 7] & 0b11) as usize;
+            let data_start = offset + Self::DATA_SET_HEADER_SIZE;
+            let data_end = if next == 0 {
+                payload.len().saturating_sub(padding)
+            } else {
+                next.saturating_sub(padding)
+            };
+            if data_end < data_start || data_end > payload.len() {
+                return Err(PowerlinkError::SdoInvalidCommandPayload);
+            }
+            entries.push(WriteMultipleParamEntry {
+                index,
+                sub_index,
+                data: payload[data_start..data_end].to_vec(),
+            });
+            if next == 0 {
+                break;
+            }
+            if next <= offset {
+                return Err(PowerlinkError::SdoInvalidCommandPayload);
+            }
+            offset = next;
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let start = payload.len();
+            let padding = (4 - entry.data.len() % 4) % 4;
+            let end = start + Self::DATA_SET_HEADER_SIZE + entry.data.len() + padding;
+            let next = if i + 1 == self.entries.len() { 0 } else { end };
+            payload.extend_from_slice(&(next as u32).to_le_bytes());
+            payload.extend_from_slice(&entry.index.to_le_bytes());
+            payload.push(entry.sub_index);
+            payload.push(padding as u8);
+            payload.extend_from_slice(&entry.data);
+            payload.resize(end, 0);
+        }
+        payload
+    }
+}
+
+/// Payload of a MaxSegmentSize command. The request carries the client's
+/// maximum segment size; the response echoes it and adds the server's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MaxSegmentSizePayload {
+    pub client: u16,
+    pub server: u16,
+}
+
+impl MaxSegmentSizePayload {
+    pub fn from_payload(payload: &[u8]) -> Result<Self, PowerlinkError> {
+        if payload.len() < 2 {
+            return Err(PowerlinkError::SdoInvalidCommandPayload);
+        }
+        let client = u16::from_le_bytes(payload[0..2].try_into()?);
+        let server = match payload.get(2..4) {
+            Some(bytes) => u16::from_le_bytes(bytes.try_into()?),
+            None => 0,
+        };
+        Ok(Self { client, server })
+    }
+
+    pub fn to_payload(&self) -> Vec<u8> {
+        [self.client.to_le_bytes(), self.server.to_le_bytes()].concat()
+    }
+}
+
+// --- Response Payload Structures ---
+
+/// Decoded response to a ReadAllByIndex request.
+///
+/// The server concatenates the values of sub-indices 1..N, so splitting the
+/// data into typed values requires the caller to know the object layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadAllByIndexResponse {
+    pub index: u16,
+    pub data: Vec<u8>,
+}
+
+impl ReadAllByIndexResponse {
+    /// Splits the raw data into typed values, using `templates` as the
+    /// per-sub-index type description (sub-index 1 first).
+    ///
+    /// The data carries no length per value, so only fixed-size types can be
+    /// split. A string or domain template fails with `TypeMismatch`.
+    pub fn values(&self, templates: &[ObjectValue]) -> Result<Vec<ObjectValue>, PowerlinkError> {
+        let mut values = Vec::with_capacity(templates.len());
+        let mut offset = 0;
+        for template in templates {
+            let size = template.fixed_size().ok_or(PowerlinkError::TypeMismatch)?;
+            if offset + size > self.data.len() {
+                return Err(PowerlinkError::BufferTooShort);
+            }
+            values.push(ObjectValue::deserialize(
+                &self.data[offset..offset + size],
+                template,
+            )?);
+            offset += size;
+        }
+        Ok(values)
+    }
+}
+
+/// The result for a single entry of a ReadMultipleParamByIndex response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipleParamReadResult {
+    pub index: u16,
+    pub sub_index: u8,
+    /// The raw data on success, or the sub-abort code for this entry.
+    pub result: Result<Vec<u8>, u32>,
+}
+
+/// Decoded response to a ReadMultipleParamByIndex request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadMultipleParamResponse {
+    pub entries: Vec<MultipleParamReadResult>,
+}
+
+impl ReadMultipleParamResponse {
+    /// Bit in the first byte of a data set indicating a sub-abort.
+    const SUB_ABORT_FLAG: u8 = 0x80;
+
+    /// Builds the response payload as sent by the SDO server:
+    /// the number of entries (U32), then per entry a flags byte (SubAbort in
+    /// bit 7), a padding byte, the data length (U16) and the data, aligned to
+    /// 4 bytes.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            let (flags, data) = match &entry.result {
+                Ok(data) => (0u8, data.clone()),
+                Err(code) => (Self::SUB_ABORT_FLAG, code.to_le_bytes().to_vec()),
+            };
+            payload.push(flags);
+            payload.push(0u8);
+            payload.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            payload.extend_from_slice(&data);
+            while payload.len() % 4 != 0 {
+                payload.push(0u8);
+            }
+        }
+        payload
+    }
+
+    /// Decodes a response payload, pairing each data set with the entry that
+    /// was requested at the same position.
+    pub fn from_payload(
+        payload: &[u8],
+        requested: &[MultipleParamEntry],
+    ) -> Result<Self, PowerlinkError> {
+        if payload.len() < 4 {
+            return Err(PowerlinkError::SdoInvalidCommandPayload);
+        }
+        let count = u32::from_le_bytes(payload[0..4].try_into()?) as usize;
+        if count != requested.len() {
+            return Err(PowerlinkError::SdoInvalidCommandPayload);
+        }
+        let mut entries = Vec::with_capacity(count);
+        let mut offset = 4;
+        for req in requested {
+            if offset + 4 > payload.len() {
+                return Err(PowerlinkError::SdoInvalidCommandPayload);
+            }
+            let flags = payload[offset];
+            let len = u16::from_le_bytes(payload[offset + 2..offset + 4].try_into()?) as usize;
+            let data_start = offset + 4;
+            let data_end = data_start + len;
+            if data_end > payload.len() {
+                return Err(PowerlinkError::SdoInvalidCommandPayload);
+            }
+            let data = &payload[data_start..data_end];
+            let result = if flags & Self::SUB_ABORT_FLAG != 0 {
+                if data.len() < 4 {
+                    return Err(PowerlinkError::SdoInvalidCommandPayload);
+                }
+                Err(u32::from_le_bytes(data[0..4].try_into()?))
+            } else {
+                Ok(data.to_vec())
+            };
+            entries.push(MultipleParamReadResult {
+                index: req.index,
+                sub_index: req.sub_index,
+                result,
+            });
+            offset = data_end.next_multiple_of(4);
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// Decoded response to a WriteMultipleParamByIndex request.
+///
+/// An empty response payload means every data set was written. Otherwise the
+/// payload lists the failed entries: Index (2 bytes), Sub-Index (1 byte),
+/// flags (SubAbort in bit 7) and the abort code (4 bytes).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WriteMultipleParamResponse {
+    pub sub_aborts: Vec<(MultipleParamEntry, u32)>,
+}
+
+impl WriteMultipleParamResponse {
+    pub fn from_payload(payload: &[u8]) -> Result<Self, PowerlinkError> {
+        if !payload.len().is_multiple_of(8) {
+            return Err(PowerlinkError::SdoInvalidCommandPayload);
+        }
+        let mut sub_aborts = Vec::new();
+        for chunk in payload.chunks_exact(8) {
+            if chunk[3] & 0x80 == 0 {
+                continue;
+            }
+            sub_aborts.push((
+                MultipleParamEntry {
+                    index: u16::from_le_bytes(chunk[0..2].try_into()?),
+                    sub_index: chunk[2],
+                },
+                u32::from_le_bytes(chunk[4..8].try_into()?),
+            ));
+        }
+        Ok(Self { sub_aborts })
+    }
+
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(self.sub_aborts.len() * 8);
+        for (entry, code) in &self.sub_aborts {
+            payload.extend_from_slice(&entry.index.to_le_bytes());
+            payload.push(entry.sub_index);
+            payload.push(0x80);
+            payload.extend_from_slice(&code.to_le_bytes());
+        }
+        payload
+    }
+
+    /// Returns true if all data sets were written successfully.
+    pub fn is_success(&self) -> bool {
+        self.sub_aborts.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -441,6 +781,112 @@ mod tests {
         assert_eq!(deserialized.header.segment_size, 4);
     }
 
+    #[test]
+    fn test_write_multiple_param_request_roundtrip() {
+        let original = WriteMultipleParamRequest {
+            entries: vec![
+                WriteMultipleParamEntry {
+                    index: 0x1006,
+                    sub_index: 0,
+                    data: vec![0x10, 0x27, 0x00, 0x00],
+                },
+                WriteMultipleParamEntry {
+                    index: 0x6000,
+                    sub_index: 1,
+                    data: vec![0xAB],
+                },
+            ],
+        };
+        let payload = original.to_payload();
+        // 8 + 4 for the first set, 8 + 1 + 3 padding for the last
+        assert_eq!(payload.len(), 24);
+        assert_eq!(&payload[0..4], &12u32.to_le_bytes());
+        assert_eq!(&payload[12..16], &0u32.to_le_bytes());
+        assert_eq!(payload[19], 3);
+        assert_eq!(
+            WriteMultipleParamRequest::from_payload(&payload),
+            Ok(original)
+        );
+    }
+
+    #[test]
+    fn test_read_multiple_param_response_roundtrip() {
+        let requested = [
+            MultipleParamEntry {
+                index: 0x1000,
+                sub_index: 0,
+            },
+            MultipleParamEntry {
+                index: 0x2000,
+                sub_index: 1,
+            },
+        ];
+        let original = ReadMultipleParamResponse {
+            entries: vec![
+                MultipleParamReadResult {
+                    index: 0x1000,
+                    sub_index: 0,
+                    result: Ok(vec![0x91, 0x01, 0x0F]),
+                },
+                MultipleParamReadResult {
+                    index: 0x2000,
+                    sub_index: 1,
+                    result: Err(0x0602_0000),
+                },
+            ],
+        };
+        let payload = original.to_payload();
+        assert_eq!(payload.len() % 4, 0);
+        assert_eq!(
+            ReadMultipleParamResponse::from_payload(&payload, &requested),
+            Ok(original)
+        );
+        // A response with a different entry count is rejected.
+        assert!(ReadMultipleParamResponse::from_payload(&payload, &requested[..1]).is_err());
+    }
+
+    #[test]
+    fn test_write_multiple_param_response_roundtrip() {
+        assert!(
+            WriteMultipleParamResponse::from_payload(&[])
+                .unwrap()
+                .is_success()
+        );
+        let original = WriteMultipleParamResponse {
+            sub_aborts: vec![(
+                MultipleParamEntry {
+                    index: 0x1006,
+                    sub_index: 0,
+                },
+                0x0609_0030,
+            )],
+        };
+        let decoded = WriteMultipleParamResponse::from_payload(&original.to_payload()).unwrap();
+        assert!(!decoded.is_success());
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_read_all_by_index_response_values() {
+        let response = ReadAllByIndexResponse {
+            index: 0x1F81,
+            data: vec![0x01, 0x00, 0x00, 0x00, 0x34, 0x12],
+        };
+        let values = response
+            .values(&[ObjectValue::Unsigned32(0), ObjectValue::Unsigned16(0)])
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![ObjectValue::Unsigned32(1), ObjectValue::Unsigned16(0x1234)]
+        );
+        assert!(response.values(&[ObjectValue::Unsigned64(0)]).is_err());
+        // A string template says nothing about the length of the received value.
+        assert_eq!(
+            response.values(&[ObjectValue::VisibleString("abcd".into())]),
+            Err(PowerlinkError::TypeMismatch)
+        );
+    }
+
     #[test]
     fn test_enum_try_from() {
         assert_eq!(CommandId::try_from(0x01), Ok(CommandId::WriteByIndex));
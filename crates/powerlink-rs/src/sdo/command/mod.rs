@@ -2,7 +2,10 @@ mod base;
 mod handler;
 
 pub use base::{
-    CommandId, CommandLayerHeader, ReadByIndexRequest, ReadByNameRequest, ReadMultipleParamRequest,
-    SdoCommand, Segmentation, WriteByIndexRequest, WriteByNameRequest,
+    CommandId, CommandLayerHeader, MaxSegmentSizePayload, MultipleParamEntry, MultipleParamReadResult,
+    ReadAllByIndexRequest, ReadAllByIndexResponse, ReadByIndexRequest, ReadByNameRequest,
+    ReadMultipleParamRequest, ReadMultipleParamResponse, SdoCommand, Segmentation,
+    WriteByIndexRequest, WriteByNameRequest, WriteMultipleParamEntry, WriteMultipleParamRequest,
+    WriteMultipleParamResponse,
 };
 pub use handler::{DefaultSdoHandler, SdoCommandHandler};
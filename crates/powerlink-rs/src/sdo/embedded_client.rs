@@ -1,34 +1,121 @@
 // crates/powerlink-rs/src/sdo/embedded_client.rs
 //! Manages the client-side state for SDO transfers embedded in PDOs.
 //!
+//! Each client channel carries at most one request at a time. The request is
+//! repeated in every TPDO container until the server answers with the same
+//! sequence number and transaction ID, the server reports a sequence error
+//! (con=3), or the SDO timeout (0x1300) expires. Finished transfers are placed
+//! in a per-channel completion queue for the application to poll.
+//!
 //! (Reference: EPSG DS 301, Section 6.3.3)
 use crate::PowerlinkError;
+use crate::od::ObjectDictionary;
+use crate::sdo::OD_IDX_SDO_TIMEOUT;
 use crate::sdo::command::{CommandId, Segmentation};
 use crate::sdo::embedded::{PdoSdoCommand, PdoSequenceLayerHeader};
 use alloc::collections::{BTreeMap, VecDeque};
-use alloc::vec;
 use alloc::vec::Vec;
 use log::{error, info, trace, warn};
 
+/// Sequence layer connection state: connection valid.
+const CON_VALID: u8 = 2;
+/// Sequence layer connection state: error response from the server.
+const CON_ERROR: u8 = 3;
+
+/// Abort code reported when the server does not answer within the SDO timeout.
+const ABORT_CODE_TIMEOUT: u32 = 0x0504_0000;
+
+/// The outcome of an embedded SDO transfer, as returned to the application.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedSdoResult {
+    /// The transaction ID that was returned when the request was queued.
+    pub transaction_id: u8,
+    pub index: u16,
+    pub sub_index: u8,
+    /// The data read from the server (empty for writes), or the reason the
+    /// transfer failed. Server aborts are reported as `SdoAborted(code)`.
+    pub outcome: Result<Vec<u8>, PowerlinkError>,
+}
+
 /// A request queued by the application to be sent via an embedded SDO client channel.
-#[derive(Debug)] // <-- ADDED
+#[derive(Debug)]
 struct SdoClientRequest {
+    transaction_id: u8,
     command_id: CommandId,
     index: u16,
     sub_index: u8,
     data: Vec<u8>,
-    // TODO: Add a callback/waker for when the response is received
+}
+
+/// A request that has been placed in a TPDO container and awaits its response.
+#[derive(Debug)]
+struct InFlightRequest {
+    request: SdoClientRequest,
+    /// The sequence number the request is currently being sent with.
+    sequence_number: u8,
+    /// Absolute time at which the transfer is aborted. Armed on the first
+    /// tick after the request was first transmitted.
+    deadline_us: Option<u64>,
 }
 
 /// The state of a single embedded SDO client connection.
 #[derive(Debug, Default)]
 struct EmbeddedSdoConnection {
-    /// The last successfully processed sequence number from the server.
-    last_sequence_number: u8,
     /// The sequence number to use for our next request.
     next_sequence_number: u8,
+    /// The transaction ID to assign to the next queued request.
+    next_transaction_id: u8,
     /// Queue of pending requests from the application.
     request_queue: VecDeque<SdoClientRequest>,
+    /// The request currently being repeated in the TPDO container.
+    in_flight: Option<InFlightRequest>,
+    /// Finished transfers waiting to be polled by the application.
+    completed: VecDeque<EmbeddedSdoResult>,
+}
+
+impl EmbeddedSdoConnection {
+    fn queue(&mut self, command_id: CommandId, index: u16, sub_index: u8, data: Vec<u8>) -> u8 {
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id = self.next_transaction_id.wrapping_add(1);
+        self.request_queue.push_back(SdoClientRequest {
+            transaction_id,
+            command_id,
+            index,
+            sub_index,
+            data,
+        });
+        transaction_id
+    }
+
+    fn complete(&mut self, request: SdoClientRequest, outcome: Result<Vec<u8>, PowerlinkError>) {
+        self.completed.push_back(EmbeddedSdoResult {
+            transaction_id: request.transaction_id,
+            index: request.index,
+            sub_index: request.sub_index,
+            outcome,
+        });
+    }
+
+    /// Builds the idle command sent while no request is in flight. It keeps the
+    /// connection valid and carries the last used sequence number, so the
+    /// server treats it as a repetition and does not process anything.
+    fn nil_command(&self) -> PdoSdoCommand {
+        PdoSdoCommand {
+            sequence_header: PdoSequenceLayerHeader {
+                sequence_number: self.next_sequence_number.wrapping_sub(1) % 64,
+                connection_state: CON_VALID,
+            },
+            transaction_id: self.next_transaction_id.wrapping_sub(1),
+            is_response: false,
+            is_aborted: false,
+            segmentation: Segmentation::Expedited,
+            valid_payload_length: 0,
+            command_id: CommandId::Nil,
+            index: 0,
+            sub_index: 0,
+            data: Vec::new(),
+        }
+    }
 }
 
 /// Manages all embedded SDO client channels (0x1280 - 0x12FF).
@@ -43,57 +130,89 @@ impl EmbeddedSdoClient {
         Self::default()
     }
 
-    /// Queues an SDO Read request to be sent on a specific client channel.
-    pub fn queue_read(
+    fn connection_for_queue(
         &mut self,
         channel_index: u16,
-        index: u16,
-        sub_index: u8,
-    ) -> Result<(), PowerlinkError> {
+    ) -> Result<&mut EmbeddedSdoConnection, PowerlinkError> {
         if !(0x1280..=0x12FF).contains(&channel_index) {
             return Err(PowerlinkError::InternalError(
                 "Invalid SDO client channel index",
             ));
         }
-        let conn = self
-            .connections
-            .entry(channel_index)
-            .or_insert_with(Default::default);
+        Ok(self.connections.entry(channel_index).or_default())
+    }
 
-        conn.request_queue.push_back(SdoClientRequest {
-            command_id: CommandId::ReadByIndex,
-            index,
-            sub_index,
-            data: Vec::new(),
-        });
-        Ok(())
+    /// Queues an SDO Read request to be sent on a specific client channel.
+    ///
+    /// Returns the transaction ID that identifies the result in the
+    /// channel's completion queue.
+    pub fn queue_read(
+        &mut self,
+        channel_index: u16,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<u8, PowerlinkError> {
+        let conn = self.connection_for_queue(channel_index)?;
+        Ok(conn.queue(CommandId::ReadByIndex, index, sub_index, Vec::new()))
     }
 
     /// Queues an SDO Write request to be sent on a specific client channel.
+    ///
+    /// Returns the transaction ID that identifies the result in the
+    /// channel's completion queue.
     pub fn queue_write(
         &mut self,
         channel_index: u16,
         index: u16,
         sub_index: u8,
         data: Vec<u8>,
-    ) -> Result<(), PowerlinkError> {
-        if !(0x1280..=0x12FF).contains(&channel_index) {
-            return Err(PowerlinkError::InternalError(
-                "Invalid SDO client channel index",
-            ));
-        }
-        let conn = self
-            .connections
-            .entry(channel_index)
-            .or_insert_with(Default::default);
+    ) -> Result<u8, PowerlinkError> {
+        let conn = self.connection_for_queue(channel_index)?;
+        Ok(conn.queue(CommandId::WriteByIndex, index, sub_index, data))
+    }
 
-        conn.request_queue.push_back(SdoClientRequest {
-            command_id: CommandId::WriteByIndex,
-            index,
-            sub_index,
-            data,
-        });
-        Ok(())
+    /// Returns the oldest finished transfer on a channel, if any.
+    pub fn pop_completed(&mut self, channel_index: u16) -> Option<EmbeddedSdoResult> {
+        self.connections
+            .get_mut(&channel_index)?
+            .completed
+            .pop_front()
+    }
+
+    /// Returns true if a channel has a request in flight or queued.
+    pub fn is_busy(&self, channel_index: u16) -> bool {
+        self.connections
+            .get(&channel_index)
+            .is_some_and(|conn| conn.in_flight.is_some() || !conn.request_queue.is_empty())
+    }
+
+    /// Checks in-flight requests against the SDO timeout (0x1300).
+    ///
+    /// A request that is not answered in time is completed with abort code
+    /// 0x05040000 and the channel moves on to its next queued request.
+    pub fn tick(&mut self, current_time_us: u64, od: &ObjectDictionary) {
+        let timeout_us = od.read_u32(OD_IDX_SDO_TIMEOUT, 0).unwrap_or(15000) as u64 * 1000;
+        for (channel_index, conn) in self.connections.iter_mut() {
+            let Some(in_flight) = conn.in_flight.as_mut() else {
+                continue;
+            };
+            let deadline = *in_flight
+                .deadline_us
+                .get_or_insert(current_time_us + timeout_us);
+            if current_time_us < deadline {
+                continue;
+            }
+            if let Some(in_flight) = conn.in_flight.take() {
+                warn!(
+                    "[SDO-PDO] Client: Request TID {} on channel {:#06X} timed out.",
+                    in_flight.request.transaction_id, channel_index
+                );
+                conn.complete(
+                    in_flight.request,
+                    Err(PowerlinkError::SdoAborted(ABORT_CODE_TIMEOUT)),
+                );
+            }
+        }
     }
 
     /// Handles an incoming SDO response from an RPDO container.
@@ -114,80 +233,116 @@ impl EmbeddedSdoClient {
             return;
         };
 
-        // Check sequence number
-        if response.sequence_header.sequence_number
-            != conn.next_sequence_number.wrapping_sub(1) % 64
-        {
+        let Some(in_flight) = conn.in_flight.as_mut() else {
             trace!(
-                "[SDO-PDO] Client: Ignoring duplicate/old response for channel {:#06X}. Seq: {}",
-                channel_index, response.sequence_header.sequence_number
+                "[SDO-PDO] Client: No request in flight on channel {:#06X}. Ignoring container.",
+                channel_index
+            );
+            return;
+        };
+
+        // The server rejected our sequence number (Spec 6.3.3.1.2.2). Continue
+        // from the server's last valid sequence number and resend.
+        if response.sequence_header.connection_state == CON_ERROR {
+            let resync_seq = response.sequence_header.sequence_number.wrapping_add(1) % 64;
+            warn!(
+                "[SDO-PDO] Client: Error response on channel {:#06X} (server Seq {}). Resending with Seq {}.",
+                channel_index, response.sequence_header.sequence_number, resync_seq
             );
+            in_flight.sequence_number = resync_seq;
+            conn.next_sequence_number = resync_seq.wrapping_add(1) % 64;
             return;
         }
 
-        conn.last_sequence_number = response.sequence_header.sequence_number;
+        if !response.is_response
+            || response.sequence_header.sequence_number != in_flight.sequence_number
+            || response.transaction_id != in_flight.request.transaction_id
+        {
+            trace!(
+                "[SDO-PDO] Client: Ignoring stale response for channel {:#06X}. Seq: {}, TID: {}",
+                channel_index, response.sequence_header.sequence_number, response.transaction_id
+            );
+            return;
+        }
 
-        // Handle response
-        if response.is_aborted {
-            let abort_code = u32::from_le_bytes(response.data[0..4].try_into().unwrap_or_default());
+        let Some(in_flight) = conn.in_flight.take() else {
+            return;
+        };
+        let outcome = if response.is_aborted {
+            let abort_code = response
+                .data
+                .get(0..4)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u32::from_le_bytes)
+                .unwrap_or_default();
             error!(
                 "[SDO-PDO] Client: Received SDO Abort on channel {:#06X}. Code: {:#010X}",
                 channel_index, abort_code
             );
-            // TODO: Notify application of failure
+            Err(PowerlinkError::SdoAborted(abort_code))
         } else {
             info!(
                 "[SDO-PDO] Client: Received SDO Response on channel {:#06X}. Data: {:02X?}",
                 channel_index, response.data
             );
-            // TODO: Notify application of success with data
-        }
+            Ok(response.data)
+        };
+        conn.complete(in_flight.request, outcome);
     }
 
-    /// Retrieves the pending request payload for a TPDO container.
-    /// This consumes the request from the queue.
+    /// Retrieves the request payload for a TPDO container.
+    ///
+    /// The in-flight request is repeated until it completes. When the channel
+    /// is idle, the next queued request is started, or a NIL command is sent.
     pub fn get_pending_request(&mut self, channel_index: u16, container_len: usize) -> Vec<u8> {
         let conn = self
             .connections
             .entry(channel_index)
             .or_insert_with(Default::default);
 
-        let Some(request) = conn.request_queue.pop_front() else {
-            // No request, send NIL
-            // TODO: Send a proper NIL command
-            return vec![0; container_len];
-        };
+        while conn.in_flight.is_none() {
+            let Some(request) = conn.request_queue.pop_front() else {
+                break;
+            };
+            // Header(8) + data must fit in the container.
+            let required_len = 8 + request.data.len();
+            if required_len > container_len {
+                error!(
+                    "[SDO-PDO] Client: Request for {:#06X} ({} bytes) exceeds container length ({} bytes). Dropping.",
+                    channel_index, required_len, container_len
+                );
+                conn.complete(request, Err(PowerlinkError::BufferTooShort));
+                continue;
+            }
+            let sequence_number = conn.next_sequence_number;
+            conn.next_sequence_number = conn.next_sequence_number.wrapping_add(1) % 64;
+            conn.in_flight = Some(InFlightRequest {
+                request,
+                sequence_number,
+                deadline_us: None,
+            });
+        }
 
-        let cmd = PdoSdoCommand {
-            sequence_header: PdoSequenceLayerHeader {
-                sequence_number: conn.next_sequence_number,
-                connection_state: 2, // Connection valid
+        let cmd = match &conn.in_flight {
+            Some(in_flight) => PdoSdoCommand {
+                sequence_header: PdoSequenceLayerHeader {
+                    sequence_number: in_flight.sequence_number,
+                    connection_state: CON_VALID,
+                },
+                transaction_id: in_flight.request.transaction_id,
+                is_response: false,
+                is_aborted: false,
+                segmentation: Segmentation::Expedited, // Always expedited
+                valid_payload_length: 0,               // Will be set
+                command_id: in_flight.request.command_id,
+                index: in_flight.request.index,
+                sub_index: in_flight.request.sub_index,
+                data: in_flight.request.data.clone(),
             },
-            transaction_id: 1, // TODO: Manage TIDs
-            is_response: false,
-            is_aborted: false,
-            segmentation: Segmentation::Expedited, // Always expedited
-            valid_payload_length: 0,               // Will be set
-            command_id: request.command_id,
-            index: request.index,
-            sub_index: request.sub_index,
-            data: request.data,
+            None => conn.nil_command(),
         };
 
-        conn.next_sequence_number = conn.next_sequence_number.wrapping_add(1) % 64;
-
         let mut payload = cmd.serialize();
-        if payload.len() > container_len {
-            error!(
-                "[SDO-PDO] Client: Request for {:#06X} ({} bytes) exceeds container length ({} bytes). Dropping.",
-                channel_index,
-                payload.len(),
-                container_len
-            );
-            // Re-queue the request? For now, just drop it.
-            return vec![0; container_len];
-        }
-
         payload.resize(container_len, 0); // Pad to fill container
         payload
     }
@@ -199,6 +354,22 @@ mod tests {
     use crate::sdo::command::{CommandId, Segmentation};
     use alloc::vec;
 
+    fn response_for(request: &PdoSdoCommand, data: Vec<u8>) -> Vec<u8> {
+        PdoSdoCommand {
+            sequence_header: request.sequence_header,
+            transaction_id: request.transaction_id,
+            is_response: true,
+            is_aborted: false,
+            segmentation: Segmentation::Expedited,
+            valid_payload_length: 0,
+            command_id: request.command_id,
+            index: request.index,
+            sub_index: request.sub_index,
+            data,
+        }
+        .serialize()
+    }
+
     #[test]
     fn test_client_queue_and_get_request() {
         let mut client = EmbeddedSdoClient::new();
@@ -224,37 +395,124 @@ mod tests {
         let mut client = EmbeddedSdoClient::new();
         let channel = 0x1280;
 
-        // 1. Generate a request to init sequence numbers
-        client.queue_read(channel, 0x2000, 0).unwrap();
-        let _ = client.get_pending_request(channel, 20);
+        let tid = client.queue_read(channel, 0x2000, 0).unwrap();
+        let request = PdoSdoCommand::deserialize(&client.get_pending_request(channel, 20)).unwrap();
+        assert_eq!(request.transaction_id, tid);
+
+        client.handle_response(
+            channel,
+            &response_for(&request, vec![0xAA, 0xBB, 0xCC, 0xDD]),
+        );
+
+        let result = client
+            .pop_completed(channel)
+            .expect("Transfer should complete");
+        assert_eq!(result.transaction_id, tid);
+        assert_eq!(result.index, 0x2000);
+        assert_eq!(result.outcome, Ok(vec![0xAA, 0xBB, 0xCC, 0xDD]));
+        assert!(client.pop_completed(channel).is_none());
+        assert!(!client.is_busy(channel));
+    }
 
-        // Expectation: client.connections[0x1280].next_sequence_number is now 1.
-        // The server responds with sequence 0 (request was 0).
+    #[test]
+    fn test_request_repeated_until_answered_and_tids_advance() {
+        let mut client = EmbeddedSdoClient::new();
+        let channel = 0x1280;
+
+        let tid1 = client.queue_read(channel, 0x2000, 0).unwrap();
+        let tid2 = client.queue_write(channel, 0x2001, 0, vec![1, 2]).unwrap();
+        assert_ne!(tid1, tid2);
+
+        let first = client.get_pending_request(channel, 20);
+        assert_eq!(client.get_pending_request(channel, 20), first);
+        let first = PdoSdoCommand::deserialize(&first).unwrap();
+
+        // A response with the wrong TID must not complete the transfer.
+        let mut wrong = PdoSdoCommand::deserialize(&response_for(&first, vec![])).unwrap();
+        wrong.transaction_id = first.transaction_id.wrapping_add(7);
+        client.handle_response(channel, &wrong.serialize());
+        assert!(client.pop_completed(channel).is_none());
+
+        client.handle_response(channel, &response_for(&first, vec![0x11]));
+        let second = PdoSdoCommand::deserialize(&client.get_pending_request(channel, 20)).unwrap();
+        assert_eq!(second.transaction_id, tid2);
+        assert_eq!(second.command_id, CommandId::WriteByIndex);
+        assert_eq!(
+            second.sequence_header.sequence_number,
+            (first.sequence_header.sequence_number + 1) % 64
+        );
+
+        // The server keeps repeating its last response; it must be ignored
+        // once the transfer has completed.
+        client.handle_response(channel, &response_for(&second, vec![]));
+        client.handle_response(channel, &response_for(&second, vec![]));
+        assert_eq!(client.pop_completed(channel).unwrap().transaction_id, tid1);
+        assert_eq!(client.pop_completed(channel).unwrap().transaction_id, tid2);
+        assert!(client.pop_completed(channel).is_none());
+
+        // Idle channel sends a NIL command.
+        let idle = PdoSdoCommand::deserialize(&client.get_pending_request(channel, 20)).unwrap();
+        assert_eq!(idle.command_id, CommandId::Nil);
+        assert_eq!(idle.sequence_header.connection_state, 2);
+    }
+
+    #[test]
+    fn test_abort_and_error_response() {
+        let mut client = EmbeddedSdoClient::new();
+        let channel = 0x1281;
+        client.queue_read(channel, 0x9999, 0).unwrap();
+        let request = PdoSdoCommand::deserialize(&client.get_pending_request(channel, 20)).unwrap();
 
-        let response_cmd = PdoSdoCommand {
+        // Server reports a sequence error; the client resends with the next sequence number.
+        let error_response = PdoSdoCommand {
             sequence_header: PdoSequenceLayerHeader {
-                sequence_number: 0,
-                connection_state: 2,
+                sequence_number: 41,
+                connection_state: 3,
             },
-            transaction_id: 1,
+            transaction_id: request.transaction_id,
             is_response: true,
             is_aborted: false,
             segmentation: Segmentation::Expedited,
-            valid_payload_length: 4,
-            command_id: CommandId::ReadByIndex,
-            index: 0x2000,
+            valid_payload_length: 0,
+            command_id: CommandId::Nil,
+            index: 0,
             sub_index: 0,
-            data: vec![0xAA, 0xBB, 0xCC, 0xDD],
+            data: vec![],
         };
+        client.handle_response(channel, &error_response.serialize());
+        assert!(client.pop_completed(channel).is_none());
+        let resent = PdoSdoCommand::deserialize(&client.get_pending_request(channel, 20)).unwrap();
+        assert_eq!(resent.sequence_header.sequence_number, 42);
+        assert_eq!(resent.transaction_id, request.transaction_id);
+
+        let mut abort = PdoSdoCommand::deserialize(&response_for(&resent, vec![])).unwrap();
+        abort.is_aborted = true;
+        abort.command_id = CommandId::Nil;
+        abort.data = 0x0602_0000u32.to_le_bytes().to_vec();
+        client.handle_response(channel, &abort.serialize());
+        assert_eq!(
+            client.pop_completed(channel).unwrap().outcome,
+            Err(PowerlinkError::SdoAborted(0x0602_0000))
+        );
+    }
 
-        let response_payload = response_cmd.serialize();
-
-        // Handle response
-        client.handle_response(channel, &response_payload);
+    #[test]
+    fn test_request_times_out() {
+        let od = ObjectDictionary::new(None);
+        let mut client = EmbeddedSdoClient::new();
+        let channel = 0x1280;
+        client.queue_read(channel, 0x2000, 0).unwrap();
+        let _ = client.get_pending_request(channel, 20);
 
-        // To verify success internally without pub accessors, we rely on logs or
-        // the fact that last_sequence_number is updated.
-        // However, for this unit test, we can assume if it didn't panic/warn, it processed.
+        client.tick(1_000, &od); // Arms the default 15 s timeout.
+        client.tick(15_000_999, &od);
+        assert!(client.pop_completed(channel).is_none());
+        client.tick(15_001_000, &od);
+        assert_eq!(
+            client.pop_completed(channel).unwrap().outcome,
+            Err(PowerlinkError::SdoAborted(0x0504_0000))
+        );
+        assert!(!client.is_busy(channel));
     }
 
     #[test]
@@ -267,9 +525,14 @@ mod tests {
 
         // Container only 10 bytes!
         let payload = client.get_pending_request(channel, 10);
-
-        // Should return empty/zero payload (dropped request)
-        assert!(payload.iter().all(|&b| b == 0));
         assert_eq!(payload.len(), 10);
+
+        // The request is dropped and reported; a NIL command is sent instead.
+        let cmd = PdoSdoCommand::deserialize(&payload).unwrap();
+        assert_eq!(cmd.command_id, CommandId::Nil);
+        assert_eq!(
+            client.pop_completed(channel).unwrap().outcome,
+            Err(PowerlinkError::BufferTooShort)
+        );
     }
 }
@@ -3,21 +3,29 @@
 //!
 //! (Reference: EPSG DS 301, Section 6.3.3)
 
-use crate::PowerlinkError;
-use crate::od::{ObjectDictionary, ObjectValue};
+use crate::od::ObjectDictionary;
 use crate::sdo::command::{CommandId, Segmentation};
 use crate::sdo::embedded::{PdoSdoCommand, PdoSequenceLayerHeader};
+use crate::sdo::state::write_od_value;
 use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 use log::{error, trace, warn};
 
+/// Sequence layer connection state: no connection (e.g. an unused container).
+const CON_NONE: u8 = 0;
+/// Sequence layer connection state: connection valid.
+const CON_VALID: u8 = 2;
+/// Sequence layer connection state: error response.
+const CON_ERROR: u8 = 3;
+
 /// The state of a single embedded SDO server connection.
 #[derive(Debug, Clone, Default)]
 struct EmbeddedSdoConnection {
-    /// The last successfully processed sequence number (0-63).
-    last_sequence_number: u8,
-    /// The response payload that is ready to be sent.
+    /// The last successfully processed sequence number (0-63), if any.
+    last_sequence_number: Option<u8>,
+    /// The last response. It is repeated in every TPDO container until the
+    /// client sends a new request, as the client may miss a cycle.
     pending_response: Option<Vec<u8>>,
 }
 
@@ -57,35 +65,67 @@ impl EmbeddedSdoServer {
             return;
         };
 
-        // Check sequence number (Spec 6.3.3.1.2.1, 6.3.3.1.2.2)
-        let expected_seq = conn.last_sequence_number.wrapping_add(1) % 64;
-        if command.sequence_header.sequence_number == conn.last_sequence_number {
-            trace!(
-                "[SDO-PDO] Server: Received duplicate request (Seq {}) for channel {:#06X}. Resending last response.",
-                command.sequence_header.sequence_number, channel_index
-            );
-            // Don't re-process, just let get_pending_response send the same response again.
-            return;
-        } else if command.sequence_header.sequence_number != expected_seq {
-            error!(
-                "[SDO-PDO] Server: Sequence number mismatch for channel {:#06X}. Expected {}, got {}. Ignoring.",
-                channel_index, expected_seq, command.sequence_header.sequence_number
-            );
-            // TODO: Per spec, we should send an Error Response (con=3).
-            // For now, we ignore and don't update our sequence number.
+        // Unused containers and idle NIL commands carry no request.
+        if command.is_response
+            || command.sequence_header.connection_state == CON_NONE
+            || command.command_id == CommandId::Nil
+        {
             return;
         }
 
+        // Check sequence number (Spec 6.3.3.1.2.1, 6.3.3.1.2.2)
+        if let Some(last_seq) = conn.last_sequence_number {
+            let expected_seq = last_seq.wrapping_add(1) % 64;
+            if command.sequence_header.sequence_number == last_seq {
+                trace!(
+                    "[SDO-PDO] Server: Received duplicate request (Seq {}) for channel {:#06X}. Resending last response.",
+                    command.sequence_header.sequence_number, channel_index
+                );
+                // Don't re-process, just let get_pending_response send the same response again.
+                return;
+            } else if command.sequence_header.sequence_number != expected_seq {
+                error!(
+                    "[SDO-PDO] Server: Sequence number mismatch for channel {:#06X}. Expected {}, got {}. Sending error response.",
+                    channel_index, expected_seq, command.sequence_header.sequence_number
+                );
+                // Report our last valid sequence number so the client can resynchronize.
+                conn.pending_response = Some(Self::error_response(&command, last_seq));
+                return;
+            }
+        }
+
         // Sequence is new and valid, update our state.
-        conn.last_sequence_number = command.sequence_header.sequence_number;
+        let sequence_number = command.sequence_header.sequence_number;
+        conn.last_sequence_number = Some(sequence_number);
 
         // Process the SDO command
-        let response_payload = Self::process_command(&command, od, conn.last_sequence_number);
+        let response_payload = Self::process_command(&command, od, sequence_number);
 
         // Store the serialized response
         conn.pending_response = Some(response_payload);
     }
 
+    /// Builds the sequence layer error response (con=3) for a request with an
+    /// unexpected sequence number.
+    fn error_response(req: &PdoSdoCommand, last_sequence_number: u8) -> Vec<u8> {
+        PdoSdoCommand {
+            sequence_header: PdoSequenceLayerHeader {
+                sequence_number: last_sequence_number,
+                connection_state: CON_ERROR,
+            },
+            transaction_id: req.transaction_id,
+            is_response: true,
+            is_aborted: false,
+            segmentation: Segmentation::Expedited,
+            valid_payload_length: 0,
+            command_id: CommandId::Nil,
+            index: req.index,
+            sub_index: req.sub_index,
+            data: Vec::new(),
+        }
+        .serialize()
+    }
+
     /// Generates a response payload for a given request.
     fn process_command(
         req: &PdoSdoCommand,
@@ -109,42 +149,9 @@ impl EmbeddedSdoServer {
                 }
             }
             CommandId::WriteByIndex => {
-                // 1. Check if object exists and get its type template
-                // We clone the template to avoid holding an immutable borrow on OD while calling write (mutable borrow)
-                let template_opt = od
-                    .read(req.index, req.sub_index)
-                    .map(|cow| cow.into_owned());
-
-                if let Some(template) = template_opt {
-                    // 2. Deserialize the raw data using the type template
-                    match ObjectValue::deserialize(&req.data, &template) {
-                        Ok(value) => {
-                            // 3. Write the value to the OD
-                            match od.write(req.index, req.sub_index, value) {
-                                Ok(_) => (None, Vec::new()), // Success
-                                Err(e) => {
-                                    // Map PowerlinkError to SDO Abort Code
-                                    let code = match e {
-                                        PowerlinkError::StorageError("Object is read-only") => {
-                                            0x0601_0002
-                                        }
-                                        PowerlinkError::TypeMismatch => 0x0607_0010,
-                                        PowerlinkError::ValidationError(_) => 0x0609_0030, // Value range exceeded
-                                        _ => 0x0800_0020, // Data cannot be transferred or stored
-                                    };
-                                    (Some(code), Vec::new())
-                                }
-                            }
-                        }
-                        Err(_) => (Some(0x0607_0010), Vec::new()), // Data type does not match (deserialization failed)
-                    }
-                } else {
-                    // Object or sub-index does not exist
-                    if od.read_object(req.index).is_none() {
-                        (Some(0x0602_0000), Vec::new())
-                    } else {
-                        (Some(0x0609_0011), Vec::new())
-                    }
+                match write_od_value(od, req.index, req.sub_index, &req.data) {
+                    Ok(()) => (None, Vec::new()),
+                    Err(code) => (Some(code), Vec::new()),
                 }
             }
             _ => (Some(0x0504_0001), Vec::new()), // Unsupported command / Command ID not valid
@@ -153,7 +160,9 @@ impl EmbeddedSdoServer {
         let response_cmd = PdoSdoCommand {
             sequence_header: PdoSequenceLayerHeader {
                 sequence_number: response_seq_num,
-                connection_state: if abort_code.is_some() { 3 } else { 2 }, // 3=Error, 2=Valid
+                // Command aborts travel over a valid connection; con=3 is
+                // reserved for sequence errors.
+                connection_state: CON_VALID,
             },
             transaction_id: req.transaction_id,
             is_response: true,
@@ -168,7 +177,7 @@ impl EmbeddedSdoServer {
             index: req.index,
             sub_index: req.sub_index,
             data: if let Some(code) = abort_code {
-                (code as u32).to_le_bytes().to_vec()
+                code.to_le_bytes().to_vec()
             } else {
                 data
             },
@@ -178,14 +187,14 @@ impl EmbeddedSdoServer {
     }
 
     /// Retrieves the pending response payload for a TPDO container.
-    /// This consumes the pending response.
+    /// The response is repeated until the next request has been processed.
     pub fn get_pending_response(&mut self, channel_index: u16, container_len: usize) -> Vec<u8> {
         let conn = self
             .connections
             .entry(channel_index)
             .or_insert_with(Default::default);
 
-        if let Some(payload) = conn.pending_response.take() {
+        if let Some(payload) = conn.pending_response.as_ref() {
             if payload.len() > container_len {
                 error!(
                     "[SDO-PDO] Server: Response for {:#06X} ({} bytes) exceeds container length ({} bytes).",
@@ -197,7 +206,7 @@ impl EmbeddedSdoServer {
                 payload[..container_len].to_vec()
             } else {
                 // Pad the response to fill the container
-                let mut padded_payload = payload;
+                let mut padded_payload = payload.clone();
                 padded_payload.resize(container_len, 0);
                 padded_payload
             }
@@ -342,8 +351,56 @@ mod tests {
         server.handle_request(0x1200, &payload, &mut od);
         let resp2 = server.get_pending_response(0x1200, 20);
 
-        // Since pending_response was taken, it will be NIL (zeros).
-        assert!(resp2.iter().all(|&x| x == 0));
+        // The last response is repeated, not processed again.
+        assert_eq!(resp2, resp1);
+    }
+
+    #[test]
+    fn test_sequence_mismatch_sends_error_response() {
+        let mut server = EmbeddedSdoServer::new();
+        let mut od = create_test_od();
+
+        let mut request = PdoSdoCommand {
+            sequence_header: PdoSequenceLayerHeader {
+                sequence_number: 7,
+                connection_state: 2,
+            },
+            transaction_id: 3,
+            is_response: false,
+            is_aborted: false,
+            segmentation: Segmentation::Expedited,
+            valid_payload_length: 0,
+            command_id: CommandId::ReadByIndex,
+            index: 0x2000,
+            sub_index: 0,
+            data: vec![],
+        };
+        server.handle_request(0x1200, &request.serialize(), &mut od);
+
+        // Skip a sequence number.
+        request.sequence_header.sequence_number = 9;
+        request.transaction_id = 4;
+        server.handle_request(0x1200, &request.serialize(), &mut od);
+        let response =
+            PdoSdoCommand::deserialize(&server.get_pending_response(0x1200, 20)).unwrap();
+        assert_eq!(response.sequence_header.connection_state, 3);
+        assert_eq!(response.sequence_header.sequence_number, 7);
+        assert_eq!(response.transaction_id, 4);
+
+        // Idle NIL commands and empty containers are ignored.
+        server.handle_request(0x1200, &[0; 20], &mut od);
+        request.command_id = CommandId::Nil;
+        server.handle_request(0x1200, &request.serialize(), &mut od);
+
+        // The client resynchronizes with the next sequence number.
+        request.command_id = CommandId::ReadByIndex;
+        request.sequence_header.sequence_number = 8;
+        server.handle_request(0x1200, &request.serialize(), &mut od);
+        let response =
+            PdoSdoCommand::deserialize(&server.get_pending_response(0x1200, 20)).unwrap();
+        assert_eq!(response.sequence_header.connection_state, 2);
+        assert_eq!(response.sequence_header.sequence_number, 8);
+        assert_eq!(response.data, 0xDEADBEEFu32.to_le_bytes().to_vec());
     }
 
     #[test]
@@ -3,9 +3,11 @@ use log::{error, info, warn};
 use crate::PowerlinkError;
 use crate::od::ObjectDictionary;
 use crate::sdo::command::{
-    CommandLayerHeader, ReadByIndexRequest, ReadByNameRequest, ReadMultipleParamRequest,
+    CommandId, CommandLayerHeader, MaxSegmentSizePayload, MultipleParamReadResult,
+    ReadByIndexRequest, ReadByNameRequest, ReadMultipleParamRequest, ReadMultipleParamResponse,
     SdoCommand, Segmentation, WriteByIndexRequest, WriteByNameRequest,
 };
+use crate::sdo::segment;
 use crate::sdo::sequence_handler::SdoSequenceHandler;
 use crate::sdo::state::{SdoServerState, SdoTransferState};
 
@@ -13,7 +15,6 @@ use crate::sdo::state::{SdoServerState, SdoTransferState};
 use alloc::vec::Vec;
 
 use crate::sdo::OD_IDX_SDO_TIMEOUT;
-const MAX_EXPEDITED_PAYLOAD: usize = 1452;
 
 pub(super) fn handle_read_by_index(
     handler: &mut SdoSequenceHandler,
@@ -31,7 +32,7 @@ pub(super) fn handle_read_by_index(
             match od.read(req.index, req.sub_index) {
                 Some(value) => {
                     let payload = value.serialize();
-                    if payload.len() <= MAX_EXPEDITED_PAYLOAD {
+                    if payload.len() <= handler.segment_size(od) {
                         info!("Responding with expedited read of {} bytes.", payload.len());
                         response_header.segment_size = payload.len() as u16;
                         SdoCommand {
@@ -51,6 +52,7 @@ pub(super) fn handle_read_by_index(
                             deadline_us: None,
                             retransmissions_left: 0,
                             last_sent_segment: None,
+                            segment_size: handler.segment_size(od),
                         };
                         // Get the first segment
                         let (response_command, is_last) =
@@ -111,6 +113,7 @@ pub(super) fn handle_write_by_index(
                         deadline_us: None,
                         retransmissions_left: 0,
                         last_sent_segment: None,
+                        segment_size: handler.segment_size(od),
                     };
 
                     match state.perform_od_write(od) {
@@ -153,6 +156,7 @@ pub(super) fn handle_write_by_index(
                         deadline_us: Some(current_time_us + timeout_ms * 1000),
                         retransmissions_left: 0, // Not applicable for server download
                         last_sent_segment: None, // Not applicable for server download
+                        segment_size: handler.segment_size(od),
                     });
                     SdoCommand {
                         header: response_header, // Send ACK response
@@ -355,7 +359,7 @@ pub(super) fn handle_read_all_by_index(
                         }
                     }
                     // Now send this payload, either expedited or segmented
-                    if payload.len() <= MAX_EXPEDITED_PAYLOAD {
+                    if payload.len() <= handler.segment_size(od) {
                         response_header.segment_size = payload.len() as u16;
                         SdoCommand {
                             header: response_header,
@@ -373,6 +377,7 @@ pub(super) fn handle_read_all_by_index(
                             deadline_us: None,
                             retransmissions_left: 0,
                             last_sent_segment: None,
+                            segment_size: handler.segment_size(od),
                         };
                         let (response_command, is_last) =
                             transfer_state.get_next_upload_segment(od, current_time_us);
@@ -408,22 +413,14 @@ pub(super) fn handle_read_multiple_params(
                 "Processing SDO ReadMultipleParamByIndex for {} entries",
                 req.entries.len()
             );
-            let mut payload = Vec::new();
-            // Iterate using a reference to avoid moving req.entries
+            let mut results = Vec::with_capacity(req.entries.len());
             for entry in &req.entries {
                 match od.read(entry.index, entry.sub_index) {
-                    Some(value) => {
-                        let data = value.serialize();
-                        // Add Sub-Abort=0, reserved=0, Padding=0 before data length and data
-                        payload.push(0u8); // SubAbort=0, reserved=0
-                        payload.push(0u8); // Padding=0
-                        payload.extend_from_slice(&(data.len() as u16).to_le_bytes()); // Use u16 for length field per spec
-                        payload.extend_from_slice(&data);
-                        // Ensure 4-byte alignment for the next entry
-                        while payload.len() % 4 != 0 {
-                            payload.push(0u8); // Padding byte
-                        }
-                    }
+                    Some(value) => results.push(MultipleParamReadResult {
+                        index: entry.index,
+                        sub_index: entry.sub_index,
+                        result: Ok(value.serialize()),
+                    }),
                     None => {
                         // If *any* entry is not found, abort the whole request
                         let abort_code = if od.read_object(entry.index).is_none() {
@@ -435,14 +432,10 @@ pub(super) fn handle_read_multiple_params(
                     }
                 }
             }
-            // Prepend total number of entries
-            let mut final_payload = Vec::new();
-            // Use req.entries.len() here, which is now valid
-            final_payload.extend_from_slice(&(req.entries.len() as u32).to_le_bytes()); // Number of entries as U32
-            final_payload.append(&mut payload);
+            let final_payload = ReadMultipleParamResponse { entries: results }.to_payload();
 
             // Now send this final_payload, either expedited or segmented
-            if final_payload.len() <= MAX_EXPEDITED_PAYLOAD {
+            if final_payload.len() <= handler.segment_size(od) {
                 response_header.segment_size = final_payload.len() as u16;
                 SdoCommand {
                     header: response_header,
@@ -460,6 +453,7 @@ pub(super) fn handle_read_multiple_params(
                     deadline_us: None,
                     retransmissions_left: 0,
                     last_sent_segment: None,
+                    segment_size: handler.segment_size(od),
                 };
                 let (response_command, is_last) =
                     transfer_state.get_next_upload_segment(od, current_time_us);
@@ -477,25 +471,143 @@ pub(super) fn handle_read_multiple_params(
 }
 
 pub(super) fn handle_max_segment_size(
+    handler: &mut SdoSequenceHandler,
     command: SdoCommand,
     mut response_header: CommandLayerHeader,
+    od: &ObjectDictionary,
 ) -> SdoCommand {
     info!("Processing SDO MaxSegmentSize command");
-    // We respond with our maximum supported size for a single SDO segment payload.
-    let max_size_server = MAX_EXPEDITED_PAYLOAD as u16; // Our server capability
-    // Extract client's max size from payload
-    let max_size_client = if command.payload.len() >= 2 {
-        u16::from_le_bytes(command.payload[0..2].try_into().unwrap_or([0, 0]))
-    } else {
-        0 // Invalid request payload
+    let request = match MaxSegmentSizePayload::from_payload(&command.payload) {
+        Ok(request) => request,
+        Err(_) => return handler.abort(command.header.transaction_id, 0x0504_0001), // Command specifier invalid
     };
+    // We respond with our maximum supported size for a single SDO segment payload.
+    let local = handler.local_segment_size(od);
+    let negotiated = segment::negotiate_segment_size(local, request.client);
+    info!(
+        "SDO segment size: client {}, server {}, using {}",
+        request.client, local, negotiated
+    );
+    handler.set_segment_size(negotiated);
 
-    response_header.segment_size = 4; // Response contains MSS Client + MSS Server (2+2 bytes)
-    let response_payload = [max_size_client.to_le_bytes(), max_size_server.to_le_bytes()].concat();
-
+    let payload = MaxSegmentSizePayload {
+        client: request.client,
+        server: local.min(u16::MAX as usize) as u16,
+    }
+    .to_payload();
+    response_header.command_id = CommandId::MaxSegmentSize;
+    response_header.segment_size = payload.len() as u16; // MSS Client + MSS Server (2+2 bytes)
     SdoCommand {
         header: response_header,
         data_size: None,
-        payload: response_payload,
+        payload,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::basic::MacAddress;
+    use crate::od::{AccessType, Object, ObjectEntry, ObjectValue};
+    use crate::sdo::server::SdoClientInfo;
+    use crate::types::{BitString, NodeId};
+    use alloc::vec;
+
+    fn create_od() -> ObjectDictionary<'static> {
+        let mut od = ObjectDictionary::new(None);
+        od.insert(
+            0x2000,
+            ObjectEntry {
+                object: Object::Record(vec![
+                    ObjectValue::Integer24(-2),
+                    ObjectValue::Unsigned40(0x12_3456_789A),
+                    ObjectValue::BitString(BitString::new(12, 0xABC)),
+                ]),
+                access: Some(AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od
+    }
+
+    fn request(command_id: CommandId, payload: Vec<u8>) -> SdoCommand {
+        SdoCommand {
+            header: CommandLayerHeader {
+                transaction_id: 1,
+                segmentation: Segmentation::Expedited,
+                command_id,
+                segment_size: payload.len() as u16,
+                ..Default::default()
+            },
+            data_size: None,
+            payload,
+        }
+    }
+
+    fn read(handler: &mut SdoSequenceHandler, od: &ObjectDictionary, sub_index: u8) -> Vec<u8> {
+        let payload = ReadByIndexRequest {
+            index: 0x2000,
+            sub_index,
+        }
+        .to_payload();
+        let command = request(CommandId::ReadByIndex, payload);
+        let header = command.header;
+        let response = handle_read_by_index(handler, command, header, od, 0);
+        assert!(!response.header.is_aborted);
+        response.payload
+    }
+
+    fn write(
+        handler: &mut SdoSequenceHandler,
+        od: &mut ObjectDictionary,
+        sub_index: u8,
+        data: &[u8],
+    ) -> SdoCommand {
+        let mut payload = vec![0x00, 0x20, sub_index, 0x00];
+        payload.extend_from_slice(data);
+        let command = request(CommandId::WriteByIndex, payload);
+        let header = command.header;
+        handle_write_by_index(handler, command, header, od, 0)
+    }
+
+    #[test]
+    fn test_expedited_transfer_of_narrow_types() {
+        let mut handler = SdoSequenceHandler::new(SdoClientInfo::Asnd {
+            source_node_id: NodeId(1),
+            source_mac: MacAddress::default(),
+        });
+        let mut od = create_od();
+
+        // Uploads carry exactly the encoded width of the data type.
+        assert_eq!(read(&mut handler, &od, 1), vec![0xFE, 0xFF, 0xFF]);
+        assert_eq!(
+            read(&mut handler, &od, 2),
+            vec![0x9A, 0x78, 0x56, 0x34, 0x12]
+        );
+        assert_eq!(read(&mut handler, &od, 3), vec![0xBC, 0x0A]);
+
+        // Downloads are sign-extended or masked to the declared width.
+        let response = write(&mut handler, &mut od, 1, &[0x00, 0x00, 0x80]);
+        assert!(!response.header.is_aborted);
+        assert_eq!(
+            od.read(0x2000, 1).unwrap().as_ref(),
+            &ObjectValue::Integer24(-0x80_0000)
+        );
+        let response = write(&mut handler, &mut od, 2, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(!response.header.is_aborted);
+        assert_eq!(
+            od.read(0x2000, 2).unwrap().as_ref(),
+            &ObjectValue::Unsigned40(0xFF_FFFF_FFFF)
+        );
+        let response = write(&mut handler, &mut od, 3, &[0x21, 0xF3]);
+        assert!(!response.header.is_aborted);
+        assert_eq!(
+            od.read(0x2000, 3).unwrap().as_ref(),
+            &ObjectValue::BitString(BitString::new(12, 0x321))
+        );
+
+        // A 40-bit object does not accept a 64-bit value.
+        let response = write(&mut handler, &mut od, 2, &[0; 8]);
+        assert!(response.header.is_aborted);
     }
 }
@@ -8,6 +8,7 @@ pub mod embedded;
 pub mod embedded_client;
 pub mod embedded_server;
 mod handlers;
+pub mod segment;
 pub mod sequence;
 pub mod sequence_handler;
 pub mod server;
@@ -17,9 +18,13 @@ pub mod transport;
 pub mod udp;
 
 pub use client::SdoClient;
+pub use client_connection::{
+    DEFAULT_SDO_WINDOW_SIZE, SdoClientRequest, SdoClientResponse, SdoClientTransport,
+    SdoTransferResult,
+};
 pub use client_manager::SdoClientManager;
 pub use command::SdoCommandHandler;
-pub use embedded_client::EmbeddedSdoClient;
+pub use embedded_client::{EmbeddedSdoClient, EmbeddedSdoResult};
 pub use embedded_server::EmbeddedSdoServer;
 pub use server::SdoServer;
 #[cfg(feature = "sdo-udp")]
@@ -0,0 +1,81 @@
+// crates/powerlink-rs/src/sdo/segment.rs
+//! Segment size limits for SDO transfers.
+//!
+//! The data carried by a single SDO frame is bounded by the transport: the
+//! asynchronous MTU (0x1F98/8) for SDO over ASnd, or the UDP datagram size for
+//! SDO over UDP. Client and server agree on the smaller of their limits with
+//! the MaxSegmentSize command (0x70) when a connection is opened.
+
+use crate::od::{ObjectDictionary, constants};
+use crate::types::{C_DLL_MAX_ASYNC_MTU, C_DLL_MIN_ASYNC_MTU};
+
+/// POWERLINK ASnd header: MessageType, Destination, Source and ServiceID.
+const ASND_HEADER_SIZE: usize = 4;
+
+/// SDO bytes in a frame that are not segment data: sequence layer header (4),
+/// fixed command layer header (8) and the DataSize field of Initiate frames (4).
+const SDO_FRAME_OVERHEAD: usize = 16;
+
+/// Largest segment over SDO/UDP:
+/// 1500 (Ethernet MTU) - 20 (IP) - 8 (UDP) - 4 (POWERLINK prefix) - 16 (SDO) = 1452.
+pub const SDO_UDP_MAX_SEGMENT_SIZE: usize = 1452;
+
+/// Smallest segment size any POWERLINK node supports (minimum AsyncMTU).
+pub const SDO_MIN_SEGMENT_SIZE: usize = C_DLL_MIN_ASYNC_MTU - ASND_HEADER_SIZE - SDO_FRAME_OVERHEAD;
+
+/// Returns the largest segment that fits into an ASnd frame, based on the
+/// AsyncMTU configured in 0x1F98/8.
+pub fn asnd_max_segment_size(od: &ObjectDictionary) -> usize {
+    let mtu = od
+        .read_u16(
+            constants::IDX_NMT_CYCLE_TIMING_REC,
+            constants::SUBIDX_NMT_CYCLE_TIMING_ASYNC_MTU_U16,
+        )
+        .map_or(C_DLL_MAX_ASYNC_MTU, usize::from)
+        .clamp(C_DLL_MIN_ASYNC_MTU, C_DLL_MAX_ASYNC_MTU);
+    mtu - ASND_HEADER_SIZE - SDO_FRAME_OVERHEAD
+}
+
+/// Combines the local limit with the size announced by the peer.
+/// A peer announcing 0 has no limit of its own.
+pub fn negotiate_segment_size(local: usize, peer: u16) -> usize {
+    match peer as usize {
+        0 => local,
+        peer => local.min(peer).max(SDO_MIN_SEGMENT_SIZE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::od::{Object, ObjectEntry, ObjectValue};
+    use alloc::vec;
+
+    #[test]
+    fn test_asnd_segment_size_follows_async_mtu() {
+        let mut od = ObjectDictionary::new(None);
+        // No 0x1F98 entry: the maximum MTU is assumed.
+        assert_eq!(asnd_max_segment_size(&od), 1480);
+
+        let mut sub_entries = vec![ObjectValue::Unsigned16(0); 9];
+        sub_entries[constants::SUBIDX_NMT_CYCLE_TIMING_ASYNC_MTU_U16 as usize - 1] =
+            ObjectValue::Unsigned16(300);
+        od.insert(
+            constants::IDX_NMT_CYCLE_TIMING_REC,
+            ObjectEntry {
+                object: Object::Record(sub_entries),
+                ..Default::default()
+            },
+        );
+        assert_eq!(asnd_max_segment_size(&od), SDO_MIN_SEGMENT_SIZE);
+    }
+
+    #[test]
+    fn test_negotiate_segment_size() {
+        assert_eq!(negotiate_segment_size(1480, 0), 1480);
+        assert_eq!(negotiate_segment_size(1480, 1452), 1452);
+        assert_eq!(negotiate_segment_size(1452, 2000), 1452);
+        // A peer cannot force segments below the protocol minimum.
+        assert_eq!(negotiate_segment_size(1480, 10), SDO_MIN_SEGMENT_SIZE);
+    }
+}
@@ -62,6 +62,20 @@ impl TryFrom<u8> for SendConnState {
     }
 }
 
+/// Number of distinct sequence numbers (6 bits).
+pub const SEQUENCE_NUMBER_MODULO: u8 = 64;
+
+/// Largest number of unacknowledged frames a sender may have in flight.
+///
+/// Half the sequence number space, so that a receiver can always tell a
+/// retransmitted (old) frame from a frame that arrived after a gap.
+pub const MAX_SEQUENCE_WINDOW: u8 = SEQUENCE_NUMBER_MODULO / 2 - 1;
+
+/// Returns how many steps `to` is ahead of `from` in the 6-bit sequence space.
+pub fn sequence_distance(from: u8, to: u8) -> u8 {
+    to.wrapping_sub(from) % SEQUENCE_NUMBER_MODULO
+}
+
 /// Represents the 4-byte header for the Asynchronous SDO Sequence Layer.
 ///
 /// (Reference: EPSG DS 301, Table 52 and 53)
@@ -136,6 +150,15 @@ mod tests {
         assert_eq!(original_header, deserialized_header);
     }
 
+    #[test]
+    fn test_sequence_distance_wraps() {
+        assert_eq!(sequence_distance(10, 12), 2);
+        assert_eq!(sequence_distance(62, 1), 3);
+        assert_eq!(sequence_distance(5, 5), 0);
+        // An older number is almost a full cycle "ahead".
+        assert_eq!(sequence_distance(5, 4), 63);
+    }
+
     #[test]
     fn test_conn_state_try_from() {
         assert_eq!(
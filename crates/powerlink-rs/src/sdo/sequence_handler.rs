@@ -6,7 +6,10 @@ use crate::sdo::command::{
     CommandId, CommandLayerHeader, SdoCommand, SdoCommandHandler, Segmentation,
 };
 use crate::sdo::handlers;
-use crate::sdo::sequence::{ReceiveConnState, SendConnState, SequenceLayerHeader};
+use crate::sdo::segment;
+use crate::sdo::sequence::{
+    MAX_SEQUENCE_WINDOW, ReceiveConnState, SendConnState, SequenceLayerHeader, sequence_distance,
+};
 use crate::sdo::server::SdoClientInfo;
 use crate::sdo::state::SdoServerState;
 use crate::sdo::transport::SdoResponseData;
@@ -22,6 +25,8 @@ pub struct SdoSequenceHandler {
     last_received_sequence_number: u8,
     /// Information about the client this handler is managing.
     client_info: SdoClientInfo,
+    /// Segment size agreed with the client via MaxSegmentSize, if any.
+    segment_size: Option<usize>,
 }
 
 impl SdoSequenceHandler {
@@ -31,6 +36,7 @@ impl SdoSequenceHandler {
             send_sequence_number: 0,
             last_received_sequence_number: 63, // Set to 63 (equiv to -1) so first received seq (0) is valid
             client_info,
+            segment_size: None,
         }
     }
 
@@ -54,6 +60,28 @@ impl SdoSequenceHandler {
         self.state = SdoServerState::Closed;
         self.send_sequence_number = 0;
         self.last_received_sequence_number = 63;
+        self.segment_size = None;
+    }
+
+    /// Returns the largest segment the transport of this connection can carry.
+    pub fn local_segment_size(&self, od: &ObjectDictionary) -> usize {
+        match self.client_info {
+            SdoClientInfo::Asnd { .. } => segment::asnd_max_segment_size(od),
+            #[cfg(feature = "sdo-udp")]
+            SdoClientInfo::Udp { .. } => segment::SDO_UDP_MAX_SEGMENT_SIZE,
+        }
+    }
+
+    /// Returns the segment size used for responses: the negotiated size, or
+    /// the transport limit if the client did not negotiate.
+    pub fn segment_size(&self, od: &ObjectDictionary) -> usize {
+        self.segment_size
+            .unwrap_or_else(|| self.local_segment_size(od))
+    }
+
+    /// Stores the segment size agreed with the client.
+    pub(super) fn set_segment_size(&mut self, segment_size: usize) {
+        self.segment_size = Some(segment_size);
     }
 
     /// Increments the send sequence number, wrapping at 64.
@@ -71,6 +99,20 @@ impl SdoSequenceHandler {
         self.last_received_sequence_number
     }
 
+    /// Returns true if the frame repeats one that was already received,
+    /// e.g. a retransmission of a window the client believes was lost.
+    fn is_stale_frame(&self, request: &SequenceLayerHeader) -> bool {
+        matches!(
+            self.state,
+            SdoServerState::Established
+                | SdoServerState::SegmentedDownload(_)
+                | SdoServerState::SegmentedUpload(_)
+        ) && sequence_distance(
+            request.send_sequence_number,
+            self.last_received_sequence_number,
+        ) <= MAX_SEQUENCE_WINDOW
+    }
+
     /// Creates an SDO Abort command. Resets internal state to Closed.
     pub(super) fn abort(&mut self, transaction_id: u8, abort_code: u32) -> SdoCommand {
         error!(
@@ -246,8 +288,27 @@ impl SdoSequenceHandler {
         }
 
         debug!("Parsed SDO sequence header: {:?}", sequence_header);
+        let is_stale = self.is_stale_frame(&sequence_header);
         let mut response_header = self.process_sequence_layer(sequence_header)?;
 
+        // Repeated frames are only acknowledged, and a gap in the sequence
+        // asks the client to resend from the last frame we accepted. In both
+        // cases the command layer must not see the frame.
+        if is_stale || response_header.receive_con == ReceiveConnState::ErrorResponse {
+            return Ok(SdoResponseData {
+                client_info: self.client_info,
+                seq_header: response_header,
+                command: SdoCommand {
+                    header: CommandLayerHeader {
+                        is_response: true,
+                        ..Default::default()
+                    },
+                    data_size: None,
+                    payload: Vec::new(),
+                },
+            });
+        }
+
         // Handle ACK-only or NIL command frames (no command payload)
         // This is valid for Initialization Handshake (empty payload) or simple ACKs.
         if command_payload.is_empty() {
@@ -330,11 +391,24 @@ impl SdoSequenceHandler {
                 response_header.receive_sequence_number = self.current_receive_sequence();
 
                 // If this is the end of a transfer, set state to Closed for pruning.
-                // Do NOT close if we are in Opening state (Handshake response), even if segment is Expedited.
-                if *self.state() != SdoServerState::Opening
-                    && (response_command.header.segmentation == Segmentation::Expedited
-                        || response_command.header.segmentation == Segmentation::Complete
-                        || response_command.header.is_aborted)
+                // Do NOT close if we are in Opening state (Handshake response), even if segment
+                // is Expedited, nor while a segmented transfer is still running (segment ACKs
+                // are plain Expedited responses).
+                let transfer_running = matches!(
+                    self.state(),
+                    SdoServerState::Opening
+                        | SdoServerState::SegmentedDownload(_)
+                        | SdoServerState::SegmentedUpload(_)
+                );
+                // The MaxSegmentSize exchange belongs to connection setup; the
+                // transfer it prepares follows on the same connection.
+                let is_negotiation =
+                    response_command.header.command_id == CommandId::MaxSegmentSize;
+                if response_command.header.is_aborted
+                    || (!transfer_running
+                        && !is_negotiation
+                        && (response_command.header.segmentation == Segmentation::Expedited
+                            || response_command.header.segmentation == Segmentation::Complete))
                 {
                     self.state = SdoServerState::Closed;
                 }
@@ -493,7 +567,7 @@ impl SdoSequenceHandler {
                 current_time_us,
             ),
             CommandId::MaxSegmentSize => {
-                handlers::handle_max_segment_size(command, response_header)
+                handlers::handle_max_segment_size(self, command, response_header, od)
             }
             // Delegate complex commands to the custom handler
             CommandId::WriteAllByIndex => command_handler.handle_write_all_by_index(command, od),
@@ -554,9 +628,12 @@ impl SdoSequenceHandler {
                 }
             }
             SdoServerState::Opening => {
-                // Client confirms connection with ConnectionValid and ACKs our Initialization seq num (0)
-                if request.send_con == SendConnState::ConnectionValid
-                    && request.receive_sequence_number == self.send_sequence_number
+                // Client confirms connection with ConnectionValid and ACKs our Initialization seq num (0).
+                // The confirming frame may already carry the first command and request an ACK.
+                if matches!(
+                    request.send_con,
+                    SendConnState::ConnectionValid | SendConnState::ConnectionValidAckRequest
+                ) && request.receive_sequence_number == self.send_sequence_number
                 {
                     self.state = SdoServerState::Established;
                     self.last_received_sequence_number = request.send_sequence_number;
@@ -592,20 +669,23 @@ impl SdoSequenceHandler {
                 let expected_seq = self.last_received_sequence_number.wrapping_add(1) % 64;
 
                 // Handle retransmission request from client is handled in `handle_request`
-                // Handle duplicate frame from client
-                if request.send_sequence_number == self.last_received_sequence_number {
+                // Handle duplicate frame from client (anything within the window
+                // behind the last accepted frame)
+                if sequence_distance(
+                    request.send_sequence_number,
+                    self.last_received_sequence_number,
+                ) <= MAX_SEQUENCE_WINDOW
+                {
                     debug!(
                         "Duplicate SDO frame received (Seq: {}). Ignoring command, sending ACK.",
                         request.send_sequence_number
                     );
-                    // Just send ACK, don't process command layer again
+                    // Just send ACK, `handle_request` skips the command layer
                     response.receive_con = ReceiveConnState::ConnectionValid;
                     response.receive_sequence_number = self.last_received_sequence_number;
                     response.send_con = SendConnState::ConnectionValid;
                     // Use the *same* send sequence number as the previous response
                     response.send_sequence_number = self.send_sequence_number;
-                // Return immediately, skipping command processing
-                // Let the command layer handle the empty payload.
                 }
                 // Handle out-of-order/lost frame from client
                 else if request.send_sequence_number != expected_seq {
@@ -615,14 +695,10 @@ impl SdoSequenceHandler {
                     );
                     // Request retransmission from the client starting after the last good one.
                     // Do not update server state or sequence numbers on error
-                    // Send ErrorResponse
                     response.receive_con = ReceiveConnState::ErrorResponse;
                     response.receive_sequence_number = self.last_received_sequence_number;
                     response.send_con = SendConnState::ConnectionValid;
                     response.send_sequence_number = self.send_sequence_number; // Resend our last frame's number
-                    return Err(PowerlinkError::SdoSequenceError(
-                        "SDO sequence number mismatch.",
-                    )); // Signal error upstream
                 }
                 // --- Sequence OK ---
                 else {
@@ -874,8 +950,18 @@ mod tests {
             0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
         ];
 
-        let result = handler.handle_request(&req_gap, &mut od, 0, &mut cmd_handler);
-
-        assert!(result.is_err());
+        let response = handler
+            .handle_request(&req_gap, &mut od, 0, &mut cmd_handler)
+            .expect("Gap handling failed");
+
+        // The server asks for a retransmission after the last good frame
+        // and does not process the command.
+        assert_eq!(
+            response.seq_header.receive_con,
+            ReceiveConnState::ErrorResponse
+        );
+        assert_eq!(response.seq_header.receive_sequence_number, 63);
+        assert!(response.command.payload.is_empty());
+        assert_eq!(*handler.state(), SdoServerState::Established);
     }
 }
@@ -1,14 +1,11 @@
 // crates/powerlink-rs/src/sdo/state.rs
-use crate::od::ObjectDictionary;
+use crate::od::{ObjectDictionary, WriteSource};
 use crate::sdo::command::{CommandId, CommandLayerHeader, SdoCommand, Segmentation};
 use crate::sdo::{OD_IDX_SDO_RETRIES, OD_IDX_SDO_TIMEOUT};
 use crate::{PowerlinkError, od::ObjectValue};
 use alloc::vec::Vec;
 use log::{debug, error, info};
 
-// Constants moved from server.rs
-const MAX_EXPEDITED_PAYLOAD: usize = 1452;
-
 /// The state of an SDO connection from the server's perspective.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum SdoServerState {
@@ -35,6 +32,8 @@ pub struct SdoTransferState {
     pub(super) deadline_us: Option<u64>,
     pub(super) retransmissions_left: u32,
     pub(super) last_sent_segment: Option<SdoCommand>,
+    /// Maximum data bytes per upload segment (see `sdo::segment`).
+    pub(super) segment_size: usize,
 }
 
 impl SdoTransferState {
@@ -55,7 +54,7 @@ impl SdoTransferState {
             segment_size: 0,
         };
 
-        let chunk_size = MAX_EXPEDITED_PAYLOAD;
+        let chunk_size = self.segment_size;
         let remaining = self.total_size.saturating_sub(self.offset);
         let current_chunk_size = chunk_size.min(remaining);
         // Clone the data slice to be sent.
@@ -180,43 +179,63 @@ impl SdoTransferState {
             self.index,
             self.sub_index
         );
-        // Get a clone of the template to avoid double mutable borrow
-        match od
-            .read(self.index, self.sub_index)
-            .map(|cow| cow.into_owned())
-        {
-            Some(type_template) => {
-                match ObjectValue::deserialize(&self.data_buffer, &type_template) {
-                    Ok(value) => {
-                        // Double-check type compatibility after deserialize, before writing
-                        if core::mem::discriminant(&value)
-                            != core::mem::discriminant(&type_template)
-                        {
-                            error!(
-                                "Type mismatch after deserialize (write_to_od): Expected {:?}, got {:?} for 0x{:04X}/{}",
-                                type_template, value, self.index, self.sub_index
-                            );
-                            return Err(0x0607_0010); // Type mismatch
-                        }
-                        match od.write(self.index, self.sub_index, value) {
-                            Ok(_) => Ok(()),
-                            // Map OD write errors (which use PowerlinkError) to SDO Abort Codes
-                            Err(PowerlinkError::StorageError("Object is read-only")) => {
-                                Err(0x0601_0002)
-                            } // Attempt to write read-only
-                            Err(PowerlinkError::TypeMismatch) => Err(0x0607_0010), // Should be caught earlier, but safety check
-                            Err(PowerlinkError::ValidationError(_)) => Err(0x0609_0030), // Value range exceeded (e.g., PDO validation)
-                            Err(_) => Err(0x0800_0020), // Data cannot be transferred or stored
-                        }
-                    }
-                    Err(PowerlinkError::BufferTooShort) => Err(0x0607_0013), // Length too low
-                    Err(_) => Err(0x0607_0010), // Data type mismatch or length error during deserialize
-                }
-            }
-            // Distinguish between Object not found and Sub-index not found
-            None if od.read_object(self.index).is_none() => Err(0x0602_0000), // Object does not exist
-            None => Err(0x0609_0011), // Sub-index does not exist
-        }
+        write_od_value(od, self.index, self.sub_index, &self.data_buffer)
+    }
+}
+
+/// Deserializes `data` using the current value as a type template and writes
+/// it to the Object Dictionary.
+///
+/// Returns the SDO abort code if the write is rejected. Shared by the
+/// asynchronous and the embedded SDO servers.
+pub(crate) fn write_od_value(
+    od: &mut ObjectDictionary,
+    index: u16,
+    sub_index: u8,
+    data: &[u8],
+) -> Result<(), u32> {
+    // Get a clone of the template to avoid double mutable borrow
+    let Some(type_template) = od.read(index, sub_index).map(|cow| cow.into_owned()) else {
+        // Distinguish between Object not found and Sub-index not found
+        return if od.read_object(index).is_none() {
+            Err(0x0602_0000) // Object does not exist
+        } else {
+            Err(0x0609_0011) // Sub-index does not exist
+        };
+    };
+    // Fixed-size types must be written with their exact length.
+    if let Some(size) = type_template.fixed_size().filter(|&size| data.len() > size) {
+        error!(
+            "SDO write to 0x{:04X}/{} too long: expected {} bytes, got {}",
+            index,
+            sub_index,
+            size,
+            data.len()
+        );
+        return Err(0x0607_0012); // Length too high
+    }
+    match ObjectValue::deserialize(data, &type_template) {
+        Ok(value) => od
+            .write_with_source(index, sub_index, value, WriteSource::Sdo)
+            .map_err(od_write_abort_code),
+        Err(PowerlinkError::BufferTooShort) => Err(0x0607_0013), // Length too low
+        Err(_) => Err(0x0607_0010), // Data type mismatch or length error during deserialize
+    }
+}
+
+/// Maps an Object Dictionary write error to the matching SDO abort code.
+pub(crate) fn od_write_abort_code(error: PowerlinkError) -> u32 {
+    match error {
+        PowerlinkError::StorageError("Object is read-only") => 0x0601_0002, // Attempt to write read-only
+        PowerlinkError::ObjectNotFound => 0x0602_0000,
+        PowerlinkError::SubObjectNotFound => 0x0609_0011,
+        PowerlinkError::TypeMismatch => 0x0607_0010,
+        PowerlinkError::ValueTooHigh => 0x0609_0031,
+        PowerlinkError::ValueTooLow => 0x0609_0032,
+        PowerlinkError::WriteRejected(code) => code,
+        // Not an allowed value, or rejected by validation (e.g. PDO mapping)
+        PowerlinkError::InvalidValue | PowerlinkError::ValidationError(_) => 0x0609_0030,
+        _ => 0x0800_0020, // Data cannot be transferred or stored
     }
 }
 
@@ -254,6 +273,7 @@ mod tests {
             deadline_us: None,
             retransmissions_left: 0,
             last_sent_segment: None,
+            segment_size: 1452,
         };
 
         // 1. Get Initiate Frame
@@ -286,6 +306,7 @@ mod tests {
             deadline_us: None,
             retransmissions_left: 0,
             last_sent_segment: None,
+            segment_size: 1452,
         };
 
         // 2. Get Next (and Last) Segment
@@ -314,6 +335,7 @@ mod tests {
             deadline_us: None,
             retransmissions_left: 0,
             last_sent_segment: None,
+            segment_size: 1452,
         };
 
         // Try to feed 150 bytes
@@ -356,6 +378,7 @@ mod tests {
             deadline_us: None,
             retransmissions_left: 0,
             last_sent_segment: None,
+            segment_size: 1452,
         };
 
         // Feed all 10 bytes
@@ -383,4 +406,75 @@ mod tests {
             panic!("OD write failed");
         }
     }
+
+    #[test]
+    fn test_write_od_value_abort_codes() {
+        let mut od = create_od();
+        od.insert(
+            0x2001,
+            crate::od::ObjectEntry {
+                object: crate::od::Object::Variable(ObjectValue::Unsigned16(5)),
+                access: Some(crate::od::AccessType::ReadWrite),
+                value_range: Some(crate::od::ValueRange {
+                    min: ObjectValue::Unsigned16(1),
+                    max: ObjectValue::Unsigned16(10),
+                    values: vec![],
+                }),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(write_od_value(&mut od, 0x2001, 0, &[10, 0]), Ok(()));
+        assert_eq!(
+            write_od_value(&mut od, 0x2001, 0, &[11, 0]),
+            Err(0x0609_0031)
+        );
+        assert_eq!(
+            write_od_value(&mut od, 0x2001, 0, &[0, 0]),
+            Err(0x0609_0032)
+        );
+        assert_eq!(write_od_value(&mut od, 0x2001, 0, &[1]), Err(0x0607_0013));
+        assert_eq!(
+            write_od_value(&mut od, 0x2001, 0, &[1, 0, 0]),
+            Err(0x0607_0012)
+        );
+        assert_eq!(
+            write_od_value(&mut od, 0x2001, 1, &[1, 0]),
+            Err(0x0609_0011)
+        );
+        assert_eq!(
+            write_od_value(&mut od, 0x2FFF, 0, &[1, 0]),
+            Err(0x0602_0000)
+        );
+        assert_eq!(od.read_u16(0x2001, 0), Some(10));
+    }
+
+    #[test]
+    fn test_write_od_value_observer_veto() {
+        struct Veto;
+        impl crate::od::ObjectObserver for Veto {
+            fn pre_write(
+                &mut self,
+                _index: u16,
+                _sub_index: u8,
+                _value: &ObjectValue,
+                source: WriteSource,
+            ) -> Result<(), u32> {
+                assert_eq!(source, WriteSource::Sdo);
+                Err(0x0800_0021)
+            }
+        }
+
+        let mut od = create_od();
+        od.insert(
+            0x2001,
+            crate::od::ObjectEntry {
+                object: crate::od::Object::Variable(ObjectValue::Unsigned8(0)),
+                access: Some(crate::od::AccessType::ReadWrite),
+                ..Default::default()
+            },
+        );
+        od.register_observer(0x2001, alloc::boxed::Box::new(Veto));
+        assert_eq!(write_od_value(&mut od, 0x2001, 0, &[1]), Err(0x0800_0021));
+    }
 }
@@ -128,8 +128,8 @@ mod tests {
         assert_eq!(&buffer[1..3], &[0x00, 0x00]); // Reserved
         assert_eq!(buffer[3], 0x05); // SDO
 
-        // Prefix (4) + Seq (4) + Cmd Hdr (4) + Cmd Payload (4) = 16 bytes
-        assert_eq!(bytes_written, 16);
+        // Prefix (4) + Seq (4) + Cmd Hdr (8) + Cmd Payload (4) = 20 bytes
+        assert_eq!(bytes_written, 20);
 
         let (deserialized_seq, deserialized_cmd) =
             deserialize_sdo_udp_payload(&buffer[..bytes_written]).unwrap();
@@ -171,7 +171,8 @@ mod tests {
         // now tests an invalid command ID to ensure the error propagates correctly.
         let bad_seq = [
             0x06, 0x00, 0x00, 0x05, 0xAA, 0x3F, 0x00, 0x00, // Valid prefix + seq
-            0x00, 0xFE, 0x00, 0x00, // Flags, Invalid Cmd ID (0xFE), Size
+            0x00, 0x01, 0x00, 0xFE, 0x00, 0x00, 0x00,
+            0x00, // TID, Flags, Invalid Cmd ID (0xFE), Size
         ];
         assert!(matches!(
             deserialize_sdo_udp_payload(&bad_seq),
@@ -181,7 +182,7 @@ mod tests {
         // Malformed Command Header (invalid command ID enum)
         let bad_cmd = [
             0x06, 0x00, 0x00, 0x05, 0xAA, 0x3F, 0x00, 0x00, // Valid prefix + seq
-            0x01, 0xFF, 0x04, 0x00, // Invalid Cmd ID (0xFF)
+            0x00, 0x01, 0x00, 0xFF, 0x04, 0x00, 0x00, 0x00, // Invalid Cmd ID (0xFF)
             0x08, 0x10, 0x01, 0x00,
         ];
         assert!(matches!(
@@ -15,6 +15,12 @@ pub type INTEGER16 = i16;
 pub type INTEGER24 = i32;
 /// Alias for INTEGER32 (32-bit signed integer).
 pub type INTEGER32 = i32;
+/// Alias for INTEGER40 (64-bit signed integer, though only 40 bits used).
+pub type INTEGER40 = i64;
+/// Alias for INTEGER48 (64-bit signed integer, though only 48 bits used).
+pub type INTEGER48 = i64;
+/// Alias for INTEGER56 (64-bit signed integer, though only 56 bits used).
+pub type INTEGER56 = i64;
 /// Alias for INTEGER64 (64-bit signed integer).
 pub type INTEGER64 = i64;
 /// Alias for UNSIGNED8 (8-bit unsigned integer).
@@ -25,6 +31,12 @@ pub type UNSIGNED16 = u16;
 pub type UNSIGNED24 = u32;
 /// Alias for UNSIGNED32 (32-bit unsigned integer).
 pub type UNSIGNED32 = u32;
+/// Alias for UNSIGNED40 (64-bit unsigned integer, though only 40 bits used).
+pub type UNSIGNED40 = u64;
+/// Alias for UNSIGNED48 (64-bit unsigned integer, though only 48 bits used).
+pub type UNSIGNED48 = u64;
+/// Alias for UNSIGNED56 (64-bit unsigned integer, though only 56 bits used).
+pub type UNSIGNED56 = u64;
 /// Alias for UNSIGNED64 (64-bit unsigned integer).
 pub type UNSIGNED64 = u64;
 /// Alias for REAL32 (32-bit floating point).
@@ -34,6 +46,35 @@ pub type REAL64 = f64;
 /// Alias for a 4-byte IP Address (IPv4).
 pub type IpAddress = [u8; 4];
 
+/// A BIT string of up to 64 bits (EPSG 311 BITSTRING).
+///
+/// On the wire it takes the smallest number of bytes that hold `len` bits,
+/// bit 0 first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BitString {
+    /// Number of bits (1-64).
+    pub len: u8,
+    /// The bits, bit 0 first. Bits at and above `len` are zero.
+    pub bits: u64,
+}
+
+impl BitString {
+    /// Creates a bit string of `len` bits. Bits of `bits` at and above `len`
+    /// are cleared.
+    pub const fn new(len: u8, bits: u64) -> Self {
+        let mask = if len >= 64 { u64::MAX } else { (1 << len) - 1 };
+        Self {
+            len,
+            bits: bits & mask,
+        }
+    }
+
+    /// Number of bytes the bit string takes on the wire.
+    pub const fn byte_len(&self) -> usize {
+        (self.len as usize).div_ceil(8)
+    }
+}
+
 /// Represents a POWERLINK Node ID, wrapping a `u8` to ensure type safety.
 ///
 /// Valid Node IDs are in the range 1-240, with special values for broadcast (255)
@@ -58,6 +99,9 @@ pub const C_DLL_ISOCHR_MAX_PAYL: u16 = 1490;
 /// Maximum asynchronous payload in bytes (1500 Bytes).
 pub const C_DLL_MAX_ASYNC_MTU: usize = 1500;
 
+/// Minimum asynchronous payload in bytes (300 Bytes).
+pub const C_DLL_MIN_ASYNC_MTU: usize = 300;
+
 /// POWERLINK default Node ID of the Managing Node (240).
 pub const C_ADR_MN_DEF_NODE_ID: u8 = 240;
 
@@ -188,4 +232,11 @@ mod tests {
         assert_eq!(NodeId::try_from(241), Err(NodeIdError::InvalidRange(241)));
         assert_eq!(NodeId::try_from(252), Err(NodeIdError::InvalidRange(252)));
     }
+
+    #[test]
+    fn test_bit_string_new_masks_excess_bits() {
+        assert_eq!(BitString::new(12, 0xF_ABC).bits, 0xABC);
+        assert_eq!(BitString::new(64, u64::MAX).bits, u64::MAX);
+        assert_eq!(BitString::new(0, 1).bits, 0);
+    }
 }
@@ -11,12 +11,16 @@ mod tests {
     use super::simulator::{NodeHarness, SimulatedInterface, VirtualNetwork};
     
     use powerlink_rs::{
-        ControlledNode, Node, NodeId, 
-        ObjectDictionaryStorage, PowerlinkError,
+        ControlledNode, ErrorHandler, LinkStatus, LoggingErrorHandler, Node, NodeId, 
+        ObjectDictionaryStorage, PowerlinkError, deserialize_frame,
     };
+    use powerlink_rs::frame::{DllError, PowerlinkFrame, ServiceId, StatusResponsePayload};
+    use powerlink_rs::frame::error::DeviceError;
     use powerlink_rs::frame::basic::MacAddress;
-    use powerlink_rs::node::ManagingNode;
+    use powerlink_rs::node::{ManagingNode, NodeEvent};
+    use powerlink_rs::node::mn::MnBootEvent;
 
+    use powerlink_rs::hal::{ConfigurationInterface, Identity};
     use powerlink_rs::nmt::states::NmtState;
     use powerlink_rs::od::{ObjectDictionary, ObjectEntry, ObjectValue, Category, AccessType}; 
     use std::cell::RefCell;
@@ -49,23 +53,68 @@ mod tests {
     }
 
     fn create_cn(node_id: u8) -> NodeHarness<ControlledNode<'static>> {
-        let mac = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, node_id]);
-        
-        // Setup minimal OD
+        create_cn_with_od(node_id, cn_od(node_id))
+    }
+
+    /// Builds a minimal OD for a CN.
+    fn cn_od(node_id: u8) -> ObjectDictionary<'static> {
         let mut od = powerlink_rs::od::utils::new_cn_default(NodeId(node_id)).unwrap();
         // Required by IdentResponse
         od.insert(0x1000, default_object_entry(ObjectValue::Unsigned32(0x12345678)));
-        
-        let node = ControlledNode::new(od, mac).unwrap();
+        od
+    }
+
+    fn create_cn_with_od(
+        node_id: u8,
+        od: ObjectDictionary<'static>,
+    ) -> NodeHarness<ControlledNode<'static>> {
+        let mac = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, node_id]);
+        let mut node = ControlledNode::new(od, mac).unwrap();
+        // The application has nothing to configure.
+        node.confirm_configuration_complete();
         let interface = Rc::new(RefCell::new(SimulatedInterface::new(node_id, mac.0)));
         
         NodeHarness::new(node, interface, NodeId(node_id))
     }
 
+    // --- Error handler recording DLL errors for assertions ---
+    struct RecordingErrorHandler(Rc<RefCell<Vec<DllError>>>);
+    impl ErrorHandler for RecordingErrorHandler {
+        fn log_error(&mut self, error: &DllError) {
+            self.0.borrow_mut().push(*error);
+        }
+    }
+
     fn create_mn() -> NodeHarness<ManagingNode<'static>> {
+        create_mn_with_optional_cns(&[], Box::new(LoggingErrorHandler))
+    }
+
+    fn create_mn_with_optional_cns(
+        optional_cns: &[u8],
+        error_handler: Box<dyn ErrorHandler>,
+    ) -> NodeHarness<ManagingNode<'static>> {
+        create_mn_with_od(mn_od(optional_cns), None, error_handler)
+    }
+
+    fn create_mn_with_od(
+        od: ObjectDictionary<'static>,
+        configuration_interface: Option<&'static dyn ConfigurationInterface>,
+        error_handler: Box<dyn ErrorHandler>,
+    ) -> NodeHarness<ManagingNode<'static>> {
         let node_id = 240;
         let mac = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0xF0]);
-        
+        let node =
+            ManagingNode::with_error_handler(od, mac, configuration_interface, error_handler)
+                .unwrap();
+        let interface = Rc::new(RefCell::new(SimulatedInterface::new(node_id, mac.0)));
+
+        NodeHarness::new(node, interface, NodeId(node_id))
+    }
+
+    /// Builds the MN's OD with CN 1 mandatory and `optional_cns` optional.
+    fn mn_od(optional_cns: &[u8]) -> ObjectDictionary<'static> {
+        let node_id = 240;
+
         // Setup minimal OD for MN
         let mut od = powerlink_rs::od::utils::new_mn_default(NodeId(node_id)).unwrap();
         
@@ -76,11 +125,17 @@ mod tests {
         
         // Configure Expected Ident for Node 1 (match CN's default)
         od.write(0x1F84, 1, ObjectValue::Unsigned32(0)).unwrap(); // DeviceType (0=don't check)
-        
-        let node = ManagingNode::new(od, mac, None).unwrap();
-        let interface = Rc::new(RefCell::new(SimulatedInterface::new(node_id, mac.0)));
-        
-        NodeHarness::new(node, interface, NodeId(node_id))
+        od.write(0x1F8D, 1, ObjectValue::Unsigned16(36)).unwrap();
+        od.write(0x1F92, 1, ObjectValue::Unsigned32(2_000_000)).unwrap();
+
+        // Optional isochronous CNs: Bits 0(Exists)=1, 1(IsCN)=1 -> 0b11 = 0x3
+        for &cn in optional_cns {
+            od.write(0x1F81, cn, ObjectValue::Unsigned32(0x3)).unwrap();
+            od.write(0x1F84, cn, ObjectValue::Unsigned32(0)).unwrap();
+            od.write(0x1F8D, cn, ObjectValue::Unsigned16(36)).unwrap();
+            od.write(0x1F92, cn, ObjectValue::Unsigned32(2_000_000)).unwrap();
+        }
+        od
     }
 
     #[test]
@@ -132,5 +187,361 @@ mod tests {
 
         assert!(mn_reached_operational, "MN did not reach Operational state. Current: {:?}", mn.node.nmt_state());
         assert!(cn_reached_operational, "CN did not reach Operational state. Current: {:?}", cn.node.nmt_state());
+
+        // Every state change is reported, starting with the power-on.
+        let transitions: Vec<_> = std::iter::from_fn(|| cn.node.poll_event())
+            .filter_map(|e| match e.event {
+                NodeEvent::NmtStateChanged { from, to } => Some((from, to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            transitions.first(),
+            Some(&(NmtState::NmtGsOff, NmtState::NmtGsInitialising))
+        );
+        assert!(transitions.windows(2).all(|pair| pair[0].1 == pair[1].0));
+        assert_eq!(transitions.last().map(|t| t.1), Some(NmtState::NmtOperational));
+    }
+
+    #[test]
+    fn test_optional_cn_joins_operational_network() {
+        // The optional CN is switched on at this cycle of the MN (20ms cycle).
+        const JOIN_AT_CYCLE: u64 = 100;
+        let cycle_time_us = 20_000;
+
+        let mut network = VirtualNetwork::new();
+        network.register_node(1);
+        network.register_node(240);
+
+        let mut cn1 = create_cn(1);
+        let mut cn2 = create_cn(2);
+        let mut mn = create_mn_with_optional_cns(&[2], Box::new(LoggingErrorHandler));
+
+        let dt = 1000;
+        let join_time = JOIN_AT_CYCLE * cycle_time_us;
+        let max_time = join_time + 2_000_000;
+        let mut joined = false;
+
+        while network.current_time() < max_time {
+            if !joined && network.current_time() >= join_time {
+                assert_eq!(mn.node.nmt_state(), NmtState::NmtOperational);
+                assert_eq!(cn1.node.nmt_state(), NmtState::NmtOperational);
+                network.register_node(2);
+                joined = true;
+            }
+
+            mn.run_cycle(&mut network);
+            cn1.run_cycle(&mut network);
+            if joined {
+                cn2.run_cycle(&mut network);
+
+                // The running network is not disturbed by the late CN.
+                assert_eq!(mn.node.nmt_state(), NmtState::NmtOperational);
+                assert_eq!(cn1.node.nmt_state(), NmtState::NmtOperational);
+                if cn2.node.nmt_state() == NmtState::NmtOperational {
+                    break;
+                }
+            }
+
+            network.tick(dt);
+        }
+
+        assert!(joined, "CN 2 never joined the network");
+        assert_eq!(
+            cn2.node.nmt_state(),
+            NmtState::NmtOperational,
+            "Late CN did not reach Operational"
+        );
+        let events: Vec<_> = std::iter::from_fn(|| mn.node.poll_event())
+            .map(|e| e.event)
+            .collect();
+        assert!(events.contains(&NodeEvent::Boot(MnBootEvent::NodeIdentified(NodeId(2)))));
+        assert!(events.contains(&NodeEvent::Boot(MnBootEvent::NodeCommunicationOk(NodeId(2)))));
+    }
+
+    /// Configuration date the MN expects from CN 2.
+    const CN2_CONF_DATE: u32 = 500;
+
+    /// Configuration manager holding a Concise DCF that sets the
+    /// configuration date (0x1020 sub 1) of CN 2.
+    struct ConfDateConfiguration([u8; 15]);
+    impl ConfigurationInterface for ConfDateConfiguration {
+        fn get_expected_identity(&self, _node_id: u8) -> Option<Identity> {
+            None
+        }
+        fn get_configuration(&self, _node_id: u8) -> Result<&[u8], PowerlinkError> {
+            Ok(&self.0)
+        }
+        fn is_software_update_required(&self, _node_id: u8, _date: u32, _time: u32) -> bool {
+            false
+        }
+    }
+
+    static CN2_CONFIGURATION: ConfDateConfiguration = {
+        let mut dcf = [0u8; 15];
+        dcf[0] = 1; // Number of entries
+        dcf[4] = 0x20; // Index 0x1020
+        dcf[5] = 0x10;
+        dcf[6] = 1; // Sub-index
+        dcf[7] = 4; // Size
+        let date = CN2_CONF_DATE.to_le_bytes();
+        dcf[11] = date[0];
+        dcf[12] = date[1];
+        dcf[13] = date[2];
+        dcf[14] = date[3];
+        ConfDateConfiguration(dcf)
+    };
+
+    #[test]
+    fn test_late_cn_with_outdated_configuration_is_reconfigured() {
+        const JOIN_AT_CYCLE: u64 = 100;
+        let cycle_time_us = 20_000;
+
+        let mut network = VirtualNetwork::new();
+        network.register_node(1);
+        network.register_node(240);
+
+        // The MN checks the configuration date of CN 2 (NMT_StartUp_U32 bit 11).
+        let mut od = mn_od(&[2]);
+        let startup = od.read_u32(0x1F80, 0).unwrap();
+        od.write(0x1F80, 0, ObjectValue::Unsigned32(startup | 1 << 11)).unwrap();
+        let mut expected_dates = vec![ObjectValue::Unsigned32(0); 254];
+        expected_dates[1] = ObjectValue::Unsigned32(CN2_CONF_DATE);
+        od.insert(0x1F26, ObjectEntry {
+            object: powerlink_rs::od::Object::Array(expected_dates),
+            ..default_object_entry(ObjectValue::Unsigned32(0))
+        });
+        let mut mn = create_mn_with_od(od, Some(&CN2_CONFIGURATION), Box::new(LoggingErrorHandler));
+
+        let mut cn1 = create_cn(1);
+        // CN 2 reports no configuration date.
+        let mut cn2_od = cn_od(2);
+        cn2_od.insert(0x1020, ObjectEntry {
+            object: powerlink_rs::od::Object::Record(vec![
+                ObjectValue::Unsigned32(0),
+                ObjectValue::Unsigned32(0),
+            ]),
+            ..default_object_entry(ObjectValue::Unsigned32(0))
+        });
+        let mut cn2 = create_cn_with_od(2, cn2_od);
+
+        let dt = 1000;
+        let join_time = JOIN_AT_CYCLE * cycle_time_us;
+        let max_time = join_time + 2_000_000;
+        let mut joined = false;
+        let mut mn_events = Vec::new();
+        let mut cn2_states = Vec::new();
+
+        while network.current_time() < max_time {
+            if !joined && network.current_time() >= join_time {
+                assert_eq!(mn.node.nmt_state(), NmtState::NmtOperational);
+                network.register_node(2);
+                joined = true;
+            }
+
+            mn.run_cycle(&mut network);
+            cn1.run_cycle(&mut network);
+            mn_events.extend(std::iter::from_fn(|| mn.node.poll_event()).map(|e| e.event));
+            if joined {
+                cn2.run_cycle(&mut network);
+                cn2_states.extend(std::iter::from_fn(|| cn2.node.poll_event()).filter_map(
+                    |e| match e.event {
+                        NodeEvent::NmtStateChanged { to, .. } => Some(to),
+                        _ => None,
+                    },
+                ));
+
+                // The running network is not disturbed by the download.
+                assert_eq!(mn.node.nmt_state(), NmtState::NmtOperational);
+                assert_eq!(cn1.node.nmt_state(), NmtState::NmtOperational);
+                if cn2.node.nmt_state() == NmtState::NmtOperational {
+                    break;
+                }
+            }
+
+            network.tick(dt);
+        }
+
+        assert_eq!(
+            cn2.node.nmt_state(),
+            NmtState::NmtOperational,
+            "Reconfigured CN did not reach Operational"
+        );
+        // The configuration was applied with NMTResetConfiguration before
+        // CN 2 was identified.
+        assert!(cn2_states.contains(&NmtState::NmtGsResetConfiguration));
+        assert!(mn_events.contains(&NodeEvent::Boot(MnBootEvent::NodeIdentified(NodeId(2)))));
+        assert!(!mn_events.iter().any(|e| matches!(
+            e,
+            NodeEvent::Boot(MnBootEvent::NodeCheckFailed { node_id: NodeId(2), .. })
+        )));
+    }
+
+    #[test]
+    fn test_dll_errors_reach_application_handler() {
+        let mut network = VirtualNetwork::new();
+        network.register_node(1);
+        network.register_node(240);
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut cn = create_cn(1);
+        let mut mn = create_mn_with_optional_cns(
+            &[],
+            Box::new(RecordingErrorHandler(errors.clone())),
+        );
+
+        let dt = 1000;
+        let max_time = 5_000_000;
+        while network.current_time() < max_time {
+            mn.run_cycle(&mut network);
+            cn.run_cycle(&mut network);
+            if mn.node.nmt_state() == NmtState::NmtOperational
+                && cn.node.nmt_state() == NmtState::NmtOperational
+            {
+                break;
+            }
+            network.tick(dt);
+        }
+        assert_eq!(mn.node.nmt_state(), NmtState::NmtOperational);
+        assert!(errors.borrow().is_empty(), "Unexpected DLL errors: {:?}", errors.borrow());
+
+        // The CN drops off the network; its missing PRes frames reach the threshold.
+        let lost_at = network.current_time();
+        while network.current_time() < lost_at + 200_000 && errors.borrow().is_empty() {
+            network.tick(dt);
+            mn.run_cycle(&mut network);
+        }
+        assert_eq!(
+            errors.borrow().first(),
+            Some(&DllError::LossOfPres { node_id: NodeId(1) })
+        );
+    }
+
+    #[test]
+    fn test_link_status_raises_loss_of_link() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let mut mn = create_mn_with_optional_cns(
+            &[],
+            Box::new(RecordingErrorHandler(errors.clone())),
+        );
+        let state = mn.node.nmt_state();
+        let link = |link_up, dropped_frames| LinkStatus {
+            link_up,
+            bad_physical_mode: false,
+            dropped_frames,
+        };
+
+        mn.node.report_link_status(link(true, 0), 0);
+        mn.node.report_link_status(link(false, 0), 1000);
+        mn.node.report_link_status(link(false, 0), 2000);
+        assert_eq!(*errors.borrow(), [DllError::LossOfLink]);
+
+        mn.node.report_link_status(link(true, 2), 3000);
+        assert_eq!(*errors.borrow(), [DllError::LossOfLink, DllError::MacBuffer]);
+        // Link errors are logged without changing the NMT state.
+        assert_eq!(mn.node.nmt_state(), state);
+    }
+
+    /// Runs the MN and CN until both are Operational.
+    fn boot_to_operational(
+        network: &mut VirtualNetwork,
+        mn: &mut NodeHarness<ManagingNode<'static>>,
+        cn: &mut NodeHarness<ControlledNode<'static>>,
+    ) {
+        let dt = 1000;
+        let max_time = network.current_time() + 5_000_000;
+        while network.current_time() < max_time {
+            mn.run_cycle(network);
+            cn.run_cycle(network);
+            if mn.node.nmt_state() == NmtState::NmtOperational
+                && cn.node.nmt_state() == NmtState::NmtOperational
+            {
+                return;
+            }
+            network.tick(dt);
+        }
+        panic!("Network did not reach Operational");
+    }
+
+    /// Runs the MN and CN for the given time.
+    fn run_for(
+        network: &mut VirtualNetwork,
+        mn: &mut NodeHarness<ManagingNode<'static>>,
+        cn: &mut NodeHarness<ControlledNode<'static>>,
+        duration_us: u64,
+    ) {
+        let end = network.current_time() + duration_us;
+        while network.current_time() < end {
+            mn.run_cycle(network);
+            cn.run_cycle(network);
+            network.tick(1000);
+        }
+    }
+
+    /// Returns the ER flags of the SoAs addressed to `node_id` and the EC flags
+    /// of the StatusResponses it sent, in the order they were sent.
+    fn exception_flags(network: &VirtualNetwork, node_id: NodeId) -> (Vec<bool>, Vec<bool>) {
+        let mut er_flags = Vec::new();
+        let mut ec_flags = Vec::new();
+        for packet in &network.packet_history {
+            match deserialize_frame(&packet.data) {
+                Ok(PowerlinkFrame::SoA(soa)) if soa.target_node_id == node_id => {
+                    er_flags.push(soa.flags.er);
+                }
+                Ok(PowerlinkFrame::ASnd(asnd))
+                    if asnd.source == node_id && asnd.service_id == ServiceId::StatusResponse =>
+                {
+                    let payload = StatusResponsePayload::deserialize(&asnd.payload).unwrap();
+                    ec_flags.push(payload.ec_flag);
+                }
+                _ => {}
+            }
+        }
+        (er_flags, ec_flags)
+    }
+
+    #[test]
+    fn test_exception_reset_handshake() {
+        let mut network = VirtualNetwork::new();
+        network.register_node(1);
+        network.register_node(240);
+
+        let mut cn = create_cn(1);
+        let mut mn = create_mn();
+        boot_to_operational(&mut network, &mut mn, &mut cn);
+        network.packet_history.clear();
+
+        // The CN signals a new error, which the MN collects with a StatusRequest.
+        let error = DeviceError {
+            error_code: 0x1000,
+            profile: 0x001,
+            static_bit: Some(16),
+            ..Default::default()
+        };
+        cn.node.raise_device_error(&error, network.current_time()).unwrap();
+
+        run_for(&mut network, &mut mn, &mut cn, 1_000_000);
+
+        let log = mn.node.cn_error_log(NodeId(1)).expect("No error log for CN 1");
+        assert!(
+            log.records().any(|record| record.entry.error_code == 0x1000),
+            "Error entry was not recorded"
+        );
+
+        // A response with EC clear makes the MN reset the CN's exception signaling
+        // with ER, which the CN acknowledges by answering with EC set.
+        let (er_flags, ec_flags) = exception_flags(&network, NodeId(1));
+        assert_eq!(ec_flags, er_flags, "Every ER must be answered with EC set");
+        assert!(er_flags.contains(&true), "MN never sent ER");
+        for (i, _) in er_flags.iter().enumerate().filter(|(_, er)| **er) {
+            assert!(i > 0 && !ec_flags[i - 1], "ER sent without EC clear");
+        }
+        assert_eq!(ec_flags.last(), Some(&true));
+
+        // Once acknowledged, the MN stops resetting the CN.
+        network.packet_history.clear();
+        run_for(&mut network, &mut mn, &mut cn, 1_000_000);
+        let (er_flags, _) = exception_flags(&network, NodeId(1));
+        assert!(!er_flags.contains(&true), "ER flags: {:?}", er_flags);
     }
-}
\ No newline at end of file
+}
@@ -25,7 +25,12 @@ impl SimulatedInterface {
     }
 
     /// Pushes a frame into the receive buffer (simulating arrival from wire).
+    /// Like a NIC, unicast frames addressed to other stations are dropped.
     pub fn push_rx(&mut self, frame: Vec<u8>) {
+        let is_unicast = frame.first().is_some_and(|b| b & 1 == 0);
+        if is_unicast && frame.get(..6) != Some(&self.local_mac[..]) {
+            return;
+        }
         self.rx_queue.push_back(frame);
     }
 
@@ -61,9 +61,12 @@ impl VirtualNetwork {
                 .or_insert_with(VecDeque::new)
                 .push_back(packet);
         } else {
-            // Broadcast: Deliver to all known inboxes (simplification for now)
-            for queue in self.inboxes.values_mut() {
-                queue.push_back(packet.clone());
+            // Broadcast: Deliver to all known inboxes except the sender's,
+            // as a NIC does not receive its own frames.
+            for (node_id, queue) in self.inboxes.iter_mut() {
+                if *node_id != packet.src_node_id {
+                    queue.push_back(packet.clone());
+                }
             }
         }
     }
@@ -98,38 +101,56 @@ impl<N: Node> NodeHarness<N> {
         }
     }
 
-    /// runs a single cycle of the node logic
+    /// Runs the node logic for one tick of the simulation.
+    ///
+    /// Every frame that arrived since the last tick is handed to the node, as
+    /// a real node would receive all of them within the tick. Without frames,
+    /// the node is run once to drive its timers.
     pub fn run_cycle(&mut self, network: &mut VirtualNetwork) {
-        // 1. Check if we have a frame waiting in the network for us
+        // 1. Check if we have frames waiting in the network for us
         // We peel frames from the network inbox into the interface's internal rx queue
         while let Some(packet) = network.receive(self.node_id.0) {
             self.interface.borrow_mut().push_rx(packet.data);
         }
-        
-        // Now the interface has data. We "receive" it from the interface into a buffer.
-        let mut rx_buffer = [0u8; 1518];
-        let rx_len = match self.interface.borrow_mut().receive_frame(&mut rx_buffer) {
-             Ok(len) => len,
-             Err(_) => 0,
-        };
-
-        // 2. Run the node cycle
-        // Fix E0061: Handle argument mismatch based on feature flags
-        #[cfg(feature = "sdo-udp")]
-        let action = if rx_len > 0 {
-             self.node.run_cycle(Some(&rx_buffer[..rx_len]), None, network.current_time())
-        } else {
-             self.node.run_cycle(None, None, network.current_time())
-        };
 
-        #[cfg(not(feature = "sdo-udp"))]
-        let action = if rx_len > 0 {
-             self.node.run_cycle(Some(&rx_buffer[..rx_len]), network.current_time())
-        } else {
-             self.node.run_cycle(None, network.current_time())
-        };
+        let mut processed_frame = false;
+        loop {
+            // Now the interface has data. We "receive" it from the interface into a buffer.
+            let mut rx_buffer = [0u8; 1518];
+            let rx_len = match self.interface.borrow_mut().receive_frame(&mut rx_buffer) {
+                 Ok(len) => len,
+                 Err(_) => 0,
+            };
+            if rx_len == 0 && processed_frame {
+                break;
+            }
+            processed_frame = true;
+
+            // 2. Run the node cycle
+            // Fix E0061: Handle argument mismatch based on feature flags
+            #[cfg(feature = "sdo-udp")]
+            let action = if rx_len > 0 {
+                 self.node.run_cycle(Some(&rx_buffer[..rx_len]), None, network.current_time())
+            } else {
+                 self.node.run_cycle(None, None, network.current_time())
+            };
+
+            #[cfg(not(feature = "sdo-udp"))]
+            let action = if rx_len > 0 {
+                 self.node.run_cycle(Some(&rx_buffer[..rx_len]), network.current_time())
+            } else {
+                 self.node.run_cycle(None, network.current_time())
+            };
+
+            // 3. Handle output actions
+            self.transmit(action, network);
+            if rx_len == 0 {
+                break;
+            }
+        }
+    }
 
-        // 3. Handle output actions
+    fn transmit(&mut self, action: NodeAction, network: &mut VirtualNetwork) {
         match action {
             NodeAction::SendFrame(frame) => {
                 // Send via interface (which pushes to network)